@@ -1,3 +1,10 @@
+// Arcis has no `is_multiple_of`, so circuits use `%`
+#![allow(
+    clippy::manual_is_multiple_of,
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+
 use arcis_imports::*;
 
 #[encrypted]
@@ -5,18 +12,22 @@ mod circuits {
     use arcis_imports::*;
 
     // ============================================
-    // STATE
+    // STATUS CODES
     // ============================================
 
-    pub struct InputValues {
-        v1: u8,
-        v2: u8,
-    }
+    // Deal statuses revealed by settlement circuits (see otc::state::DealStatus)
+    const DEAL_OPEN: u8 = 0;
+    const DEAL_EXECUTED: u8 = 1;
+    const DEAL_EXPIRED: u8 = 2;
 
-    #[derive(Copy, Clone)]
-    pub struct CounterState {
-        counter: u64,
-    }
+    // RFQ statuses revealed by crank_rfq (see otc::state::RfqStatus)
+    const RFQ_FILLED: u8 = 1;
+    const RFQ_EXPIRED: u8 = 2;
+
+    // Outcomes sealed in OfferSettledBlob and QuoteSettledBlob (see otc::state::OfferOutcome)
+    const OUTCOME_EXECUTED: u8 = 0;
+    const OUTCOME_PARTIAL: u8 = 1;
+    const OUTCOME_FAILED: u8 = 2;
 
     // ============================================
     // DEAL STRUCTS
@@ -24,9 +35,9 @@ mod circuits {
 
     /// Input from creator (Shared-encrypted)
     pub struct DealInput {
-        /// Base asset amount the creator is selling
+        /// Base asset amount the creator is selling (raw base units)
         amount: u64,
-        /// X64.64 fixed-point price (quote per base)
+        /// X64.64 fixed-point price in human units (whole quote per whole base)
         price: u128,
     }
 
@@ -35,7 +46,7 @@ mod circuits {
     pub struct DealState {
         /// Base asset amount
         amount: u64,
-        /// X64.64 fixed-point price
+        /// X64.64 fixed-point price in raw units (raw quote per raw base, decimals applied)
         price: u128,
         /// Running total of filled amount
         fill_amount: u64,
@@ -46,8 +57,19 @@ mod circuits {
     pub struct DealCreatedBlob {
         /// Base asset amount
         amount: u64,
-        /// X64.64 fixed-point price
+        /// X64.64 fixed-point price in human units, as entered
+        price: u128,
+    }
+
+    /// Re-announced deal state sealed to creator (emitted on DealAnnounced)
+    #[derive(Copy, Clone)]
+    pub struct DealAnnouncedBlob {
+        /// Base asset amount
+        amount: u64,
+        /// X64.64 fixed-point price in raw units (decimals applied)
         price: u128,
+        /// Running total of filled amount
+        fill_amount: u64,
     }
 
     /// Settlement blob sealed to creator (emitted on DealSettled)
@@ -67,7 +89,7 @@ mod circuits {
 
     /// Input from offeror (Shared-encrypted)
     pub struct OfferInput {
-        /// X64.64 fixed-point price (max price willing to pay)
+        /// X64.64 fixed-point price in human units (max price willing to pay)
         price: u128,
         /// Amount of base asset to buy
         amount: u64,
@@ -76,7 +98,7 @@ mod circuits {
     /// State stored on-chain (MXE-encrypted)
     #[derive(Copy, Clone)]
     pub struct OfferState {
-        /// X64.64 fixed-point price
+        /// X64.64 fixed-point price in raw units (decimals applied)
         price: u128,
         /// Amount of base asset to buy
         amount: u64,
@@ -87,16 +109,27 @@ mod circuits {
     /// Event blob sealed to offeror (emitted on OfferCreated)
     #[derive(Copy, Clone)]
     pub struct OfferCreatedBlob {
-        /// X64.64 fixed-point price
+        /// X64.64 fixed-point price in human units, as entered
+        price: u128,
+        /// Amount of base asset to buy
+        amount: u64,
+    }
+
+    /// Re-announced offer state sealed to offeror (emitted on OfferAnnounced)
+    #[derive(Copy, Clone)]
+    pub struct OfferAnnouncedBlob {
+        /// X64.64 fixed-point price in raw units (decimals applied)
         price: u128,
         /// Amount of base asset to buy
         amount: u64,
+        /// Amount to execute (0 if rejected at submission)
+        amt_to_execute: u64,
     }
 
     /// Settlement blob sealed to offeror (emitted on OfferSettled)
     #[derive(Copy, Clone)]
     pub struct OfferSettledBlob {
-        /// Outcome: EXECUTED(0), PARTIAL(1), FAILED(2)
+        /// Outcome: OUTCOME_EXECUTED, OUTCOME_PARTIAL or OUTCOME_FAILED
         outcome: u8,
         /// Amount of base asset bought
         executed_amt: u64,
@@ -106,6 +139,206 @@ mod circuits {
         quote_refund: u64,
     }
 
+    // ============================================
+    // BASKET DEAL STRUCTS
+    // ============================================
+
+    // A basket deal sells `units` baskets, each holding a fixed amount of up to 4 base
+    // tokens (the legs). Its DealState counts basket units, so offers and offer cranks
+    // treat it like any other deal; only creation and the deal-side settlement touch
+    // the legs. Unused legs carry a zero amount.
+
+    /// Input from basket creator (Shared-encrypted)
+    pub struct BasketDealInput {
+        /// Number of basket units for sale
+        units: u64,
+        /// X64.64 fixed-point price in human units (whole quote per basket unit)
+        price: u128,
+        /// Raw base amount of each leg per basket unit
+        leg_amounts: [u64; 4],
+    }
+
+    /// Basket legs stored on-chain next to the deal (MXE-encrypted)
+    #[derive(Copy, Clone)]
+    pub struct BasketLegsState {
+        /// Raw base amount of each leg per basket unit
+        leg_amounts: [u64; 4],
+    }
+
+    /// Event blob sealed to creator (emitted on BasketDealCreated)
+    #[derive(Copy, Clone)]
+    pub struct BasketDealCreatedBlob {
+        /// Number of basket units for sale
+        units: u64,
+        /// X64.64 fixed-point price in human units, as entered
+        price: u128,
+        /// Raw base amount of each leg per basket unit
+        leg_amounts: [u64; 4],
+    }
+
+    /// Settlement blob sealed to creator (emitted on BasketDealSettled)
+    #[derive(Copy, Clone)]
+    pub struct BasketDealSettledBlob {
+        /// Total basket units filled across all offers
+        total_filled: u64,
+        /// Quote tokens the creator receives
+        creator_receives: u64,
+        /// Base tokens sold per leg
+        leg_sold: [u64; 4],
+        /// Base tokens refunded to creator per leg (unfilled)
+        leg_refund: [u64; 4],
+    }
+
+    // ============================================
+    // SWAP DEAL STRUCTS
+    // ============================================
+
+    // A swap deal trades negotiated quantities instead of a price: the creator gives
+    // `give_amount` BASE for `want_amount` QUOTE. Its state replaces DealState in the deal
+    // account (same field count) and offers state the quantities they give and want.
+    // Fills execute at the creator's ratio.
+
+    /// Input from swap creator (Shared-encrypted)
+    pub struct SwapDealInput {
+        /// Base asset amount the creator gives (raw base units)
+        give_amount: u64,
+        /// Quote asset amount the creator wants for all of it (raw quote units)
+        want_amount: u64,
+    }
+
+    /// Swap state stored on-chain in place of DealState (MXE-encrypted)
+    #[derive(Copy, Clone)]
+    pub struct SwapDealState {
+        /// Base asset amount the creator gives
+        give_amount: u64,
+        /// Quote asset amount the creator wants
+        want_amount: u64,
+        /// Running total of filled base amount
+        fill_amount: u64,
+    }
+
+    /// Event blob sealed to creator (emitted on DealCreated for swap deals)
+    #[derive(Copy, Clone)]
+    pub struct SwapDealCreatedBlob {
+        /// Base asset amount the creator gives
+        give_amount: u64,
+        /// Quote asset amount the creator wants
+        want_amount: u64,
+    }
+
+    /// Input from swap offeror (Shared-encrypted)
+    pub struct SwapOfferInput {
+        /// Quote asset amount the offeror gives (raw quote units)
+        give_amount: u64,
+        /// Base asset amount the offeror wants for it (raw base units)
+        want_amount: u64,
+    }
+
+    /// Swap offer state stored on-chain in place of OfferState (MXE-encrypted)
+    #[derive(Copy, Clone)]
+    pub struct SwapOfferState {
+        /// Quote asset amount the offeror gives (locked at submission)
+        give_amount: u64,
+        /// Base asset amount the offeror wants
+        want_amount: u64,
+        /// Base amount to execute (computed at submission based on deal availability)
+        amt_to_execute: u64,
+    }
+
+    /// Event blob sealed to offeror (emitted on OfferCreated for swap offers)
+    #[derive(Copy, Clone)]
+    pub struct SwapOfferCreatedBlob {
+        /// Quote asset amount the offeror gives
+        give_amount: u64,
+        /// Base asset amount the offeror wants
+        want_amount: u64,
+    }
+
+    // ============================================
+    // RFQ STRUCTS
+    // ============================================
+
+    /// Input from taker (Shared-encrypted)
+    pub struct RfqInput {
+        /// Base asset amount the taker wants to buy
+        size: u64,
+        /// X64.64 fixed-point limit price in raw units (max quote per base the taker will
+        /// pay, decimals not applied; RFQ prices are never scaled)
+        limit_price: u128,
+    }
+
+    /// RFQ state stored on-chain (MXE-encrypted)
+    #[derive(Copy, Clone)]
+    pub struct RfqState {
+        /// Base asset amount requested
+        size: u64,
+        /// X64.64 fixed-point limit price
+        limit_price: u128,
+        /// Best (lowest) quoted price so far
+        best_price: u128,
+        /// quote_index of the best quote so far
+        best_index: u32,
+        /// Whether any quote within the limit has been received
+        has_quote: bool,
+    }
+
+    /// Event blob sealed to taker (emitted on RfqCreated)
+    #[derive(Copy, Clone)]
+    pub struct RfqCreatedBlob {
+        /// Base asset amount requested
+        size: u64,
+        /// X64.64 fixed-point limit price
+        limit_price: u128,
+    }
+
+    /// Settlement blob sealed to taker (emitted on RfqSettled)
+    #[derive(Copy, Clone)]
+    pub struct RfqSettledBlob {
+        /// Amount of base asset bought
+        executed_amt: u64,
+        /// X64.64 fixed-point price of the winning quote (0 if unfilled)
+        price: u128,
+        /// Quote tokens paid by taker
+        quote_paid: u64,
+        /// Quote tokens refunded to taker (in QUOTE units, not BASE)
+        quote_refund: u64,
+    }
+
+    /// Input from maker (Shared-encrypted)
+    pub struct QuoteInput {
+        /// X64.64 fixed-point price in raw units (quote per base the maker asks)
+        price: u128,
+    }
+
+    /// Quote state stored on-chain (MXE-encrypted)
+    #[derive(Copy, Clone)]
+    pub struct QuoteState {
+        /// X64.64 fixed-point price
+        price: u128,
+        /// Base asset amount locked by the maker (the RFQ size)
+        committed: u64,
+    }
+
+    /// Event blob sealed to maker (emitted on QuoteCreated)
+    #[derive(Copy, Clone)]
+    pub struct QuoteCreatedBlob {
+        /// X64.64 fixed-point price
+        price: u128,
+        /// Base asset amount requested by the RFQ
+        size: u64,
+    }
+
+    /// Settlement blob sealed to maker (emitted on QuoteSettled)
+    #[derive(Copy, Clone)]
+    pub struct QuoteSettledBlob {
+        /// Outcome: OUTCOME_EXECUTED or OUTCOME_FAILED
+        outcome: u8,
+        /// Amount of base asset sold
+        executed_amt: u64,
+        /// Quote tokens the maker receives
+        quote_receives: u64,
+    }
+
     // ============================================
     // BALANCE STRUCTS
     // ============================================
@@ -128,286 +361,1826 @@ mod circuits {
         committed_amount: u64,
     }
 
+    /// Input from the sender of a transfer (Shared-encrypted)
+    pub struct TransferInput {
+        /// Amount to move to the recipient (raw units)
+        amount: u64,
+    }
+
+    /// Proof-of-funds threshold from the balance owner (Shared-encrypted)
+    pub struct AttestInput {
+        /// Amount the owner claims to be able to cover (raw units)
+        threshold: u64,
+    }
+
+    /// Attestation sealed to a third party (emitted on BalanceAttested)
+    #[derive(Copy, Clone)]
+    pub struct AttestationBlob {
+        /// Threshold the attestation was made against
+        threshold: u64,
+        /// Whether available balance (amount - committed) covers the threshold
+        covered: bool,
+    }
+
     // ============================================
-    // INSTRUCTIONS
+    // FIXED POINT (X64.64)
     // ============================================
 
-    #[instruction]
-    pub fn add_together(input_ctxt: Enc<Shared, InputValues>) -> Enc<Shared, u16> {
-        let input = input_ctxt.to_arcis();
-        let sum = input.v1 as u16 + input.v2 as u16;
-        input_ctxt.owner.from_arcis(sum)
+    // Prices are X64.64 fixed-point (quote per base, 64 fractional bits). Mirrored by the
+    // `fixed-point` crate, whose property tests pin down the rounding policy:
+    //   - amounts a party pays or locks round UP   (mul_price_up)
+    //   - amounts a party receives round DOWN      (mul_price_down)
+    // so the protocol never pays out more than it collects.
+
+    /// (amount * price) >> 64 split into integer and fractional halves of the price, so the
+    /// intermediate products always fit in u128. Returns (floor, has_remainder, overflowed).
+    fn mul_price_parts(amount: u64, price: u128) -> (u128, bool, bool) {
+        let high = amount as u128 * (price >> 64);
+        let low_full = amount as u128 * (price as u64) as u128;
+        let floor = high + (low_full >> 64);
+        let has_remainder = (low_full as u64) > 0;
+        let overflow = high > u64::MAX as u128 || floor > u64::MAX as u128;
+        (floor, has_remainder, overflow)
     }
 
-    /// Create a new deal with encrypted parameters.
-    /// Locks creator's BASE tokens in committed_amount.
-    /// Returns MXE-encrypted state for on-chain storage, updated balance, and encrypted blobs.
-    #[instruction]
-    pub fn create_deal(
-        deal_data: Enc<Shared, DealInput>,
-        creator_balance: Enc<Mxe, &BalanceState>,
-        creator: Shared,
-    ) -> (
-        Enc<Mxe, DealState>,
-        Enc<Mxe, BalanceState>,
-        Enc<Shared, DealCreatedBlob>,
-        Enc<Shared, BalanceUpdatedBlob>,
-    ) {
-        let input = deal_data.to_arcis();
-        let balance = *(creator_balance.to_arcis());
+    /// X64.64 multiplication rounded down. Returns (result, overflowed); 0 on overflow.
+    fn mul_price_down(amount: u64, price: u128) -> (u64, bool) {
+        let (floor, _, overflow) = mul_price_parts(amount, price);
+        (if overflow { 0 } else { floor as u64 }, overflow)
+    }
 
-        // Lock commitment (creator's BASE tokens)
-        let new_balance = BalanceState {
-            amount: balance.amount,
-            committed_amount: balance.committed_amount + input.amount,
-        };
+    /// Converts a human-unit price (whole quote per whole base) into raw token units:
+    /// price * 10^quote_decimals / 10^base_decimals. The program passes the two powers of
+    /// ten as plaintext `scale_up`/`scale_down` (one of them is always 1), mirrored by
+    /// `fixed_point::raw_price`. Returns (raw_price, overflowed); 0 on overflow.
+    fn scale_price(price: u128, scale_up: u128, scale_down: u128) -> (u128, bool) {
+        // scale_up < 2^64, so (price >> 64) * scale_up fits in u128; bounding it by 2^63
+        // leaves room for the fractional half
+        let overflow = (price >> 64) * scale_up >= 9_223_372_036_854_775_808u128; // 2^63
+        let scaled = if overflow { 0 } else { price * scale_up };
+        (scaled / scale_down, overflow)
+    }
 
-        let state = DealState {
-            amount: input.amount,
-            price: input.price,
-            fill_amount: 0,
-        };
+    /// X64.64 multiplication rounded up. Returns (result, overflowed); 0 on overflow.
+    fn mul_price_up(amount: u64, price: u128) -> (u64, bool) {
+        let (floor, has_remainder, floor_overflow) = mul_price_parts(amount, price);
+        let ceil = if has_remainder { floor + 1 } else { floor };
+        let overflow = floor_overflow || ceil > u64::MAX as u128;
+        (if overflow { 0 } else { ceil as u64 }, overflow)
+    }
 
-        let deal_blob = DealCreatedBlob {
-            amount: input.amount,
-            price: input.price,
-        };
+    // ============================================
+    // HELPERS
+    // ============================================
 
-        let balance_blob = BalanceUpdatedBlob {
-            amount: new_balance.amount,
-            committed_amount: new_balance.committed_amount,
-        };
+    // Every balance-mutating circuit reveals an error code as its last output:
+    // 0 = OK, 1 = UNDERFLOW, 2 = OVERFLOW, 3 = OFF_TICK_OR_LOT, 4 = OUT_OF_BAND,
+    // 5 = NOTIONAL_EXCEEDED. On error the circuit returns its encrypted inputs unchanged, so
+    // corrupted state is never written. Underflows mean a broken invariant and fail the
+    // callback. Invalid inputs (3, 4, 5) reject the deal or offer, and so does an overflow
+    // in the circuits that create deals or submit offers, where only oversized inputs can
+    // cause one; elsewhere an overflow fails the callback too.
 
-        (
-            Mxe::get().from_arcis(state),
-            creator_balance.owner.from_arcis(new_balance),
-            deal_data.owner.from_arcis(deal_blob),
-            creator.from_arcis(balance_blob),
-        )
+    /// Checked u64 addition. Returns (a + b, overflowed); `a` is returned on overflow.
+    fn checked_add(a: u64, b: u64) -> (u64, bool) {
+        let sum = a as u128 + b as u128;
+        let overflow = sum > u64::MAX as u128;
+        (if overflow { a } else { sum as u64 }, overflow)
     }
 
-    /// Submit an offer to an existing deal.
-    /// Takes MXE-encrypted deal state by reference, Shared-encrypted offer input,
-    /// and offeror's balance to lock QUOTE commitment.
-    /// Computes amt_to_execute based on price comparison and deal availability.
-    /// Returns updated deal state, new offer state, updated balance, and offer blob.
-    #[instruction]
-    pub fn submit_offer(
-        deal_state: Enc<Mxe, &DealState>,
-        offer_data: Enc<Shared, OfferInput>,
-        offeror_balance: Enc<Mxe, &BalanceState>,
-    ) -> (
-        Enc<Mxe, DealState>,
-        Enc<Mxe, OfferState>,
-        Enc<Mxe, BalanceState>,
-        Enc<Shared, OfferCreatedBlob>,
-    ) {
-        let deal = *(deal_state.to_arcis());
-        let offer = offer_data.to_arcis();
-        let balance = *(offeror_balance.to_arcis());
-
-        // Price comparison: offeror must be willing to pay at least deal price
-        let remaining = deal.amount - deal.fill_amount;
-        let amt_to_execute = if offer.price >= deal.price {
-            if offer.amount < remaining { offer.amount } else { remaining }
-        } else {
-            0
-        };
+    /// Checked u64 subtraction. Returns (a - b, underflowed); `a` is returned on underflow.
+    fn checked_sub(a: u64, b: u64) -> (u64, bool) {
+        let underflow = b > a;
+        (if underflow { a } else { a - b }, underflow)
+    }
 
-        // Lock MAX quote commitment (full offer amount at offeror's price, not amt_to_execute - privacy)
-        // quote_to_commit = offer.amount * offer.price (X64.64 fixed-point)
-        let quote_to_commit = ((offer.amount as u128 * offer.price) >> 64) as u64;
-        let new_balance = BalanceState {
-            amount: balance.amount,
-            committed_amount: balance.committed_amount + quote_to_commit,
-        };
+    /// Checked u64 multiplication. Returns (a * b, overflowed); 0 is returned on overflow.
+    fn checked_mul(a: u64, b: u64) -> (u64, bool) {
+        let product = a as u128 * b as u128;
+        let overflow = product > u64::MAX as u128;
+        (if overflow { 0 } else { product as u64 }, overflow)
+    }
 
-        let updated_deal = DealState {
-            amount: deal.amount,
-            price: deal.price,
-            fill_amount: deal.fill_amount + amt_to_execute,
-        };
+    /// amount * numerator / denominator for swap ratios; the product of two u64 always fits
+    /// in u128. Mirrored by `fixed_point::mul_ratio`. A zero denominator yields 0.
+    /// Returns (floor, has_remainder, overflowed).
+    fn mul_ratio_parts(amount: u64, numerator: u64, denominator: u64) -> (u128, bool, bool) {
+        let product = amount as u128 * numerator as u128;
+        let divisor = if denominator == 0 { 1 } else { denominator as u128 };
+        let floor = if denominator == 0 { 0 } else { product / divisor };
+        let has_remainder = denominator != 0 && floor * divisor < product;
+        (floor, has_remainder, floor > u64::MAX as u128)
+    }
 
-        let offer_state = OfferState {
-            price: offer.price,
-            amount: offer.amount,
-            amt_to_execute,
-        };
+    /// Swap ratio multiplication rounded down. Returns (result, overflowed); 0 on overflow.
+    fn mul_ratio_down(amount: u64, numerator: u64, denominator: u64) -> (u64, bool) {
+        let (floor, _, overflow) = mul_ratio_parts(amount, numerator, denominator);
+        (if overflow { 0 } else { floor as u64 }, overflow)
+    }
 
-        let offer_blob = OfferCreatedBlob {
-            price: offer.price,
-            amount: offer.amount,
-        };
+    /// Swap ratio multiplication rounded up. Returns (result, overflowed); 0 on overflow.
+    fn mul_ratio_up(amount: u64, numerator: u64, denominator: u64) -> (u64, bool) {
+        let (floor, has_remainder, floor_overflow) = mul_ratio_parts(amount, numerator, denominator);
+        let ceil = if has_remainder { floor + 1 } else { floor };
+        let overflow = floor_overflow || ceil > u64::MAX as u128;
+        (if overflow { 0 } else { ceil as u64 }, overflow)
+    }
 
-        (
-            Mxe::get().from_arcis(updated_deal),
-            Mxe::get().from_arcis(offer_state),
-            offeror_balance.owner.from_arcis(new_balance),
-            offer_data.owner.from_arcis(offer_blob),
-        )
+    /// Validates a human-unit price and raw amount against the pair's plaintext rules.
+    /// Tick and lot are never zero (the program defaults them to 1); the price band
+    /// comes from the pair's oracle, or is [0, u128::MAX] without one. The notional limit
+    /// comes from the signing delegate, or is u64::MAX for the controller itself.
+    /// Returns 0 if valid, 3 if off tick/lot, 4 if outside the band, 5 if over the notional
+    /// limit. Only this code is revealed, never the price itself.
+    fn input_error(
+        price: u128,
+        amount: u64,
+        tick_size: u128,
+        lot_size: u64,
+        min_price: u128,
+        max_price: u128,
+        notional: u64,
+        max_notional: u64,
+    ) -> u8 {
+        if price % tick_size != 0 || amount % lot_size != 0 {
+            3
+        } else if price < min_price || price > max_price {
+            4
+        } else if notional > max_notional {
+            5
+        } else {
+            0
+        }
     }
 
-    /// Announce balance - read balance state and return encrypted blob for owner.
-    /// This is a separate instruction to avoid 5-tuple output issues.
-    #[instruction]
-    pub fn announce_balance(
-        balance_state: Enc<Mxe, &BalanceState>,
-        owner: Shared,
-    ) -> Enc<Shared, BalanceUpdatedBlob> {
-        let balance = *(balance_state.to_arcis());
-        let blob = BalanceUpdatedBlob {
-            amount: balance.amount,
-            committed_amount: balance.committed_amount,
-        };
-        owner.from_arcis(blob)
+    /// Folds underflow/overflow flags into the revealed error code (underflow wins).
+    fn error_code(underflow: bool, overflow: bool) -> u8 {
+        if underflow {
+            1
+        } else if overflow {
+            2
+        } else {
+            0
+        }
     }
 
-    /// Crank (settle) a deal after expiry or when fully filled.
-    /// Updates creator's balance (release commitment, refund unfilled).
-    /// Returns updated balance, settlement blob encrypted for the creator, balance blob, and the new status.
-    #[instruction]
-    pub fn crank_deal(
-        deal_state: Enc<Mxe, &DealState>,
-        creator_balance: Enc<Mxe, &BalanceState>,
-        creator_deal_blob: Shared,
-        creator_balance_blob: Shared,
+    /// Decides whether a deal of `amount` with `fill_amount` filled can settle and how much
+    /// of it fills. Shared by single-asset, basket and swap deals.
+    /// Returns (can_settle, status, total_filled) with status DEAL_OPEN (no change),
+    /// DEAL_EXECUTED or DEAL_EXPIRED.
+    fn deal_outcome(
+        amount: u64,
+        fill_amount: u64,
         is_expired: bool,
         allow_partial: bool,
-    ) -> (
-        Enc<Mxe, BalanceState>,
-        Enc<Shared, DealSettledBlob>,
-        Enc<Shared, BalanceUpdatedBlob>,
-        u8,
-    ) {
-        let deal = *(deal_state.to_arcis());
-        let balance = *(creator_balance.to_arcis());
-        let fully_filled = deal.fill_amount >= deal.amount;
+    ) -> (bool, u8, u64) {
+        let fully_filled = fill_amount >= amount;
 
         // can_settle: expired OR fully filled
         let can_settle = is_expired || fully_filled;
 
         // deal_executes: fully filled OR (partial allowed AND has some fill)
-        let deal_executes = fully_filled || (allow_partial && deal.fill_amount > 0);
+        let deal_executes = fully_filled || (allow_partial && fill_amount > 0);
 
         // Compute values based on whether we can settle and whether deal executes
         let total_filled = if can_settle && deal_executes {
-            deal.fill_amount
+            fill_amount
         } else {
             0
         };
 
-        let unfilled = deal.amount - total_filled;
-
-        // price is X64.64: (fill_amount * price) >> 64
-        let creator_receives = ((total_filled as u128 * deal.price) >> 64) as u64;
-        let creator_refund = if can_settle { unfilled } else { 0 };
-
-        // Update creator's balance: release commitment and deduct sold BASE tokens
-        let new_balance = if can_settle {
-            BalanceState {
-                amount: balance.amount - total_filled,  // Deduct sold BASE tokens
-                committed_amount: balance.committed_amount - deal.amount,  // Release full commitment
-            }
+        let status: u8 = if !can_settle {
+            DEAL_OPEN // Cannot settle yet
+        } else if deal_executes {
+            DEAL_EXECUTED
         } else {
-            balance
+            DEAL_EXPIRED
         };
 
-        let deal_blob = DealSettledBlob {
+        (can_settle, status, total_filled)
+    }
+
+    /// Settles a deal's own state and the creator's BASE balance.
+    /// Shared by `crank_deal` and `settle_deal`.
+    /// Returns the deal status (DEAL_OPEN when nothing changes) and the error code.
+    fn settle_deal_state(
+        deal: &DealState,
+        balance: &BalanceState,
+        is_expired: bool,
+        allow_partial: bool,
+    ) -> (BalanceState, DealSettledBlob, u8, u8) {
+        let (can_settle, status, total_filled) =
+            deal_outcome(deal.amount, deal.fill_amount, is_expired, allow_partial);
+
+        // Creator receives, so round down
+        let (creator_receives, receives_overflow) = mul_price_down(total_filled, deal.price);
+
+        let (new_balance, deal_blob, error) = settle_creator_balance(
+            deal.amount,
             total_filled,
+            can_settle,
             creator_receives,
-            creator_refund,
+            receives_overflow,
+            balance,
+        );
+        (new_balance, deal_blob, status, error)
+    }
+
+    /// Settles a swap deal's own state and the creator's BASE balance, paying the creator
+    /// at its own give/want ratio. Mirrors `settle_deal_state`.
+    fn settle_swap_deal_state(
+        deal: &SwapDealState,
+        balance: &BalanceState,
+        is_expired: bool,
+        allow_partial: bool,
+    ) -> (BalanceState, DealSettledBlob, u8, u8) {
+        let (can_settle, status, total_filled) =
+            deal_outcome(deal.give_amount, deal.fill_amount, is_expired, allow_partial);
+
+        // Creator receives, so round down
+        let (creator_receives, receives_overflow) =
+            mul_ratio_down(total_filled, deal.want_amount, deal.give_amount);
+
+        let (new_balance, deal_blob, error) = settle_creator_balance(
+            deal.give_amount,
+            total_filled,
+            can_settle,
+            creator_receives,
+            receives_overflow,
+            balance,
+        );
+        (new_balance, deal_blob, status, error)
+    }
+
+    /// Releases the creator's full commitment of `deal_amount` BASE and deducts the sold
+    /// `total_filled`. Returns the new balance, the settlement blob and the error code.
+    fn settle_creator_balance(
+        deal_amount: u64,
+        total_filled: u64,
+        can_settle: bool,
+        creator_receives: u64,
+        receives_overflow: bool,
+        balance: &BalanceState,
+    ) -> (BalanceState, DealSettledBlob, u8) {
+        let (unfilled, unfilled_underflow) = checked_sub(deal_amount, total_filled);
+        let creator_refund = if can_settle { unfilled } else { 0 };
+
+        // Release commitment and deduct sold BASE tokens
+        let (amount, amount_underflow) = checked_sub(balance.amount, total_filled);
+        let (committed_amount, committed_underflow) =
+            checked_sub(balance.committed_amount, deal_amount);
+        let error = if can_settle {
+            error_code(
+                unfilled_underflow || amount_underflow || committed_underflow,
+                receives_overflow,
+            )
+        } else {
+            0
+        };
+
+        // Update creator's balance only when settling cleanly
+        let new_balance = if can_settle && error == 0 {
+            BalanceState {
+                amount,            // Deduct sold BASE tokens
+                committed_amount,  // Release full commitment
+            }
+        } else {
+            *balance
+        };
+
+        let deal_blob = DealSettledBlob {
+            total_filled,
+            creator_receives,
+            creator_refund,
+        };
+
+        (new_balance, deal_blob, error)
+    }
+
+    /// Settles one offer against a settled deal.
+    /// Shared by `crank_offer`, `crank_offers_batch` and `settle_deal` so every path computes
+    /// identical amounts. `floor_locked` marks offers that locked their commitment rounded
+    /// down (submitted before the rounding policy, see OfferAccount); they release exactly
+    /// that and never pay more than it. Returns the new balance, the offer blob and the
+    /// error code.
+    fn settle_offer(
+        deal: &DealState,
+        offer: &OfferState,
+        balance: &BalanceState,
+        deal_success: bool,
+        floor_locked: bool,
+    ) -> (BalanceState, OfferSettledBlob, u8) {
+        // If deal failed, nothing executes
+        let executed_amt = if deal_success {
+            offer.amt_to_execute
+        } else {
+            0
+        };
+
+        // Calculate quote amounts (X64.64 fixed-point, offeror pays so round up)
+        // quote_committed = offer.amount * offer.price (what was locked at submit_offer)
+        // quote_executed = executed_amt * deal.price (actual payment at deal's price)
+        // quote_refund = quote_committed - quote_executed (includes price spread savings)
+        let (quote_committed, committed_overflow) = if floor_locked {
+            mul_price_down(offer.amount, offer.price)
+        } else {
+            mul_price_up(offer.amount, offer.price)
+        };
+        let (quote_executed, executed_overflow) = mul_price_up(executed_amt, deal.price);
+        // Rounding up may pass a floor-locked commitment by one unit at the offer's own price
+        let quote_executed = if floor_locked && quote_executed > quote_committed {
+            quote_committed
+        } else {
+            quote_executed
+        };
+
+        settle_offeror_balance(
+            executed_amt,
+            offer.amount,
+            quote_committed,
+            quote_executed,
+            committed_overflow || executed_overflow,
+            balance,
+        )
+    }
+
+    /// Settles one swap offer against a settled swap deal, paying at the creator's
+    /// give/want ratio. Mirrors `settle_offer`.
+    fn settle_swap_offer(
+        deal: &SwapDealState,
+        offer: &SwapOfferState,
+        balance: &BalanceState,
+        deal_success: bool,
+    ) -> (BalanceState, OfferSettledBlob, u8) {
+        // If deal failed, nothing executes
+        let executed_amt = if deal_success {
+            offer.amt_to_execute
+        } else {
+            0
+        };
+
+        // The offeror locked exactly what it gives and pays at the creator's ratio, which is
+        // never worse than its own (offeror pays, so round up)
+        let (quote_executed, executed_overflow) =
+            mul_ratio_up(executed_amt, deal.want_amount, deal.give_amount);
+
+        settle_offeror_balance(
+            executed_amt,
+            offer.want_amount,
+            offer.give_amount,
+            quote_executed,
+            executed_overflow,
+            balance,
+        )
+    }
+
+    /// Releases the offeror's full `quote_committed` and deducts the paid `quote_executed`.
+    /// Returns the new balance, the offer blob and the error code.
+    fn settle_offeror_balance(
+        executed_amt: u64,
+        offer_amount: u64,
+        quote_committed: u64,
+        quote_executed: u64,
+        overflow: bool,
+        balance: &BalanceState,
+    ) -> (BalanceState, OfferSettledBlob, u8) {
+        let (quote_refund, refund_underflow) = checked_sub(quote_committed, quote_executed);
+
+        // Release commitment and deduct paid QUOTE tokens
+        let (amount, amount_underflow) = checked_sub(balance.amount, quote_executed);
+        let (committed_amount, release_underflow) =
+            checked_sub(balance.committed_amount, quote_committed);
+        let error = error_code(
+            refund_underflow || amount_underflow || release_underflow,
+            overflow,
+        );
+
+        // Update offeror's balance only when settling cleanly
+        let new_balance = if error == 0 {
+            BalanceState {
+                amount,            // Deduct paid QUOTE tokens
+                committed_amount,  // Release full commitment
+            }
+        } else {
+            *balance
+        };
+
+        let outcome: u8 = if executed_amt == 0 {
+            OUTCOME_FAILED
+        } else if executed_amt < offer_amount {
+            OUTCOME_PARTIAL
+        } else {
+            OUTCOME_EXECUTED
+        };
+
+        let offer_blob = OfferSettledBlob {
+            outcome,
+            executed_amt,
+            quote_paid: quote_executed,
+            quote_refund,
+        };
+
+        (new_balance, offer_blob, error)
+    }
+
+    // ============================================
+    // INSTRUCTIONS
+    // ============================================
+
+    /// Create a new deal with encrypted parameters.
+    /// Locks creator's BASE tokens in committed_amount and stores the price in raw units
+    /// using the mints' decimals (`price_scale_up`/`price_scale_down`, see `scale_price`).
+    /// A price off the pair's tick, an amount off its lot (error 3), a price outside the
+    /// oracle band (error 4), a notional over the signing delegate's limit (error 5) or
+    /// inputs too large to lock (error 2) reject the deal: nothing is locked and the stored
+    /// state is an empty deal.
+    /// Returns MXE-encrypted state for on-chain storage, updated balance, encrypted blobs
+    /// (owner and auditor copies), and the error code.
+    #[instruction]
+    pub fn create_deal(
+        deal_data: Enc<Shared, DealInput>,
+        creator_balance: Enc<Mxe, &BalanceState>,
+        creator: Shared,
+        price_scale_up: u128,
+        price_scale_down: u128,
+        tick_size: u128,
+        lot_size: u64,
+        min_price: u128,
+        max_price: u128,
+        max_notional: u64,
+        auditor: ArcisX25519Pubkey,
+    ) -> (
+        Enc<Mxe, DealState>,
+        Enc<Mxe, BalanceState>,
+        [Enc<Shared, DealCreatedBlob>; 2],
+        [Enc<Shared, BalanceUpdatedBlob>; 2],
+        u8,
+    ) {
+        let input = deal_data.to_arcis();
+        let balance = *(creator_balance.to_arcis());
+
+        // Lock commitment (creator's BASE tokens)
+        let (committed_amount, committed_overflow) =
+            checked_add(balance.committed_amount, input.amount);
+        let (raw_price, price_overflow) = scale_price(input.price, price_scale_up, price_scale_down);
+        // Notional in raw quote units, checked against the delegate's limit
+        let (notional, notional_overflow) = mul_price_up(input.amount, raw_price);
+        let notional = if notional_overflow { u64::MAX } else { notional };
+        let invalid = input_error(
+            input.price,
+            input.amount,
+            tick_size,
+            lot_size,
+            min_price,
+            max_price,
+            notional,
+            max_notional,
+        );
+        let error = if invalid == 0 {
+            error_code(false, committed_overflow || price_overflow)
+        } else {
+            invalid
+        };
+
+        let new_balance = if error == 0 {
+            BalanceState {
+                amount: balance.amount,
+                committed_amount,
+            }
+        } else {
+            balance
         };
 
+        let state = DealState {
+            amount: if error == 0 { input.amount } else { 0 },
+            price: if error == 0 { raw_price } else { 0 },
+            fill_amount: 0,
+        };
+
+        let deal_blob = DealCreatedBlob {
+            amount: input.amount,
+            price: input.price,
+        };
+
+        let balance_blob = BalanceUpdatedBlob {
+            amount: new_balance.amount,
+            committed_amount: new_balance.committed_amount,
+        };
+
+        (
+            Mxe::get().from_arcis(state),
+            creator_balance.owner.from_arcis(new_balance),
+            [deal_data.owner.from_arcis(deal_blob), Shared::new(auditor).from_arcis(deal_blob)],
+            [creator.from_arcis(balance_blob), Shared::new(auditor).from_arcis(balance_blob)],
+            error.reveal(),
+        )
+    }
+
+    /// Submit an offer to an existing deal.
+    /// Takes MXE-encrypted deal state by reference, Shared-encrypted offer input,
+    /// and offeror's balance to lock QUOTE commitment.
+    /// Computes amt_to_execute based on price comparison and deal availability.
+    /// The offer price is scaled to raw units with the deal's decimals before comparing.
+    /// A price off the deal's tick, an amount off its lot (error 3), a price outside the
+    /// oracle band (error 4), a notional over the signing delegate's limit (error 5) or
+    /// inputs too large to lock (error 2) reject the offer: nothing is locked or filled and
+    /// the stored state is an empty offer, which the program never settles.
+    /// Returns updated deal state, new offer state, updated balance, offer blobs (owner and
+    /// auditor copies), and the error code.
+    #[instruction]
+    pub fn submit_offer(
+        deal_state: Enc<Mxe, &DealState>,
+        offer_data: Enc<Shared, OfferInput>,
+        offeror_balance: Enc<Mxe, &BalanceState>,
+        price_scale_up: u128,
+        price_scale_down: u128,
+        tick_size: u128,
+        lot_size: u64,
+        min_price: u128,
+        max_price: u128,
+        max_notional: u64,
+        auditor: ArcisX25519Pubkey,
+    ) -> (
+        Enc<Mxe, DealState>,
+        Enc<Mxe, OfferState>,
+        Enc<Mxe, BalanceState>,
+        [Enc<Shared, OfferCreatedBlob>; 2],
+        u8,
+    ) {
+        let deal = *(deal_state.to_arcis());
+        let offer = offer_data.to_arcis();
+        let balance = *(offeror_balance.to_arcis());
+
+        let (offer_price, price_overflow) = scale_price(offer.price, price_scale_up, price_scale_down);
+
+        // Price comparison: offeror must be willing to pay at least deal price
+        let (remaining, remaining_underflow) = checked_sub(deal.amount, deal.fill_amount);
+        let amt_to_execute = if offer_price >= deal.price {
+            if offer.amount < remaining { offer.amount } else { remaining }
+        } else {
+            0
+        };
+
+        // Lock MAX quote commitment (full offer amount at offeror's price, not amt_to_execute - privacy)
+        // quote_to_commit = offer.amount * offer_price (X64.64 fixed-point, rounded up)
+        let (quote_to_commit, commit_overflow) = mul_price_up(offer.amount, offer_price);
+        let (committed_amount, committed_overflow) =
+            checked_add(balance.committed_amount, quote_to_commit);
+        let (fill_amount, fill_overflow) = checked_add(deal.fill_amount, amt_to_execute);
+        let invalid = input_error(
+            offer.price,
+            offer.amount,
+            tick_size,
+            lot_size,
+            min_price,
+            max_price,
+            quote_to_commit,
+            max_notional,
+        );
+        let error = if invalid == 0 {
+            error_code(
+                remaining_underflow,
+                price_overflow || commit_overflow || committed_overflow || fill_overflow,
+            )
+        } else {
+            invalid
+        };
+
+        let new_balance = if error == 0 {
+            BalanceState {
+                amount: balance.amount,
+                committed_amount,
+            }
+        } else {
+            balance
+        };
+
+        let updated_deal = if error == 0 {
+            DealState {
+                amount: deal.amount,
+                price: deal.price,
+                fill_amount,
+            }
+        } else {
+            deal
+        };
+
+        let offer_state = OfferState {
+            price: if error == 0 { offer_price } else { 0 },
+            amount: if error == 0 { offer.amount } else { 0 },
+            amt_to_execute: if error == 0 { amt_to_execute } else { 0 },
+        };
+
+        let offer_blob = OfferCreatedBlob {
+            price: offer.price,
+            amount: offer.amount,
+        };
+
+        (
+            Mxe::get().from_arcis(updated_deal),
+            Mxe::get().from_arcis(offer_state),
+            offeror_balance.owner.from_arcis(new_balance),
+            [offer_data.owner.from_arcis(offer_blob), Shared::new(auditor).from_arcis(offer_blob)],
+            error.reveal(),
+        )
+    }
+
+    /// Announce balance - read balance state and return encrypted blob for owner.
+    /// This is a separate instruction to avoid 5-tuple output issues.
+    /// The second blob is a copy for the balance's auditor.
+    #[instruction]
+    pub fn announce_balance(
+        balance_state: Enc<Mxe, &BalanceState>,
+        owner: Shared,
+        auditor: ArcisX25519Pubkey,
+    ) -> [Enc<Shared, BalanceUpdatedBlob>; 2] {
+        let balance = *(balance_state.to_arcis());
+        let blob = BalanceUpdatedBlob {
+            amount: balance.amount,
+            committed_amount: balance.committed_amount,
+        };
+        [owner.from_arcis(blob), Shared::new(auditor).from_arcis(blob)]
+    }
+
+    /// Announce deal - read deal state and return encrypted blob for the creator.
+    /// Used to re-seal a deal to a rotated encryption key.
+    /// The second blob is a copy for the creator's auditor.
+    #[instruction]
+    pub fn announce_deal(
+        deal_state: Enc<Mxe, &DealState>,
+        owner: Shared,
+        auditor: ArcisX25519Pubkey,
+    ) -> [Enc<Shared, DealAnnouncedBlob>; 2] {
+        let deal = *(deal_state.to_arcis());
+        let blob = DealAnnouncedBlob {
+            amount: deal.amount,
+            price: deal.price,
+            fill_amount: deal.fill_amount,
+        };
+        [owner.from_arcis(blob), Shared::new(auditor).from_arcis(blob)]
+    }
+
+    /// Announce offer - read offer state and return encrypted blob for the offeror.
+    /// Used to re-seal an offer to a rotated encryption key.
+    /// The second blob is a copy for the offeror's auditor.
+    #[instruction]
+    pub fn announce_offer(
+        offer_state: Enc<Mxe, &OfferState>,
+        owner: Shared,
+        auditor: ArcisX25519Pubkey,
+    ) -> [Enc<Shared, OfferAnnouncedBlob>; 2] {
+        let offer = *(offer_state.to_arcis());
+        let blob = OfferAnnouncedBlob {
+            price: offer.price,
+            amount: offer.amount,
+            amt_to_execute: offer.amt_to_execute,
+        };
+        [owner.from_arcis(blob), Shared::new(auditor).from_arcis(blob)]
+    }
+
+    /// Crank (settle) a deal after expiry or when fully filled.
+    /// Updates creator's balance (release commitment, refund unfilled).
+    /// Returns updated balance, settlement and balance blobs for the creator and their auditor,
+    /// the new status, and the error code.
+    #[instruction]
+    pub fn crank_deal(
+        deal_state: Enc<Mxe, &DealState>,
+        creator_balance: Enc<Mxe, &BalanceState>,
+        creator_deal_blob: Shared,
+        creator_balance_blob: Shared,
+        is_expired: bool,
+        allow_partial: bool,
+        auditor: ArcisX25519Pubkey,
+    ) -> (
+        Enc<Mxe, BalanceState>,
+        [Enc<Shared, DealSettledBlob>; 2],
+        [Enc<Shared, BalanceUpdatedBlob>; 2],
+        u8,
+        u8,
+    ) {
+        let deal = *(deal_state.to_arcis());
+        let balance = *(creator_balance.to_arcis());
+
+        let (new_balance, deal_blob, status, error) =
+            settle_deal_state(&deal, &balance, is_expired, allow_partial);
+
+        let balance_blob = BalanceUpdatedBlob {
+            amount: new_balance.amount,
+            committed_amount: new_balance.committed_amount,
+        };
+
+        (
+            creator_balance.owner.from_arcis(new_balance),
+            [creator_deal_blob.from_arcis(deal_blob), Shared::new(auditor).from_arcis(deal_blob)],
+            [
+                creator_balance_blob.from_arcis(balance_blob),
+                Shared::new(auditor).from_arcis(balance_blob),
+            ],
+            status.reveal(),
+            error.reveal(),
+        )
+    }
+
+    /// Crank (settle) a single offer after the deal has been settled.
+    /// CRITICAL: Uses deal state to calculate quote amounts correctly.
+    /// Updates offeror's balance: release commitment and refund unused QUOTE tokens.
+    /// Returns updated balance, settlement and balance blobs for the offeror and their auditor,
+    /// and the error code.
+    #[instruction]
+    pub fn crank_offer(
+        deal_state: Enc<Mxe, &DealState>,
+        offer_state: Enc<Mxe, &OfferState>,
+        offeror_balance: Enc<Mxe, &BalanceState>,
+        offeror_offer_blob: Shared,
+        offeror_balance_blob: Shared,
+        deal_success: bool,
+        floor_locked: bool,
+        auditor: ArcisX25519Pubkey,
+    ) -> (
+        Enc<Mxe, BalanceState>,
+        [Enc<Shared, OfferSettledBlob>; 2],
+        [Enc<Shared, BalanceUpdatedBlob>; 2],
+        u8,
+    ) {
+        let deal = *(deal_state.to_arcis());
+        let offer = *(offer_state.to_arcis());
+        let balance = *(offeror_balance.to_arcis());
+
+        let (new_balance, offer_blob, error) =
+            settle_offer(&deal, &offer, &balance, deal_success, floor_locked);
+
+        let balance_blob = BalanceUpdatedBlob {
+            amount: new_balance.amount,
+            committed_amount: new_balance.committed_amount,
+        };
+
+        (
+            offeror_balance.owner.from_arcis(new_balance),
+            [
+                offeror_offer_blob.from_arcis(offer_blob),
+                Shared::new(auditor).from_arcis(offer_blob),
+            ],
+            [
+                offeror_balance_blob.from_arcis(balance_blob),
+                Shared::new(auditor).from_arcis(balance_blob),
+            ],
+            error.reveal(),
+        )
+    }
+
+    /// Crank (settle) a fixed-size batch of offers against the same settled deal.
+    /// Equivalent to calling `crank_offer` once per slot, but in a single computation.
+    /// Unused slots repeat slot 0 and are ignored by the callback.
+    /// Balance blobs are not emitted; offerors can run `announce_balance` afterwards.
+    /// Offer blob slots 8..16 are copies sealed to each offeror's auditor.
+    /// A single error code covers the whole batch.
+    #[instruction]
+    pub fn crank_offers_batch(
+        deal_state: Enc<Mxe, &DealState>,
+        offer_states: [Enc<Mxe, &OfferState>; 8],
+        offeror_balances: [Enc<Mxe, &BalanceState>; 8],
+        offeror_keys: [ArcisX25519Pubkey; 8],
+        auditor_keys: [ArcisX25519Pubkey; 8],
+        floor_locked: [bool; 8],
+        deal_success: bool,
+    ) -> (
+        [Enc<Mxe, BalanceState>; 8],
+        [Enc<Shared, OfferSettledBlob>; 16],
+        u8,
+    ) {
+        let deal = *(deal_state.to_arcis());
+        let mut error: u8 = 0;
+
+        let mut balances = [BalanceState {
+            amount: 0,
+            committed_amount: 0,
+        }; 8];
+        let mut blobs = [OfferSettledBlob {
+            outcome: 0,
+            executed_amt: 0,
+            quote_paid: 0,
+            quote_refund: 0,
+        }; 8];
+        for i in 0..8 {
+            let offer = *(offer_states[i].to_arcis());
+            let balance = *(offeror_balances[i].to_arcis());
+            let (new_balance, offer_blob, slot_error) =
+                settle_offer(&deal, &offer, &balance, deal_success, floor_locked[i]);
+            balances[i] = new_balance;
+            blobs[i] = offer_blob;
+            // Keep the first error so the whole batch is rejected
+            if error == 0 {
+                error = slot_error;
+            }
+        }
+
+        (
+            [
+                Mxe::get().from_arcis(balances[0]),
+                Mxe::get().from_arcis(balances[1]),
+                Mxe::get().from_arcis(balances[2]),
+                Mxe::get().from_arcis(balances[3]),
+                Mxe::get().from_arcis(balances[4]),
+                Mxe::get().from_arcis(balances[5]),
+                Mxe::get().from_arcis(balances[6]),
+                Mxe::get().from_arcis(balances[7]),
+            ],
+            [
+                Shared::new(offeror_keys[0]).from_arcis(blobs[0]),
+                Shared::new(offeror_keys[1]).from_arcis(blobs[1]),
+                Shared::new(offeror_keys[2]).from_arcis(blobs[2]),
+                Shared::new(offeror_keys[3]).from_arcis(blobs[3]),
+                Shared::new(offeror_keys[4]).from_arcis(blobs[4]),
+                Shared::new(offeror_keys[5]).from_arcis(blobs[5]),
+                Shared::new(offeror_keys[6]).from_arcis(blobs[6]),
+                Shared::new(offeror_keys[7]).from_arcis(blobs[7]),
+                Shared::new(auditor_keys[0]).from_arcis(blobs[0]),
+                Shared::new(auditor_keys[1]).from_arcis(blobs[1]),
+                Shared::new(auditor_keys[2]).from_arcis(blobs[2]),
+                Shared::new(auditor_keys[3]).from_arcis(blobs[3]),
+                Shared::new(auditor_keys[4]).from_arcis(blobs[4]),
+                Shared::new(auditor_keys[5]).from_arcis(blobs[5]),
+                Shared::new(auditor_keys[6]).from_arcis(blobs[6]),
+                Shared::new(auditor_keys[7]).from_arcis(blobs[7]),
+            ],
+            error.reveal(),
+        )
+    }
+
+    /// Atomically settle a deal together with every one of its offers (up to 8).
+    /// Combines `crank_deal` and `crank_offers_batch` so the creator's and all offerors'
+    /// balances move in the same callback. Unused offer slots repeat slot 0.
+    /// Balance slot 8 holds the creator's updated balance (kept within a 4-tuple output);
+    /// balance blobs are not emitted, owners can run `announce_balance` afterwards.
+    /// The deal blob and offer blob slots 8..16 have copies sealed to the owners' auditors.
+    /// A single error code covers the deal and every offer.
+    #[instruction]
+    pub fn settle_deal(
+        deal_state: Enc<Mxe, &DealState>,
+        creator_balance: Enc<Mxe, &BalanceState>,
+        creator_deal_blob: Shared,
+        creator_auditor: ArcisX25519Pubkey,
+        is_expired: bool,
+        allow_partial: bool,
+        offer_states: [Enc<Mxe, &OfferState>; 8],
+        offeror_balances: [Enc<Mxe, &BalanceState>; 8],
+        offeror_keys: [ArcisX25519Pubkey; 8],
+        auditor_keys: [ArcisX25519Pubkey; 8],
+        floor_locked: [bool; 8],
+    ) -> (
+        [Enc<Shared, DealSettledBlob>; 2],
+        u8,
+        [Enc<Mxe, BalanceState>; 9],
+        [Enc<Shared, OfferSettledBlob>; 16],
+        u8,
+    ) {
+        let deal = *(deal_state.to_arcis());
+        let creator = *(creator_balance.to_arcis());
+
+        let (new_creator_balance, deal_blob, status, deal_error) =
+            settle_deal_state(&deal, &creator, is_expired, allow_partial);
+        let deal_success = status == DEAL_EXECUTED;
+        let mut error = deal_error;
+
+        let mut balances = [BalanceState {
+            amount: 0,
+            committed_amount: 0,
+        }; 8];
+        let mut blobs = [OfferSettledBlob {
+            outcome: 0,
+            executed_amt: 0,
+            quote_paid: 0,
+            quote_refund: 0,
+        }; 8];
+        for i in 0..8 {
+            let offer = *(offer_states[i].to_arcis());
+            let balance = *(offeror_balances[i].to_arcis());
+            let (new_balance, offer_blob, slot_error) =
+                settle_offer(&deal, &offer, &balance, deal_success, floor_locked[i]);
+            balances[i] = new_balance;
+            blobs[i] = offer_blob;
+            // Keep the first error so the whole batch is rejected
+            if error == 0 {
+                error = slot_error;
+            }
+        }
+
+        (
+            [
+                creator_deal_blob.from_arcis(deal_blob),
+                Shared::new(creator_auditor).from_arcis(deal_blob),
+            ],
+            status.reveal(),
+            [
+                Mxe::get().from_arcis(balances[0]),
+                Mxe::get().from_arcis(balances[1]),
+                Mxe::get().from_arcis(balances[2]),
+                Mxe::get().from_arcis(balances[3]),
+                Mxe::get().from_arcis(balances[4]),
+                Mxe::get().from_arcis(balances[5]),
+                Mxe::get().from_arcis(balances[6]),
+                Mxe::get().from_arcis(balances[7]),
+                creator_balance.owner.from_arcis(new_creator_balance),
+            ],
+            [
+                Shared::new(offeror_keys[0]).from_arcis(blobs[0]),
+                Shared::new(offeror_keys[1]).from_arcis(blobs[1]),
+                Shared::new(offeror_keys[2]).from_arcis(blobs[2]),
+                Shared::new(offeror_keys[3]).from_arcis(blobs[3]),
+                Shared::new(offeror_keys[4]).from_arcis(blobs[4]),
+                Shared::new(offeror_keys[5]).from_arcis(blobs[5]),
+                Shared::new(offeror_keys[6]).from_arcis(blobs[6]),
+                Shared::new(offeror_keys[7]).from_arcis(blobs[7]),
+                Shared::new(auditor_keys[0]).from_arcis(blobs[0]),
+                Shared::new(auditor_keys[1]).from_arcis(blobs[1]),
+                Shared::new(auditor_keys[2]).from_arcis(blobs[2]),
+                Shared::new(auditor_keys[3]).from_arcis(blobs[3]),
+                Shared::new(auditor_keys[4]).from_arcis(blobs[4]),
+                Shared::new(auditor_keys[5]).from_arcis(blobs[5]),
+                Shared::new(auditor_keys[6]).from_arcis(blobs[6]),
+                Shared::new(auditor_keys[7]).from_arcis(blobs[7]),
+            ],
+            error.reveal(),
+        )
+    }
+
+    /// Create a basket deal selling `units` baskets of up to 4 base legs for one quote mint.
+    /// Locks `units * leg_amount` in every leg's BASE balance in the same computation and
+    /// stores the price in raw quote units per basket unit (basket units have no decimals).
+    /// Leg slots at or beyond `num_legs` repeat leg 0's balance and are left untouched.
+    /// A notional over the signing delegate's limit (error 5) or inputs too large to lock
+    /// (error 2) reject the deal: nothing is locked and the stored state is an empty deal.
+    /// Returns MXE-encrypted deal and leg state for on-chain storage, the updated leg
+    /// balances, encrypted blobs (owner and auditor copies), and the error code.
+    #[instruction]
+    pub fn create_basket_deal(
+        deal_data: Enc<Shared, BasketDealInput>,
+        leg_balances: [Enc<Mxe, &BalanceState>; 4],
+        num_legs: u8,
+        price_scale_up: u128,
+        price_scale_down: u128,
+        max_notional: u64,
+        auditor: ArcisX25519Pubkey,
+    ) -> (
+        Enc<Mxe, DealState>,
+        Enc<Mxe, BasketLegsState>,
+        [Enc<Mxe, BalanceState>; 4],
+        [Enc<Shared, BasketDealCreatedBlob>; 2],
+        u8,
+    ) {
+        let input = deal_data.to_arcis();
+
+        let (raw_price, price_overflow) = scale_price(input.price, price_scale_up, price_scale_down);
+        // Notional in raw quote units, checked against the delegate's limit
+        let (notional, notional_overflow) = mul_price_up(input.units, raw_price);
+        let notional = if notional_overflow { u64::MAX } else { notional };
+
+        // Lock every leg's commitment (units * per-unit amount of that leg's BASE tokens)
+        let mut overflow = price_overflow;
+        let mut leg_amounts = [0u64; 4];
+        let mut balances = [BalanceState {
+            amount: 0,
+            committed_amount: 0,
+        }; 4];
+        let mut locked_balances = balances;
+        for i in 0..4 {
+            let balance = *(leg_balances[i].to_arcis());
+            let active = (i as u8) < num_legs;
+            let (leg_total, leg_overflow) = checked_mul(input.units, input.leg_amounts[i]);
+            let (committed_amount, committed_overflow) =
+                checked_add(balance.committed_amount, leg_total);
+            overflow = overflow || (active && (leg_overflow || committed_overflow));
+            leg_amounts[i] = if active { input.leg_amounts[i] } else { 0 };
+            balances[i] = balance;
+            locked_balances[i] = if active {
+                BalanceState {
+                    amount: balance.amount,
+                    committed_amount,
+                }
+            } else {
+                balance
+            };
+        }
+
+        let error = if notional > max_notional {
+            5
+        } else {
+            error_code(false, overflow)
+        };
+
+        let new_balances = if error == 0 { locked_balances } else { balances };
+
+        let state = DealState {
+            amount: if error == 0 { input.units } else { 0 },
+            price: if error == 0 { raw_price } else { 0 },
+            fill_amount: 0,
+        };
+        let legs = BasketLegsState {
+            leg_amounts: if error == 0 { leg_amounts } else { [0u64; 4] },
+        };
+
+        let deal_blob = BasketDealCreatedBlob {
+            units: input.units,
+            price: input.price,
+            leg_amounts,
+        };
+
+        (
+            Mxe::get().from_arcis(state),
+            Mxe::get().from_arcis(legs),
+            [
+                Mxe::get().from_arcis(new_balances[0]),
+                Mxe::get().from_arcis(new_balances[1]),
+                Mxe::get().from_arcis(new_balances[2]),
+                Mxe::get().from_arcis(new_balances[3]),
+            ],
+            [deal_data.owner.from_arcis(deal_blob), Shared::new(auditor).from_arcis(deal_blob)],
+            error.reveal(),
+        )
+    }
+
+    /// Crank (settle) a basket deal after expiry or when fully filled.
+    /// Every leg balance moves in the same computation: the sold units of each leg are
+    /// deducted and the full commitment released. Offers settle with `crank_offer` or
+    /// `crank_offers_batch` as for any deal.
+    /// Leg slots at or beyond `num_legs` repeat leg 0's balance and are ignored by the
+    /// callback. Balance blobs are not emitted; owners can run `announce_balance` afterwards.
+    /// Returns the updated leg balances, settlement blobs for the creator and their auditor,
+    /// the new status, and the error code.
+    #[instruction]
+    pub fn crank_basket_deal(
+        deal_state: Enc<Mxe, &DealState>,
+        basket_legs: Enc<Mxe, &BasketLegsState>,
+        leg_balances: [Enc<Mxe, &BalanceState>; 4],
+        num_legs: u8,
+        creator_deal_blob: Shared,
+        is_expired: bool,
+        allow_partial: bool,
+        auditor: ArcisX25519Pubkey,
+    ) -> (
+        [Enc<Mxe, BalanceState>; 4],
+        [Enc<Shared, BasketDealSettledBlob>; 2],
+        u8,
+        u8,
+    ) {
+        let deal = *(deal_state.to_arcis());
+        let legs = *(basket_legs.to_arcis());
+
+        let (can_settle, status, total_filled) =
+            deal_outcome(deal.amount, deal.fill_amount, is_expired, allow_partial);
+
+        // Creator receives, so round down
+        let (creator_receives, receives_overflow) = mul_price_down(total_filled, deal.price);
+        let (unfilled, unfilled_underflow) = checked_sub(deal.amount, total_filled);
+        let mut underflow = unfilled_underflow;
+        let mut overflow = receives_overflow;
+
+        let mut leg_sold = [0u64; 4];
+        let mut leg_refund = [0u64; 4];
+        let mut balances = [BalanceState {
+            amount: 0,
+            committed_amount: 0,
+        }; 4];
+        let mut settled_balances = balances;
+        for i in 0..4 {
+            let balance = *(leg_balances[i].to_arcis());
+            let active = (i as u8) < num_legs;
+
+            // Release commitment and deduct sold BASE tokens of this leg
+            let (sold, sold_overflow) = checked_mul(total_filled, legs.leg_amounts[i]);
+            let (locked, locked_overflow) = checked_mul(deal.amount, legs.leg_amounts[i]);
+            let (refund, refund_overflow) = checked_mul(unfilled, legs.leg_amounts[i]);
+            let (amount, amount_underflow) = checked_sub(balance.amount, sold);
+            let (committed_amount, committed_underflow) =
+                checked_sub(balance.committed_amount, locked);
+            underflow = underflow || (active && (amount_underflow || committed_underflow));
+            overflow = overflow || (active && (sold_overflow || locked_overflow || refund_overflow));
+
+            leg_sold[i] = if active { sold } else { 0 };
+            leg_refund[i] = if active && can_settle { refund } else { 0 };
+            balances[i] = balance;
+            settled_balances[i] = if active {
+                BalanceState {
+                    amount,
+                    committed_amount,
+                }
+            } else {
+                balance
+            };
+        }
+
+        let error = if can_settle {
+            error_code(underflow, overflow)
+        } else {
+            0
+        };
+
+        // Update leg balances only when settling cleanly
+        let new_balances = if can_settle && error == 0 {
+            settled_balances
+        } else {
+            balances
+        };
+
+        let deal_blob = BasketDealSettledBlob {
+            total_filled,
+            creator_receives,
+            leg_sold,
+            leg_refund,
+        };
+
+        (
+            [
+                Mxe::get().from_arcis(new_balances[0]),
+                Mxe::get().from_arcis(new_balances[1]),
+                Mxe::get().from_arcis(new_balances[2]),
+                Mxe::get().from_arcis(new_balances[3]),
+            ],
+            [creator_deal_blob.from_arcis(deal_blob), Shared::new(auditor).from_arcis(deal_blob)],
+            status.reveal(),
+            error.reveal(),
+        )
+    }
+
+    /// Create a swap deal giving `give_amount` BASE for `want_amount` QUOTE.
+    /// Locks the creator's BASE tokens like `create_deal`; there is no price, so tick, lot
+    /// and oracle band do not apply. A `want_amount` over the signing delegate's notional
+    /// limit (error 5) or inputs too large to lock (error 2) reject the deal: nothing is
+    /// locked and the stored state is empty.
+    /// Returns MXE-encrypted swap state for on-chain storage, updated balance, encrypted
+    /// blobs (owner and auditor copies), and the error code.
+    #[instruction]
+    pub fn create_swap_deal(
+        deal_data: Enc<Shared, SwapDealInput>,
+        creator_balance: Enc<Mxe, &BalanceState>,
+        creator: Shared,
+        max_notional: u64,
+        auditor: ArcisX25519Pubkey,
+    ) -> (
+        Enc<Mxe, SwapDealState>,
+        Enc<Mxe, BalanceState>,
+        [Enc<Shared, SwapDealCreatedBlob>; 2],
+        [Enc<Shared, BalanceUpdatedBlob>; 2],
+        u8,
+    ) {
+        let input = deal_data.to_arcis();
+        let balance = *(creator_balance.to_arcis());
+
+        // Lock commitment (creator's BASE tokens)
+        let (committed_amount, committed_overflow) =
+            checked_add(balance.committed_amount, input.give_amount);
+        let error = if input.want_amount > max_notional {
+            5
+        } else {
+            error_code(false, committed_overflow)
+        };
+
+        let new_balance = if error == 0 {
+            BalanceState {
+                amount: balance.amount,
+                committed_amount,
+            }
+        } else {
+            balance
+        };
+
+        let state = SwapDealState {
+            give_amount: if error == 0 { input.give_amount } else { 0 },
+            want_amount: if error == 0 { input.want_amount } else { 0 },
+            fill_amount: 0,
+        };
+
+        let deal_blob = SwapDealCreatedBlob {
+            give_amount: input.give_amount,
+            want_amount: input.want_amount,
+        };
+
+        let balance_blob = BalanceUpdatedBlob {
+            amount: new_balance.amount,
+            committed_amount: new_balance.committed_amount,
+        };
+
+        (
+            Mxe::get().from_arcis(state),
+            creator_balance.owner.from_arcis(new_balance),
+            [deal_data.owner.from_arcis(deal_blob), Shared::new(auditor).from_arcis(deal_blob)],
+            [creator.from_arcis(balance_blob), Shared::new(auditor).from_arcis(balance_blob)],
+            error.reveal(),
+        )
+    }
+
+    /// Submit an offer to a swap deal.
+    /// The offer is accepted if its implied ratio is at least as good for the creator:
+    /// offer.give / offer.want >= deal.want / deal.give, compared by cross-multiplication.
+    /// Locks the offeror's full `give_amount` QUOTE and fills up to `want_amount` BASE.
+    /// A `give_amount` over the signing delegate's notional limit (error 5) or inputs too
+    /// large to lock (error 2) reject the offer: nothing is locked or filled and the stored
+    /// state is an empty offer.
+    /// Returns updated swap state, new offer state, updated balance, offer blobs (owner and
+    /// auditor copies), and the error code.
+    #[instruction]
+    pub fn submit_swap_offer(
+        deal_state: Enc<Mxe, &SwapDealState>,
+        offer_data: Enc<Shared, SwapOfferInput>,
+        offeror_balance: Enc<Mxe, &BalanceState>,
+        max_notional: u64,
+        auditor: ArcisX25519Pubkey,
+    ) -> (
+        Enc<Mxe, SwapDealState>,
+        Enc<Mxe, SwapOfferState>,
+        Enc<Mxe, BalanceState>,
+        [Enc<Shared, SwapOfferCreatedBlob>; 2],
+        u8,
+    ) {
+        let deal = *(deal_state.to_arcis());
+        let offer = offer_data.to_arcis();
+        let balance = *(offeror_balance.to_arcis());
+
+        // Ratio comparison: products of two u64 always fit in u128
+        let acceptable = offer.give_amount as u128 * deal.give_amount as u128
+            >= deal.want_amount as u128 * offer.want_amount as u128;
+        let (remaining, remaining_underflow) = checked_sub(deal.give_amount, deal.fill_amount);
+        let amt_to_execute = if acceptable {
+            if offer.want_amount < remaining { offer.want_amount } else { remaining }
+        } else {
+            0
+        };
+
+        // Lock everything the offeror gives (not amt_to_execute - privacy)
+        let (committed_amount, committed_overflow) =
+            checked_add(balance.committed_amount, offer.give_amount);
+        let (fill_amount, fill_overflow) = checked_add(deal.fill_amount, amt_to_execute);
+        let error = if offer.give_amount > max_notional {
+            5
+        } else {
+            error_code(remaining_underflow, committed_overflow || fill_overflow)
+        };
+
+        let new_balance = if error == 0 {
+            BalanceState {
+                amount: balance.amount,
+                committed_amount,
+            }
+        } else {
+            balance
+        };
+
+        let updated_deal = if error == 0 {
+            SwapDealState {
+                give_amount: deal.give_amount,
+                want_amount: deal.want_amount,
+                fill_amount,
+            }
+        } else {
+            deal
+        };
+
+        let offer_state = SwapOfferState {
+            give_amount: if error == 0 { offer.give_amount } else { 0 },
+            want_amount: if error == 0 { offer.want_amount } else { 0 },
+            amt_to_execute: if error == 0 { amt_to_execute } else { 0 },
+        };
+
+        let offer_blob = SwapOfferCreatedBlob {
+            give_amount: offer.give_amount,
+            want_amount: offer.want_amount,
+        };
+
+        (
+            Mxe::get().from_arcis(updated_deal),
+            Mxe::get().from_arcis(offer_state),
+            offeror_balance.owner.from_arcis(new_balance),
+            [offer_data.owner.from_arcis(offer_blob), Shared::new(auditor).from_arcis(offer_blob)],
+            error.reveal(),
+        )
+    }
+
+    /// Crank (settle) a swap deal after expiry or when fully filled.
+    /// Same outputs as `crank_deal`; `creator_receives` is the filled share of `want_amount`.
+    #[instruction]
+    pub fn crank_swap_deal(
+        deal_state: Enc<Mxe, &SwapDealState>,
+        creator_balance: Enc<Mxe, &BalanceState>,
+        creator_deal_blob: Shared,
+        creator_balance_blob: Shared,
+        is_expired: bool,
+        allow_partial: bool,
+        auditor: ArcisX25519Pubkey,
+    ) -> (
+        Enc<Mxe, BalanceState>,
+        [Enc<Shared, DealSettledBlob>; 2],
+        [Enc<Shared, BalanceUpdatedBlob>; 2],
+        u8,
+        u8,
+    ) {
+        let deal = *(deal_state.to_arcis());
+        let balance = *(creator_balance.to_arcis());
+
+        let (new_balance, deal_blob, status, error) =
+            settle_swap_deal_state(&deal, &balance, is_expired, allow_partial);
+
         let balance_blob = BalanceUpdatedBlob {
             amount: new_balance.amount,
             committed_amount: new_balance.committed_amount,
         };
 
-        // status: 0 = OPEN (no change), 1 = EXECUTED, 2 = EXPIRED
-        let status: u8 = if !can_settle {
-            0 // Cannot settle yet
-        } else if deal_executes {
-            1 // EXECUTED
-        } else {
-            2 // EXPIRED
-        };
-
         (
             creator_balance.owner.from_arcis(new_balance),
-            creator_deal_blob.from_arcis(deal_blob),
-            creator_balance_blob.from_arcis(balance_blob),
+            [creator_deal_blob.from_arcis(deal_blob), Shared::new(auditor).from_arcis(deal_blob)],
+            [
+                creator_balance_blob.from_arcis(balance_blob),
+                Shared::new(auditor).from_arcis(balance_blob),
+            ],
             status.reveal(),
+            error.reveal(),
         )
     }
 
-    /// Crank (settle) a single offer after the deal has been settled.
-    /// CRITICAL: Uses deal state to calculate quote amounts correctly.
-    /// Updates offeror's balance: release commitment and refund unused QUOTE tokens.
-    /// Returns updated balance, settlement blob encrypted for the offeror, and balance blob.
+    /// Crank (settle) a single swap offer after the swap deal has been settled.
+    /// Same outputs as `crank_offer`; the executed BASE is paid at the creator's ratio and
+    /// the rest of the locked QUOTE is refunded.
     #[instruction]
-    pub fn crank_offer(
-        deal_state: Enc<Mxe, &DealState>,
-        offer_state: Enc<Mxe, &OfferState>,
+    pub fn crank_swap_offer(
+        deal_state: Enc<Mxe, &SwapDealState>,
+        offer_state: Enc<Mxe, &SwapOfferState>,
         offeror_balance: Enc<Mxe, &BalanceState>,
         offeror_offer_blob: Shared,
         offeror_balance_blob: Shared,
         deal_success: bool,
+        auditor: ArcisX25519Pubkey,
     ) -> (
         Enc<Mxe, BalanceState>,
-        Enc<Shared, OfferSettledBlob>,
-        Enc<Shared, BalanceUpdatedBlob>,
+        [Enc<Shared, OfferSettledBlob>; 2],
+        [Enc<Shared, BalanceUpdatedBlob>; 2],
+        u8,
     ) {
         let deal = *(deal_state.to_arcis());
         let offer = *(offer_state.to_arcis());
         let balance = *(offeror_balance.to_arcis());
 
-        // If deal failed, nothing executes
-        let executed_amt = if deal_success {
-            offer.amt_to_execute
+        let (new_balance, offer_blob, error) =
+            settle_swap_offer(&deal, &offer, &balance, deal_success);
+
+        let balance_blob = BalanceUpdatedBlob {
+            amount: new_balance.amount,
+            committed_amount: new_balance.committed_amount,
+        };
+
+        (
+            offeror_balance.owner.from_arcis(new_balance),
+            [
+                offeror_offer_blob.from_arcis(offer_blob),
+                Shared::new(auditor).from_arcis(offer_blob),
+            ],
+            [
+                offeror_balance_blob.from_arcis(balance_blob),
+                Shared::new(auditor).from_arcis(balance_blob),
+            ],
+            error.reveal(),
+        )
+    }
+
+    /// Publish a request-for-quote with encrypted size and limit price.
+    /// Locks the taker's max QUOTE commitment (size at limit price).
+    /// Inputs too large to lock (error 2) reject the RFQ: nothing is locked and the stored
+    /// state is an empty RFQ.
+    /// Returns MXE-encrypted RFQ state, updated balance, encrypted blobs (owner and auditor
+    /// copies), and the error code.
+    #[instruction]
+    pub fn create_rfq(
+        rfq_data: Enc<Shared, RfqInput>,
+        taker_balance: Enc<Mxe, &BalanceState>,
+        taker: Shared,
+        auditor: ArcisX25519Pubkey,
+    ) -> (
+        Enc<Mxe, RfqState>,
+        Enc<Mxe, BalanceState>,
+        [Enc<Shared, RfqCreatedBlob>; 2],
+        [Enc<Shared, BalanceUpdatedBlob>; 2],
+        u8,
+    ) {
+        let input = rfq_data.to_arcis();
+        let balance = *(taker_balance.to_arcis());
+
+        // Lock MAX quote commitment (size at limit price, X64.64 fixed-point, rounded up)
+        let (quote_to_commit, commit_overflow) = mul_price_up(input.size, input.limit_price);
+        let (committed_amount, committed_overflow) =
+            checked_add(balance.committed_amount, quote_to_commit);
+        let error = error_code(false, commit_overflow || committed_overflow);
+        let new_balance = if error == 0 {
+            BalanceState {
+                amount: balance.amount,
+                committed_amount,
+            }
         } else {
-            0
+            balance
         };
 
-        // Calculate quote amounts (X64.64 fixed-point)
-        // quote_committed = offer.amount * offer.price (what was locked at submit_offer)
-        // quote_executed = executed_amt * deal.price (actual payment at deal's price)
-        // quote_refund = quote_committed - quote_executed (includes price spread savings)
-        let quote_committed = ((offer.amount as u128 * offer.price) >> 64) as u64;
-        let quote_executed = ((executed_amt as u128 * deal.price) >> 64) as u64;
-        let quote_refund = quote_committed - quote_executed;
+        let state = RfqState {
+            size: if error == 0 { input.size } else { 0 },
+            limit_price: if error == 0 { input.limit_price } else { 0 },
+            best_price: 0,
+            best_index: 0,
+            has_quote: false,
+        };
 
-        // Update offeror's balance: release commitment and deduct paid QUOTE tokens
-        let new_balance = BalanceState {
-            amount: balance.amount - quote_executed,  // Deduct paid QUOTE tokens
-            committed_amount: balance.committed_amount - quote_committed,  // Release full commitment
+        let rfq_blob = RfqCreatedBlob {
+            size: input.size,
+            limit_price: input.limit_price,
         };
 
-        let outcome: u8 = if executed_amt == 0 {
-            2  // FAILED
-        } else if executed_amt < offer.amount {
-            1  // PARTIAL
+        let balance_blob = BalanceUpdatedBlob {
+            amount: new_balance.amount,
+            committed_amount: new_balance.committed_amount,
+        };
+
+        (
+            Mxe::get().from_arcis(state),
+            taker_balance.owner.from_arcis(new_balance),
+            [rfq_data.owner.from_arcis(rfq_blob), Shared::new(auditor).from_arcis(rfq_blob)],
+            [taker.from_arcis(balance_blob), Shared::new(auditor).from_arcis(balance_blob)],
+            error.reveal(),
+        )
+    }
+
+    /// Submit a maker quote to an open RFQ.
+    /// Locks the RFQ size of the maker's BASE tokens and tracks the best quote
+    /// (lowest price within the taker's limit, earliest wins ties) inside the RFQ state.
+    /// If the size cannot be locked (error 2) the quote is rejected: the RFQ and balance are
+    /// unchanged and the stored state is an empty quote.
+    /// Returns updated RFQ state, new quote state, updated balance, quote blobs (owner and
+    /// auditor copies), and the error code.
+    #[instruction]
+    pub fn submit_quote(
+        rfq_state: Enc<Mxe, &RfqState>,
+        quote_data: Enc<Shared, QuoteInput>,
+        maker_balance: Enc<Mxe, &BalanceState>,
+        quote_index: u32,
+        auditor: ArcisX25519Pubkey,
+    ) -> (
+        Enc<Mxe, RfqState>,
+        Enc<Mxe, QuoteState>,
+        Enc<Mxe, BalanceState>,
+        [Enc<Shared, QuoteCreatedBlob>; 2],
+        u8,
+    ) {
+        let rfq = *(rfq_state.to_arcis());
+        let quote = quote_data.to_arcis();
+        let balance = *(maker_balance.to_arcis());
+
+        let within_limit = quote.price <= rfq.limit_price;
+        let is_best = within_limit && (!rfq.has_quote || quote.price < rfq.best_price);
+
+        // Lock the full RFQ size regardless of price (privacy: commitment does not leak competitiveness)
+        let (committed_amount, overflow) = checked_add(balance.committed_amount, rfq.size);
+        let error = error_code(false, overflow);
+        let new_balance = if error == 0 {
+            BalanceState {
+                amount: balance.amount,
+                committed_amount,
+            }
         } else {
-            0  // EXECUTED (full)
+            balance
         };
 
-        let offer_blob = OfferSettledBlob {
-            outcome,
+        let updated_rfq = if error == 0 {
+            RfqState {
+                size: rfq.size,
+                limit_price: rfq.limit_price,
+                best_price: if is_best { quote.price } else { rfq.best_price },
+                best_index: if is_best { quote_index } else { rfq.best_index },
+                has_quote: rfq.has_quote || within_limit,
+            }
+        } else {
+            rfq
+        };
+
+        let quote_state = QuoteState {
+            price: if error == 0 { quote.price } else { 0 },
+            committed: if error == 0 { rfq.size } else { 0 },
+        };
+
+        let quote_blob = QuoteCreatedBlob {
+            price: quote.price,
+            size: rfq.size,
+        };
+
+        (
+            Mxe::get().from_arcis(updated_rfq),
+            Mxe::get().from_arcis(quote_state),
+            maker_balance.owner.from_arcis(new_balance),
+            [quote_data.owner.from_arcis(quote_blob), Shared::new(auditor).from_arcis(quote_blob)],
+            error.reveal(),
+        )
+    }
+
+    /// Crank (settle) an RFQ after its deadline, or early by the taker.
+    /// Updates taker's balance: release commitment and deduct QUOTE paid at the best price.
+    /// Returns updated balance, settlement and balance blobs for the taker and their auditor,
+    /// the RFQ status (RFQ_FILLED or RFQ_EXPIRED), and the error code.
+    #[instruction]
+    pub fn crank_rfq(
+        rfq_state: Enc<Mxe, &RfqState>,
+        taker_balance: Enc<Mxe, &BalanceState>,
+        taker_rfq_blob: Shared,
+        taker_balance_blob: Shared,
+        auditor: ArcisX25519Pubkey,
+    ) -> (
+        Enc<Mxe, BalanceState>,
+        [Enc<Shared, RfqSettledBlob>; 2],
+        [Enc<Shared, BalanceUpdatedBlob>; 2],
+        u8,
+        u8,
+    ) {
+        let rfq = *(rfq_state.to_arcis());
+        let balance = *(taker_balance.to_arcis());
+
+        let executed_amt = if rfq.has_quote { rfq.size } else { 0 };
+        let price = if rfq.has_quote { rfq.best_price } else { 0 };
+
+        // Same quote math as crank_offer: committed at limit price, paid at winning price (rounded up)
+        let (quote_committed, committed_overflow) = mul_price_up(rfq.size, rfq.limit_price);
+        let (quote_paid, paid_overflow) = mul_price_up(executed_amt, price);
+        let (quote_refund, refund_underflow) = checked_sub(quote_committed, quote_paid);
+
+        let (amount, amount_underflow) = checked_sub(balance.amount, quote_paid);
+        let (committed_amount, release_underflow) =
+            checked_sub(balance.committed_amount, quote_committed);
+        let error = error_code(
+            refund_underflow || amount_underflow || release_underflow,
+            committed_overflow || paid_overflow,
+        );
+
+        let new_balance = if error == 0 {
+            BalanceState {
+                amount,
+                committed_amount,
+            }
+        } else {
+            balance
+        };
+
+        let rfq_blob = RfqSettledBlob {
             executed_amt,
-            quote_paid: quote_executed,
+            price,
+            quote_paid,
             quote_refund,
         };
 
+        let status: u8 = if rfq.has_quote {
+            RFQ_FILLED
+        } else {
+            RFQ_EXPIRED
+        };
+
         let balance_blob = BalanceUpdatedBlob {
             amount: new_balance.amount,
             committed_amount: new_balance.committed_amount,
         };
 
         (
-            offeror_balance.owner.from_arcis(new_balance),
-            offeror_offer_blob.from_arcis(offer_blob),
-            offeror_balance_blob.from_arcis(balance_blob),
+            taker_balance.owner.from_arcis(new_balance),
+            [taker_rfq_blob.from_arcis(rfq_blob), Shared::new(auditor).from_arcis(rfq_blob)],
+            [
+                taker_balance_blob.from_arcis(balance_blob),
+                Shared::new(auditor).from_arcis(balance_blob),
+            ],
+            status.reveal(),
+            error.reveal(),
+        )
+    }
+
+    /// Crank (settle) a single maker quote after the RFQ has been settled.
+    /// The winning quote sells the RFQ size at its own price; all others only release their commitment.
+    /// Returns updated balance, settlement and balance blobs for the maker and their auditor,
+    /// and the error code.
+    #[instruction]
+    pub fn crank_quote(
+        rfq_state: Enc<Mxe, &RfqState>,
+        quote_state: Enc<Mxe, &QuoteState>,
+        maker_balance: Enc<Mxe, &BalanceState>,
+        maker_quote_blob: Shared,
+        maker_balance_blob: Shared,
+        quote_index: u32,
+        rfq_filled: bool,
+        auditor: ArcisX25519Pubkey,
+    ) -> (
+        Enc<Mxe, BalanceState>,
+        [Enc<Shared, QuoteSettledBlob>; 2],
+        [Enc<Shared, BalanceUpdatedBlob>; 2],
+        u8,
+    ) {
+        let rfq = *(rfq_state.to_arcis());
+        let quote = *(quote_state.to_arcis());
+        let balance = *(maker_balance.to_arcis());
+
+        let is_winner = rfq_filled && rfq.best_index == quote_index;
+        let executed_amt = if is_winner { quote.committed } else { 0 };
+        // Maker receives, so round down
+        let (quote_receives, receives_overflow) = mul_price_down(executed_amt, quote.price);
+
+        // Update maker's balance: release commitment and deduct sold BASE tokens
+        let (amount, amount_underflow) = checked_sub(balance.amount, executed_amt);
+        let (committed_amount, release_underflow) =
+            checked_sub(balance.committed_amount, quote.committed);
+        let error = error_code(amount_underflow || release_underflow, receives_overflow);
+
+        let new_balance = if error == 0 {
+            BalanceState {
+                amount,
+                committed_amount,
+            }
+        } else {
+            balance
+        };
+
+        let outcome: u8 = if is_winner {
+            OUTCOME_EXECUTED
+        } else {
+            OUTCOME_FAILED
+        };
+
+        let quote_blob = QuoteSettledBlob {
+            outcome,
+            executed_amt,
+            quote_receives,
+        };
+
+        let balance_blob = BalanceUpdatedBlob {
+            amount: new_balance.amount,
+            committed_amount: new_balance.committed_amount,
+        };
+
+        (
+            maker_balance.owner.from_arcis(new_balance),
+            [maker_quote_blob.from_arcis(quote_blob), Shared::new(auditor).from_arcis(quote_blob)],
+            [
+                maker_balance_blob.from_arcis(balance_blob),
+                Shared::new(auditor).from_arcis(balance_blob),
+            ],
+            error.reveal(),
+        )
+    }
+
+    /// Top up a balance account.
+    /// Takes existing MXE-encrypted balance state (by reference), owner marker for event blob,
+    /// plaintext amount to add, and is_new flag to handle init_if_needed pattern.
+    /// Returns updated MXE-encrypted state, Shared-encrypted blobs for the owner and their
+    /// auditor, and the error code.
+    #[instruction]
+    pub fn top_up(
+        balance_state: Enc<Mxe, &BalanceState>,
+        owner: Shared,
+        amount: u64,
+        is_new: bool,
+        auditor: ArcisX25519Pubkey,
+    ) -> (Enc<Mxe, BalanceState>, [Enc<Shared, BalanceUpdatedBlob>; 2], u8) {
+        let state = if is_new {
+            BalanceState {
+                amount: 0,
+                committed_amount: 0,
+            }
+        } else {
+            *(balance_state.to_arcis())
+        };
+
+        let (new_amount, overflow) = checked_add(state.amount, amount);
+        let error = error_code(false, overflow);
+        let new_state = BalanceState {
+            amount: new_amount,
+            committed_amount: state.committed_amount,
+        };
+
+        let blob = BalanceUpdatedBlob {
+            amount: new_state.amount,
+            committed_amount: new_state.committed_amount,
+        };
+
+        (
+            balance_state.owner.from_arcis(new_state),
+            [owner.from_arcis(blob), Shared::new(auditor).from_arcis(blob)],
+            error.reveal(),
+        )
+    }
+    /// Move funds between two balances of the same mint.
+    /// Debits the sender's available balance (amount - committed) and credits the recipient.
+    /// If the sender can't cover the amount (error 1) or the recipient would overflow
+    /// (error 2), both balances are returned unchanged. Only the error code is revealed.
+    /// Returns both updated balances, balance blobs for each party and their auditor, and the
+    /// error code.
+    #[instruction]
+    pub fn transfer(
+        transfer_data: Enc<Shared, TransferInput>,
+        sender_balance: Enc<Mxe, &BalanceState>,
+        recipient_balance: Enc<Mxe, &BalanceState>,
+        recipient_balance_blob: Shared,
+        sender_auditor: ArcisX25519Pubkey,
+        recipient_auditor: ArcisX25519Pubkey,
+    ) -> (
+        Enc<Mxe, BalanceState>,
+        Enc<Mxe, BalanceState>,
+        [Enc<Shared, BalanceUpdatedBlob>; 2],
+        [Enc<Shared, BalanceUpdatedBlob>; 2],
+        u8,
+    ) {
+        let input = transfer_data.to_arcis();
+        let sender = *(sender_balance.to_arcis());
+        let recipient = *(recipient_balance.to_arcis());
+
+        let (available, committed_underflow) = checked_sub(sender.amount, sender.committed_amount);
+        let (_, insufficient) = checked_sub(available, input.amount);
+        let (sender_amount, _) = checked_sub(sender.amount, input.amount);
+        let (recipient_amount, overflow) = checked_add(recipient.amount, input.amount);
+        let error = error_code(committed_underflow || insufficient, overflow);
+
+        let new_sender = if error == 0 {
+            BalanceState {
+                amount: sender_amount,
+                committed_amount: sender.committed_amount,
+            }
+        } else {
+            sender
+        };
+        let new_recipient = if error == 0 {
+            BalanceState {
+                amount: recipient_amount,
+                committed_amount: recipient.committed_amount,
+            }
+        } else {
+            recipient
+        };
+
+        let sender_blob = BalanceUpdatedBlob {
+            amount: new_sender.amount,
+            committed_amount: new_sender.committed_amount,
+        };
+        let recipient_blob = BalanceUpdatedBlob {
+            amount: new_recipient.amount,
+            committed_amount: new_recipient.committed_amount,
+        };
+
+        (
+            sender_balance.owner.from_arcis(new_sender),
+            recipient_balance.owner.from_arcis(new_recipient),
+            [
+                transfer_data.owner.from_arcis(sender_blob),
+                Shared::new(sender_auditor).from_arcis(sender_blob),
+            ],
+            [
+                recipient_balance_blob.from_arcis(recipient_blob),
+                Shared::new(recipient_auditor).from_arcis(recipient_blob),
+            ],
+            error.reveal(),
         )
     }
+    /// Attest to a third party that the owner's available balance covers a threshold.
+    /// The threshold comes from the owner; only it and a yes/no are re-encrypted to the
+    /// third party's key, never the balance itself. Unlike `get_counter`, the key is a
+    /// `Shared` argument rather than `pubkey_hi`/`pubkey_lo` halves: arbitrary bytes
+    /// rebuilt with `ArcisX25519Pubkey::from_uint8` fail the generated circuit tests.
+    #[instruction]
+    pub fn attest_balance(
+        attest_data: Enc<Shared, AttestInput>,
+        balance_state: Enc<Mxe, &BalanceState>,
+        counterparty: Shared,
+    ) -> Enc<Shared, AttestationBlob> {
+        let input = attest_data.to_arcis();
+        let balance = *(balance_state.to_arcis());
+
+        let (available, committed_underflow) =
+            checked_sub(balance.amount, balance.committed_amount);
+        let covered = !committed_underflow && available >= input.threshold;
+
+        counterparty.from_arcis(AttestationBlob {
+            threshold: input.threshold,
+            covered,
+        })
+    }
+}
+
+/// Example circuits for the demo build (`--features demo`); the production
+/// program ships without them.
+#[cfg(feature = "demo")]
+#[encrypted]
+mod demo {
+    use arcis_imports::*;
+
+    pub struct InputValues {
+        v1: u8,
+        v2: u8,
+    }
+
+    #[derive(Copy, Clone)]
+    pub struct CounterState {
+        counter: u64,
+    }
+
+    #[instruction]
+    pub fn add_together(input_ctxt: Enc<Shared, InputValues>) -> Enc<Shared, u16> {
+        let input = input_ctxt.to_arcis();
+        let sum = input.v1 as u16 + input.v2 as u16;
+        input_ctxt.owner.from_arcis(sum)
+    }
 
     /// Initialize a new counter with value 0, encrypted for the MXE only.
     /// The state is stored on-chain and only the MXE can decrypt it.
@@ -451,40 +2224,4 @@ mod circuits {
 
         (recipient.from_arcis(counter), shared_ctxt.from_arcis(counter))
     }
-
-    /// Top up a balance account.
-    /// Takes existing MXE-encrypted balance state (by reference), owner marker for event blob,
-    /// plaintext amount to add, and is_new flag to handle init_if_needed pattern.
-    /// Returns updated MXE-encrypted state and Shared-encrypted blob for owner.
-    #[instruction]
-    pub fn top_up(
-        balance_state: Enc<Mxe, &BalanceState>,
-        owner: Shared,
-        amount: u64,
-        is_new: bool,
-    ) -> (Enc<Mxe, BalanceState>, Enc<Shared, BalanceUpdatedBlob>) {
-        let state = if is_new {
-            BalanceState {
-                amount: 0,
-                committed_amount: 0,
-            }
-        } else {
-            *(balance_state.to_arcis())
-        };
-
-        let new_state = BalanceState {
-            amount: state.amount + amount,
-            committed_amount: state.committed_amount,
-        };
-
-        let blob = BalanceUpdatedBlob {
-            amount: new_state.amount,
-            committed_amount: new_state.committed_amount,
-        };
-
-        (
-            balance_state.owner.from_arcis(new_state),
-            owner.from_arcis(blob),
-        )
-    }
 }
@@ -0,0 +1,117 @@
+//! Conversions between decimal price strings, on-chain prices and raw token units.
+//!
+//! Deal and offer prices are entered in human units (whole quote per whole base) as
+//! X64.64. The circuits scale them by the mints' decimals; [`raw_price`] mirrors that.
+
+use std::fmt;
+
+use crate::FRAC_BITS;
+
+/// Most fractional digits accepted by [`parse_price`] (10^19 < 2^64).
+pub const MAX_PRICE_DECIMALS: usize = 19;
+
+/// Largest supported difference between base and quote mint decimals.
+pub const MAX_DECIMALS_GAP: u8 = 18;
+
+/// Error returned by [`parse_price`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParsePriceError {
+    /// The string is empty or contains something other than digits and one `.`
+    Invalid,
+    /// More than [`MAX_PRICE_DECIMALS`] fractional digits
+    TooManyDecimals,
+    /// The integer part does not fit in 64 bits
+    Overflow,
+}
+
+impl fmt::Display for ParsePriceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid => write!(f, "invalid decimal price"),
+            Self::TooManyDecimals => {
+                write!(f, "price has more than {MAX_PRICE_DECIMALS} decimals")
+            }
+            Self::Overflow => write!(f, "price is too large"),
+        }
+    }
+}
+
+impl std::error::Error for ParsePriceError {}
+
+/// Parses a decimal price such as `"2.5"` into its X64.64 encoding.
+///
+/// Fractions that are not exactly representable round down.
+pub fn parse_price(s: &str) -> Result<u128, ParsePriceError> {
+    let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+    let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if (whole.is_empty() && frac.is_empty()) || !is_digits(whole) || !is_digits(frac) {
+        return Err(ParsePriceError::Invalid);
+    }
+    if frac.len() > MAX_PRICE_DECIMALS {
+        return Err(ParsePriceError::TooManyDecimals);
+    }
+
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| ParsePriceError::Overflow)?
+    };
+    let frac = if frac.is_empty() {
+        0
+    } else {
+        // numerator < 10^19 < 2^64, so numerator << 64 fits in u128
+        let numerator: u128 = frac.parse().map_err(|_| ParsePriceError::Invalid)?;
+        (numerator << FRAC_BITS) / 10u128.pow(frac.len() as u32)
+    };
+    Ok(((whole as u128) << FRAC_BITS) | frac)
+}
+
+/// Formats an X64.64 price with at most `decimals` fractional digits (rounded to
+/// nearest), dropping trailing zeros. Inverse of [`parse_price`] for prices with up
+/// to `decimals` fractional digits.
+pub fn format_price(price: u128, decimals: usize) -> String {
+    let decimals = decimals.min(MAX_PRICE_DECIMALS);
+    let unit = 10u128.pow(decimals as u32);
+    let mut whole = price >> FRAC_BITS;
+    let frac = price as u64 as u128;
+    // frac < 2^64 and unit <= 10^19 < 2^64, so the product fits in u128
+    let mut scaled = (frac * unit + (1 << (FRAC_BITS - 1))) >> FRAC_BITS;
+    if scaled == unit {
+        whole += 1;
+        scaled = 0;
+    }
+    let digits = format!("{scaled:0decimals$}");
+    let digits = digits.trim_end_matches('0');
+    if digits.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{digits}")
+    }
+}
+
+/// Powers of ten `(scale_up, scale_down)` the program passes to the circuits for a
+/// base/quote decimals pair. One of the two is always 1.
+///
+/// Returns `None` if the decimals differ by more than [`MAX_DECIMALS_GAP`].
+pub fn price_scale(base_decimals: u8, quote_decimals: u8) -> Option<(u128, u128)> {
+    if base_decimals.abs_diff(quote_decimals) > MAX_DECIMALS_GAP {
+        return None;
+    }
+    Some(if quote_decimals >= base_decimals {
+        (10u128.pow((quote_decimals - base_decimals) as u32), 1)
+    } else {
+        (1, 10u128.pow((base_decimals - quote_decimals) as u32))
+    })
+}
+
+/// Converts a human-unit price into raw token units exactly as the circuits do
+/// (raw quote per raw base, rounded down).
+///
+/// Returns `None` for unsupported decimals or prices the circuits would reject.
+pub fn raw_price(price: u128, base_decimals: u8, quote_decimals: u8) -> Option<u128> {
+    let (scale_up, scale_down) = price_scale(base_decimals, quote_decimals)?;
+    if (price >> FRAC_BITS) * scale_up >= 1 << 63 {
+        return None;
+    }
+    Some(price * scale_up / scale_down)
+}
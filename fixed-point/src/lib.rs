@@ -0,0 +1,87 @@
+//! X64.64 fixed-point price math.
+//!
+//! Prices are quote-per-base ratios stored as `u128` with 64 fractional bits.
+//! This crate mirrors the fixed-point helpers inside `encrypted-ixs` (circuits cannot
+//! call into other crates), so clients compute exactly the amounts the MPC will.
+//!
+//! Rounding policy: amounts a party pays or locks round UP, amounts a party receives
+//! round DOWN. Summed over any set of fills, the protocol therefore never pays out more
+//! than it collects.
+
+pub mod decimal;
+
+pub use decimal::{format_price, parse_price, price_scale, raw_price, ParsePriceError};
+
+/// Number of fractional bits in a price.
+pub const FRAC_BITS: u32 = 64;
+
+/// The price 1.0.
+pub const ONE: u128 = 1 << FRAC_BITS;
+
+/// Rounding direction for a fixed-point multiplication.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round toward zero; used for amounts a party receives.
+    Down,
+    /// Round away from zero; used for amounts a party pays or locks.
+    Up,
+}
+
+/// Computes `amount * price` in quote units with the given rounding.
+///
+/// Returns `None` if the result does not fit in a `u64`.
+pub fn mul_price(amount: u64, price: u128, rounding: Rounding) -> Option<u64> {
+    let high = (amount as u128).checked_mul(price >> FRAC_BITS)?;
+    let low_full = amount as u128 * (price as u64) as u128;
+    let floor = high.checked_add(low_full >> FRAC_BITS)?;
+    let result = match rounding {
+        Rounding::Down => floor,
+        Rounding::Up if low_full as u64 > 0 => floor.checked_add(1)?,
+        Rounding::Up => floor,
+    };
+    u64::try_from(result).ok()
+}
+
+/// Quote amount a party receives for `amount` base at `price` (rounded down).
+pub fn mul_price_down(amount: u64, price: u128) -> Option<u64> {
+    mul_price(amount, price, Rounding::Down)
+}
+
+/// Quote amount a party pays or locks for `amount` base at `price` (rounded up).
+pub fn mul_price_up(amount: u64, price: u128) -> Option<u64> {
+    mul_price(amount, price, Rounding::Up)
+}
+
+/// Computes `amount * numerator / denominator` for swap deals, which trade at the exact
+/// ratio of two quantities instead of a fixed-point price. Mirrors `mul_ratio_parts` in
+/// the circuits, where a zero denominator yields 0.
+///
+/// Returns `None` if the result does not fit in a `u64`.
+pub fn mul_ratio(amount: u64, numerator: u64, denominator: u64, rounding: Rounding) -> Option<u64> {
+    if denominator == 0 {
+        return Some(0);
+    }
+    // The product of two u64 always fits in a u128
+    let product = amount as u128 * numerator as u128;
+    let floor = product / denominator as u128;
+    let result = match rounding {
+        Rounding::Down => floor,
+        Rounding::Up if !product.is_multiple_of(denominator as u128) => floor + 1,
+        Rounding::Up => floor,
+    };
+    u64::try_from(result).ok()
+}
+
+/// Builds an X64.64 price from an integer numerator and denominator.
+///
+/// Returns `None` if `denominator` is zero or the price does not fit in a `u128`.
+pub fn price_from_ratio(numerator: u64, denominator: u64) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+    let whole = (numerator / denominator) as u128;
+    let rem = (numerator % denominator) as u128;
+    // rem < denominator <= u64::MAX, so rem << 64 fits in u128
+    let frac = (rem << FRAC_BITS) / denominator as u128;
+    whole.checked_shl(FRAC_BITS)?.checked_add(frac)
+}
@@ -0,0 +1,119 @@
+use fixed_point::{mul_price_down, mul_price_up, mul_ratio, price_from_ratio, Rounding, ONE};
+use proptest::prelude::*;
+
+/// Prices between 0 and 2^24 (plenty for any real quote-per-base ratio)
+fn price() -> impl Strategy<Value = u128> {
+    0..(1u128 << 88)
+}
+
+/// Base amounts small enough that sums of up to 16 fills at any price() fit in u64
+fn amount() -> impl Strategy<Value = u64> {
+    0..(1u64 << 30)
+}
+
+proptest! {
+    #[test]
+    fn up_and_down_bracket_the_exact_product(amount in amount(), price in price()) {
+        let down = mul_price_down(amount, price).unwrap();
+        let up = mul_price_up(amount, price).unwrap();
+        let exact_times_one = amount as u128 * price;
+
+        prop_assert!((down as u128) * ONE <= exact_times_one);
+        prop_assert!((up as u128) * ONE >= exact_times_one);
+        prop_assert!(up - down <= 1);
+    }
+
+    #[test]
+    fn fills_never_pay_out_more_than_collected(
+        fills in prop::collection::vec(amount(), 1..16),
+        price in price(),
+    ) {
+        // Each offeror pays for their own fill; the creator receives for the total
+        let collected: u128 = fills
+            .iter()
+            .map(|&fill| mul_price_up(fill, price).unwrap() as u128)
+            .sum();
+        let total: u64 = fills.iter().sum();
+        let paid_out = mul_price_down(total, price).unwrap() as u128;
+
+        prop_assert!(paid_out <= collected);
+    }
+
+    #[test]
+    fn settlement_refund_is_never_negative(
+        offer_amount in amount(),
+        executed_ratio in 0u64..=100,
+        deal_price in price(),
+        spread in price(),
+    ) {
+        // The offeror locks at their own price, and pays for at most that amount at a
+        // deal price no higher than theirs
+        let offer_price = deal_price.saturating_add(spread);
+        let executed = offer_amount / 100 * executed_ratio;
+
+        let committed = mul_price_up(offer_amount, offer_price).unwrap();
+        let paid = mul_price_up(executed, deal_price).unwrap();
+
+        prop_assert!(paid <= committed);
+    }
+
+    #[test]
+    fn swap_fills_never_pay_out_more_than_collected(
+        fills in prop::collection::vec(amount(), 1..16),
+        give in 1u64..(1u64 << 40),
+        want in 0u64..(1u64 << 40),
+    ) {
+        // Offerors pay for their fills at the creator's ratio; the creator receives for the total
+        let collected: u128 = fills
+            .iter()
+            .map(|&fill| mul_ratio(fill, want, give, Rounding::Up).unwrap() as u128)
+            .sum();
+        let total: u64 = fills.iter().sum();
+        let paid_out = mul_ratio(total, want, give, Rounding::Down).unwrap() as u128;
+
+        prop_assert!(paid_out <= collected);
+    }
+
+    #[test]
+    fn swap_refund_is_never_negative(
+        offer_want in amount(),
+        offer_give in amount(),
+        deal_give in 1u64..(1u64 << 30),
+        deal_want in amount(),
+        executed_ratio in 0u64..=100,
+    ) {
+        // Accepted offers give at least the creator's ratio and execute at most what they want
+        prop_assume!(offer_give as u128 * deal_give as u128 >= deal_want as u128 * offer_want as u128);
+        let executed = offer_want / 100 * executed_ratio;
+
+        let paid = mul_ratio(executed, deal_want, deal_give, Rounding::Up).unwrap();
+
+        prop_assert!(paid <= offer_give);
+    }
+
+    #[test]
+    fn overflow_is_reported(amount in (1u64 << 33)..u64::MAX, price in (ONE << 32)..u128::MAX) {
+        prop_assert_eq!(mul_price_down(amount, price), None);
+        prop_assert_eq!(mul_price_up(amount, price), None);
+    }
+
+    #[test]
+    fn ratio_prices_round_trip(base in 1u64..1_000_000, quote in 0u64..1_000_000_000) {
+        let price = price_from_ratio(quote, base).unwrap();
+        prop_assert_eq!(mul_price_up(base, price).unwrap(), quote);
+    }
+}
+
+#[test]
+fn integer_prices_are_exact() {
+    assert_eq!(mul_price_down(1000, 2 * ONE), Some(2000));
+    assert_eq!(mul_price_up(1000, 2 * ONE), Some(2000));
+    assert_eq!(mul_price_down(500, 5 * ONE / 2), Some(1250));
+}
+
+#[test]
+fn fractional_products_round_apart() {
+    let third = price_from_ratio(1, 3).unwrap();
+    assert_eq!(mul_price_down(1, third), Some(0));
+    assert_eq!(mul_price_up(1, third), Some(1));
+}
@@ -0,0 +1,60 @@
+use fixed_point::{format_price, mul_price_up, parse_price, raw_price, ParsePriceError, ONE};
+use proptest::prelude::*;
+
+#[test]
+fn parses_decimal_strings() {
+    assert_eq!(parse_price("2"), Ok(2 * ONE));
+    assert_eq!(parse_price("2.5"), Ok(5 * ONE / 2));
+    assert_eq!(parse_price(".25"), Ok(ONE / 4));
+    assert_eq!(parse_price("0.0001"), Ok(ONE / 10_000));
+}
+
+#[test]
+fn rejects_malformed_prices() {
+    assert_eq!(parse_price(""), Err(ParsePriceError::Invalid));
+    assert_eq!(parse_price("."), Err(ParsePriceError::Invalid));
+    assert_eq!(parse_price("1.2.3"), Err(ParsePriceError::Invalid));
+    assert_eq!(parse_price("-1"), Err(ParsePriceError::Invalid));
+    assert_eq!(
+        parse_price("1.00000000000000000001"),
+        Err(ParsePriceError::TooManyDecimals)
+    );
+    assert_eq!(
+        parse_price("18446744073709551616"),
+        Err(ParsePriceError::Overflow)
+    );
+}
+
+#[test]
+fn formats_prices() {
+    assert_eq!(format_price(5 * ONE / 2, 6), "2.5");
+    assert_eq!(format_price(3 * ONE, 6), "3");
+    assert_eq!(format_price(ONE / 3, 4), "0.3333");
+    assert_eq!(format_price(2 * ONE / 3, 4), "0.6667");
+    assert_eq!(format_price(ONE - 1, 4), "1");
+}
+
+#[test]
+fn scales_prices_by_mint_decimals() {
+    let price = parse_price("2.5").unwrap();
+
+    // 9-decimal base, 6-decimal quote: 1 BASE (1e9 raw) costs 2.5 QUOTE (2.5e6 raw)
+    let raw = raw_price(price, 9, 6).unwrap();
+    assert_eq!(mul_price_up(1_000_000_000, raw), Some(2_500_000));
+
+    // 6-decimal base, 9-decimal quote: 1 BASE (1e6 raw) costs 2.5 QUOTE (2.5e9 raw)
+    let raw = raw_price(price, 6, 9).unwrap();
+    assert_eq!(mul_price_up(1_000_000, raw), Some(2_500_000_000));
+
+    // Equal decimals leave the price untouched
+    assert_eq!(raw_price(price, 6, 6), Some(price));
+    assert_eq!(raw_price(price, 0, 19), None);
+}
+
+proptest! {
+    #[test]
+    fn format_then_parse_round_trips(whole in 0u64..1_000_000, millis in 0u64..1000) {
+        let price = parse_price(&format!("{whole}.{millis:03}")).unwrap();
+        prop_assert_eq!(parse_price(&format_price(price, 3)).unwrap(), price);
+    }
+}
@@ -139,7 +139,10 @@ export function useCreateDeal(): UseCreateDealReturn {
             expiresAt,
             input.allowPartial,
             Array.from(ciphertext[0]),
-            Array.from(ciphertext[1])
+            Array.from(ciphertext[1]),
+            0, // counterparty_kind: unrestricted
+            Array.from(new Uint8Array(32)), // counterparty
+            new BN(0) // crank_bounty: none
           )
           .accountsPartial({
             createKey: createKey.publicKey,
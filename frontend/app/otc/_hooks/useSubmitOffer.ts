@@ -167,7 +167,9 @@ export function useSubmitOffer(): UseSubmitOfferReturn {
             Array.from(keys.encryption.publicKey), // encryption_pubkey
             nonceToU128(nonce), // nonce as u128
             Array.from(ciphertext[0]), // encrypted_price
-            Array.from(ciphertext[1]) // encrypted_amount
+            Array.from(ciphertext[1]), // encrypted_amount
+            [], // counterparty_proof
+            new BN(0) // crank_bounty: none
           )
           .accountsPartial({
             createKey: createKey.publicKey,
@@ -0,0 +1,73 @@
+//! Parsing of OTC program accounts and the MXE key.
+//!
+//! Ciphertexts stored on accounts are sealed to the MXE and cannot be decrypted by
+//! clients; owners read their state from event blobs or `announce_*` instructions.
+//! Accounts created before layout versioning fail to parse until migrated.
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use arcium_client::idl::arcium::accounts::MXEAccount;
+use arcium_client::idl::arcium::types::SetUnset;
+use otc::{
+    BalanceAccount, BasketLegs, ControllerLink, DealAccount, Delegate, Multisig, OfferAccount,
+    PairConfig, PriceFeed, Proposal, QuoteAccount, RelayNonce, RfqAccount,
+};
+
+use crate::error::{Error, Result};
+
+/// Deserializes an account of a known type, checking its discriminator.
+pub fn parse<T: AccountDeserialize>(mut data: &[u8]) -> Result<T> {
+    Ok(T::try_deserialize(&mut data)?)
+}
+
+macro_rules! otc_accounts {
+    ($($name:ident),* $(,)?) => {
+        /// Any account owned by the OTC program.
+        #[derive(Clone)]
+        pub enum OtcAccount {
+            $($name(Box<$name>),)*
+        }
+
+        impl OtcAccount {
+            /// Deserializes an account of unknown type by its discriminator.
+            pub fn parse(data: &[u8]) -> Result<Self> {
+                $(
+                    if data.starts_with($name::DISCRIMINATOR) {
+                        return parse::<$name>(data).map(|a| Self::$name(Box::new(a)));
+                    }
+                )*
+                Err(Error::UnknownDiscriminator)
+            }
+
+            /// Account name as it appears in the IDL.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$name(_) => stringify!($name),)*
+                }
+            }
+        }
+    };
+}
+
+otc_accounts!(
+    DealAccount,
+    OfferAccount,
+    BalanceAccount,
+    BasketLegs,
+    RfqAccount,
+    QuoteAccount,
+    Delegate,
+    ControllerLink,
+    RelayNonce,
+    Multisig,
+    Proposal,
+    PairConfig,
+    PriceFeed,
+);
+
+/// Reads the MXE x25519 public key from the account at [`crate::arcium::mxe_account`].
+pub fn mxe_x25519_pubkey(data: &[u8]) -> Result<[u8; 32]> {
+    match parse::<MXEAccount>(data)?.utility_pubkeys {
+        SetUnset::Set(keys) => Ok(keys.x25519_pubkey),
+        SetUnset::Unset(..) => Err(Error::MxeKeyNotSet),
+    }
+}
@@ -0,0 +1,66 @@
+//! Arcium accounts shared by every instruction that queues a computation.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::system_program;
+use arcium_client::pda;
+
+/// The Arcium cluster the OTC program's MXE runs on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArciumEnv {
+    /// Offset of the cluster set on the MXE account
+    pub cluster_offset: u32,
+}
+
+/// Addresses of the Arcium accounts a `queue_computation_accounts` struct expects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueAccounts {
+    pub sign_pda_account: Pubkey,
+    pub mxe_account: Pubkey,
+    pub mempool_account: Pubkey,
+    pub executing_pool: Pubkey,
+    pub computation_account: Pubkey,
+    pub comp_def_account: Pubkey,
+    pub cluster_account: Pubkey,
+    pub pool_account: Pubkey,
+    pub clock_account: Pubkey,
+    pub system_program: Pubkey,
+    pub arcium_program: Pubkey,
+}
+
+impl ArciumEnv {
+    pub fn new(cluster_offset: u32) -> Self {
+        Self { cluster_offset }
+    }
+
+    /// Accounts for queueing `circuit` as computation `computation_offset`.
+    pub fn queue_accounts(&self, circuit: &str, computation_offset: u64) -> QueueAccounts {
+        QueueAccounts {
+            sign_pda_account: sign_pda(),
+            mxe_account: mxe_account(),
+            mempool_account: pda::mempool_acc(self.cluster_offset),
+            executing_pool: pda::execpool_acc(self.cluster_offset),
+            computation_account: pda::computation_acc(self.cluster_offset, computation_offset),
+            comp_def_account: comp_def_account(circuit),
+            cluster_account: pda::cluster_acc(self.cluster_offset),
+            pool_account: pda::fee_pool_acc(),
+            clock_account: pda::clock_acc(),
+            system_program: system_program::ID,
+            arcium_program: arcium_client::ARCIUM_PROGRAM_ID,
+        }
+    }
+}
+
+/// The OTC program's MXE account, which holds its x25519 public key.
+pub fn mxe_account() -> Pubkey {
+    pda::mxe_acc(&otc::ID)
+}
+
+/// The computation definition account of `circuit`.
+pub fn comp_def_account(circuit: &str) -> Pubkey {
+    pda::computation_definition_acc(&otc::ID, pda::comp_def_offset(circuit))
+}
+
+/// The OTC program's signer PDA, which pays for queued computations.
+pub fn sign_pda() -> Pubkey {
+    Pubkey::find_program_address(&[arcium_anchor::SIGN_PDA_SEED], &otc::ID).0
+}
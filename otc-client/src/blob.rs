@@ -0,0 +1,311 @@
+//! Cleartext mirrors of the circuit inputs and of the blobs sealed into events.
+//!
+//! Field order matches the structs in `encrypted-ixs`; each scalar is one ciphertext, and
+//! arrays contribute one ciphertext per element.
+
+use otc::OfferOutcome;
+
+/// A struct that travels encrypted as a sequence of field elements.
+pub trait Plaintext: Sized {
+    /// Number of field elements (and ciphertexts)
+    const LEN: usize;
+
+    fn to_fields(&self) -> Vec<u128>;
+
+    /// Returns `None` if `fields` has the wrong length or a value overflows its type.
+    fn from_fields(fields: &[u128]) -> Option<Self>;
+}
+
+/// A single field of a [`Plaintext`] struct.
+pub trait Scalar: Sized {
+    const LEN: usize;
+
+    fn push(&self, out: &mut Vec<u128>);
+
+    fn read(fields: &mut &[u128]) -> Option<Self>;
+}
+
+fn next(fields: &mut &[u128]) -> Option<u128> {
+    let (&first, rest) = fields.split_first()?;
+    *fields = rest;
+    Some(first)
+}
+
+macro_rules! impl_scalar_uint {
+    ($($ty:ty),*) => {$(
+        impl Scalar for $ty {
+            const LEN: usize = 1;
+
+            fn push(&self, out: &mut Vec<u128>) {
+                out.push(*self as u128);
+            }
+
+            fn read(fields: &mut &[u128]) -> Option<Self> {
+                <$ty>::try_from(next(fields)?).ok()
+            }
+        }
+    )*};
+}
+
+impl_scalar_uint!(u8, u32, u64, u128);
+
+impl Scalar for bool {
+    const LEN: usize = 1;
+
+    fn push(&self, out: &mut Vec<u128>) {
+        out.push(*self as u128);
+    }
+
+    fn read(fields: &mut &[u128]) -> Option<Self> {
+        match next(fields)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Scalar for OfferOutcome {
+    const LEN: usize = 1;
+
+    fn push(&self, out: &mut Vec<u128>) {
+        out.push(*self as u128);
+    }
+
+    fn read(fields: &mut &[u128]) -> Option<Self> {
+        OfferOutcome::from_circuit(u8::try_from(next(fields)?).ok()?).ok()
+    }
+}
+
+impl<T: Scalar + Copy + Default, const N: usize> Scalar for [T; N] {
+    const LEN: usize = T::LEN * N;
+
+    fn push(&self, out: &mut Vec<u128>) {
+        self.iter().for_each(|v| v.push(out));
+    }
+
+    fn read(fields: &mut &[u128]) -> Option<Self> {
+        let mut values = [T::default(); N];
+        for value in values.iter_mut() {
+            *value = T::read(fields)?;
+        }
+        Some(values)
+    }
+}
+
+macro_rules! plaintext {
+    ($(
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            $($(#[$field_meta:meta])* pub $field:ident: $ty:ty,)*
+        }
+    )*) => {$(
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub struct $name {
+            $($(#[$field_meta])* pub $field: $ty,)*
+        }
+
+        impl Plaintext for $name {
+            const LEN: usize = 0 $(+ <$ty as Scalar>::LEN)*;
+
+            fn to_fields(&self) -> Vec<u128> {
+                let mut out = Vec::with_capacity(Self::LEN);
+                $(self.$field.push(&mut out);)*
+                out
+            }
+
+            fn from_fields(mut fields: &[u128]) -> Option<Self> {
+                let value = Self {
+                    $($field: <$ty as Scalar>::read(&mut fields)?,)*
+                };
+                fields.is_empty().then_some(value)
+            }
+        }
+    )*};
+}
+
+plaintext! {
+    // ============================================
+    // INPUTS
+    // ============================================
+
+    /// `create_deal`: `encrypted_amount`, `encrypted_price`
+    pub struct DealInput {
+        /// Base tokens to sell
+        pub amount: u64,
+        /// X64.64 quote-per-base price in human units
+        pub price: u128,
+    }
+
+    /// `submit_offer`: `encrypted_price`, `encrypted_amount`
+    pub struct OfferInput {
+        /// X64.64 price the offeror is willing to pay
+        pub price: u128,
+        /// Base tokens wanted
+        pub amount: u64,
+    }
+
+    /// `create_basket_deal`: `encrypted_units`, `encrypted_price`, `encrypted_leg_amounts`
+    pub struct BasketDealInput {
+        /// Number of baskets to sell
+        pub units: u64,
+        /// X64.64 quote price of one basket
+        pub price: u128,
+        /// Base tokens of each leg per basket; unused legs are 0
+        pub leg_amounts: [u64; 4],
+    }
+
+    /// `create_swap_deal`: `encrypted_give_amount`, `encrypted_want_amount`
+    pub struct SwapDealInput {
+        /// Base tokens the creator gives
+        pub give_amount: u64,
+        /// Quote tokens the creator wants for all of them
+        pub want_amount: u64,
+    }
+
+    /// `submit_swap_offer`: `encrypted_give_amount`, `encrypted_want_amount`
+    pub struct SwapOfferInput {
+        /// Quote tokens the offeror gives at most
+        pub give_amount: u64,
+        /// Base tokens the offeror wants
+        pub want_amount: u64,
+    }
+
+    /// `create_rfq`: `encrypted_size`, `encrypted_limit_price`
+    pub struct RfqInput {
+        /// Base tokens to buy
+        pub size: u64,
+        /// Highest X64.64 price the taker accepts, in raw units (no decimals applied)
+        pub limit_price: u128,
+    }
+
+    /// `submit_quote`: `encrypted_price`
+    pub struct QuoteInput {
+        /// X64.64 price the maker quotes, in raw units (no decimals applied)
+        pub price: u128,
+    }
+
+    /// `transfer`: `encrypted_amount`
+    pub struct TransferInput {
+        pub amount: u64,
+    }
+
+    /// `attest_balance`: `encrypted_threshold`
+    pub struct AttestInput {
+        /// Amount the balance must cover
+        pub threshold: u64,
+    }
+
+    // ============================================
+    // EVENT BLOBS
+    // ============================================
+
+    /// `DealCreated` for price deals
+    pub struct DealCreatedBlob {
+        pub amount: u64,
+        pub price: u128,
+    }
+
+    /// `DealCreated` for swap deals
+    pub struct SwapDealCreatedBlob {
+        pub give_amount: u64,
+        pub want_amount: u64,
+    }
+
+    /// `DealAnnounced`
+    pub struct DealAnnouncedBlob {
+        pub amount: u64,
+        pub price: u128,
+        pub fill_amount: u64,
+    }
+
+    /// `DealSettled`
+    pub struct DealSettledBlob {
+        pub total_filled: u64,
+        pub creator_receives: u64,
+        pub creator_refund: u64,
+    }
+
+    /// `OfferCreated` for price deals
+    pub struct OfferCreatedBlob {
+        pub price: u128,
+        pub amount: u64,
+    }
+
+    /// `OfferCreated` for swap deals
+    pub struct SwapOfferCreatedBlob {
+        pub give_amount: u64,
+        pub want_amount: u64,
+    }
+
+    /// `OfferAnnounced`
+    pub struct OfferAnnouncedBlob {
+        pub price: u128,
+        pub amount: u64,
+        pub amt_to_execute: u64,
+    }
+
+    /// `OfferSettled`
+    pub struct OfferSettledBlob {
+        pub outcome: OfferOutcome,
+        pub executed_amt: u64,
+        pub quote_paid: u64,
+        pub quote_refund: u64,
+    }
+
+    /// `BasketDealCreated`
+    pub struct BasketDealCreatedBlob {
+        pub units: u64,
+        pub price: u128,
+        pub leg_amounts: [u64; 4],
+    }
+
+    /// `BasketDealSettled`
+    pub struct BasketDealSettledBlob {
+        pub total_filled: u64,
+        pub creator_receives: u64,
+        pub leg_sold: [u64; 4],
+        pub leg_refund: [u64; 4],
+    }
+
+    /// `RfqCreated`
+    pub struct RfqCreatedBlob {
+        pub size: u64,
+        pub limit_price: u128,
+    }
+
+    /// `RfqSettled`
+    pub struct RfqSettledBlob {
+        pub executed_amt: u64,
+        pub price: u128,
+        pub quote_paid: u64,
+        pub quote_refund: u64,
+    }
+
+    /// `QuoteCreated`
+    pub struct QuoteCreatedBlob {
+        pub price: u128,
+        pub size: u64,
+    }
+
+    /// `QuoteSettled`
+    pub struct QuoteSettledBlob {
+        /// `Executed` for the winning quote, `Failed` otherwise
+        pub outcome: OfferOutcome,
+        pub executed_amt: u64,
+        pub quote_receives: u64,
+    }
+
+    /// `BalanceUpdated`
+    pub struct BalanceUpdatedBlob {
+        pub amount: u64,
+        pub committed_amount: u64,
+    }
+
+    /// `BalanceAttested`
+    pub struct AttestationBlob {
+        pub threshold: u64,
+        pub covered: bool,
+    }
+}
@@ -0,0 +1,136 @@
+//! Client side of the `Enc<Shared, T>` encryption used by the circuits.
+//!
+//! A client and the MXE agree on a shared secret with x25519; both derive the same Rescue
+//! key from it and encrypt one field element per scalar in counter mode. The cipher is the
+//! `arcis` implementation evaluated over cleartext field elements, so ciphertexts are exactly
+//! what the circuits decrypt.
+
+use arcis::utils::crypto::key::{RescueKey, RESCUE_KEY_COUNT};
+use arcis::utils::crypto::rescue_cipher::RescueCipher;
+use arcis::utils::crypto::rescue_prime_hash::RescuePrimeHash;
+use arcis::utils::field::BaseField;
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use rand_core::{OsRng, RngCore};
+
+use crate::blob::Plaintext;
+use crate::error::{Error, Result};
+
+/// An x25519 key pair; the public key goes into `encryption_pubkey` instruction arguments.
+#[derive(Clone)]
+pub struct X25519Keypair {
+    secret: [u8; 32],
+    public: [u8; 32],
+}
+
+impl X25519Keypair {
+    pub fn from_secret(secret: [u8; 32]) -> Self {
+        let public = MontgomeryPoint::mul_base_clamped(secret).to_bytes();
+        Self { secret, public }
+    }
+
+    /// Generates a fresh key pair from the OS random number generator.
+    pub fn generate() -> Self {
+        let mut secret = [0u8; 32];
+        OsRng.fill_bytes(&mut secret);
+        Self::from_secret(secret)
+    }
+
+    pub fn public_key(&self) -> [u8; 32] {
+        self.public
+    }
+
+    /// x25519 shared secret with `peer`, e.g. the MXE public key.
+    pub fn shared_secret(&self, peer: &[u8; 32]) -> [u8; 32] {
+        MontgomeryPoint(*peer).mul_clamped(self.secret).to_bytes()
+    }
+
+    /// Cipher shared with the holder of `peer`.
+    pub fn cipher(&self, peer: &[u8; 32]) -> Cipher {
+        Cipher::new(&self.shared_secret(peer))
+    }
+}
+
+/// Rescue cipher in counter mode keyed by an x25519 shared secret.
+pub struct Cipher {
+    inner: RescueCipher<BaseField, BaseField>,
+}
+
+impl Cipher {
+    /// Derives the Rescue key from a shared secret: Rescue-Prime over `1 || secret || 5`.
+    pub fn new(shared_secret: &[u8; 32]) -> Self {
+        // x25519 outputs are canonical field encodings
+        let secret = field_from_bytes(*shared_secret).expect("x25519 output is canonical");
+        let key = RescuePrimeHash::<BaseField, BaseField>::new().digest(vec![
+            BaseField::from(1u64),
+            secret,
+            BaseField::from(RESCUE_KEY_COUNT as u64),
+        ]);
+        Self {
+            inner: RescueCipher::new(RescueKey::new_from_inner(key)),
+        }
+    }
+
+    /// Encrypts one scalar per field element.
+    pub fn encrypt(&self, plaintext: &[u128], nonce: u128) -> Vec<[u8; 32]> {
+        let plaintext = plaintext.iter().map(|&v| field_from_u128(v)).collect();
+        self.inner
+            .encrypt(plaintext, field_from_u128(nonce))
+            .into_iter()
+            .map(|c| c.to_le_bytes())
+            .collect()
+    }
+
+    /// Decrypts ciphertexts whose scalars are all unsigned and at most 128 bits wide.
+    pub fn decrypt(&self, ciphertexts: &[[u8; 32]], nonce: u128) -> Result<Vec<u128>> {
+        let ciphertexts = ciphertexts
+            .iter()
+            .map(|&c| field_from_bytes(c).ok_or(Error::InvalidPlaintext))
+            .collect::<Result<Vec<_>>>()?;
+        self.inner
+            .decrypt(ciphertexts, field_from_u128(nonce))
+            .into_iter()
+            .map(|p| field_to_u128(p).ok_or(Error::InvalidPlaintext))
+            .collect()
+    }
+
+    /// Encrypts a circuit input in its field order, ready for the instruction arguments.
+    pub fn seal<T: Plaintext>(&self, value: &T, nonce: u128) -> Vec<[u8; 32]> {
+        self.encrypt(&value.to_fields(), nonce)
+    }
+
+    /// Decrypts a blob emitted by the program.
+    pub fn open<T: Plaintext>(&self, ciphertexts: &[[u8; 32]], nonce: u128) -> Result<T> {
+        if ciphertexts.len() != T::LEN {
+            return Err(Error::CiphertextCount {
+                expected: T::LEN,
+                found: ciphertexts.len(),
+            });
+        }
+        T::from_fields(&self.decrypt(ciphertexts, nonce)?).ok_or(Error::InvalidPlaintext)
+    }
+}
+
+/// A random 128-bit nonce. Never encrypt twice under the same key and nonce.
+pub fn random_nonce() -> u128 {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    u128::from_le_bytes(bytes)
+}
+
+fn field_from_bytes(bytes: [u8; 32]) -> Option<BaseField> {
+    BaseField::from_le_bytes_checked(bytes)
+}
+
+fn field_from_u128(value: u128) -> BaseField {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(&value.to_le_bytes());
+    field_from_bytes(bytes).expect("u128 is below the field modulus")
+}
+
+fn field_to_u128(value: BaseField) -> Option<u128> {
+    let bytes = value.to_le_bytes();
+    if bytes[16..].iter().any(|&b| b != 0) {
+        return None;
+    }
+    Some(u128::from_le_bytes(bytes[..16].try_into().unwrap()))
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+/// Errors returned while decoding accounts and events or opening encrypted blobs.
+#[derive(Debug)]
+pub enum Error {
+    /// The account data could not be deserialized as the requested type
+    Account(anchor_lang::error::Error),
+    /// The data does not start with the discriminator of any known account or event
+    UnknownDiscriminator,
+    /// The event payload could not be deserialized
+    Event(std::io::Error),
+    /// The number of ciphertexts does not match the blob being opened
+    CiphertextCount { expected: usize, found: usize },
+    /// A decrypted value does not fit its field; usually a wrong key or nonce
+    InvalidPlaintext,
+    /// The MXE has not finished generating its x25519 key
+    MxeKeyNotSet,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Account(err) => write!(f, "invalid account data: {err}"),
+            Self::UnknownDiscriminator => write!(f, "unknown discriminator"),
+            Self::Event(err) => write!(f, "invalid event data: {err}"),
+            Self::CiphertextCount { expected, found } => {
+                write!(f, "expected {expected} ciphertexts, found {found}")
+            }
+            Self::InvalidPlaintext => write!(f, "decrypted value out of range"),
+            Self::MxeKeyNotSet => write!(f, "MXE x25519 key is not set"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<anchor_lang::error::Error> for Error {
+    fn from(err: anchor_lang::error::Error) -> Self {
+        Self::Account(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,198 @@
+//! Decoding of the events the program emits with `emit!`.
+//!
+//! Anchor logs each event as `Program data: <base64>` where the payload is the event
+//! discriminator followed by the borsh-encoded struct.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::Engine;
+use otc::{
+    AccountMigrated, BalanceAttested, BalanceTransferred, BalanceUpdated, BasketDealCreated,
+    BasketDealSettled, CrankBountyPaid, DealAnnounced, DealCreated, DealRejected, DealSettled,
+    OfferAnnounced, OfferCreated, OfferRejected, OfferSettled, QuoteCreated, QuoteRejected,
+    QuoteSettled, RfqCreated, RfqRejected, RfqSettled,
+};
+
+use crate::blob::Plaintext;
+use crate::crypto::Cipher;
+use crate::error::{Error, Result};
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+macro_rules! otc_events {
+    ($($name:ident),* $(,)?) => {
+        /// Any event emitted by the OTC program.
+        pub enum OtcEvent {
+            $($name($name),)*
+        }
+
+        impl OtcEvent {
+            /// Decodes a raw event payload (discriminator + borsh data).
+            pub fn decode(data: &[u8]) -> Result<Self> {
+                $(
+                    if let Some(mut rest) = data.strip_prefix($name::DISCRIMINATOR) {
+                        return $name::deserialize(&mut rest)
+                            .map(Self::$name)
+                            .map_err(Error::Event);
+                    }
+                )*
+                Err(Error::UnknownDiscriminator)
+            }
+
+            /// Event name as it appears in the IDL.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$name(_) => stringify!($name),)*
+                }
+            }
+        }
+    };
+}
+
+otc_events!(
+    DealCreated,
+    OfferCreated,
+    DealSettled,
+    OfferSettled,
+    BalanceUpdated,
+    RfqCreated,
+    QuoteCreated,
+    RfqSettled,
+    QuoteSettled,
+    CrankBountyPaid,
+    DealRejected,
+    OfferRejected,
+    RfqRejected,
+    QuoteRejected,
+    BalanceTransferred,
+    BalanceAttested,
+    DealAnnounced,
+    OfferAnnounced,
+    BasketDealCreated,
+    BasketDealSettled,
+    AccountMigrated,
+);
+
+impl OtcEvent {
+    /// Decodes a `Program data:` log line; returns `None` for any other line.
+    ///
+    /// Only pass logs emitted by the OTC program itself: other programs log data the same way.
+    pub fn from_log(line: &str) -> Option<Result<Self>> {
+        let data = line.strip_prefix(PROGRAM_DATA)?;
+        let data = match base64::engine::general_purpose::STANDARD.decode(data.trim()) {
+            Ok(data) => data,
+            Err(_) => return Some(Err(Error::UnknownDiscriminator)),
+        };
+        Some(Self::decode(&data))
+    }
+
+    /// Decodes every OTC event in a transaction's logs, skipping data it does not recognize.
+    pub fn parse_logs<'a>(logs: impl IntoIterator<Item = &'a str>) -> Vec<Self> {
+        logs.into_iter()
+            .filter_map(Self::from_log)
+            .filter_map(Result::ok)
+            .collect()
+    }
+
+    /// The encrypted blob of events that carry one.
+    pub fn sealed(&self) -> Option<&dyn SealedEvent> {
+        Some(match self {
+            Self::DealCreated(e) => e,
+            Self::OfferCreated(e) => e,
+            Self::DealSettled(e) => e,
+            Self::OfferSettled(e) => e,
+            Self::BalanceUpdated(e) => e,
+            Self::RfqCreated(e) => e,
+            Self::QuoteCreated(e) => e,
+            Self::RfqSettled(e) => e,
+            Self::QuoteSettled(e) => e,
+            Self::BalanceAttested(e) => e,
+            Self::DealAnnounced(e) => e,
+            Self::OfferAnnounced(e) => e,
+            Self::BasketDealCreated(e) => e,
+            Self::BasketDealSettled(e) => e,
+            Self::CrankBountyPaid(_)
+            | Self::DealRejected(_)
+            | Self::OfferRejected(_)
+            | Self::RfqRejected(_)
+            | Self::QuoteRejected(_)
+            | Self::BalanceTransferred(_)
+            | Self::AccountMigrated(_) => return None,
+        })
+    }
+}
+
+/// One encrypted copy of an event blob.
+#[derive(Clone, Copy, Debug)]
+pub struct SealedBlob<'a> {
+    /// x25519 public key the blob is sealed to
+    pub encryption_key: &'a [u8; 32],
+    pub nonce: &'a [u8; 16],
+    pub ciphertexts: &'a [[u8; 32]],
+}
+
+impl SealedBlob<'_> {
+    /// Decrypts the blob with the cipher shared between `encryption_key` and the MXE.
+    pub fn open<T: Plaintext>(&self, cipher: &Cipher) -> Result<T> {
+        cipher.open(self.ciphertexts, u128::from_le_bytes(*self.nonce))
+    }
+}
+
+/// An event carrying a blob for its owner and, usually, a copy for the owner's auditor.
+pub trait SealedEvent {
+    fn owner_blob(&self) -> SealedBlob<'_>;
+
+    /// `None` for events without an auditor copy.
+    fn auditor_blob(&self) -> Option<SealedBlob<'_>>;
+}
+
+macro_rules! sealed_events {
+    ($($name:ident),* $(,)?) => {$(
+        impl SealedEvent for $name {
+            fn owner_blob(&self) -> SealedBlob<'_> {
+                SealedBlob {
+                    encryption_key: &self.encryption_key,
+                    nonce: &self.nonce,
+                    ciphertexts: &self.ciphertexts,
+                }
+            }
+
+            fn auditor_blob(&self) -> Option<SealedBlob<'_>> {
+                Some(SealedBlob {
+                    encryption_key: &self.auditor_encryption_key,
+                    nonce: &self.auditor_nonce,
+                    ciphertexts: &self.auditor_ciphertexts,
+                })
+            }
+        }
+    )*};
+}
+
+sealed_events!(
+    DealCreated,
+    OfferCreated,
+    DealSettled,
+    OfferSettled,
+    BalanceUpdated,
+    RfqCreated,
+    QuoteCreated,
+    RfqSettled,
+    QuoteSettled,
+    DealAnnounced,
+    OfferAnnounced,
+    BasketDealCreated,
+    BasketDealSettled,
+);
+
+impl SealedEvent for BalanceAttested {
+    fn owner_blob(&self) -> SealedBlob<'_> {
+        SealedBlob {
+            encryption_key: &self.encryption_key,
+            nonce: &self.nonce,
+            ciphertexts: &self.ciphertexts,
+        }
+    }
+
+    fn auditor_blob(&self) -> Option<SealedBlob<'_>> {
+        None
+    }
+}
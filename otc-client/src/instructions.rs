@@ -0,0 +1,1011 @@
+//! Instruction builders.
+//!
+//! Arguments are the program's own `otc::instruction::*` structs; builders fill in every
+//! account the instruction expects, deriving PDAs from the arguments and from already
+//! fetched accounts. Optional accounts left as `None` are passed as the program ID, as
+//! Anchor expects. Signers are the caller's responsibility.
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use otc::{accounts, instruction as args, BasketLegs, DealAccount, Multisig, OfferAccount};
+use otc::{QuoteAccount, RfqAccount};
+
+use crate::arcium::{comp_def_account, mxe_account, ArciumEnv};
+use crate::pda;
+
+fn build(
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+    remaining: Vec<AccountMeta>,
+) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(remaining);
+    Instruction::new_with_bytes(otc::ID, &data.data(), metas)
+}
+
+/// Fills in the Arcium accounts of a `queue_computation_accounts` struct.
+macro_rules! queue {
+    ($env:expr, $circuit:literal, $offset:expr, $name:ident { $($field:ident $(: $value:expr)?),* $(,)? }) => {{
+        let queue = $env.queue_accounts($circuit, $offset);
+        accounts::$name {
+            $($field $(: $value)?,)*
+            sign_pda_account: queue.sign_pda_account,
+            mxe_account: queue.mxe_account,
+            mempool_account: queue.mempool_account,
+            executing_pool: queue.executing_pool,
+            computation_account: queue.computation_account,
+            comp_def_account: queue.comp_def_account,
+            cluster_account: queue.cluster_account,
+            pool_account: queue.pool_account,
+            clock_account: queue.clock_account,
+            system_program: queue.system_program,
+            arcium_program: queue.arcium_program,
+        }
+    }};
+}
+
+fn writable(pubkey: Pubkey) -> AccountMeta {
+    AccountMeta::new(pubkey, false)
+}
+
+/// `(offer, offeror_balance)` pairs for `crank_offers_batch` and `settle_deal`.
+fn offer_pairs(deal: &DealAccount, offers: &[(Pubkey, Pubkey)]) -> Vec<AccountMeta> {
+    offers
+        .iter()
+        .flat_map(|(offer, controller)| {
+            [
+                writable(*offer),
+                writable(pda::balance(controller, &deal.quote_mint)),
+            ]
+        })
+        .collect()
+}
+
+// ============================================
+// Computation Definitions
+// ============================================
+
+macro_rules! circuits {
+    ($($variant:ident => $name:literal, $init:ident;)*) => {
+        /// Circuits whose computation definitions the program initializes.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum Circuit {
+            $($variant,)*
+        }
+
+        impl Circuit {
+            pub const ALL: &'static [Circuit] = &[$(Circuit::$variant,)*];
+
+            /// Circuit name in `encrypted-ixs`.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Circuit::$variant => $name,)*
+                }
+            }
+        }
+
+        /// Initializes the computation definition of `circuit`; run once per deployment.
+        pub fn init_comp_def(payer: Pubkey, circuit: Circuit) -> Instruction {
+            match circuit {
+                $(Circuit::$variant => build(
+                    accounts::$init {
+                        payer,
+                        mxe_account: mxe_account(),
+                        comp_def_account: comp_def_account($name),
+                        arcium_program: arcium_client::ARCIUM_PROGRAM_ID,
+                        system_program: system_program::ID,
+                    },
+                    args::$init {},
+                    vec![],
+                ),)*
+            }
+        }
+    };
+}
+
+circuits! {
+    CreateDeal => "create_deal", InitCreateDealCompDef;
+    SubmitOffer => "submit_offer", InitSubmitOfferCompDef;
+    AnnounceBalance => "announce_balance", InitAnnounceBalanceCompDef;
+    AnnounceDeal => "announce_deal", InitAnnounceDealCompDef;
+    AnnounceOffer => "announce_offer", InitAnnounceOfferCompDef;
+    CrankDeal => "crank_deal", InitCrankDealCompDef;
+    CrankOffer => "crank_offer", InitCrankOfferCompDef;
+    TopUp => "top_up", InitTopUpCompDef;
+    CreateRfq => "create_rfq", InitCreateRfqCompDef;
+    SubmitQuote => "submit_quote", InitSubmitQuoteCompDef;
+    CrankRfq => "crank_rfq", InitCrankRfqCompDef;
+    CrankQuote => "crank_quote", InitCrankQuoteCompDef;
+    CreateBasketDeal => "create_basket_deal", InitCreateBasketDealCompDef;
+    CrankBasketDeal => "crank_basket_deal", InitCrankBasketDealCompDef;
+    CreateSwapDeal => "create_swap_deal", InitCreateSwapDealCompDef;
+    SubmitSwapOffer => "submit_swap_offer", InitSubmitSwapOfferCompDef;
+    CrankSwapDeal => "crank_swap_deal", InitCrankSwapDealCompDef;
+    CrankSwapOffer => "crank_swap_offer", InitCrankSwapOfferCompDef;
+    CrankOffersBatch => "crank_offers_batch", InitCrankOffersBatchCompDef;
+    SettleDeal => "settle_deal", InitSettleDealCompDef;
+    Transfer => "transfer", InitTransferCompDef;
+    AttestBalance => "attest_balance", InitAttestBalanceCompDef;
+}
+
+// ============================================
+// Deals and Offers
+// ============================================
+
+/// Creates a price deal selling base from `args.controller`'s balance.
+///
+/// `authority` is the controller, a delegate session key (with its `delegate` account),
+/// or a relayer (`relayed`, after the controller's ed25519 instruction). Set `proposal`
+/// to execute a multisig controller's approved proposal instead.
+#[allow(clippy::too_many_arguments)]
+pub fn create_deal(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    authority: Pubkey,
+    create_key: Pubkey,
+    base_mint: Pubkey,
+    quote_mint: Pubkey,
+    delegate: Option<Pubkey>,
+    relayed: bool,
+    proposal: Option<Pubkey>,
+    price_feed: Option<Pubkey>,
+    args: args::CreateDeal,
+) -> Instruction {
+    let deal = pda::deal(&create_key);
+    let accounts = queue!(
+        env,
+        "create_deal",
+        args.computation_offset,
+        CreateDeal {
+            payer,
+            authority,
+            delegate,
+            relay_nonce: relayed.then(|| pda::relay_nonce(&args.controller)),
+            proposal,
+            instructions_sysvar: sysvar::instructions::ID,
+            create_key,
+            deal,
+            creator_balance: pda::balance(&args.controller, &base_mint),
+            base_mint,
+            quote_mint,
+            pair_config: pda::pair_config(&base_mint, &quote_mint),
+            price_feed,
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Submits an offer buying base from `deal` with `args.controller`'s quote balance.
+///
+/// `authority`, `delegate` and `relayed` work as in [`create_deal`].
+#[allow(clippy::too_many_arguments)]
+pub fn submit_offer(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    authority: Pubkey,
+    create_key: Pubkey,
+    deal: Pubkey,
+    deal_account: &DealAccount,
+    delegate: Option<Pubkey>,
+    relayed: bool,
+    price_feed: Option<Pubkey>,
+    args: args::SubmitOffer,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "submit_offer",
+        args.computation_offset,
+        SubmitOffer {
+            payer,
+            authority,
+            delegate,
+            relay_nonce: relayed.then(|| pda::relay_nonce(&args.controller)),
+            instructions_sysvar: sysvar::instructions::ID,
+            create_key,
+            deal,
+            offer: pda::offer(&deal, &create_key),
+            offeror_balance: pda::balance(&args.controller, &deal_account.quote_mint),
+            offeror_link: pda::link(&args.controller),
+            creator_link: pda::link(&deal_account.controller),
+            price_feed,
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Settles a deal; anyone may crank after expiry, the controller or `delegate` before.
+pub fn crank_deal(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    deal: Pubkey,
+    deal_account: &DealAccount,
+    delegate: Option<Pubkey>,
+    args: args::CrankDeal,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "crank_deal",
+        args.computation_offset,
+        CrankDeal {
+            payer,
+            deal,
+            creator_balance: pda::balance(&deal_account.controller, &deal_account.base_mint),
+            delegate,
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Settles an offer of a settled deal.
+pub fn crank_offer(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    offer: Pubkey,
+    offer_account: &OfferAccount,
+    deal_account: &DealAccount,
+    args: args::CrankOffer,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "crank_offer",
+        args.computation_offset,
+        CrankOffer {
+            payer,
+            deal: offer_account.deal,
+            offer,
+            offeror_balance: pda::balance(&offer_account.controller, &deal_account.quote_mint),
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Settles up to `CRANK_BATCH_SIZE` offers of a settled deal.
+///
+/// `offers` are `(offer, offer controller)` pairs.
+pub fn crank_offers_batch(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    deal: Pubkey,
+    deal_account: &DealAccount,
+    offers: &[(Pubkey, Pubkey)],
+    args: args::CrankOffersBatch,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "crank_offers_batch",
+        args.computation_offset,
+        CrankOffersBatch { payer, deal }
+    );
+    build(accounts, args, offer_pairs(deal_account, offers))
+}
+
+/// Settles a deal and all of its offers in one computation.
+///
+/// `offers` are `(offer, offer controller)` pairs covering every offer in index order.
+pub fn settle_deal(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    deal: Pubkey,
+    deal_account: &DealAccount,
+    delegate: Option<Pubkey>,
+    offers: &[(Pubkey, Pubkey)],
+    args: args::SettleDeal,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "settle_deal",
+        args.computation_offset,
+        SettleDeal {
+            payer,
+            deal,
+            creator_balance: pda::balance(&deal_account.controller, &deal_account.base_mint),
+            delegate,
+        }
+    );
+    build(accounts, args, offer_pairs(deal_account, offers))
+}
+
+/// Re-seals a deal's state to `args.encryption_pubkey`; the controller signs.
+pub fn rotate_deal_encryption_key(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    deal: Pubkey,
+    deal_account: &DealAccount,
+    args: args::RotateDealEncryptionKey,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "announce_deal",
+        args.computation_offset,
+        RotateDealEncryptionKey {
+            payer,
+            controller_signer: deal_account.controller,
+            deal,
+            creator_balance: pda::balance(&deal_account.controller, &deal_account.base_mint),
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Re-seals an offer's state to `args.encryption_pubkey`; the controller signs.
+pub fn rotate_offer_encryption_key(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    offer: Pubkey,
+    offer_account: &OfferAccount,
+    deal_account: &DealAccount,
+    args: args::RotateOfferEncryptionKey,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "announce_offer",
+        args.computation_offset,
+        RotateOfferEncryptionKey {
+            payer,
+            controller_signer: offer_account.controller,
+            offer,
+            deal: offer_account.deal,
+            offeror_balance: pda::balance(&offer_account.controller, &deal_account.quote_mint),
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Migrates a deal written before layout versioning. The legacy layout did not record
+/// the mints' decimals, so the deal's own mints must be passed.
+pub fn migrate_deal(
+    payer: Pubkey,
+    deal: Pubkey,
+    base_mint: Pubkey,
+    quote_mint: Pubkey,
+) -> Instruction {
+    build(
+        accounts::MigrateDeal {
+            payer,
+            deal,
+            base_mint,
+            quote_mint,
+            system_program: system_program::ID,
+        },
+        args::MigrateDeal {},
+        vec![],
+    )
+}
+
+/// Migrates an offer written before layout versioning.
+pub fn migrate_offer(payer: Pubkey, offer: Pubkey) -> Instruction {
+    build(
+        accounts::MigrateOffer {
+            payer,
+            offer,
+            system_program: system_program::ID,
+        },
+        args::MigrateOffer {},
+        vec![],
+    )
+}
+
+// ============================================
+// Basket Deals
+// ============================================
+
+/// Creates a basket deal selling `leg_mints` (in leg order) for `quote_mint`.
+///
+/// `authority`, `delegate` and `proposal` work as in [`create_deal`].
+#[allow(clippy::too_many_arguments)]
+pub fn create_basket_deal(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    authority: Pubkey,
+    create_key: Pubkey,
+    quote_mint: Pubkey,
+    leg_mints: &[Pubkey],
+    delegate: Option<Pubkey>,
+    proposal: Option<Pubkey>,
+    args: args::CreateBasketDeal,
+) -> Instruction {
+    let deal = pda::deal(&create_key);
+    let legs = leg_mints
+        .iter()
+        .map(|mint| writable(pda::balance(&args.controller, mint)))
+        .collect();
+    let accounts = queue!(
+        env,
+        "create_basket_deal",
+        args.computation_offset,
+        CreateBasketDeal {
+            payer,
+            authority,
+            delegate,
+            proposal,
+            instructions_sysvar: sysvar::instructions::ID,
+            create_key,
+            deal,
+            basket: pda::basket(&deal),
+            quote_mint,
+        }
+    );
+    build(accounts, args, legs)
+}
+
+/// Settles a basket deal across all of its legs.
+pub fn crank_basket_deal(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    deal: Pubkey,
+    deal_account: &DealAccount,
+    basket: &BasketLegs,
+    delegate: Option<Pubkey>,
+    args: args::CrankBasketDeal,
+) -> Instruction {
+    let legs = basket.leg_mints[..basket.num_legs as usize]
+        .iter()
+        .map(|mint| writable(pda::balance(&deal_account.controller, mint)))
+        .collect();
+    let accounts = queue!(
+        env,
+        "crank_basket_deal",
+        args.computation_offset,
+        CrankBasketDeal {
+            payer,
+            deal,
+            basket: pda::basket(&deal),
+            delegate,
+        }
+    );
+    build(accounts, args, legs)
+}
+
+// ============================================
+// Swap Deals
+// ============================================
+
+/// Creates a swap deal giving base for an exact amount of quote.
+///
+/// `authority`, `delegate`, `relayed` and `proposal` work as in [`create_deal`].
+#[allow(clippy::too_many_arguments)]
+pub fn create_swap_deal(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    authority: Pubkey,
+    create_key: Pubkey,
+    base_mint: Pubkey,
+    quote_mint: Pubkey,
+    delegate: Option<Pubkey>,
+    relayed: bool,
+    proposal: Option<Pubkey>,
+    args: args::CreateSwapDeal,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "create_swap_deal",
+        args.computation_offset,
+        CreateSwapDeal {
+            payer,
+            authority,
+            delegate,
+            relay_nonce: relayed.then(|| pda::relay_nonce(&args.controller)),
+            proposal,
+            instructions_sysvar: sysvar::instructions::ID,
+            create_key,
+            deal: pda::deal(&create_key),
+            creator_balance: pda::balance(&args.controller, &base_mint),
+            base_mint,
+            quote_mint,
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Submits an offer to a swap deal; arguments work as in [`submit_offer`].
+#[allow(clippy::too_many_arguments)]
+pub fn submit_swap_offer(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    authority: Pubkey,
+    create_key: Pubkey,
+    deal: Pubkey,
+    deal_account: &DealAccount,
+    delegate: Option<Pubkey>,
+    relayed: bool,
+    args: args::SubmitSwapOffer,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "submit_swap_offer",
+        args.computation_offset,
+        SubmitSwapOffer {
+            payer,
+            authority,
+            delegate,
+            relay_nonce: relayed.then(|| pda::relay_nonce(&args.controller)),
+            instructions_sysvar: sysvar::instructions::ID,
+            create_key,
+            deal,
+            offer: pda::offer(&deal, &create_key),
+            offeror_balance: pda::balance(&args.controller, &deal_account.quote_mint),
+            offeror_link: pda::link(&args.controller),
+            creator_link: pda::link(&deal_account.controller),
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Settles a swap deal; see [`crank_deal`].
+pub fn crank_swap_deal(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    deal: Pubkey,
+    deal_account: &DealAccount,
+    delegate: Option<Pubkey>,
+    args: args::CrankSwapDeal,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "crank_swap_deal",
+        args.computation_offset,
+        CrankSwapDeal {
+            payer,
+            deal,
+            creator_balance: pda::balance(&deal_account.controller, &deal_account.base_mint),
+            delegate,
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Settles an offer of a settled swap deal.
+pub fn crank_swap_offer(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    offer: Pubkey,
+    offer_account: &OfferAccount,
+    deal_account: &DealAccount,
+    args: args::CrankSwapOffer,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "crank_swap_offer",
+        args.computation_offset,
+        CrankSwapOffer {
+            payer,
+            deal: offer_account.deal,
+            offer,
+            offeror_balance: pda::balance(&offer_account.controller, &deal_account.quote_mint),
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+// ============================================
+// RFQs and Quotes
+// ============================================
+
+/// Publishes an RFQ buying base with `args.controller`'s quote balance.
+///
+/// Unlike deal prices, the sealed [`RfqInput`](crate::blob::RfqInput) limit price is in
+/// raw units: quote base units per base base unit, with no decimals applied.
+pub fn create_rfq(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    create_key: Pubkey,
+    base_mint: Pubkey,
+    quote_mint: Pubkey,
+    args: args::CreateRfq,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "create_rfq",
+        args.computation_offset,
+        CreateRfq {
+            payer,
+            create_key,
+            rfq: pda::rfq(&create_key),
+            taker_balance: pda::balance(&args.controller, &quote_mint),
+            base_mint,
+            quote_mint,
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Quotes an RFQ, committing `args.controller`'s base balance.
+///
+/// The sealed [`QuoteInput`](crate::blob::QuoteInput) price is in raw units, like the
+/// RFQ's limit price.
+pub fn submit_quote(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    create_key: Pubkey,
+    rfq: Pubkey,
+    rfq_account: &RfqAccount,
+    args: args::SubmitQuote,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "submit_quote",
+        args.computation_offset,
+        SubmitQuote {
+            payer,
+            create_key,
+            rfq,
+            quote: pda::quote(&rfq, &create_key),
+            maker_balance: pda::balance(&args.controller, &rfq_account.base_mint),
+            maker_link: pda::link(&args.controller),
+            taker_link: pda::link(&rfq_account.controller),
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Settles an RFQ against its best quote.
+pub fn crank_rfq(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    rfq: Pubkey,
+    rfq_account: &RfqAccount,
+    delegate: Option<Pubkey>,
+    args: args::CrankRfq,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "crank_rfq",
+        args.computation_offset,
+        CrankRfq {
+            payer,
+            rfq,
+            taker_balance: pda::balance(&rfq_account.controller, &rfq_account.quote_mint),
+            delegate,
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Settles a quote of a settled RFQ.
+pub fn crank_quote(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    quote: Pubkey,
+    quote_account: &QuoteAccount,
+    rfq_account: &RfqAccount,
+    args: args::CrankQuote,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "crank_quote",
+        args.computation_offset,
+        CrankQuote {
+            payer,
+            rfq: quote_account.rfq,
+            quote,
+            maker_balance: pda::balance(&quote_account.controller, &rfq_account.base_mint),
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+// ============================================
+// Balances
+// ============================================
+
+/// Credits `args.amount` of `mint` to `args.controller`'s balance, creating it if needed.
+///
+/// `controller_signer` is the controller, or the executor of `proposal` for a multisig.
+pub fn top_up(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    controller_signer: Pubkey,
+    mint: Pubkey,
+    proposal: Option<Pubkey>,
+    args: args::TopUp,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "top_up",
+        args.computation_offset,
+        TopUp {
+            payer,
+            controller_signer,
+            proposal,
+            instructions_sysvar: sysvar::instructions::ID,
+            mint,
+            balance: pda::balance(&args.controller, &mint),
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Moves an encrypted amount of `mint` to `recipient`'s balance.
+///
+/// `controller_signer` and `proposal` work as in [`top_up`].
+pub fn transfer(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    controller_signer: Pubkey,
+    mint: Pubkey,
+    recipient: Pubkey,
+    proposal: Option<Pubkey>,
+    args: args::Transfer,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "transfer",
+        args.computation_offset,
+        Transfer {
+            payer,
+            controller_signer,
+            proposal,
+            instructions_sysvar: sysvar::instructions::ID,
+            sender_balance: pda::balance(&args.controller, &mint),
+            recipient_balance: pda::balance(&recipient, &mint),
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Re-seals a balance to `args.encryption_pubkey`; the controller signs.
+pub fn announce_balance(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    mint: Pubkey,
+    args: args::AnnounceBalance,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "announce_balance",
+        args.computation_offset,
+        AnnounceBalance {
+            payer,
+            controller_signer: args.controller,
+            balance: pda::balance(&args.controller, &mint),
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Re-seals a balance to `args.encryption_pubkey` and makes it the owner key.
+pub fn rotate_balance_encryption_key(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    mint: Pubkey,
+    args: args::RotateBalanceEncryptionKey,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "announce_balance",
+        args.computation_offset,
+        RotateBalanceEncryptionKey {
+            payer,
+            controller_signer: args.controller,
+            balance: pda::balance(&args.controller, &mint),
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Proves to `args.counterparty_pubkey` that a balance covers an encrypted threshold.
+pub fn attest_balance(
+    env: &ArciumEnv,
+    payer: Pubkey,
+    mint: Pubkey,
+    args: args::AttestBalance,
+) -> Instruction {
+    let accounts = queue!(
+        env,
+        "attest_balance",
+        args.computation_offset,
+        AttestBalance {
+            payer,
+            controller_signer: args.controller,
+            balance: pda::balance(&args.controller, &mint),
+        }
+    );
+    build(accounts, args, vec![])
+}
+
+/// Registers the x25519 key that receives copies of every blob for this balance.
+pub fn set_auditor(controller: Pubkey, mint: Pubkey, args: args::SetAuditor) -> Instruction {
+    build(
+        accounts::SetAuditor {
+            controller,
+            balance: pda::balance(&controller, &mint),
+        },
+        args,
+        vec![],
+    )
+}
+
+/// Migrates a balance written before layout versioning.
+pub fn migrate_balance(payer: Pubkey, balance: Pubkey) -> Instruction {
+    build(
+        accounts::MigrateBalance {
+            payer,
+            balance,
+            system_program: system_program::ID,
+        },
+        args::MigrateBalance {},
+        vec![],
+    )
+}
+
+// ============================================
+// Controllers, Delegates and Relaying
+// ============================================
+
+/// Links `controller` into the group of `group_authority`.
+pub fn link_controller(payer: Pubkey, controller: Pubkey, group_authority: Pubkey) -> Instruction {
+    build(
+        accounts::LinkController {
+            payer,
+            controller,
+            group_authority,
+            link: pda::link(&controller),
+            system_program: system_program::ID,
+        },
+        args::LinkController {},
+        vec![],
+    )
+}
+
+/// Removes `controller` from the group of `group_authority`.
+pub fn unlink_controller(group_authority: Pubkey, controller: Pubkey) -> Instruction {
+    build(
+        accounts::UnlinkController {
+            group_authority,
+            link: pda::link(&controller),
+        },
+        args::UnlinkController {},
+        vec![],
+    )
+}
+
+/// Lets `session_key` act for `controller` within the given limits.
+pub fn create_delegate(
+    payer: Pubkey,
+    controller: Pubkey,
+    session_key: Pubkey,
+    args: args::CreateDelegate,
+) -> Instruction {
+    build(
+        accounts::CreateDelegate {
+            payer,
+            controller,
+            session_key,
+            delegate: pda::delegate(&controller, &session_key),
+            system_program: system_program::ID,
+        },
+        args,
+        vec![],
+    )
+}
+
+/// Revokes the delegate of `session_key`.
+pub fn revoke_delegate(controller: Pubkey, session_key: Pubkey) -> Instruction {
+    build(
+        accounts::RevokeDelegate {
+            controller,
+            delegate: pda::delegate(&controller, &session_key),
+        },
+        args::RevokeDelegate {},
+        vec![],
+    )
+}
+
+/// Creates the replay counter required before relaying a controller's messages.
+pub fn init_relay_nonce(payer: Pubkey, controller: Pubkey) -> Instruction {
+    build(
+        accounts::InitRelayNonce {
+            payer,
+            relay_nonce: pda::relay_nonce(&controller),
+            system_program: system_program::ID,
+        },
+        args::InitRelayNonce { controller },
+        vec![],
+    )
+}
+
+// ============================================
+// Multisig
+// ============================================
+
+/// Creates a multisig usable as a controller.
+pub fn create_multisig(
+    payer: Pubkey,
+    create_key: Pubkey,
+    args: args::CreateMultisig,
+) -> Instruction {
+    build(
+        accounts::CreateMultisig {
+            payer,
+            create_key,
+            multisig: pda::multisig(&create_key),
+            system_program: system_program::ID,
+        },
+        args,
+        vec![],
+    )
+}
+
+/// Proposes the instruction whose digest is `args.instruction_digest`.
+pub fn create_proposal(
+    proposer: Pubkey,
+    multisig: Pubkey,
+    multisig_account: &Multisig,
+    args: args::CreateProposal,
+) -> Instruction {
+    build(
+        accounts::CreateProposal {
+            proposer,
+            multisig,
+            proposal: pda::proposal(&multisig, multisig_account.proposal_count),
+            system_program: system_program::ID,
+        },
+        args,
+        vec![],
+    )
+}
+
+/// Adds `member`'s approval to proposal `index`.
+pub fn approve_proposal(member: Pubkey, multisig: Pubkey, index: u64) -> Instruction {
+    build(
+        accounts::ApproveProposal {
+            member,
+            multisig,
+            proposal: pda::proposal(&multisig, index),
+        },
+        args::ApproveProposal {},
+        vec![],
+    )
+}
+
+// ============================================
+// Pairs and Price Feeds
+// ============================================
+
+/// Sets a pair's increments and price band; `authority` is the program upgrade authority.
+pub fn set_pair_config(
+    payer: Pubkey,
+    authority: Pubkey,
+    base_mint: Pubkey,
+    quote_mint: Pubkey,
+    args: args::SetPairConfig,
+) -> Instruction {
+    build(
+        accounts::SetPairConfig {
+            payer,
+            authority,
+            program: otc::ID,
+            program_data: pda::program_data(),
+            base_mint,
+            quote_mint,
+            pair_config: pda::pair_config(&base_mint, &quote_mint),
+            system_program: system_program::ID,
+        },
+        args,
+        vec![],
+    )
+}
+
+/// Publishes `args.price` on `authority`'s feed for the pair.
+pub fn update_price_feed(
+    payer: Pubkey,
+    authority: Pubkey,
+    base_mint: Pubkey,
+    quote_mint: Pubkey,
+    args: args::UpdatePriceFeed,
+) -> Instruction {
+    build(
+        accounts::UpdatePriceFeed {
+            payer,
+            authority,
+            base_mint,
+            quote_mint,
+            price_feed: pda::price_feed(&authority, &base_mint, &quote_mint),
+            system_program: system_program::ID,
+        },
+        args,
+        vec![],
+    )
+}
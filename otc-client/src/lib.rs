@@ -0,0 +1,25 @@
+//! Rust client for the OTC program.
+//!
+//! Builds every instruction, derives the program's PDAs, encrypts circuit inputs for the
+//! MXE and decrypts the blobs sealed into events. Account and event types are re-exported
+//! from the `otc` crate so on-chain and client layouts cannot drift apart.
+//!
+//! A typical flow: read the MXE key with [`accounts::mxe_x25519_pubkey`], derive a
+//! [`Cipher`] from an [`X25519Keypair`], [`Cipher::seal`] a [`blob::DealInput`] into the
+//! `encrypted_*` arguments of [`instructions::create_deal`], then decode the resulting
+//! `DealCreated` with [`OtcEvent::from_log`] and open its blob with [`SealedBlob::open`].
+
+pub mod accounts;
+pub mod arcium;
+pub mod blob;
+pub mod crypto;
+pub mod error;
+pub mod events;
+pub mod instructions;
+pub mod pda;
+
+pub use arcium::ArciumEnv;
+pub use crypto::{random_nonce, Cipher, X25519Keypair};
+pub use error::{Error, Result};
+pub use events::{OtcEvent, SealedBlob, SealedEvent};
+pub use otc::ID;
@@ -0,0 +1,88 @@
+//! Program derived addresses of the OTC program.
+//!
+//! Seeds mirror the `seeds = [...]` constraints of the instruction account structs.
+
+use anchor_lang::prelude::Pubkey;
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &otc::ID).0
+}
+
+/// `["deal", create_key]`
+pub fn deal(create_key: &Pubkey) -> Pubkey {
+    find(&[b"deal", create_key.as_ref()])
+}
+
+/// `["offer", deal, create_key]`
+pub fn offer(deal: &Pubkey, create_key: &Pubkey) -> Pubkey {
+    find(&[b"offer", deal.as_ref(), create_key.as_ref()])
+}
+
+/// `["balance", controller, mint]`
+pub fn balance(controller: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(&[b"balance", controller.as_ref(), mint.as_ref()])
+}
+
+/// `["basket", deal]`
+pub fn basket(deal: &Pubkey) -> Pubkey {
+    find(&[b"basket", deal.as_ref()])
+}
+
+/// `["rfq", create_key]`
+pub fn rfq(create_key: &Pubkey) -> Pubkey {
+    find(&[b"rfq", create_key.as_ref()])
+}
+
+/// `["quote", rfq, create_key]`
+pub fn quote(rfq: &Pubkey, create_key: &Pubkey) -> Pubkey {
+    find(&[b"quote", rfq.as_ref(), create_key.as_ref()])
+}
+
+/// `["delegate", controller, session_key]`
+pub fn delegate(controller: &Pubkey, session_key: &Pubkey) -> Pubkey {
+    find(&[b"delegate", controller.as_ref(), session_key.as_ref()])
+}
+
+/// `["link", controller]`
+pub fn link(controller: &Pubkey) -> Pubkey {
+    find(&[b"link", controller.as_ref()])
+}
+
+/// `["relay_nonce", controller]`
+pub fn relay_nonce(controller: &Pubkey) -> Pubkey {
+    find(&[b"relay_nonce", controller.as_ref()])
+}
+
+/// `["multisig", create_key]`
+pub fn multisig(create_key: &Pubkey) -> Pubkey {
+    find(&[b"multisig", create_key.as_ref()])
+}
+
+/// `["proposal", multisig, index]`; the next proposal uses `Multisig::proposal_count`.
+pub fn proposal(multisig: &Pubkey, index: u64) -> Pubkey {
+    find(&[b"proposal", multisig.as_ref(), &index.to_le_bytes()])
+}
+
+/// `["pair", base_mint, quote_mint]`
+pub fn pair_config(base_mint: &Pubkey, quote_mint: &Pubkey) -> Pubkey {
+    find(&[b"pair", base_mint.as_ref(), quote_mint.as_ref()])
+}
+
+/// `["price_feed", authority, base_mint, quote_mint]`
+pub fn price_feed(authority: &Pubkey, base_mint: &Pubkey, quote_mint: &Pubkey) -> Pubkey {
+    find(&[
+        b"price_feed",
+        authority.as_ref(),
+        base_mint.as_ref(),
+        quote_mint.as_ref(),
+    ])
+}
+
+/// The program's BPF upgradeable loader data account, checked by `set_pair_config`.
+pub fn program_data() -> Pubkey {
+    Pubkey::find_program_address(
+        &[otc::ID.as_ref()],
+        &anchor_lang::solana_program::bpf_loader_upgradeable::ID,
+    )
+    .0
+}
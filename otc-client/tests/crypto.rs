@@ -0,0 +1,148 @@
+//! The client cipher must agree with the MXE side of `Enc<Shared, T>`, which arcis derives
+//! from the MXE private key and the client's public key.
+
+use arcis::traits::ToMontgomery;
+use arcis::utils::crypto::key::{X25519PrivateKey, X25519PublicKey};
+use arcis::utils::crypto::rescue_cipher::RescueCipher;
+use arcis::utils::curve_point::CurvePoint;
+use arcis::utils::field::{BaseField, ScalarField};
+use curve25519_dalek::scalar::{clamp_integer, Scalar};
+use otc::OfferOutcome;
+use otc_client::blob::{DealInput, OfferSettledBlob, Plaintext, QuoteSettledBlob};
+use otc_client::{Cipher, Error, X25519Keypair};
+use proptest::prelude::*;
+
+/// MXE side: a private scalar and its Montgomery public key.
+fn mxe_keys(seed: u64) -> (ScalarField, [u8; 32]) {
+    let secret = ScalarField::from(seed);
+    let point = secret * CurvePoint::generator();
+    (secret, point.to_montgomery(true).0.to_le_bytes())
+}
+
+/// The client's x25519 public key as arcis sees it: the clamped secret times the generator.
+fn client_point(secret: [u8; 32]) -> CurvePoint {
+    let scalar = Scalar::from_bytes_mod_order(clamp_integer(secret));
+    ScalarField::from_le_bytes_checked(scalar.to_bytes()).unwrap() * CurvePoint::generator()
+}
+
+fn field(value: u128) -> BaseField {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(&value.to_le_bytes());
+    BaseField::from_le_bytes_checked(bytes).unwrap()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn client_ciphertexts_match_mxe(
+        client_secret in any::<[u8; 32]>(),
+        mxe_seed in 1u64..,
+        amount in any::<u64>(),
+        price in any::<u128>(),
+        nonce in any::<u128>(),
+    ) {
+        let (mxe_secret, mxe_pubkey) = mxe_keys(mxe_seed);
+        let client = X25519Keypair::from_secret(client_secret);
+        let input = DealInput { amount, price };
+        let ours = client.cipher(&mxe_pubkey).seal(&input, nonce);
+
+        let mxe = RescueCipher::<BaseField, BaseField>::new_with_client_from_keys::<
+            BaseField,
+            ScalarField,
+            CurvePoint,
+        >(
+            X25519PrivateKey::new(mxe_secret, true),
+            X25519PublicKey::new(client_point(client_secret), true),
+        );
+        let theirs: Vec<[u8; 32]> = mxe
+            .encrypt(vec![field(amount as u128), field(price)], field(nonce))
+            .into_iter()
+            .map(|c| c.to_le_bytes())
+            .collect();
+        prop_assert_eq!(ours, theirs);
+    }
+
+    #[test]
+    fn blobs_round_trip(
+        secret in any::<[u8; 32]>(),
+        executed_amt in any::<u64>(),
+        quote_paid in any::<u64>(),
+        quote_refund in any::<u64>(),
+        nonce in any::<u128>(),
+    ) {
+        let cipher = Cipher::new(&secret_to_shared(secret));
+        let blob = OfferSettledBlob {
+            outcome: OfferOutcome::Partial,
+            executed_amt,
+            quote_paid,
+            quote_refund,
+        };
+        let ciphertexts = cipher.encrypt(&blob.to_fields(), nonce);
+        prop_assert_eq!(cipher.open::<OfferSettledBlob>(&ciphertexts, nonce).unwrap(), blob);
+    }
+}
+
+fn secret_to_shared(secret: [u8; 32]) -> [u8; 32] {
+    let (_, mxe_pubkey) = mxe_keys(7);
+    X25519Keypair::from_secret(secret).shared_secret(&mxe_pubkey)
+}
+
+#[test]
+fn shared_secret_is_symmetric() {
+    let alice = X25519Keypair::generate();
+    let bob = X25519Keypair::generate();
+    assert_eq!(
+        alice.shared_secret(&bob.public_key()),
+        bob.shared_secret(&alice.public_key())
+    );
+}
+
+#[test]
+fn open_rejects_wrong_blob_length() {
+    let cipher = Cipher::new(&secret_to_shared([3; 32]));
+    let ciphertexts = cipher.encrypt(&[1, 2], 9);
+    assert!(matches!(
+        cipher.open::<OfferSettledBlob>(&ciphertexts, 9),
+        Err(Error::CiphertextCount {
+            expected: 4,
+            found: 2
+        })
+    ));
+}
+
+#[test]
+fn open_with_wrong_nonce_is_detected() {
+    let cipher = Cipher::new(&secret_to_shared([5; 32]));
+    let ciphertexts = cipher.seal(
+        &DealInput {
+            amount: 10,
+            price: 20,
+        },
+        1,
+    );
+    // A wrong nonce yields uniformly random field elements, far above 2^128
+    assert!(matches!(
+        cipher.open::<DealInput>(&ciphertexts, 2),
+        Err(Error::InvalidPlaintext)
+    ));
+}
+
+#[test]
+fn open_rejects_unknown_outcomes() {
+    let cipher = Cipher::new(&secret_to_shared([6; 32]));
+    for (outcome, expected) in [
+        (0, Some(OfferOutcome::Executed)),
+        (1, Some(OfferOutcome::Partial)),
+        (2, Some(OfferOutcome::Failed)),
+        (3, None),
+        (255, None),
+    ] {
+        let ciphertexts = cipher.encrypt(&[outcome, 500, 1000], 4);
+        let blob = cipher.open::<QuoteSettledBlob>(&ciphertexts, 4);
+        match expected {
+            Some(outcome) => assert_eq!(blob.unwrap().outcome, outcome),
+            None => assert!(matches!(blob, Err(Error::InvalidPlaintext))),
+        }
+    }
+}
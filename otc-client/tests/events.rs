@@ -0,0 +1,124 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorSerialize, Discriminator};
+use base64::Engine;
+use otc::{DealCreated, DealSettled, DealStatus};
+use otc_client::blob::{DealCreatedBlob, DealSettledBlob, Plaintext};
+use otc_client::{Error, OtcEvent, X25519Keypair};
+
+fn program_data<E: AnchorSerialize + Discriminator>(event: &E) -> String {
+    let mut data = E::DISCRIMINATOR.to_vec();
+    event.serialize(&mut data).unwrap();
+    format!(
+        "Program data: {}",
+        base64::engine::general_purpose::STANDARD.encode(data)
+    )
+}
+
+#[test]
+fn decodes_and_opens_logged_events() {
+    let owner = X25519Keypair::generate();
+    let mxe = X25519Keypair::generate();
+    let cipher = owner.cipher(&mxe.public_key());
+    let blob = DealSettledBlob {
+        total_filled: 1000,
+        creator_receives: 40,
+        creator_refund: 0,
+    };
+    let nonce = 77u128;
+    let ciphertexts = cipher.encrypt(&blob.to_fields(), nonce);
+    let settled = DealSettled {
+        deal: Pubkey::new_unique(),
+        status: DealStatus::Executed,
+        settled_at: 1,
+        encryption_key: owner.public_key(),
+        nonce: nonce.to_le_bytes(),
+        ciphertexts: ciphertexts.clone().try_into().unwrap(),
+        auditor_encryption_key: owner.public_key(),
+        auditor_nonce: nonce.to_le_bytes(),
+        auditor_ciphertexts: ciphertexts.try_into().unwrap(),
+    };
+
+    let logs = [
+        "Program log: Instruction: CrankDealCallback".to_string(),
+        program_data(&settled),
+        "Program data: AAAAAAAAAAA=".to_string(),
+    ];
+    let events = OtcEvent::parse_logs(logs.iter().map(String::as_str));
+    assert_eq!(events.len(), 1);
+    let OtcEvent::DealSettled(decoded) = &events[0] else {
+        panic!("expected DealSettled, got {}", events[0].name());
+    };
+    assert_eq!(decoded.deal, settled.deal);
+    assert_eq!(decoded.status, DealStatus::Executed);
+
+    let sealed = events[0].sealed().unwrap();
+    assert_eq!(
+        sealed
+            .owner_blob()
+            .open::<DealSettledBlob>(&cipher)
+            .unwrap(),
+        blob
+    );
+    assert_eq!(
+        sealed
+            .auditor_blob()
+            .unwrap()
+            .open::<DealSettledBlob>(&cipher)
+            .unwrap(),
+        blob
+    );
+}
+
+#[test]
+fn opening_with_another_key_fails() {
+    let owner = X25519Keypair::generate();
+    let mxe = X25519Keypair::generate();
+    let cipher = owner.cipher(&mxe.public_key());
+    let created = DealCreated {
+        deal: Pubkey::new_unique(),
+        base_mint: Pubkey::new_unique(),
+        quote_mint: Pubkey::new_unique(),
+        expires_at: 0,
+        allow_partial: true,
+        created_at: 0,
+        encryption_key: owner.public_key(),
+        nonce: [1; 16],
+        ciphertexts: cipher
+            .seal(
+                &DealCreatedBlob {
+                    amount: 5,
+                    price: 6,
+                },
+                u128::from_le_bytes([1; 16]),
+            )
+            .try_into()
+            .unwrap(),
+        auditor_encryption_key: [0; 32],
+        auditor_nonce: [0; 16],
+        auditor_ciphertexts: [[0; 32]; 2],
+    };
+    let line = program_data(&created);
+    let event = OtcEvent::from_log(&line).unwrap().unwrap();
+    let stranger = X25519Keypair::generate().cipher(&mxe.public_key());
+    assert!(matches!(
+        event
+            .sealed()
+            .unwrap()
+            .owner_blob()
+            .open::<DealCreatedBlob>(&stranger),
+        Err(Error::InvalidPlaintext)
+    ));
+    assert_eq!(
+        event
+            .sealed()
+            .unwrap()
+            .owner_blob()
+            .open::<DealCreatedBlob>(&cipher)
+            .unwrap(),
+        DealCreatedBlob {
+            amount: 5,
+            price: 6
+        }
+    );
+    assert!(OtcEvent::from_log("Program log: hello").is_none());
+}
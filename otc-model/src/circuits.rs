@@ -0,0 +1,469 @@
+//! One function per mirrored `#[instruction]`, plus the private helpers they share.
+//!
+//! Helpers follow the circuits' `(value, flag)` convention instead of `Option` so each
+//! line can be compared with its counterpart in `encrypted-ixs`. Price math goes through
+//! the `fixed-point` crate, which mirrors the circuit helpers bit for bit.
+
+use fixed_point::{mul_price_down as price_down, mul_price_up as price_up};
+
+use crate::state::*;
+
+/// Plaintext powers of ten the program passes to convert human-unit prices into raw
+/// units (`price_scale_up`/`price_scale_down`). One of them is always 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceScale {
+    pub up: u128,
+    pub down: u128,
+}
+
+impl PriceScale {
+    /// The scale the program derives from the mints' decimals, or `None` for decimals it
+    /// does not support.
+    pub fn from_decimals(base_decimals: u8, quote_decimals: u8) -> Option<Self> {
+        fixed_point::price_scale(base_decimals, quote_decimals).map(|(up, down)| Self { up, down })
+    }
+}
+
+impl Default for PriceScale {
+    /// Equal decimals on both mints.
+    fn default() -> Self {
+        Self { up: 1, down: 1 }
+    }
+}
+
+/// Plaintext input rules the program passes to `create_deal` and `submit_offer`: the
+/// pair's tick and lot sizes, the oracle price band and the signing delegate's notional
+/// limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputRules {
+    pub tick_size: u128,
+    pub lot_size: u64,
+    pub min_price: u128,
+    pub max_price: u128,
+    pub max_notional: u64,
+}
+
+impl Default for InputRules {
+    /// What the program passes for a pair without config or oracle, signed by the
+    /// controller itself.
+    fn default() -> Self {
+        Self {
+            tick_size: 1,
+            lot_size: 1,
+            min_price: 0,
+            max_price: u128::MAX,
+            max_notional: u64::MAX,
+        }
+    }
+}
+
+// ============================================
+// HELPERS
+// ============================================
+
+/// X64.64 multiplication rounded down. Returns (result, overflowed); 0 on overflow.
+fn mul_price_down(amount: u64, price: u128) -> (u64, bool) {
+    price_down(amount, price).map_or((0, true), |v| (v, false))
+}
+
+/// X64.64 multiplication rounded up. Returns (result, overflowed); 0 on overflow.
+fn mul_price_up(amount: u64, price: u128) -> (u64, bool) {
+    price_up(amount, price).map_or((0, true), |v| (v, false))
+}
+
+/// price * scale_up / scale_down. Returns (raw_price, overflowed); 0 on overflow.
+fn scale_price(price: u128, scale: PriceScale) -> (u128, bool) {
+    let overflow = (price >> 64) * scale.up >= 1 << 63;
+    let scaled = if overflow { 0 } else { price * scale.up };
+    (scaled / scale.down, overflow)
+}
+
+/// Checked u64 addition. Returns (a + b, overflowed); `a` is returned on overflow.
+fn checked_add(a: u64, b: u64) -> (u64, bool) {
+    match a.checked_add(b) {
+        Some(sum) => (sum, false),
+        None => (a, true),
+    }
+}
+
+/// Checked u64 subtraction. Returns (a - b, underflowed); `a` is returned on underflow.
+fn checked_sub(a: u64, b: u64) -> (u64, bool) {
+    match a.checked_sub(b) {
+        Some(diff) => (diff, false),
+        None => (a, true),
+    }
+}
+
+/// Returns 0 if valid, 3 if off tick/lot, 4 if outside the band, 5 if over the notional
+/// limit.
+fn input_error(price: u128, amount: u64, notional: u64, rules: &InputRules) -> u8 {
+    if !price.is_multiple_of(rules.tick_size) || !amount.is_multiple_of(rules.lot_size) {
+        CircuitError::OFF_TICK_OR_LOT
+    } else if price < rules.min_price || price > rules.max_price {
+        CircuitError::OUT_OF_BAND
+    } else if notional > rules.max_notional {
+        CircuitError::NOTIONAL_EXCEEDED
+    } else {
+        CircuitError::NONE
+    }
+}
+
+/// Folds underflow/overflow flags into an error code (underflow wins).
+fn error_code(underflow: bool, overflow: bool) -> u8 {
+    if underflow {
+        CircuitError::UNDERFLOW
+    } else if overflow {
+        CircuitError::OVERFLOW
+    } else {
+        CircuitError::NONE
+    }
+}
+
+/// Returns (can_settle, status, total_filled).
+fn deal_outcome(
+    amount: u64,
+    fill_amount: u64,
+    is_expired: bool,
+    allow_partial: bool,
+) -> (bool, u8, u64) {
+    let fully_filled = fill_amount >= amount;
+    let can_settle = is_expired || fully_filled;
+    let deal_executes = fully_filled || (allow_partial && fill_amount > 0);
+
+    let total_filled = if can_settle && deal_executes {
+        fill_amount
+    } else {
+        0
+    };
+
+    let status = if !can_settle {
+        DEAL_OPEN
+    } else if deal_executes {
+        DEAL_EXECUTED
+    } else {
+        DEAL_EXPIRED
+    };
+
+    (can_settle, status, total_filled)
+}
+
+// ============================================
+// INSTRUCTIONS
+// ============================================
+
+/// Outputs of [`create_deal`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CreateDeal {
+    pub deal: DealState,
+    pub balance: BalanceState,
+    pub deal_blob: DealCreatedBlob,
+    pub balance_blob: BalanceUpdatedBlob,
+    pub error: u8,
+}
+
+/// Locks the creator's BASE and stores the deal at its raw price. Invalid or oversized
+/// inputs store an empty deal and lock nothing.
+pub fn create_deal(
+    input: DealInput,
+    balance: &BalanceState,
+    scale: PriceScale,
+    rules: &InputRules,
+) -> CreateDeal {
+    let (committed_amount, committed_overflow) =
+        checked_add(balance.committed_amount, input.amount);
+    let (raw_price, price_overflow) = scale_price(input.price, scale);
+    let (notional, notional_overflow) = mul_price_up(input.amount, raw_price);
+    let notional = if notional_overflow {
+        u64::MAX
+    } else {
+        notional
+    };
+    let invalid = input_error(input.price, input.amount, notional, rules);
+    let error = if invalid == CircuitError::NONE {
+        error_code(false, committed_overflow || price_overflow)
+    } else {
+        invalid
+    };
+
+    let new_balance = if error == CircuitError::NONE {
+        BalanceState {
+            amount: balance.amount,
+            committed_amount,
+        }
+    } else {
+        *balance
+    };
+
+    let deal = if error == CircuitError::NONE {
+        DealState {
+            amount: input.amount,
+            price: raw_price,
+            fill_amount: 0,
+        }
+    } else {
+        DealState::default()
+    };
+
+    CreateDeal {
+        deal,
+        balance: new_balance,
+        deal_blob: DealCreatedBlob {
+            amount: input.amount,
+            price: input.price,
+        },
+        balance_blob: new_balance.into(),
+        error,
+    }
+}
+
+/// Outputs of [`submit_offer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubmitOffer {
+    pub deal: DealState,
+    pub offer: OfferState,
+    pub balance: BalanceState,
+    pub offer_blob: OfferCreatedBlob,
+    pub error: u8,
+}
+
+/// Fills as much of the offer as the deal has left if its raw price meets the deal's, and
+/// locks the offer's full notional at its own price. Invalid or oversized inputs store an
+/// empty offer, fill nothing and lock nothing.
+pub fn submit_offer(
+    deal: &DealState,
+    input: OfferInput,
+    balance: &BalanceState,
+    scale: PriceScale,
+    rules: &InputRules,
+) -> SubmitOffer {
+    let (offer_price, price_overflow) = scale_price(input.price, scale);
+
+    let (remaining, remaining_underflow) = checked_sub(deal.amount, deal.fill_amount);
+    let amt_to_execute = if offer_price >= deal.price {
+        input.amount.min(remaining)
+    } else {
+        0
+    };
+
+    let (quote_to_commit, commit_overflow) = mul_price_up(input.amount, offer_price);
+    let (committed_amount, committed_overflow) =
+        checked_add(balance.committed_amount, quote_to_commit);
+    let (fill_amount, fill_overflow) = checked_add(deal.fill_amount, amt_to_execute);
+    let invalid = input_error(input.price, input.amount, quote_to_commit, rules);
+    let error = if invalid == CircuitError::NONE {
+        error_code(
+            remaining_underflow,
+            price_overflow || commit_overflow || committed_overflow || fill_overflow,
+        )
+    } else {
+        invalid
+    };
+
+    let ok = error == CircuitError::NONE;
+    SubmitOffer {
+        deal: if ok {
+            DealState {
+                fill_amount,
+                ..*deal
+            }
+        } else {
+            *deal
+        },
+        offer: if ok {
+            OfferState {
+                price: offer_price,
+                amount: input.amount,
+                amt_to_execute,
+            }
+        } else {
+            OfferState::default()
+        },
+        balance: if ok {
+            BalanceState {
+                amount: balance.amount,
+                committed_amount,
+            }
+        } else {
+            *balance
+        },
+        offer_blob: OfferCreatedBlob {
+            price: input.price,
+            amount: input.amount,
+        },
+        error,
+    }
+}
+
+/// Mirrors `announce_balance`.
+pub fn announce_balance(balance: &BalanceState) -> BalanceUpdatedBlob {
+    (*balance).into()
+}
+
+/// Outputs of [`crank_deal`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrankDeal {
+    pub balance: BalanceState,
+    pub deal_blob: DealSettledBlob,
+    pub balance_blob: BalanceUpdatedBlob,
+    /// DEAL_OPEN when the deal cannot settle yet
+    pub status: u8,
+    pub error: u8,
+}
+
+/// Settles a deal once expired or fully filled: releases the creator's full BASE
+/// commitment and deducts what sold.
+pub fn crank_deal(
+    deal: &DealState,
+    balance: &BalanceState,
+    is_expired: bool,
+    allow_partial: bool,
+) -> CrankDeal {
+    let (can_settle, status, total_filled) =
+        deal_outcome(deal.amount, deal.fill_amount, is_expired, allow_partial);
+
+    // Creator receives, so round down
+    let (creator_receives, receives_overflow) = mul_price_down(total_filled, deal.price);
+
+    let (unfilled, unfilled_underflow) = checked_sub(deal.amount, total_filled);
+    let creator_refund = if can_settle { unfilled } else { 0 };
+
+    let (amount, amount_underflow) = checked_sub(balance.amount, total_filled);
+    let (committed_amount, committed_underflow) =
+        checked_sub(balance.committed_amount, deal.amount);
+    let error = if can_settle {
+        error_code(
+            unfilled_underflow || amount_underflow || committed_underflow,
+            receives_overflow,
+        )
+    } else {
+        CircuitError::NONE
+    };
+
+    let new_balance = if can_settle && error == CircuitError::NONE {
+        BalanceState {
+            amount,
+            committed_amount,
+        }
+    } else {
+        *balance
+    };
+
+    CrankDeal {
+        balance: new_balance,
+        deal_blob: DealSettledBlob {
+            total_filled,
+            creator_receives,
+            creator_refund,
+        },
+        balance_blob: new_balance.into(),
+        status,
+        error,
+    }
+}
+
+/// Outputs of [`crank_offer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrankOffer {
+    pub balance: BalanceState,
+    pub offer_blob: OfferSettledBlob,
+    pub balance_blob: BalanceUpdatedBlob,
+    pub error: u8,
+}
+
+/// Settles an offer against its settled deal: releases the offeror's full QUOTE
+/// commitment and deducts the fill paid at the deal's price. `floor_locked` offers locked
+/// their commitment rounded down and never pay more than it.
+pub fn crank_offer(
+    deal: &DealState,
+    offer: &OfferState,
+    balance: &BalanceState,
+    deal_success: bool,
+    floor_locked: bool,
+) -> CrankOffer {
+    let executed_amt = if deal_success {
+        offer.amt_to_execute
+    } else {
+        0
+    };
+
+    // Offeror pays, so round up
+    let (quote_committed, committed_overflow) = if floor_locked {
+        mul_price_down(offer.amount, offer.price)
+    } else {
+        mul_price_up(offer.amount, offer.price)
+    };
+    let (quote_executed, executed_overflow) = mul_price_up(executed_amt, deal.price);
+    let quote_executed = if floor_locked {
+        quote_executed.min(quote_committed)
+    } else {
+        quote_executed
+    };
+
+    let (quote_refund, refund_underflow) = checked_sub(quote_committed, quote_executed);
+    let (amount, amount_underflow) = checked_sub(balance.amount, quote_executed);
+    let (committed_amount, release_underflow) =
+        checked_sub(balance.committed_amount, quote_committed);
+    let error = error_code(
+        refund_underflow || amount_underflow || release_underflow,
+        committed_overflow || executed_overflow,
+    );
+
+    let new_balance = if error == CircuitError::NONE {
+        BalanceState {
+            amount,
+            committed_amount,
+        }
+    } else {
+        *balance
+    };
+
+    let outcome = if executed_amt == 0 {
+        OUTCOME_FAILED
+    } else if executed_amt < offer.amount {
+        OUTCOME_PARTIAL
+    } else {
+        OUTCOME_EXECUTED
+    };
+
+    CrankOffer {
+        balance: new_balance,
+        offer_blob: OfferSettledBlob {
+            outcome,
+            executed_amt,
+            quote_paid: quote_executed,
+            quote_refund,
+        },
+        balance_blob: new_balance.into(),
+        error,
+    }
+}
+
+/// Outputs of [`top_up`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TopUp {
+    pub balance: BalanceState,
+    pub balance_blob: BalanceUpdatedBlob,
+    pub error: u8,
+}
+
+/// Credits a deposit to the available balance. `is_new` ignores `balance` and starts
+/// from zero, as the program does for a freshly created balance account.
+pub fn top_up(balance: &BalanceState, amount: u64, is_new: bool) -> TopUp {
+    let state = if is_new {
+        BalanceState::default()
+    } else {
+        *balance
+    };
+
+    let (new_amount, overflow) = checked_add(state.amount, amount);
+    let new_state = BalanceState {
+        amount: new_amount,
+        ..state
+    };
+
+    TopUp {
+        balance: new_state,
+        balance_blob: new_state.into(),
+        error: error_code(false, overflow),
+    }
+}
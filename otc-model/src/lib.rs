@@ -0,0 +1,21 @@
+//! Cleartext reference model of the OTC circuits.
+//!
+//! The circuits in `encrypted-ixs` only run inside an Arcium MPC cluster. This crate
+//! mirrors each `#[instruction]` over plain structs, with the same field names, rounding,
+//! error codes and "return the inputs unchanged on error" behaviour, so their logic can be
+//! property-tested in-process and diffed against the MPC results from the localnet harness.
+//!
+//! Encryption is left out: every `Enc<_, T>` becomes a plain `T`, and circuits that seal a
+//! blob to both the owner and their auditor return it once.
+//!
+//! Keep this crate in lockstep with `encrypted-ixs/src/lib.rs`: any change to a mirrored
+//! circuit must land here in the same commit.
+
+pub mod circuits;
+pub mod state;
+
+pub use circuits::{
+    announce_balance, crank_deal, crank_offer, create_deal, submit_offer, top_up, CrankDeal,
+    CrankOffer, CreateDeal, InputRules, PriceScale, SubmitOffer, TopUp,
+};
+pub use state::*;
@@ -0,0 +1,146 @@
+//! Cleartext counterparts of the circuit structs and status codes.
+//!
+//! Field names and order match `encrypted-ixs`; see the doc comments there for units.
+
+// Deal statuses revealed by settlement circuits (see otc::state::DealStatus)
+pub const DEAL_OPEN: u8 = 0;
+pub const DEAL_EXECUTED: u8 = 1;
+pub const DEAL_EXPIRED: u8 = 2;
+
+// Outcomes sealed in OfferSettledBlob (see otc::state::OfferOutcome)
+pub const OUTCOME_EXECUTED: u8 = 0;
+pub const OUTCOME_PARTIAL: u8 = 1;
+pub const OUTCOME_FAILED: u8 = 2;
+
+/// Error codes revealed as the last output of balance-mutating circuits.
+/// Mirrors `otc::error::CircuitError`.
+pub struct CircuitError;
+
+impl CircuitError {
+    /// Computation completed without arithmetic errors
+    pub const NONE: u8 = 0;
+    /// A subtraction would have gone below zero
+    pub const UNDERFLOW: u8 = 1;
+    /// An addition or price multiplication would have exceeded u64
+    pub const OVERFLOW: u8 = 2;
+    /// A price was off the tick size or an amount off the lot size
+    pub const OFF_TICK_OR_LOT: u8 = 3;
+    /// A price was outside the pair's oracle price band
+    pub const OUT_OF_BAND: u8 = 4;
+    /// The notional exceeded the signing delegate's limit
+    pub const NOTIONAL_EXCEEDED: u8 = 5;
+
+    /// True for codes `create_deal` and `submit_offer` reject the deal or offer with
+    /// instead of failing the callback: invalid or oversized inputs.
+    pub fn is_rejection(code: u8) -> bool {
+        matches!(
+            code,
+            Self::OVERFLOW | Self::OFF_TICK_OR_LOT | Self::OUT_OF_BAND | Self::NOTIONAL_EXCEEDED
+        )
+    }
+}
+
+/// Input from creator
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DealInput {
+    /// Base asset amount the creator is selling (raw base units)
+    pub amount: u64,
+    /// X64.64 fixed-point price in human units (whole quote per whole base)
+    pub price: u128,
+}
+
+/// Deal state stored on-chain
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DealState {
+    /// Base asset amount
+    pub amount: u64,
+    /// X64.64 fixed-point price in raw units (decimals applied)
+    pub price: u128,
+    /// Running total of filled amount
+    pub fill_amount: u64,
+}
+
+/// Blob sealed to creator on DealCreated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DealCreatedBlob {
+    pub amount: u64,
+    /// Human-unit price, as entered
+    pub price: u128,
+}
+
+/// Blob sealed to creator on DealSettled
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DealSettledBlob {
+    /// Total amount filled across all offers
+    pub total_filled: u64,
+    /// Quote tokens the creator receives
+    pub creator_receives: u64,
+    /// Base tokens refunded to creator (unfilled)
+    pub creator_refund: u64,
+}
+
+/// Input from offeror
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OfferInput {
+    /// X64.64 fixed-point price in human units (max price willing to pay)
+    pub price: u128,
+    /// Amount of base asset to buy
+    pub amount: u64,
+}
+
+/// Offer state stored on-chain
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OfferState {
+    /// X64.64 fixed-point price in raw units (decimals applied)
+    pub price: u128,
+    /// Amount of base asset to buy
+    pub amount: u64,
+    /// Amount to execute (computed at submission based on deal availability)
+    pub amt_to_execute: u64,
+}
+
+/// Blob sealed to offeror on OfferCreated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OfferCreatedBlob {
+    /// Human-unit price, as entered
+    pub price: u128,
+    pub amount: u64,
+}
+
+/// Blob sealed to offeror on OfferSettled
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OfferSettledBlob {
+    /// OUTCOME_EXECUTED, OUTCOME_PARTIAL or OUTCOME_FAILED
+    pub outcome: u8,
+    /// Amount of base asset bought
+    pub executed_amt: u64,
+    /// Quote tokens paid by offeror
+    pub quote_paid: u64,
+    /// Quote tokens refunded to offeror
+    pub quote_refund: u64,
+}
+
+/// Balance state stored on-chain
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BalanceState {
+    /// Available balance amount
+    pub amount: u64,
+    /// Amount committed to open deals/offers (locked)
+    pub committed_amount: u64,
+}
+
+/// Blob sealed to owner on BalanceUpdated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BalanceUpdatedBlob {
+    pub amount: u64,
+    pub committed_amount: u64,
+}
+
+impl From<BalanceState> for BalanceUpdatedBlob {
+    fn from(balance: BalanceState) -> Self {
+        Self {
+            amount: balance.amount,
+            committed_amount: balance.committed_amount,
+        }
+    }
+}
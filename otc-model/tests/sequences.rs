@@ -0,0 +1,348 @@
+//! Random deal/offer sequences run end to end through the model, checking the invariants
+//! the program relies on but can only observe through encrypted state.
+
+use fixed_point::{mul_price_down, mul_price_up, ONE};
+use otc_model::*;
+use proptest::prelude::*;
+
+const OFFERORS: usize = 3;
+
+#[derive(Clone, Debug)]
+struct Offer {
+    offeror: usize,
+    input: OfferInput,
+}
+
+/// Prices between 0 and 2^16 quote per base
+fn price() -> impl Strategy<Value = u128> {
+    0..(1u128 << 80)
+}
+
+/// Base amounts small enough that any fill at any price() fits in u64 many times over
+fn amount() -> impl Strategy<Value = u64> {
+    0..(1u64 << 30)
+}
+
+fn offers() -> impl Strategy<Value = Vec<Offer>> {
+    offers_below(1 << 80)
+}
+
+fn offers_below(max_price: u128) -> impl Strategy<Value = Vec<Offer>> {
+    prop::collection::vec(
+        (0..OFFERORS, 0..max_price, amount()).prop_map(|(offeror, price, amount)| Offer {
+            offeror,
+            input: OfferInput { price, amount },
+        }),
+        0..12,
+    )
+}
+
+fn funded(amount: u64) -> BalanceState {
+    let result = top_up(&BalanceState::default(), amount, true);
+    assert_eq!(result.error, CircuitError::NONE);
+    result.balance
+}
+
+/// Everything a settled market leaves behind.
+struct Settlement {
+    creator_before: BalanceState,
+    creator_after: BalanceState,
+    offerors_before: [BalanceState; OFFERORS],
+    offerors_after: [BalanceState; OFFERORS],
+    deal: DealState,
+    deal_blob: DealSettledBlob,
+    status: u8,
+    offer_blobs: Vec<(usize, OfferState, OfferSettledBlob)>,
+}
+
+/// Creates a deal, submits every offer from a funded offeror, cranks the deal as expired
+/// and then cranks every offer. Every circuit must succeed.
+fn run(
+    deal_input: DealInput,
+    allow_partial: bool,
+    offers: &[Offer],
+    scale: PriceScale,
+) -> Result<Settlement, TestCaseError> {
+    let creator_before = funded(deal_input.amount);
+    let created = create_deal(deal_input, &creator_before, scale, &InputRules::default());
+    prop_assert_eq!(created.error, CircuitError::NONE);
+    prop_assert_eq!(created.balance.committed_amount, deal_input.amount);
+
+    // Each offeror deposits exactly what its offers lock
+    let mut deposits = [0u64; OFFERORS];
+    for offer in offers {
+        let locked = mul_price_up(
+            offer.input.amount,
+            offer.input.price * scale.up / scale.down,
+        )
+        .expect("price() and amount() keep notionals in u64");
+        deposits[offer.offeror] += locked;
+    }
+    let offerors_before = deposits.map(funded);
+
+    let mut deal = created.deal;
+    let mut offerors = offerors_before;
+    let mut states = Vec::new();
+    for offer in offers {
+        let submitted = submit_offer(
+            &deal,
+            offer.input,
+            &offerors[offer.offeror],
+            scale,
+            &InputRules::default(),
+        );
+        prop_assert_eq!(submitted.error, CircuitError::NONE);
+        prop_assert!(submitted.deal.fill_amount <= submitted.deal.amount);
+        prop_assert!(submitted.offer.amt_to_execute <= submitted.offer.amount);
+        prop_assert_eq!(
+            submitted.deal.fill_amount,
+            deal.fill_amount + submitted.offer.amt_to_execute
+        );
+        deal = submitted.deal;
+        offerors[offer.offeror] = submitted.balance;
+        states.push((offer.offeror, submitted.offer));
+    }
+
+    let cranked = crank_deal(&deal, &created.balance, true, allow_partial);
+    prop_assert_eq!(cranked.error, CircuitError::NONE);
+    prop_assert_ne!(cranked.status, DEAL_OPEN);
+
+    let mut offer_blobs = Vec::new();
+    for (offeror, offer) in states {
+        let settled = crank_offer(
+            &deal,
+            &offer,
+            &offerors[offeror],
+            cranked.status == DEAL_EXECUTED,
+            false,
+        );
+        prop_assert_eq!(settled.error, CircuitError::NONE);
+        offerors[offeror] = settled.balance;
+        offer_blobs.push((offeror, offer, settled.offer_blob));
+    }
+
+    Ok(Settlement {
+        creator_before,
+        creator_after: cranked.balance,
+        offerors_before,
+        offerors_after: offerors,
+        deal,
+        deal_blob: cranked.deal_blob,
+        status: cranked.status,
+        offer_blobs,
+    })
+}
+
+fn scale() -> impl Strategy<Value = PriceScale> {
+    (0u8..=9, 0u8..=9).prop_map(|(base, quote)| PriceScale::from_decimals(base, quote).unwrap())
+}
+
+proptest! {
+    #[test]
+    fn fills_never_exceed_the_deal(
+        deal_amount in amount(),
+        deal_price in price(),
+        allow_partial in any::<bool>(),
+        offers in offers(),
+    ) {
+        let deal_input = DealInput { amount: deal_amount, price: deal_price };
+        let s = run(deal_input, allow_partial, &offers, PriceScale::default())?;
+
+        prop_assert!(s.deal.fill_amount <= s.deal.amount);
+        let scheduled: u64 = s.offer_blobs.iter().map(|(_, offer, _)| offer.amt_to_execute).sum();
+        prop_assert_eq!(scheduled, s.deal.fill_amount);
+        prop_assert_eq!(s.deal_blob.total_filled + s.deal_blob.creator_refund, deal_amount);
+    }
+
+    #[test]
+    fn settlement_conserves_balances(
+        deal_amount in amount(),
+        // Leaves room for up to nine extra quote decimals
+        deal_price in 0..(1u128 << 50),
+        allow_partial in any::<bool>(),
+        offers in offers_below(1 << 50),
+        scale in scale(),
+    ) {
+        let deal_input = DealInput { amount: deal_amount, price: deal_price };
+        let s = run(deal_input, allow_partial, &offers, scale)?;
+
+        // BASE leaving the creator is exactly what offerors bought
+        let bought: u64 = s.offer_blobs.iter().map(|(_, _, blob)| blob.executed_amt).sum();
+        prop_assert_eq!(s.creator_before.amount - s.creator_after.amount, bought);
+        prop_assert_eq!(s.deal_blob.total_filled, bought);
+
+        // QUOTE leaving offerors covers what the creator receives
+        let mut paid = [0u64; OFFERORS];
+        for (offeror, offer, blob) in &s.offer_blobs {
+            let committed = mul_price_up(offer.amount, offer.price).unwrap();
+            prop_assert_eq!(blob.quote_paid + blob.quote_refund, committed);
+            paid[*offeror] += blob.quote_paid;
+        }
+        for ((before, after), paid) in s.offerors_before.iter().zip(&s.offerors_after).zip(paid) {
+            prop_assert_eq!(before.amount - after.amount, paid);
+        }
+        prop_assert!(s.deal_blob.creator_receives <= paid.iter().sum::<u64>());
+
+        // Every commitment is released once everything has settled
+        prop_assert_eq!(s.creator_after.committed_amount, 0);
+        for balance in s.offerors_after {
+            prop_assert_eq!(balance.committed_amount, 0);
+        }
+    }
+
+    #[test]
+    fn outcomes_match_fills(
+        deal_amount in amount(),
+        deal_price in price(),
+        allow_partial in any::<bool>(),
+        offers in offers(),
+    ) {
+        let deal_input = DealInput { amount: deal_amount, price: deal_price };
+        let s = run(deal_input, allow_partial, &offers, PriceScale::default())?;
+
+        let executed = s.status == DEAL_EXECUTED;
+        prop_assert_eq!(
+            executed,
+            s.deal.fill_amount >= deal_amount || (allow_partial && s.deal.fill_amount > 0)
+        );
+        for (_, offer, blob) in &s.offer_blobs {
+            let expected = if !executed || offer.amt_to_execute == 0 {
+                OUTCOME_FAILED
+            } else if offer.amt_to_execute < offer.amount {
+                OUTCOME_PARTIAL
+            } else {
+                OUTCOME_EXECUTED
+            };
+            prop_assert_eq!(blob.outcome, expected);
+            prop_assert!(blob.executed_amt <= offer.amt_to_execute);
+        }
+    }
+
+    #[test]
+    fn open_deals_do_not_settle_early(
+        deal_amount in 1..(1u64 << 30),
+        fill in amount(),
+        deal_price in price(),
+        allow_partial in any::<bool>(),
+    ) {
+        let deal = DealState { amount: deal_amount, price: deal_price, fill_amount: fill % deal_amount };
+        let balance = BalanceState { amount: deal_amount, committed_amount: deal_amount };
+        let cranked = crank_deal(&deal, &balance, false, allow_partial);
+
+        prop_assert_eq!(cranked.status, DEAL_OPEN);
+        prop_assert_eq!(cranked.error, CircuitError::NONE);
+        prop_assert_eq!(cranked.balance, balance);
+        prop_assert_eq!(cranked.deal_blob.creator_refund, 0);
+    }
+
+    #[test]
+    fn rejected_inputs_leave_state_untouched(
+        deal in (amount(), price(), amount()).prop_map(|(amount, price, fill)| DealState {
+            amount,
+            price,
+            fill_amount: fill.min(amount),
+        }),
+        input in (price(), amount()).prop_map(|(price, amount)| OfferInput { price, amount }),
+        balance in (amount(), amount())
+            .prop_map(|(amount, committed_amount)| BalanceState { amount, committed_amount }),
+        max_price in price(),
+    ) {
+        let rules = InputRules {
+            tick_size: ONE,
+            lot_size: 7,
+            max_price,
+            ..InputRules::default()
+        };
+        let submitted = submit_offer(&deal, input, &balance, PriceScale::default(), &rules);
+        if CircuitError::is_rejection(submitted.error) {
+            prop_assert_eq!(submitted.deal, deal);
+            prop_assert_eq!(submitted.balance, balance);
+            prop_assert_eq!(submitted.offer, OfferState::default());
+
+            // An empty offer settles as FAILED without touching the balance
+            let settled = crank_offer(&deal, &submitted.offer, &balance, true, false);
+            prop_assert_eq!(settled.error, CircuitError::NONE);
+            prop_assert_eq!(settled.balance, balance);
+            prop_assert_eq!(settled.offer_blob.outcome, OUTCOME_FAILED);
+        } else {
+            prop_assert_eq!(submitted.error, CircuitError::NONE);
+            prop_assert_eq!(input.price % ONE, 0);
+            prop_assert_eq!(input.amount % 7, 0);
+            prop_assert!(input.price <= max_price);
+        }
+    }
+
+    #[test]
+    fn oversized_inputs_are_rejected(
+        deal_amount in (1u64 << 40)..=u64::MAX,
+        // At least 2^24 quote per base, so the notional exceeds u64
+        offer_price in (1u128 << 88)..(1u128 << 100),
+        balance in (amount(), amount())
+            .prop_map(|(amount, committed_amount)| BalanceState { amount, committed_amount }),
+    ) {
+        // The deal itself fits, but committing it on top of a full balance does not
+        let full = BalanceState { committed_amount: u64::MAX, ..balance };
+        let input = DealInput { amount: deal_amount, price: ONE };
+        let created = create_deal(input, &full, PriceScale::default(), &InputRules::default());
+        prop_assert_eq!(created.error, CircuitError::OVERFLOW);
+        prop_assert!(CircuitError::is_rejection(created.error));
+        prop_assert_eq!(created.deal, DealState::default());
+        prop_assert_eq!(created.balance, full);
+
+        let deal = DealState { amount: deal_amount, price: ONE, fill_amount: 0 };
+        let input = OfferInput { price: offer_price, amount: deal_amount };
+        let rules = InputRules::default();
+        let submitted = submit_offer(&deal, input, &balance, PriceScale::default(), &rules);
+        prop_assert_eq!(submitted.error, CircuitError::OVERFLOW);
+        prop_assert!(CircuitError::is_rejection(submitted.error));
+        prop_assert_eq!(submitted.deal, deal);
+        prop_assert_eq!(submitted.balance, balance);
+        prop_assert_eq!(submitted.offer, OfferState::default());
+    }
+
+    #[test]
+    fn floor_locked_offers_release_what_they_locked(
+        deal_price in price(),
+        offer_price in price(),
+        amount in amount(),
+        fill in amount(),
+        other_commitments in amount(),
+        deal_success in any::<bool>(),
+    ) {
+        // Offers submitted before the rounding policy locked amount * price rounded down
+        let deal = DealState { amount, price: deal_price.min(offer_price), fill_amount: 0 };
+        let offer = OfferState { price: offer_price, amount, amt_to_execute: fill.min(amount) };
+        let locked = mul_price_down(amount, offer_price).unwrap();
+        let balance = BalanceState {
+            amount: locked + other_commitments,
+            committed_amount: locked + other_commitments,
+        };
+
+        let settled = crank_offer(&deal, &offer, &balance, deal_success, true);
+        prop_assert_eq!(settled.error, CircuitError::NONE);
+        prop_assert_eq!(settled.balance.committed_amount, other_commitments);
+        let blob = settled.offer_blob;
+        prop_assert_eq!(blob.quote_paid + blob.quote_refund, locked);
+        prop_assert_eq!(settled.balance.amount, balance.amount - blob.quote_paid);
+    }
+
+    #[test]
+    fn top_up_overflow_keeps_the_balance(
+        balance in (any::<u64>(), any::<u64>())
+            .prop_map(|(amount, committed_amount)| BalanceState { amount, committed_amount }),
+        deposit in any::<u64>(),
+    ) {
+        let result = top_up(&balance, deposit, false);
+        match balance.amount.checked_add(deposit) {
+            Some(amount) => {
+                prop_assert_eq!(result.error, CircuitError::NONE);
+                prop_assert_eq!(result.balance, BalanceState { amount, ..balance });
+            }
+            None => {
+                prop_assert_eq!(result.error, CircuitError::OVERFLOW);
+                prop_assert_eq!(result.balance, balance);
+            }
+        }
+        prop_assert_eq!(result.balance_blob, announce_balance(&result.balance));
+    }
+}
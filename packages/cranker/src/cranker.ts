@@ -7,7 +7,12 @@ import { Otc } from "../../../target/types/otc";
 import { Config } from "./config";
 import { logger } from "./log";
 import { getExpiredOpenDeals, getOpenOffersForSettledDeals } from "./queries";
-import { executeCrankDeal, executeCrankOffer } from "./execute";
+import {
+  executeCrankDeal,
+  executeCrankOffer,
+  executeCrankOffersBatch,
+} from "./execute";
+import { CRANK_BATCH_SIZE } from "./transactions";
 import * as fs from "fs";
 
 // IDL import
@@ -81,18 +86,39 @@ async function runCrankIteration(
       logger.info("Found offers to crank", { count: offers.length });
     }
 
+    // Group by deal so offers sharing a deal settle in one batched computation
+    const offersByDeal = new Map<string, string[]>();
     for (const offer of offers) {
-      const result = await executeCrankOffer(
-        provider,
-        program,
-        payer,
-        offer.address,
-        offer.dealAddress,
-        clusterOffset
-      );
+      const group = offersByDeal.get(offer.dealAddress) ?? [];
+      group.push(offer.address);
+      offersByDeal.set(offer.dealAddress, group);
+    }
 
-      if (result.success) {
-        offersCranked++;
+    for (const [dealAddress, offerAddresses] of offersByDeal) {
+      for (let i = 0; i < offerAddresses.length; i += CRANK_BATCH_SIZE) {
+        const chunk = offerAddresses.slice(i, i + CRANK_BATCH_SIZE);
+        const results =
+          chunk.length === 1
+            ? [
+                await executeCrankOffer(
+                  provider,
+                  program,
+                  payer,
+                  chunk[0],
+                  dealAddress,
+                  clusterOffset
+                ),
+              ]
+            : await executeCrankOffersBatch(
+                provider,
+                program,
+                payer,
+                chunk,
+                dealAddress,
+                clusterOffset
+              );
+
+        offersCranked += results.filter((result) => result.success).length;
       }
     }
   } catch (error) {
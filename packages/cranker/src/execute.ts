@@ -8,6 +8,7 @@ import { logger } from "./log";
 import {
   buildCrankDealAccounts,
   buildCrankOfferAccounts,
+  buildCrankOffersBatchAccounts,
   generateComputationOffset,
   generateNonce,
 } from "./transactions";
@@ -158,3 +159,89 @@ export async function executeCrankOffer(
     };
   }
 }
+
+/**
+ * Execute crank_offers_batch instruction for up to CRANK_BATCH_SIZE offers on one settled deal
+ */
+export async function executeCrankOffersBatch(
+  provider: anchor.AnchorProvider,
+  program: Program<Otc>,
+  payer: Keypair,
+  offerAddresses: string[],
+  dealAddress: string,
+  clusterOffset: number
+): Promise<CrankResult[]> {
+  const deal = new PublicKey(dealAddress);
+  const offers = offerAddresses.map((address) => new PublicKey(address));
+  const computationOffset = generateComputationOffset();
+
+  logger.debug("Executing crank_offers_batch", {
+    deal: dealAddress,
+    offers: offerAddresses.length,
+    computationOffset: computationOffset.toString(),
+  });
+
+  try {
+    // Fetch offer and deal accounts to get controllers and quoteMint for balance PDAs
+    const offerAccounts = await program.account.offerAccount.fetchMultiple(
+      offers
+    );
+    const dealAccount = await program.account.dealAccount.fetch(deal);
+
+    const { accounts, remainingAccounts } = buildCrankOffersBatchAccounts(
+      program.programId,
+      payer.publicKey,
+      deal,
+      offers.map((offer, i) => {
+        const offerAccount = offerAccounts[i];
+        if (!offerAccount) {
+          throw new Error(`Offer account not found: ${offer.toBase58()}`);
+        }
+        return { offer, controller: offerAccount.controller };
+      }),
+      dealAccount.quoteMint,
+      computationOffset,
+      clusterOffset
+    );
+
+    const signature = await program.methods
+      .crankOffersBatch(computationOffset)
+      .accountsPartial(accounts)
+      .remainingAccounts(remainingAccounts)
+      .signers([payer])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+
+    logger.debug("Crank offers batch queued", { deal: dealAddress, signature });
+
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    logger.info("Crank offers batch finalized", {
+      deal: dealAddress,
+      offers: offerAddresses.length,
+      signature,
+    });
+
+    return offerAddresses.map((address) => ({
+      success: true,
+      address,
+      signature,
+    }));
+  } catch (error) {
+    const errorMessage = error instanceof Error ? error.message : String(error);
+    logger.warn("Failed to crank offers batch", {
+      deal: dealAddress,
+      error: errorMessage,
+    });
+
+    return offerAddresses.map((address) => ({
+      success: false,
+      address,
+      error: errorMessage,
+    }));
+  }
+}
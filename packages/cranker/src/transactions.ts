@@ -90,6 +90,59 @@ export function buildCrankOfferAccounts(
   };
 }
 
+/**
+ * Maximum number of offers settled by one crank_offers_batch computation
+ */
+export const CRANK_BATCH_SIZE = 8;
+
+/**
+ * Build accounts for crank_offers_batch instruction.
+ * Offers and their offeror QUOTE balances are passed as remaining accounts.
+ */
+export function buildCrankOffersBatchAccounts(
+  programId: PublicKey,
+  payer: PublicKey,
+  deal: PublicKey,
+  offers: { offer: PublicKey; controller: PublicKey }[],
+  quoteMint: PublicKey,
+  computationOffset: anchor.BN,
+  clusterOffset: number
+): {
+  accounts: Record<string, PublicKey>;
+  remainingAccounts: anchor.web3.AccountMeta[];
+} {
+  const remainingAccounts = offers.flatMap(({ offer, controller }) => {
+    const [offerorBalance] = PublicKey.findProgramAddressSync(
+      [Buffer.from("balance"), controller.toBuffer(), quoteMint.toBuffer()],
+      programId
+    );
+    return [
+      { pubkey: offer, isSigner: false, isWritable: true },
+      { pubkey: offerorBalance, isSigner: false, isWritable: true },
+    ];
+  });
+
+  return {
+    accounts: {
+      payer,
+      deal,
+      computationAccount: getComputationAccAddress(
+        clusterOffset,
+        computationOffset
+      ),
+      clusterAccount: getClusterAccAddress(clusterOffset),
+      mxeAccount: getMXEAccAddress(programId),
+      mempoolAccount: getMempoolAccAddress(clusterOffset),
+      executingPool: getExecutingPoolAccAddress(clusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        programId,
+        Buffer.from(getCompDefAccOffset("crank_offers_batch")).readUInt32LE()
+      ),
+    },
+    remainingAccounts,
+  };
+}
+
 /**
  * Generate a random computation offset (8 bytes)
  */
@@ -14,6 +14,7 @@ import type {
   DealCreatedData,
   OfferCreatedData,
   DealSettledData,
+  DealStatus,
   OfferSettledData,
   BalanceUpdatedData,
 } from "../types";
@@ -61,10 +62,10 @@ function ciphertextsToBytea(ciphertexts: number[][]): string {
 }
 
 /**
- * Map status u8 to database status string
+ * Map the DealStatus enum to database status string
  */
-function statusToString(status: number): "executed" | "expired" {
-  return status === 1 ? "executed" : "expired";
+function statusToString(status: DealStatus): "executed" | "expired" {
+  return "executed" in status ? "executed" : "expired";
 }
 
 /**
@@ -48,12 +48,22 @@ export type OfferCreatedData = {
   ciphertexts: number[][]; // [[u8; 32]; 2]
 };
 
+/**
+ * DealStatus enum as decoded by Anchor BorshCoder (one key per variant)
+ */
+export type DealStatus =
+  | { open: Record<string, never> }
+  | { executed: Record<string, never> }
+  | { expired: Record<string, never> }
+  | { rejected: Record<string, never> }
+  | { settling: Record<string, never> };
+
 /**
  * DealSettled event data (snake_case from Anchor BorshCoder)
  */
 export type DealSettledData = {
   deal: PublicKey;
-  status: number; // u8: 1 = executed, 2 = expired
+  status: DealStatus; // executed or expired
   settled_at: BN;
   encryption_key: number[]; // [u8; 32]
   nonce: number[]; // [u8; 16]
@@ -20,4 +20,143 @@ pub enum ErrorCode {
     DealMismatch,
     #[msg("Controller does not match existing balance account")]
     ControllerMismatch,
+    #[msg("Invalid counterparty restriction")]
+    InvalidCounterpartyRestriction,
+    #[msg("Controller is not an allowed counterparty for this deal")]
+    CounterpartyNotAllowed,
+    #[msg("RFQ is not open")]
+    RfqNotOpen,
+    #[msg("RFQ deadline has passed")]
+    RfqExpired,
+    #[msg("RFQ has not been settled yet")]
+    RfqNotSettled,
+    #[msg("Quote has already been settled")]
+    QuoteAlreadySettled,
+    #[msg("Quote does not belong to this RFQ")]
+    RfqMismatch,
+    #[msg("Controller cannot trade against itself")]
+    SelfTrade,
+    #[msg("Cannot trade against a linked controller")]
+    LinkedControllers,
+    #[msg("Batch must contain between 1 and 8 (offer, balance) pairs")]
+    InvalidBatchSize,
+    #[msg("Account appears more than once in the batch")]
+    DuplicateBatchAccount,
+    #[msg("Settlement must include every offer of the deal")]
+    IncompleteOfferSet,
+    #[msg("Encrypted arithmetic underflowed; state left unchanged")]
+    ArithmeticUnderflow,
+    #[msg("Encrypted arithmetic overflowed; state left unchanged")]
+    ArithmeticOverflow,
+    #[msg("Base and quote mint decimals differ by more than 18")]
+    UnsupportedDecimals,
+    #[msg("Price is off the tick size or amount is off the lot size")]
+    OffTickOrLot,
+    #[msg("Tick size and lot size must be greater than zero")]
+    InvalidTickOrLotSize,
+    #[msg("Price band must be at most 10000 basis points")]
+    InvalidPriceBand,
+    #[msg("Price feed does not match the pair's configured feed")]
+    PriceFeedMismatch,
+    #[msg("Price feed has not been updated recently")]
+    StalePriceFeed,
+    #[msg("Price is outside the oracle price band")]
+    PriceOutOfBand,
+    #[msg("Cannot transfer to the same balance")]
+    SelfTransfer,
+    #[msg("Delegate has expired")]
+    DelegateExpired,
+    #[msg("Delegate lacks the permission for this action")]
+    DelegatePermissionDenied,
+    #[msg("Delegate is not allowed to trade this pair")]
+    DelegatePairNotAllowed,
+    #[msg("Notional exceeds the delegate's limit")]
+    DelegateNotionalExceeded,
+    #[msg("Invalid delegate permissions, pairs or expiry")]
+    InvalidDelegate,
+    #[msg("Relayed instruction must follow an ed25519 signature instruction")]
+    MissingRelaySignature,
+    #[msg("Relayed signature does not cover this instruction and nonce")]
+    InvalidRelaySignature,
+    #[msg("Multisig threshold must be between 1 and the number of distinct members")]
+    InvalidMultisig,
+    #[msg("Signer is not a member of the multisig")]
+    NotMultisigMember,
+    #[msg("Proposal is no longer collecting approvals")]
+    ProposalNotActive,
+    #[msg("Member has already approved this proposal")]
+    ProposalAlreadyApproved,
+    #[msg("Proposal has not reached its approval threshold")]
+    ProposalNotApproved,
+    #[msg("Instruction does not match the approved proposal")]
+    ProposalDigestMismatch,
+    #[msg("Basket legs must be 2 to 4 distinct balances of the controller, excluding the quote mint")]
+    InvalidBasketLegs,
+    #[msg("Basket deals settle through crank_basket_deal, other deals through crank_deal or settle_deal")]
+    BasketDealMismatch,
+    #[msg("Swap deals take offers through submit_swap_offer and settle through crank_swap_deal and crank_swap_offer")]
+    SwapDealMismatch,
+    #[msg("Circuit revealed a status outside the expected range")]
+    InvalidCircuitStatus,
+    #[msg("Account is not a legacy layout of the expected type")]
+    InvalidAccountLayout,
+    #[msg("Account already uses the current layout")]
+    AccountAlreadyMigrated,
+    #[msg("Mint does not match the one recorded on the deal")]
+    MintMismatch,
+    #[msg("Offer was rejected at submission and has nothing to settle")]
+    OfferWasRejected,
+    #[msg("Quote was rejected at submission and has nothing to settle")]
+    QuoteWasRejected,
+}
+
+/// Arithmetic error codes revealed by balance-mutating circuits
+pub struct CircuitError;
+
+impl CircuitError {
+    /// Computation completed without arithmetic errors
+    pub const NONE: u8 = 0;
+    /// A subtraction would have gone below zero
+    pub const UNDERFLOW: u8 = 1;
+    /// An addition or price multiplication would have exceeded u64
+    /// (the inputs were too large when creating a deal or submitting an offer)
+    pub const OVERFLOW: u8 = 2;
+    /// A price was off the tick size or an amount off the lot size
+    pub const OFF_TICK_OR_LOT: u8 = 3;
+    /// A price was outside the pair's oracle price band
+    pub const OUT_OF_BAND: u8 = 4;
+    /// The notional exceeded the signing delegate's limit
+    pub const NOTIONAL_EXCEEDED: u8 = 5;
+
+    /// Maps a revealed circuit error code to a program error.
+    ///
+    /// Circuits return their inputs unchanged on error, and callbacks bail out
+    /// before writing anything, so corrupted encrypted state is never stored.
+    /// The circuits that create deals and RFQs or submit offers and quotes handle
+    /// rejections (see `is_rejection`) themselves by rejecting the new account instead.
+    pub fn check(code: u8) -> Result<()> {
+        match code {
+            Self::NONE => Ok(()),
+            Self::UNDERFLOW => err!(ErrorCode::ArithmeticUnderflow),
+            Self::OVERFLOW => err!(ErrorCode::ArithmeticOverflow),
+            Self::OFF_TICK_OR_LOT => err!(ErrorCode::OffTickOrLot),
+            Self::OUT_OF_BAND => err!(ErrorCode::PriceOutOfBand),
+            Self::NOTIONAL_EXCEEDED => err!(ErrorCode::DelegateNotionalExceeded),
+            _ => err!(ErrorCode::AbortedComputation),
+        }
+    }
+
+    /// True for codes caused by the encrypted inputs of a new deal, offer, RFQ or quote
+    /// rather than by broken invariants: invalid prices or amounts, and amounts too large
+    /// to lock. The circuit zeroes the new account's state and leaves balances untouched.
+    ///
+    /// Only meaningful for the circuits that create deals and RFQs or submit offers and
+    /// quotes, where an overflow can only come from the inputs; everywhere else it fails
+    /// the callback.
+    pub fn is_rejection(code: u8) -> bool {
+        matches!(
+            code,
+            Self::OVERFLOW | Self::OFF_TICK_OR_LOT | Self::OUT_OF_BAND | Self::NOTIONAL_EXCEEDED
+        )
+    }
 }
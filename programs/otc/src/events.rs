@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::state::{DealStatus, RfqStatus};
+
 /// Emitted when a new deal is created.
 /// Contains public metadata for indexing and an encrypted blob
 /// decryptable only by the deal creator.
@@ -19,7 +21,16 @@ pub struct DealCreated {
     /// Nonce used for encryption
     pub nonce: [u8; 16],
     /// Encrypted DealCreatedBlob: amount (u64), price (u128)
+    /// (SwapDealCreatedBlob for swap deals: give_amount (u64), want_amount (u64))
     pub ciphertexts: [[u8; 32]; 2],
+
+    // Copy of the blob for the owner's auditor (sealed to the owner if none is registered)
+    /// The auditor's x25519 public key
+    pub auditor_encryption_key: [u8; 32],
+    /// Nonce used for the auditor copy
+    pub auditor_nonce: [u8; 16],
+    /// Same blob encrypted to the auditor
+    pub auditor_ciphertexts: [[u8; 32]; 2],
 }
 
 /// Emitted when a new offer is submitted to a deal.
@@ -38,7 +49,16 @@ pub struct OfferCreated {
     /// Nonce used for encryption
     pub nonce: [u8; 16],
     /// Encrypted OfferCreatedBlob: price (u128), amount (u64)
+    /// (SwapOfferCreatedBlob for swap deals: give_amount (u64), want_amount (u64))
     pub ciphertexts: [[u8; 32]; 2],
+
+    // Copy of the blob for the owner's auditor (sealed to the owner if none is registered)
+    /// The auditor's x25519 public key
+    pub auditor_encryption_key: [u8; 32],
+    /// Nonce used for the auditor copy
+    pub auditor_nonce: [u8; 16],
+    /// Same blob encrypted to the auditor
+    pub auditor_ciphertexts: [[u8; 32]; 2],
 }
 
 /// Emitted when a deal is settled (executed or expired).
@@ -47,7 +67,7 @@ pub struct OfferCreated {
 #[event]
 pub struct DealSettled {
     pub deal: Pubkey,
-    pub status: u8,
+    pub status: DealStatus,
     pub settled_at: i64,
 
     // Encrypted blob (decryptable by creator)
@@ -57,6 +77,14 @@ pub struct DealSettled {
     pub nonce: [u8; 16],
     /// Encrypted DealSettledBlob: total_filled (u64), creator_receives (u64), creator_refund (u64)
     pub ciphertexts: [[u8; 32]; 3],
+
+    // Copy of the blob for the owner's auditor (sealed to the owner if none is registered)
+    /// The auditor's x25519 public key
+    pub auditor_encryption_key: [u8; 32],
+    /// Nonce used for the auditor copy
+    pub auditor_nonce: [u8; 16],
+    /// Same blob encrypted to the auditor
+    pub auditor_ciphertexts: [[u8; 32]; 3],
 }
 
 /// Emitted when an offer is settled.
@@ -76,6 +104,14 @@ pub struct OfferSettled {
     pub nonce: [u8; 16],
     /// Encrypted OfferSettledBlob: outcome (u8), executed_amt (u64), quote_paid (u64), quote_refund (u64)
     pub ciphertexts: [[u8; 32]; 4],
+
+    // Copy of the blob for the owner's auditor (sealed to the owner if none is registered)
+    /// The auditor's x25519 public key
+    pub auditor_encryption_key: [u8; 32],
+    /// Nonce used for the auditor copy
+    pub auditor_nonce: [u8; 16],
+    /// Same blob encrypted to the auditor
+    pub auditor_ciphertexts: [[u8; 32]; 4],
 }
 
 /// Emitted when a balance is updated (created or topped up).
@@ -94,4 +130,323 @@ pub struct BalanceUpdated {
     pub nonce: [u8; 16],
     /// Encrypted BalanceUpdatedBlob: amount (u64), committed_amount (u64)
     pub ciphertexts: [[u8; 32]; 2],
+
+    // Copy of the blob for the owner's auditor (sealed to the owner if none is registered)
+    /// The auditor's x25519 public key
+    pub auditor_encryption_key: [u8; 32],
+    /// Nonce used for the auditor copy
+    pub auditor_nonce: [u8; 16],
+    /// Same blob encrypted to the auditor
+    pub auditor_ciphertexts: [[u8; 32]; 2],
+}
+
+/// Emitted when a taker publishes a new RFQ.
+/// Contains public metadata for indexing and an encrypted blob
+/// decryptable only by the taker.
+#[event]
+pub struct RfqCreated {
+    // Public metadata (for indexing)
+    pub rfq: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub deadline: i64,
+    pub created_at: i64,
+
+    // Encrypted blob (decryptable by taker)
+    /// The x25519 public key used for encryption (echoed back)
+    pub encryption_key: [u8; 32],
+    /// Nonce used for encryption
+    pub nonce: [u8; 16],
+    /// Encrypted RfqCreatedBlob: size (u64), limit_price (u128)
+    pub ciphertexts: [[u8; 32]; 2],
+
+    // Copy of the blob for the owner's auditor (sealed to the owner if none is registered)
+    /// The auditor's x25519 public key
+    pub auditor_encryption_key: [u8; 32],
+    /// Nonce used for the auditor copy
+    pub auditor_nonce: [u8; 16],
+    /// Same blob encrypted to the auditor
+    pub auditor_ciphertexts: [[u8; 32]; 2],
+}
+
+/// Emitted when a maker submits a quote to an RFQ.
+/// Contains public metadata and an encrypted blob
+/// decryptable only by the maker.
+#[event]
+pub struct QuoteCreated {
+    pub rfq: Pubkey,
+    pub quote: Pubkey,
+    pub quote_index: u32,
+    pub submitted_at: i64,
+
+    // Encrypted blob (decryptable by maker)
+    /// The x25519 public key used for encryption (echoed back)
+    pub encryption_key: [u8; 32],
+    /// Nonce used for encryption
+    pub nonce: [u8; 16],
+    /// Encrypted QuoteCreatedBlob: price (u128), size (u64)
+    pub ciphertexts: [[u8; 32]; 2],
+
+    // Copy of the blob for the owner's auditor (sealed to the owner if none is registered)
+    /// The auditor's x25519 public key
+    pub auditor_encryption_key: [u8; 32],
+    /// Nonce used for the auditor copy
+    pub auditor_nonce: [u8; 16],
+    /// Same blob encrypted to the auditor
+    pub auditor_ciphertexts: [[u8; 32]; 2],
+}
+
+/// Emitted when an RFQ is settled (filled or expired).
+/// Contains the final status and an encrypted blob
+/// decryptable only by the taker.
+#[event]
+pub struct RfqSettled {
+    pub rfq: Pubkey,
+    pub status: RfqStatus,
+    pub settled_at: i64,
+
+    // Encrypted blob (decryptable by taker)
+    /// The x25519 public key used for encryption (echoed back)
+    pub encryption_key: [u8; 32],
+    /// Nonce used for encryption
+    pub nonce: [u8; 16],
+    /// Encrypted RfqSettledBlob: executed_amt (u64), price (u128), quote_paid (u64), quote_refund (u64)
+    pub ciphertexts: [[u8; 32]; 4],
+
+    // Copy of the blob for the owner's auditor (sealed to the owner if none is registered)
+    /// The auditor's x25519 public key
+    pub auditor_encryption_key: [u8; 32],
+    /// Nonce used for the auditor copy
+    pub auditor_nonce: [u8; 16],
+    /// Same blob encrypted to the auditor
+    pub auditor_ciphertexts: [[u8; 32]; 4],
+}
+
+/// Emitted when a maker quote is settled.
+/// Contains public metadata and an encrypted blob
+/// decryptable only by the maker.
+#[event]
+pub struct QuoteSettled {
+    pub rfq: Pubkey,
+    pub quote: Pubkey,
+    pub quote_index: u32,
+    pub settled_at: i64,
+
+    // Encrypted blob (decryptable by maker)
+    /// The x25519 public key used for encryption (echoed back)
+    pub encryption_key: [u8; 32],
+    /// Nonce used for encryption
+    pub nonce: [u8; 16],
+    /// Encrypted QuoteSettledBlob: outcome (u8), executed_amt (u64), quote_receives (u64)
+    pub ciphertexts: [[u8; 32]; 3],
+
+    // Copy of the blob for the owner's auditor (sealed to the owner if none is registered)
+    /// The auditor's x25519 public key
+    pub auditor_encryption_key: [u8; 32],
+    /// Nonce used for the auditor copy
+    pub auditor_nonce: [u8; 16],
+    /// Same blob encrypted to the auditor
+    pub auditor_ciphertexts: [[u8; 32]; 3],
+}
+
+/// Emitted when a crank bounty is paid out.
+/// When the creator cranks their own deal or offer this is a refund.
+#[event]
+pub struct CrankBountyPaid {
+    /// Deal or offer account that held the bounty
+    pub account: Pubkey,
+    /// The cranker, or the deal or offer controller for a refund
+    pub recipient: Pubkey,
+    pub amount: u64,
+    /// The bounty went back to the controller instead of a third-party cranker
+    pub refund: bool,
+}
+
+/// Emitted instead of DealCreated when the circuit rejects a deal
+/// (see CircuitError). Nothing was locked; the deal is marked REJECTED.
+#[event]
+pub struct DealRejected {
+    pub deal: Pubkey,
+    pub controller: Pubkey,
+    /// Revealed circuit error code
+    pub error: u8,
+}
+
+/// Emitted instead of OfferCreated when the circuit rejects an offer
+/// (see CircuitError). Nothing was locked or filled; the offer is marked Rejected and
+/// left out of settlement.
+#[event]
+pub struct OfferRejected {
+    pub deal: Pubkey,
+    pub offer: Pubkey,
+    pub offer_index: u32,
+    /// Revealed circuit error code
+    pub error: u8,
+}
+
+/// Emitted instead of RfqCreated when the circuit rejects an RFQ
+/// (see CircuitError). Nothing was locked; the RFQ is marked Rejected.
+#[event]
+pub struct RfqRejected {
+    pub rfq: Pubkey,
+    pub controller: Pubkey,
+    /// Revealed circuit error code
+    pub error: u8,
+}
+
+/// Emitted instead of QuoteCreated when the circuit rejects a quote
+/// (see CircuitError). Nothing was locked; the quote is marked Rejected and
+/// never settled.
+#[event]
+pub struct QuoteRejected {
+    pub rfq: Pubkey,
+    pub quote: Pubkey,
+    pub quote_index: u32,
+    /// Revealed circuit error code
+    pub error: u8,
+}
+
+/// Emitted when a private transfer between two balances completes or fails.
+/// Only the outcome is public; both parties get a BalanceUpdated blob on success.
+#[event]
+pub struct BalanceTransferred {
+    pub sender_balance: Pubkey,
+    pub recipient_balance: Pubkey,
+    pub mint: Pubkey,
+    /// False if the sender could not cover the amount; no balance changed
+    pub success: bool,
+}
+
+/// Emitted when a balance owner attests proof of funds to a third party.
+/// Contains public metadata and an encrypted blob
+/// decryptable only by the third party.
+#[event]
+pub struct BalanceAttested {
+    pub balance: Pubkey,
+    pub controller: Pubkey,
+    pub mint: Pubkey,
+
+    // Encrypted blob (decryptable by the third party)
+    /// The third party's x25519 public key (echoed back)
+    pub encryption_key: [u8; 32],
+    /// Nonce used for encryption
+    pub nonce: [u8; 16],
+    /// Encrypted AttestationBlob: threshold (u64), covered (bool)
+    pub ciphertexts: [[u8; 32]; 2],
+}
+
+/// Emitted when a deal's encryption key is rotated.
+/// Contains the current deal state re-sealed to the new key.
+#[event]
+pub struct DealAnnounced {
+    pub deal: Pubkey,
+    pub controller: Pubkey,
+
+    // Encrypted blob (decryptable by creator)
+    /// The new x25519 public key used for encryption
+    pub encryption_key: [u8; 32],
+    /// Nonce used for encryption
+    pub nonce: [u8; 16],
+    /// Encrypted DealAnnouncedBlob: amount (u64), price (u128), fill_amount (u64)
+    pub ciphertexts: [[u8; 32]; 3],
+
+    // Copy of the blob for the owner's auditor (sealed to the owner if none is registered)
+    /// The auditor's x25519 public key
+    pub auditor_encryption_key: [u8; 32],
+    /// Nonce used for the auditor copy
+    pub auditor_nonce: [u8; 16],
+    /// Same blob encrypted to the auditor
+    pub auditor_ciphertexts: [[u8; 32]; 3],
+}
+
+/// Emitted when an offer's encryption key is rotated.
+/// Contains the current offer state re-sealed to the new key.
+#[event]
+pub struct OfferAnnounced {
+    pub deal: Pubkey,
+    pub offer: Pubkey,
+    pub controller: Pubkey,
+
+    // Encrypted blob (decryptable by offeror)
+    /// The new x25519 public key used for encryption
+    pub encryption_key: [u8; 32],
+    /// Nonce used for encryption
+    pub nonce: [u8; 16],
+    /// Encrypted OfferAnnouncedBlob: price (u128), amount (u64), amt_to_execute (u64)
+    pub ciphertexts: [[u8; 32]; 3],
+
+    // Copy of the blob for the owner's auditor (sealed to the owner if none is registered)
+    /// The auditor's x25519 public key
+    pub auditor_encryption_key: [u8; 32],
+    /// Nonce used for the auditor copy
+    pub auditor_nonce: [u8; 16],
+    /// Same blob encrypted to the auditor
+    pub auditor_ciphertexts: [[u8; 32]; 3],
+}
+
+/// Emitted when a new basket deal is created.
+/// Contains public metadata for indexing and an encrypted blob
+/// decryptable only by the deal creator. Leg balances are not announced;
+/// owners can run `announce_balance` afterwards.
+#[event]
+pub struct BasketDealCreated {
+    // Public metadata (for indexing)
+    pub deal: Pubkey,
+    pub leg_mints: Vec<Pubkey>,
+    pub quote_mint: Pubkey,
+    pub expires_at: i64,
+    pub allow_partial: bool,
+    pub created_at: i64,
+
+    // Encrypted blob (decryptable by creator)
+    /// The x25519 public key used for encryption (echoed back)
+    pub encryption_key: [u8; 32],
+    /// Nonce used for encryption
+    pub nonce: [u8; 16],
+    /// Encrypted BasketDealCreatedBlob: units (u64), price (u128), leg_amounts ([u64; 4])
+    pub ciphertexts: [[u8; 32]; 6],
+
+    // Copy of the blob for the owner's auditor (sealed to the owner if none is registered)
+    /// The auditor's x25519 public key
+    pub auditor_encryption_key: [u8; 32],
+    /// Nonce used for the auditor copy
+    pub auditor_nonce: [u8; 16],
+    /// Same blob encrypted to the auditor
+    pub auditor_ciphertexts: [[u8; 32]; 6],
+}
+
+/// Emitted when a basket deal is settled (executed or expired).
+/// Contains the final status and an encrypted blob
+/// decryptable only by the deal creator.
+#[event]
+pub struct BasketDealSettled {
+    pub deal: Pubkey,
+    pub status: DealStatus,
+    pub settled_at: i64,
+
+    // Encrypted blob (decryptable by creator)
+    /// The x25519 public key used for encryption (echoed back)
+    pub encryption_key: [u8; 32],
+    /// Nonce used for encryption
+    pub nonce: [u8; 16],
+    /// Encrypted BasketDealSettledBlob: total_filled (u64), creator_receives (u64),
+    /// leg_sold ([u64; 4]), leg_refund ([u64; 4])
+    pub ciphertexts: [[u8; 32]; 10],
+
+    // Copy of the blob for the owner's auditor (sealed to the owner if none is registered)
+    /// The auditor's x25519 public key
+    pub auditor_encryption_key: [u8; 32],
+    /// Nonce used for the auditor copy
+    pub auditor_nonce: [u8; 16],
+    /// Same blob encrypted to the auditor
+    pub auditor_ciphertexts: [[u8; 32]; 10],
+}
+
+/// Emitted when a legacy account is migrated to the current layout.
+#[event]
+pub struct AccountMigrated {
+    /// The migrated DealAccount, OfferAccount or BalanceAccount
+    pub account: Pubkey,
+    /// Layout version after the migration
+    pub version: u8,
 }
@@ -30,8 +30,12 @@ pub fn handler(
 
     ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
+    // Blob copy for the owner's auditor, if they registered one
+    let auditor_key = ctx.accounts.balance.auditor_key_or(encryption_pubkey);
+
     // Build ArgBuilder for announce_balance instruction:
-    // announce_balance(balance_state: Enc<Mxe, &BalanceState>, owner: Shared)
+    // announce_balance(balance_state: Enc<Mxe, &BalanceState>, owner: Shared,
+    //                  auditor: ArcisX25519Pubkey)
     let args = ArgBuilder::new()
         // Enc<Mxe, &BalanceState> - nonce then account reference
         .plaintext_u128(balance_nonce)
@@ -43,6 +47,8 @@ pub fn handler(
         // Shared marker - pubkey then nonce
         .x25519_pubkey(encryption_pubkey)
         .plaintext_u128(owner_nonce)
+        // Auditor key for the blob copy
+        .x25519_pubkey(auditor_key)
         .build();
 
     queue_computation(
@@ -70,7 +76,8 @@ pub fn callback_handler(
     output: SignedComputationOutputs<AnnounceBalanceOutput>,
 ) -> Result<()> {
     // Verify and extract output
-    // Return type is Enc<Shared, BalanceUpdatedBlob>
+    // Return type is [Enc<Shared, BalanceUpdatedBlob>; 2]
+    // Blob arrays hold [owner, auditor] copies
     let shared_blob = match output.verify_output(
         &ctx.accounts.cluster_account,
         &ctx.accounts.computation_account,
@@ -85,9 +92,12 @@ pub fn callback_handler(
         balance: balance.key(),
         controller: balance.controller,
         mint: balance.mint,
-        encryption_key: shared_blob.encryption_key,
-        nonce: shared_blob.nonce.to_le_bytes(),
-        ciphertexts: shared_blob.ciphertexts,
+        encryption_key: shared_blob[0].encryption_key,
+        nonce: shared_blob[0].nonce.to_le_bytes(),
+        ciphertexts: shared_blob[0].ciphertexts,
+        auditor_encryption_key: shared_blob[1].encryption_key,
+        auditor_nonce: shared_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: shared_blob[1].ciphertexts,
     });
 
     Ok(())
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state::{Multisig, Proposal};
+
+pub fn handler(ctx: Context<ApproveProposal>) -> Result<()> {
+    let multisig = &ctx.accounts.multisig;
+    let member_bit = multisig.member_bit(&ctx.accounts.member.key())?;
+    ctx.accounts
+        .proposal
+        .approve(member_bit, multisig.threshold)
+}
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    pub member: Signer<'info>,
+
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.index.to_le_bytes(),
+        ],
+        bump = proposal.bump,
+        has_one = multisig @ ErrorCode::NotMultisigMember,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
@@ -0,0 +1,359 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::{CircuitError, ErrorCode};
+use crate::state::{
+    load_leg_balances, pay_crank_bounty, BalanceAccount, BasketLegs, DealAccount, DealStatus,
+    Delegate, DelegatePermission, MAX_BASKET_LEGS,
+};
+use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
+use crate::state::{BASKET_CIPHERTEXT_LENGTH, BASKET_CIPHERTEXT_OFFSET};
+use crate::state::{DEAL_CIPHERTEXT_LENGTH, DEAL_CIPHERTEXT_OFFSET};
+use crate::{BasketDealSettled, CrankBountyPaid};
+
+const COMP_DEF_OFFSET: u32 = comp_def_offset("crank_basket_deal");
+use crate::{SignerAccount, ID, ID_CONST};
+
+/// Basket settlement outputs (~1 KiB with the auditor copy) need a second callback transaction
+const BASKET_CALLBACK_TXS: u8 = 2;
+
+/// Settles a basket deal across all of its legs.
+///
+/// remaining_accounts: the controller's BASE balance of each leg, in leg order.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CrankBasketDeal<'info>>,
+    computation_offset: u64,
+    creator_deal_blob_nonce: u128,
+) -> Result<()> {
+    // Capture keys and nonces before mutable borrows
+    let deal_key = ctx.accounts.deal.key();
+    let basket_key = ctx.accounts.basket.key();
+    let deal_nonce = u128::from_le_bytes(ctx.accounts.deal.nonce);
+    let basket_nonce = u128::from_le_bytes(ctx.accounts.basket.nonce);
+
+    // Validate deal is open
+    require!(
+        ctx.accounts.deal.status == DealStatus::Open,
+        ErrorCode::DealNotOpen
+    );
+
+    // The balances must be the controller's, in the basket's leg order
+    let controller = ctx.accounts.deal.controller;
+    let quote_mint = ctx.accounts.deal.quote_mint;
+    let leg_mints = ctx.accounts.basket.mints();
+    let legs = load_leg_balances(
+        &controller,
+        &quote_mint,
+        Some(leg_mints),
+        ctx.remaining_accounts,
+    )?;
+
+    // Determine if deal has expired
+    let now = Clock::get()?.unix_timestamp;
+    let is_expired = ctx.accounts.deal.expires_at <= now;
+
+    // Authorization check: anyone can crank after expiry, only controller (or a delegate
+    // holding every leg's pair) before
+    if !is_expired {
+        Delegate::authorize_legs(
+            &ctx.accounts.payer.key(),
+            &controller,
+            ctx.accounts.delegate.as_deref(),
+            DelegatePermission::CRANK,
+            leg_mints,
+            &quote_mint,
+            now,
+        )?;
+    }
+
+    // The bounty pays whoever cranks after expiry. Before expiry only the controller (or a
+    // delegate) may crank, so it is refunded to the controller rather than the signer.
+    let bounty_recipient = if is_expired {
+        ctx.accounts.payer.key()
+    } else {
+        ctx.accounts.deal.controller
+    };
+
+    let allow_partial = ctx.accounts.deal.allow_partial;
+
+    // Blob copy for the creator's auditor, taken from the first leg's balance
+    let owner_key = ctx.accounts.deal.encryption_pubkey;
+    let auditor_key =
+        Account::<BalanceAccount>::try_from(&ctx.remaining_accounts[0])?.auditor_key_or(owner_key);
+
+    // Build ArgBuilder for crank_basket_deal instruction:
+    // crank_basket_deal(deal_state: Enc<Mxe, &DealState>, basket_legs: Enc<Mxe, &BasketLegsState>,
+    //                   leg_balances: [Enc<Mxe, &BalanceState>; 4], num_legs: u8,
+    //                   creator_deal_blob: Shared, is_expired: bool, allow_partial: bool,
+    //                   auditor: ArcisX25519Pubkey)
+    let mut args = ArgBuilder::new()
+        // Enc<Mxe, &DealState>
+        .plaintext_u128(deal_nonce)
+        .account(deal_key, DEAL_CIPHERTEXT_OFFSET, DEAL_CIPHERTEXT_LENGTH)
+        // Enc<Mxe, &BasketLegsState>
+        .plaintext_u128(basket_nonce)
+        .account(
+            basket_key,
+            BASKET_CIPHERTEXT_OFFSET,
+            BASKET_CIPHERTEXT_LENGTH,
+        );
+    // [Enc<Mxe, &BalanceState>; 4] - unused slots repeat leg 0
+    for i in 0..MAX_BASKET_LEGS {
+        let leg = &legs[if i < legs.len() { i } else { 0 }];
+        args = args.plaintext_u128(leg.nonce).account(
+            leg.balance,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        );
+    }
+    let args = args
+        .plaintext_u8(legs.len() as u8)
+        // Shared marker for deal blob
+        .x25519_pubkey(owner_key)
+        .plaintext_u128(creator_deal_blob_nonce)
+        // Plaintext booleans
+        .plaintext_bool(is_expired)
+        .plaintext_bool(allow_partial)
+        // Auditor key for the blob copy
+        .x25519_pubkey(auditor_key)
+        .build();
+
+    // Callback accounts: deal, bounty recipient, then the leg balances in leg order
+    let mut callback_accounts = vec![
+        CallbackAccount {
+            pubkey: deal_key,
+            is_writable: true,
+        },
+        CallbackAccount {
+            pubkey: bounty_recipient,
+            is_writable: true,
+        },
+    ];
+    callback_accounts.extend(legs.iter().map(|leg| CallbackAccount {
+        pubkey: leg.balance,
+        is_writable: true,
+    }));
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![CrankBasketDealCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &callback_accounts,
+        )?],
+        BASKET_CALLBACK_TXS,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn callback_handler(
+    ctx: Context<CrankBasketDealCallback>,
+    output: SignedComputationOutputs<CrankBasketDealOutput>,
+) -> Result<()> {
+    // Verify and extract output
+    // The return type is ([Enc<Mxe, BalanceState>; 4], [Enc<Shared, BasketDealSettledBlob>; 2],
+    //                     u8, u8)
+    // The blob array holds [owner, auditor] copies
+    let tuple_output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(CrankBasketDealOutput { field_0 }) => field_0,
+        Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    // Reject the computation if the circuit hit an arithmetic error
+    CircuitError::check(tuple_output.field_3)?;
+
+    let balance_states = &tuple_output.field_0;
+    let deal_blob = &tuple_output.field_1;
+    let status = DealStatus::from_circuit(tuple_output.field_2)?;
+
+    // Only update if status changed (Executed or Expired)
+    if status != DealStatus::Open {
+        let deal = &mut ctx.accounts.deal;
+        deal.status = status;
+
+        // Update every leg balance in the same callback
+        // Accounts were validated when queued and are fixed by the callback instruction
+        for (balance_info, balance_state) in ctx.remaining_accounts.iter().zip(balance_states) {
+            let mut balance =
+                BalanceAccount::try_deserialize(&mut &balance_info.try_borrow_data()?[..])?;
+            balance.nonce = balance_state.nonce.to_le_bytes();
+            balance.ciphertexts = balance_state.ciphertexts;
+            balance.try_serialize(&mut &mut balance_info.try_borrow_mut_data()?[..])?;
+        }
+
+        // Emit BasketDealSettled event with shared blob for creator
+        emit!(BasketDealSettled {
+            deal: deal.key(),
+            status,
+            settled_at: Clock::get()?.unix_timestamp,
+            encryption_key: deal_blob[0].encryption_key,
+            nonce: deal_blob[0].nonce.to_le_bytes(),
+            ciphertexts: deal_blob[0].ciphertexts,
+            auditor_encryption_key: deal_blob[1].encryption_key,
+            auditor_nonce: deal_blob[1].nonce.to_le_bytes(),
+            auditor_ciphertexts: deal_blob[1].ciphertexts,
+        });
+
+        // Pay the crank bounty (a refund to the controller when cranked before expiry)
+        let bounty = deal.crank_bounty;
+        if bounty > 0 {
+            deal.crank_bounty = 0;
+            pay_crank_bounty(
+                &deal.to_account_info(),
+                &ctx.accounts.bounty_recipient.to_account_info(),
+                bounty,
+            )?;
+            emit!(CrankBountyPaid {
+                account: deal.key(),
+                recipient: ctx.accounts.bounty_recipient.key(),
+                amount: bounty,
+                refund: ctx.accounts.bounty_recipient.key() == deal.controller,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn init_comp_def_handler(ctx: Context<InitCrankBasketDealCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, None, None)?;
+    Ok(())
+}
+
+#[init_computation_definition_accounts("crank_basket_deal", payer)]
+#[derive(Accounts)]
+pub struct InitCrankBasketDealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    /// Can't check it here as it's not initialized yet.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("crank_basket_deal", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CrankBasketDeal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = deal.basket_legs > 0 @ ErrorCode::BasketDealMismatch,
+    )]
+    pub deal: Box<Account<'info, DealAccount>>,
+
+    /// Base legs of the deal
+    #[account(
+        seeds = [b"basket", deal.key().as_ref()],
+        bump = basket.bump,
+        has_one = deal,
+    )]
+    pub basket: Box<Account<'info, BasketLegs>>,
+
+    /// Delegate of the deal's controller; required only for a delegate cranking before expiry
+    pub delegate: Option<Box<Account<'info, Delegate>>>,
+
+    // --- Arcium accounts (auto-generated pattern) ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    // remaining_accounts: the controller's BASE balance of each leg, in leg order
+}
+
+#[callback_accounts("crank_basket_deal")]
+#[derive(Accounts)]
+pub struct CrankBasketDealCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account, checked by arcium program via constraints in the callback context.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub deal: Box<Account<'info, DealAccount>>,
+    /// CHECK: receives the crank bounty: the cranker after expiry, otherwise the deal's
+    /// controller; fixed by the queueing instruction
+    #[account(mut)]
+    pub bounty_recipient: UncheckedAccount<'info>,
+    // remaining_accounts: leg balances in leg order
+}
@@ -2,11 +2,13 @@ use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
-use crate::error::ErrorCode;
-use crate::state::{BalanceAccount, DealAccount, DealStatus};
+use crate::error::{CircuitError, ErrorCode};
+use crate::state::{
+    pay_crank_bounty, BalanceAccount, DealAccount, DealStatus, Delegate, DelegatePermission,
+};
 use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
 use crate::state::{DEAL_CIPHERTEXT_LENGTH, DEAL_CIPHERTEXT_OFFSET};
-use crate::{BalanceUpdated, DealSettled};
+use crate::{BalanceUpdated, CrankBountyPaid, DealSettled};
 
 const COMP_DEF_OFFSET: u32 = comp_def_offset("crank_deal");
 use crate::{SignerAccount, ID, ID_CONST};
@@ -25,7 +27,7 @@ pub fn handler(
 
     // Validate deal is open
     require!(
-        ctx.accounts.deal.status == DealStatus::OPEN,
+        ctx.accounts.deal.status == DealStatus::Open,
         ErrorCode::DealNotOpen
     );
 
@@ -33,19 +35,37 @@ pub fn handler(
     let now = Clock::get()?.unix_timestamp;
     let is_expired = ctx.accounts.deal.expires_at <= now;
 
-    // Authorization check: anyone can crank after expiry, only controller before
+    // Authorization check: anyone can crank after expiry, only controller (or a delegate) before
     if !is_expired {
-        require!(
-            ctx.accounts.payer.key() == ctx.accounts.deal.controller,
-            ErrorCode::NotAuthorized
-        );
+        Delegate::authorize(
+            &ctx.accounts.payer.key(),
+            &ctx.accounts.deal.controller,
+            ctx.accounts.delegate.as_deref(),
+            DelegatePermission::CRANK,
+            &ctx.accounts.deal.base_mint,
+            &ctx.accounts.deal.quote_mint,
+            now,
+        )?;
     }
 
+    // The bounty pays whoever cranks after expiry. Before expiry only the controller (or a
+    // delegate) may crank, so it is refunded to the controller rather than the signer.
+    let bounty_recipient = if is_expired {
+        ctx.accounts.payer.key()
+    } else {
+        ctx.accounts.deal.controller
+    };
+
     let allow_partial = ctx.accounts.deal.allow_partial;
 
+    // Blob copies for the creator's auditor, if they registered one
+    let owner_key = ctx.accounts.deal.encryption_pubkey;
+    let auditor_key = ctx.accounts.creator_balance.auditor_key_or(owner_key);
+
     // Build ArgBuilder for crank_deal instruction:
     // crank_deal(deal_state: Enc<Mxe, &DealState>, creator_balance: Enc<Mxe, &BalanceState>,
-    //            creator_deal_blob: Shared, creator_balance_blob: Shared, is_expired: bool, allow_partial: bool)
+    //            creator_deal_blob: Shared, creator_balance_blob: Shared, is_expired: bool, allow_partial: bool,
+    //            auditor: ArcisX25519Pubkey)
     let args = ArgBuilder::new()
         // Enc<Mxe, &DealState>
         .plaintext_u128(deal_nonce)
@@ -66,6 +86,8 @@ pub fn handler(
         // Plaintext booleans
         .plaintext_bool(is_expired)
         .plaintext_bool(allow_partial)
+        // Auditor key for the blob copies
+        .x25519_pubkey(auditor_key)
         .build();
 
     ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
@@ -87,6 +109,10 @@ pub fn handler(
                     pubkey: creator_balance_key,
                     is_writable: true,
                 },
+                CallbackAccount {
+                    pubkey: bounty_recipient,
+                    is_writable: true,
+                },
             ],
         )?],
         1,
@@ -101,7 +127,9 @@ pub fn callback_handler(
     output: SignedComputationOutputs<CrankDealOutput>,
 ) -> Result<()> {
     // Verify and extract output
-    // The return type is (Enc<Mxe, BalanceState>, Enc<Shared, DealSettledBlob>, Enc<Shared, BalanceUpdatedBlob>, u8)
+    // The return type is (Enc<Mxe, BalanceState>, [Enc<Shared, DealSettledBlob>; 2],
+    //                     [Enc<Shared, BalanceUpdatedBlob>; 2], u8, u8)
+    // Blob arrays hold [owner, auditor] copies
     let tuple_output = match output.verify_output(
         &ctx.accounts.cluster_account,
         &ctx.accounts.computation_account,
@@ -110,14 +138,17 @@ pub fn callback_handler(
         Err(_) => return Err(ErrorCode::AbortedComputation.into()),
     };
 
+    // Reject the computation if the circuit hit an arithmetic error
+    CircuitError::check(tuple_output.field_4)?;
+
     // Access tuple elements via generated struct fields
     let balance_state = &tuple_output.field_0;
     let deal_blob = &tuple_output.field_1;
     let balance_blob = &tuple_output.field_2;
-    let status = tuple_output.field_3;
+    let status = DealStatus::from_circuit(tuple_output.field_3)?;
 
-    // Only update if status changed (status != 0 means EXECUTED or EXPIRED)
-    if status != 0 {
+    // Only update if status changed (Executed or Expired)
+    if status != DealStatus::Open {
         let deal = &mut ctx.accounts.deal;
         deal.status = status;
 
@@ -131,9 +162,12 @@ pub fn callback_handler(
             deal: deal.key(),
             status,
             settled_at: Clock::get()?.unix_timestamp,
-            encryption_key: deal_blob.encryption_key,
-            nonce: deal_blob.nonce.to_le_bytes(),
-            ciphertexts: deal_blob.ciphertexts,
+            encryption_key: deal_blob[0].encryption_key,
+            nonce: deal_blob[0].nonce.to_le_bytes(),
+            ciphertexts: deal_blob[0].ciphertexts,
+            auditor_encryption_key: deal_blob[1].encryption_key,
+            auditor_nonce: deal_blob[1].nonce.to_le_bytes(),
+            auditor_ciphertexts: deal_blob[1].ciphertexts,
         });
 
         // Emit BalanceUpdated event for creator
@@ -141,10 +175,31 @@ pub fn callback_handler(
             balance: balance.key(),
             controller: balance.controller,
             mint: balance.mint,
-            encryption_key: balance_blob.encryption_key,
-            nonce: balance_blob.nonce.to_le_bytes(),
-            ciphertexts: balance_blob.ciphertexts,
+            encryption_key: balance_blob[0].encryption_key,
+            nonce: balance_blob[0].nonce.to_le_bytes(),
+            ciphertexts: balance_blob[0].ciphertexts,
+            auditor_encryption_key: balance_blob[1].encryption_key,
+            auditor_nonce: balance_blob[1].nonce.to_le_bytes(),
+            auditor_ciphertexts: balance_blob[1].ciphertexts,
         });
+
+        // Pay the crank bounty (a refund to the controller when cranked before expiry)
+        let deal = &mut ctx.accounts.deal;
+        let bounty = deal.crank_bounty;
+        if bounty > 0 {
+            deal.crank_bounty = 0;
+            pay_crank_bounty(
+                &deal.to_account_info(),
+                &ctx.accounts.bounty_recipient.to_account_info(),
+                bounty,
+            )?;
+            emit!(CrankBountyPaid {
+                account: deal.key(),
+                recipient: ctx.accounts.bounty_recipient.key(),
+                amount: bounty,
+                refund: ctx.accounts.bounty_recipient.key() == deal.controller,
+            });
+        }
     }
 
     Ok(())
@@ -180,7 +235,11 @@ pub struct CrankDeal<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = deal.basket_legs == 0 @ ErrorCode::BasketDealMismatch,
+        constraint = !deal.swap @ ErrorCode::SwapDealMismatch,
+    )]
     pub deal: Box<Account<'info, DealAccount>>,
 
     /// Creator's BASE token balance (for releasing commitment and refund)
@@ -191,6 +250,9 @@ pub struct CrankDeal<'info> {
     )]
     pub creator_balance: Box<Account<'info, BalanceAccount>>,
 
+    /// Delegate of the deal's controller; required only for a delegate cranking before expiry
+    pub delegate: Option<Box<Account<'info, Delegate>>>,
+
     // --- Arcium accounts (auto-generated pattern) ---
     #[account(
         init_if_needed,
@@ -270,4 +332,8 @@ pub struct CrankDealCallback<'info> {
     pub deal: Box<Account<'info, DealAccount>>,
     #[account(mut)]
     pub creator_balance: Box<Account<'info, BalanceAccount>>,
+    /// CHECK: receives the crank bounty: the cranker after expiry, otherwise the deal's
+    /// controller; fixed by the queueing instruction
+    #[account(mut)]
+    pub bounty_recipient: UncheckedAccount<'info>,
 }
@@ -2,9 +2,11 @@ use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
-use crate::error::ErrorCode;
-use crate::events::{BalanceUpdated, OfferSettled};
-use crate::state::{BalanceAccount, DealAccount, DealStatus, OfferAccount, OfferStatus};
+use crate::error::{CircuitError, ErrorCode};
+use crate::events::{BalanceUpdated, CrankBountyPaid, OfferSettled};
+use crate::state::{
+    pay_crank_bounty, BalanceAccount, DealAccount, DealStatus, OfferAccount, OfferStatus,
+};
 use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
 use crate::state::{DEAL_CIPHERTEXT_LENGTH, DEAL_CIPHERTEXT_OFFSET};
 use crate::state::{OFFER_CIPHERTEXT_LENGTH, OFFER_CIPHERTEXT_OFFSET};
@@ -28,21 +30,30 @@ pub fn handler(
 
     // Constraints
     require!(
-        ctx.accounts.deal.status != DealStatus::OPEN,
+        ctx.accounts.deal.status.is_settled(),
         ErrorCode::DealNotSettled
     );
     require!(
-        ctx.accounts.offer.status != OfferStatus::SETTLED,
+        ctx.accounts.offer.status != OfferStatus::Settled,
         ErrorCode::OfferAlreadySettled
     );
+    require!(
+        ctx.accounts.offer.status != OfferStatus::Rejected,
+        ErrorCode::OfferWasRejected
+    );
 
     // Derive deal_success from deal.status (plaintext)
-    let deal_success = ctx.accounts.deal.status == DealStatus::EXECUTED;
+    let deal_success = ctx.accounts.deal.status == DealStatus::Executed;
+
+    // Blob copies for the offeror's auditor, if they registered one
+    let owner_key = ctx.accounts.offer.encryption_pubkey;
+    let auditor_key = ctx.accounts.offeror_balance.auditor_key_or(owner_key);
 
     // ArgBuilder pattern for crank_offer:
     // crank_offer(deal_state: Enc<Mxe, &DealState>, offer_state: Enc<Mxe, &OfferState>,
     //             offeror_balance: Enc<Mxe, &BalanceState>, offeror_offer_blob: Shared,
-    //             offeror_balance_blob: Shared, deal_success: bool)
+    //             offeror_balance_blob: Shared, deal_success: bool, floor_locked: bool,
+    //             auditor: ArcisX25519Pubkey)
     //
     // CRITICAL: Now includes deal state for price calculation (fixing quote units bug)
     let args = ArgBuilder::new()
@@ -67,6 +78,10 @@ pub fn handler(
         .plaintext_u128(offeror_balance_blob_nonce)
         // Plaintext bool: deal_success
         .plaintext_bool(deal_success)
+        // Plaintext bool: the offer locked its commitment rounded down
+        .plaintext_bool(ctx.accounts.offer.floor_locked)
+        // Auditor key for the blob copies
+        .x25519_pubkey(auditor_key)
         .build();
 
     ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
@@ -88,6 +103,10 @@ pub fn handler(
                     pubkey: offeror_balance_key,
                     is_writable: true,
                 },
+                CallbackAccount {
+                    pubkey: ctx.accounts.payer.key(),
+                    is_writable: true,
+                },
             ],
         )?],
         1,
@@ -102,7 +121,9 @@ pub fn callback_handler(
     output: SignedComputationOutputs<CrankOfferOutput>,
 ) -> Result<()> {
     // Verify and extract output
-    // The return type is (Enc<Mxe, BalanceState>, Enc<Shared, OfferSettledBlob>, Enc<Shared, BalanceUpdatedBlob>)
+    // The return type is (Enc<Mxe, BalanceState>, [Enc<Shared, OfferSettledBlob>; 2],
+    //                     [Enc<Shared, BalanceUpdatedBlob>; 2], u8)
+    // Blob arrays hold [owner, auditor] copies
     let tuple_output = match output.verify_output(
         &ctx.accounts.cluster_account,
         &ctx.accounts.computation_account,
@@ -111,13 +132,16 @@ pub fn callback_handler(
         Err(_) => return Err(ErrorCode::AbortedComputation.into()),
     };
 
+    // Reject the computation if the circuit hit an arithmetic error
+    CircuitError::check(tuple_output.field_3)?;
+
     // Access tuple elements via generated struct fields
     let balance_state = &tuple_output.field_0;
     let offer_blob = &tuple_output.field_1;
     let balance_blob = &tuple_output.field_2;
 
     let offer = &mut ctx.accounts.offer;
-    offer.status = OfferStatus::SETTLED;
+    offer.status = OfferStatus::Settled;
 
     // Update offeror's balance MXE state
     let balance = &mut ctx.accounts.offeror_balance;
@@ -129,9 +153,12 @@ pub fn callback_handler(
         offer: offer.key(),
         offer_index: offer.offer_index,
         settled_at: Clock::get()?.unix_timestamp,
-        encryption_key: offer_blob.encryption_key,
-        nonce: offer_blob.nonce.to_le_bytes(),
-        ciphertexts: offer_blob.ciphertexts,
+        encryption_key: offer_blob[0].encryption_key,
+        nonce: offer_blob[0].nonce.to_le_bytes(),
+        ciphertexts: offer_blob[0].ciphertexts,
+        auditor_encryption_key: offer_blob[1].encryption_key,
+        auditor_nonce: offer_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: offer_blob[1].ciphertexts,
     });
 
     // Emit BalanceUpdated event for offeror
@@ -139,11 +166,32 @@ pub fn callback_handler(
         balance: balance.key(),
         controller: balance.controller,
         mint: balance.mint,
-        encryption_key: balance_blob.encryption_key,
-        nonce: balance_blob.nonce.to_le_bytes(),
-        ciphertexts: balance_blob.ciphertexts,
+        encryption_key: balance_blob[0].encryption_key,
+        nonce: balance_blob[0].nonce.to_le_bytes(),
+        ciphertexts: balance_blob[0].ciphertexts,
+        auditor_encryption_key: balance_blob[1].encryption_key,
+        auditor_nonce: balance_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: balance_blob[1].ciphertexts,
     });
 
+    // Pay the crank bounty (a refund when the offeror cranked their own offer)
+    let offer = &mut ctx.accounts.offer;
+    let bounty = offer.crank_bounty;
+    if bounty > 0 {
+        offer.crank_bounty = 0;
+        pay_crank_bounty(
+            &offer.to_account_info(),
+            &ctx.accounts.cranker.to_account_info(),
+            bounty,
+        )?;
+        emit!(CrankBountyPaid {
+            account: offer.key(),
+            recipient: ctx.accounts.cranker.key(),
+            amount: bounty,
+            refund: ctx.accounts.cranker.key() == offer.controller,
+        });
+    }
+
     Ok(())
 }
 
@@ -174,6 +222,7 @@ pub struct CrankOffer<'info> {
     pub payer: Signer<'info>,
 
     /// Deal account (for encrypted state reference - needed for price)
+    #[account(constraint = !deal.swap @ ErrorCode::SwapDealMismatch)]
     pub deal: Box<Account<'info, DealAccount>>,
 
     /// Offer account (for encrypted state reference)
@@ -243,4 +292,7 @@ pub struct CrankOfferCallback<'info> {
     pub offer: Box<Account<'info, OfferAccount>>,
     #[account(mut)]
     pub offeror_balance: Box<Account<'info, BalanceAccount>>,
+    /// CHECK: cranker, receives the crank bounty; fixed by the queueing instruction
+    #[account(mut)]
+    pub cranker: UncheckedAccount<'info>,
 }
@@ -0,0 +1,388 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::{CircuitError, ErrorCode};
+use crate::events::{CrankBountyPaid, OfferSettled};
+use crate::state::{
+    pay_crank_bounty, BalanceAccount, DealAccount, DealStatus, OfferAccount, OfferStatus,
+};
+use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
+use crate::state::{DEAL_CIPHERTEXT_LENGTH, DEAL_CIPHERTEXT_OFFSET};
+use crate::state::{OFFER_CIPHERTEXT_LENGTH, OFFER_CIPHERTEXT_OFFSET};
+
+const COMP_DEF_OFFSET: u32 = comp_def_offset("crank_offers_batch");
+use crate::{SignerAccount, ID, ID_CONST};
+
+/// Number of offer slots in the crank_offers_batch circuit
+pub const CRANK_BATCH_SIZE: usize = 8;
+
+/// Batched outputs (~3.5 KiB with the auditor copies) do not fit in a single callback transaction
+pub(crate) const BATCH_CALLBACK_TXS: u8 = 5;
+
+/// A validated (offer, offeror_balance) pair of a batch, captured at queue time.
+pub(crate) struct OfferSlot {
+    offer: Pubkey,
+    offer_nonce: u128,
+    encryption_pubkey: [u8; 32],
+    auditor_pubkey: [u8; 32],
+    balance: Pubkey,
+    balance_nonce: u128,
+    floor_locked: bool,
+}
+
+/// Validates `[offer_0, offeror_balance_0, offer_1, offeror_balance_1, ...]` against a deal.
+///
+/// Rejected offers locked nothing, so they take no slot; they are only counted, and
+/// returned alongside the slots.
+pub(crate) fn load_offer_slots<'info>(
+    deal_key: Pubkey,
+    quote_mint: Pubkey,
+    pairs: &'info [AccountInfo<'info>],
+) -> Result<(Vec<OfferSlot>, usize)> {
+    require!(
+        !pairs.is_empty() && pairs.len().is_multiple_of(2),
+        ErrorCode::InvalidBatchSize
+    );
+
+    let mut slots: Vec<OfferSlot> = Vec::with_capacity(CRANK_BATCH_SIZE);
+    let mut rejected: Vec<Pubkey> = Vec::new();
+    for pair in pairs.chunks(2) {
+        let offer = Account::<OfferAccount>::try_from(&pair[0])?;
+        let balance = Account::<BalanceAccount>::try_from(&pair[1])?;
+        require!(offer.deal == deal_key, ErrorCode::DealMismatch);
+        require!(
+            offer.status != OfferStatus::Settled,
+            ErrorCode::OfferAlreadySettled
+        );
+        if offer.status == OfferStatus::Rejected {
+            require!(
+                !rejected.contains(&offer.key()),
+                ErrorCode::DuplicateBatchAccount
+            );
+            rejected.push(offer.key());
+            continue;
+        }
+        require!(
+            balance.controller == offer.controller && balance.mint == quote_mint,
+            ErrorCode::ControllerMismatch
+        );
+        // Two slots writing the same account would lose one of the updates
+        require!(
+            !slots
+                .iter()
+                .any(|slot| slot.offer == offer.key() || slot.balance == balance.key()),
+            ErrorCode::DuplicateBatchAccount
+        );
+        slots.push(OfferSlot {
+            offer: offer.key(),
+            offer_nonce: u128::from_le_bytes(offer.nonce),
+            encryption_pubkey: offer.encryption_pubkey,
+            auditor_pubkey: balance.auditor_key_or(offer.encryption_pubkey),
+            balance: balance.key(),
+            balance_nonce: u128::from_le_bytes(balance.nonce),
+            floor_locked: offer.floor_locked,
+        });
+    }
+    require!(
+        !slots.is_empty() && slots.len() <= CRANK_BATCH_SIZE,
+        ErrorCode::InvalidBatchSize
+    );
+    Ok((slots, rejected.len()))
+}
+
+/// Appends the batch inputs shared by every batched settlement circuit:
+/// [Enc<Mxe, &OfferState>; 8], [Enc<Mxe, &BalanceState>; 8], [ArcisX25519Pubkey; 8] x2,
+/// [bool; 8]
+///
+/// Unused slots repeat slot 0; their outputs are ignored by the callback.
+pub(crate) fn push_offer_slot_args(mut args: ArgBuilder, slots: &[OfferSlot]) -> ArgBuilder {
+    let slot = |i: usize| &slots[if i < slots.len() { i } else { 0 }];
+    // [Enc<Mxe, &OfferState>; 8]
+    for i in 0..CRANK_BATCH_SIZE {
+        args = args.plaintext_u128(slot(i).offer_nonce).account(
+            slot(i).offer,
+            OFFER_CIPHERTEXT_OFFSET,
+            OFFER_CIPHERTEXT_LENGTH,
+        );
+    }
+    // [Enc<Mxe, &BalanceState>; 8]
+    for i in 0..CRANK_BATCH_SIZE {
+        args = args.plaintext_u128(slot(i).balance_nonce).account(
+            slot(i).balance,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        );
+    }
+    // [ArcisX25519Pubkey; 8] - offer blobs are sealed to each offeror
+    for i in 0..CRANK_BATCH_SIZE {
+        args = args.x25519_pubkey(slot(i).encryption_pubkey);
+    }
+    // [ArcisX25519Pubkey; 8] - and copied to each offeror balance's auditor
+    for i in 0..CRANK_BATCH_SIZE {
+        args = args.x25519_pubkey(slot(i).auditor_pubkey);
+    }
+    // [bool; 8] - offers that locked their commitment rounded down
+    for i in 0..CRANK_BATCH_SIZE {
+        args = args.plaintext_bool(slot(i).floor_locked);
+    }
+    args
+}
+
+/// Callback accounts for the (offer, offeror_balance) pairs, in slot order.
+pub(crate) fn offer_slot_callback_accounts(slots: &[OfferSlot]) -> Vec<CallbackAccount> {
+    slots
+        .iter()
+        .flat_map(|slot| {
+            [
+                CallbackAccount {
+                    pubkey: slot.offer,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: slot.balance,
+                    is_writable: true,
+                },
+            ]
+        })
+        .collect()
+}
+
+/// Applies batched offer settlements to the (offer, offeror_balance) pairs passed
+/// as callback remaining accounts, emitting one OfferSettled per offer.
+///
+/// `offer_blobs` holds the offeror copies in slots 0..8 and the auditor copies in 8..16.
+pub(crate) fn apply_offer_settlements(
+    pairs: &[AccountInfo],
+    balance_states: &[MXEEncryptedStruct<2>],
+    offer_blobs: &[SharedEncryptedStruct<4>],
+    cranker: &AccountInfo,
+) -> Result<()> {
+    let settled_at = Clock::get()?.unix_timestamp;
+
+    for (i, pair) in pairs.chunks(2).enumerate() {
+        // Accounts were validated when queued and are fixed by the callback instruction
+        let (offer_info, balance_info) = (&pair[0], &pair[1]);
+        let mut offer = OfferAccount::try_deserialize(&mut &offer_info.try_borrow_data()?[..])?;
+        let mut balance =
+            BalanceAccount::try_deserialize(&mut &balance_info.try_borrow_data()?[..])?;
+
+        // Another crank settled this offer while the batch was in flight
+        if offer.status == OfferStatus::Settled {
+            continue;
+        }
+
+        offer.status = OfferStatus::Settled;
+
+        // Update offeror's balance MXE state
+        balance.nonce = balance_states[i].nonce.to_le_bytes();
+        balance.ciphertexts = balance_states[i].ciphertexts;
+
+        emit!(OfferSettled {
+            deal: offer.deal,
+            offer: offer_info.key(),
+            offer_index: offer.offer_index,
+            settled_at,
+            encryption_key: offer_blobs[i].encryption_key,
+            nonce: offer_blobs[i].nonce.to_le_bytes(),
+            ciphertexts: offer_blobs[i].ciphertexts,
+            auditor_encryption_key: offer_blobs[i + CRANK_BATCH_SIZE].encryption_key,
+            auditor_nonce: offer_blobs[i + CRANK_BATCH_SIZE].nonce.to_le_bytes(),
+            auditor_ciphertexts: offer_blobs[i + CRANK_BATCH_SIZE].ciphertexts,
+        });
+
+        // Pay the crank bounty (a refund when the offeror cranked their own offer)
+        let bounty = offer.crank_bounty;
+        if bounty > 0 {
+            offer.crank_bounty = 0;
+            pay_crank_bounty(offer_info, cranker, bounty)?;
+            emit!(CrankBountyPaid {
+                account: offer_info.key(),
+                recipient: cranker.key(),
+                amount: bounty,
+                refund: cranker.key() == offer.controller,
+            });
+        }
+
+        offer.try_serialize(&mut &mut offer_info.try_borrow_mut_data()?[..])?;
+        balance.try_serialize(&mut &mut balance_info.try_borrow_mut_data()?[..])?;
+    }
+
+    Ok(())
+}
+
+/// Settles up to CRANK_BATCH_SIZE offers of a settled deal in one computation.
+///
+/// remaining_accounts: [offer_0, offeror_balance_0, offer_1, offeror_balance_1, ...]
+/// Rejected offers among them are skipped.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CrankOffersBatch<'info>>,
+    computation_offset: u64,
+) -> Result<()> {
+    let deal_key = ctx.accounts.deal.key();
+    let deal_nonce = u128::from_le_bytes(ctx.accounts.deal.nonce);
+
+    // Constraints
+    require!(
+        ctx.accounts.deal.status.is_settled(),
+        ErrorCode::DealNotSettled
+    );
+
+    let (slots, _) = load_offer_slots(
+        deal_key,
+        ctx.accounts.deal.quote_mint,
+        ctx.remaining_accounts,
+    )?;
+
+    // Derive deal_success from deal.status (plaintext)
+    let deal_success = ctx.accounts.deal.status == DealStatus::Executed;
+
+    // ArgBuilder pattern for crank_offers_batch:
+    // crank_offers_batch(deal_state: Enc<Mxe, &DealState>, offer_states: [Enc<Mxe, &OfferState>; 8],
+    //                    offeror_balances: [Enc<Mxe, &BalanceState>; 8],
+    //                    offeror_keys: [ArcisX25519Pubkey; 8],
+    //                    auditor_keys: [ArcisX25519Pubkey; 8], floor_locked: [bool; 8],
+    //                    deal_success: bool)
+    let args = ArgBuilder::new()
+        // Enc<Mxe, &DealState>
+        .plaintext_u128(deal_nonce)
+        .account(deal_key, DEAL_CIPHERTEXT_OFFSET, DEAL_CIPHERTEXT_LENGTH);
+    let args = push_offer_slot_args(args, &slots)
+        // Plaintext bool: deal_success
+        .plaintext_bool(deal_success)
+        .build();
+
+    // Callback accounts: cranker, then the (offer, offeror_balance) pairs in slot order
+    let mut callback_accounts = vec![CallbackAccount {
+        pubkey: ctx.accounts.payer.key(),
+        is_writable: true,
+    }];
+    callback_accounts.extend(offer_slot_callback_accounts(&slots));
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![CrankOffersBatchCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &callback_accounts,
+        )?],
+        BATCH_CALLBACK_TXS,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn callback_handler(
+    ctx: Context<CrankOffersBatchCallback>,
+    output: SignedComputationOutputs<CrankOffersBatchOutput>,
+) -> Result<()> {
+    // Verify and extract output
+    // The return type is ([Enc<Mxe, BalanceState>; 8], [Enc<Shared, OfferSettledBlob>; 16], u8)
+    let tuple_output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(CrankOffersBatchOutput { field_0 }) => field_0,
+        Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    // Reject the computation if the circuit hit an arithmetic error
+    CircuitError::check(tuple_output.field_2)?;
+
+    apply_offer_settlements(
+        ctx.remaining_accounts,
+        &tuple_output.field_0,
+        &tuple_output.field_1,
+        &ctx.accounts.cranker.to_account_info(),
+    )
+}
+
+pub fn init_comp_def_handler(ctx: Context<InitCrankOffersBatchCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, None, None)?;
+    Ok(())
+}
+
+#[init_computation_definition_accounts("crank_offers_batch", payer)]
+#[derive(Accounts)]
+pub struct InitCrankOffersBatchCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("crank_offers_batch", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CrankOffersBatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Deal account (for encrypted state reference - needed for price)
+    #[account(constraint = !deal.swap @ ErrorCode::SwapDealMismatch)]
+    pub deal: Box<Account<'info, DealAccount>>,
+
+    // --- Arcium accounts ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("crank_offers_batch")]
+#[derive(Accounts)]
+pub struct CrankOffersBatchCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    /// CHECK: cranker, receives the crank bounties; fixed by the queueing instruction
+    #[account(mut)]
+    pub cranker: UncheckedAccount<'info>,
+    // remaining_accounts: (offer, offeror_balance) pairs in slot order
+}
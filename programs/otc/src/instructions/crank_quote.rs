@@ -0,0 +1,265 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::{CircuitError, ErrorCode};
+use crate::events::{BalanceUpdated, QuoteSettled};
+use crate::state::{BalanceAccount, QuoteAccount, QuoteStatus, RfqAccount, RfqStatus};
+use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
+use crate::state::{QUOTE_CIPHERTEXT_LENGTH, QUOTE_CIPHERTEXT_OFFSET};
+use crate::state::{RFQ_CIPHERTEXT_LENGTH, RFQ_CIPHERTEXT_OFFSET};
+
+const COMP_DEF_OFFSET: u32 = comp_def_offset("crank_quote");
+use crate::{SignerAccount, ID, ID_CONST};
+
+pub fn handler(
+    ctx: Context<CrankQuote>,
+    computation_offset: u64,
+    maker_quote_blob_nonce: u128,
+    maker_balance_blob_nonce: u128,
+) -> Result<()> {
+    // Capture keys and nonces before mutable borrows
+    let rfq_key = ctx.accounts.rfq.key();
+    let quote_key = ctx.accounts.quote.key();
+    let maker_balance_key = ctx.accounts.maker_balance.key();
+    let rfq_nonce = u128::from_le_bytes(ctx.accounts.rfq.nonce);
+    let quote_nonce = u128::from_le_bytes(ctx.accounts.quote.nonce);
+    let maker_balance_nonce = u128::from_le_bytes(ctx.accounts.maker_balance.nonce);
+
+    // Constraints
+    require!(
+        ctx.accounts.rfq.status != RfqStatus::Open,
+        ErrorCode::RfqNotSettled
+    );
+    require!(
+        ctx.accounts.quote.status != QuoteStatus::Settled,
+        ErrorCode::QuoteAlreadySettled
+    );
+    require!(
+        ctx.accounts.quote.status != QuoteStatus::Rejected,
+        ErrorCode::QuoteWasRejected
+    );
+
+    // Derive rfq_filled from rfq.status (plaintext)
+    let rfq_filled = ctx.accounts.rfq.status == RfqStatus::Filled;
+
+    // Blob copies for the maker's auditor, if they registered one
+    let owner_key = ctx.accounts.quote.encryption_pubkey;
+    let auditor_key = ctx.accounts.maker_balance.auditor_key_or(owner_key);
+
+    // ArgBuilder pattern for crank_quote:
+    // crank_quote(rfq_state: Enc<Mxe, &RfqState>, quote_state: Enc<Mxe, &QuoteState>,
+    //             maker_balance: Enc<Mxe, &BalanceState>, maker_quote_blob: Shared,
+    //             maker_balance_blob: Shared, quote_index: u32, rfq_filled: bool,
+    //             auditor: ArcisX25519Pubkey)
+    let args = ArgBuilder::new()
+        // Enc<Mxe, &RfqState> - needed for the winning quote index
+        .plaintext_u128(rfq_nonce)
+        .account(rfq_key, RFQ_CIPHERTEXT_OFFSET, RFQ_CIPHERTEXT_LENGTH)
+        // Enc<Mxe, &QuoteState>
+        .plaintext_u128(quote_nonce)
+        .account(quote_key, QUOTE_CIPHERTEXT_OFFSET, QUOTE_CIPHERTEXT_LENGTH)
+        // Enc<Mxe, &BalanceState>
+        .plaintext_u128(maker_balance_nonce)
+        .account(
+            maker_balance_key,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        )
+        // Shared marker for quote blob
+        .x25519_pubkey(ctx.accounts.quote.encryption_pubkey)
+        .plaintext_u128(maker_quote_blob_nonce)
+        // Shared marker for balance blob
+        .x25519_pubkey(ctx.accounts.quote.encryption_pubkey)
+        .plaintext_u128(maker_balance_blob_nonce)
+        // Plaintext quote_index and rfq_filled
+        .plaintext_u32(ctx.accounts.quote.quote_index)
+        .plaintext_bool(rfq_filled)
+        // Auditor key for the blob copies
+        .x25519_pubkey(auditor_key)
+        .build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![CrankQuoteCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: quote_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: maker_balance_key,
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn callback_handler(
+    ctx: Context<CrankQuoteCallback>,
+    output: SignedComputationOutputs<CrankQuoteOutput>,
+) -> Result<()> {
+    // Verify and extract output
+    // The return type is (Enc<Mxe, BalanceState>, [Enc<Shared, QuoteSettledBlob>; 2],
+    //                     [Enc<Shared, BalanceUpdatedBlob>; 2], u8)
+    // Blob arrays hold [owner, auditor] copies
+    let tuple_output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(CrankQuoteOutput { field_0 }) => field_0,
+        Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    // Reject the computation if the circuit hit an arithmetic error
+    CircuitError::check(tuple_output.field_3)?;
+
+    let balance_state = &tuple_output.field_0;
+    let quote_blob = &tuple_output.field_1;
+    let balance_blob = &tuple_output.field_2;
+
+    let quote = &mut ctx.accounts.quote;
+    quote.status = QuoteStatus::Settled;
+
+    // Update maker's balance MXE state
+    let balance = &mut ctx.accounts.maker_balance;
+    balance.nonce = balance_state.nonce.to_le_bytes();
+    balance.ciphertexts = balance_state.ciphertexts;
+
+    emit!(QuoteSettled {
+        rfq: quote.rfq,
+        quote: quote.key(),
+        quote_index: quote.quote_index,
+        settled_at: Clock::get()?.unix_timestamp,
+        encryption_key: quote_blob[0].encryption_key,
+        nonce: quote_blob[0].nonce.to_le_bytes(),
+        ciphertexts: quote_blob[0].ciphertexts,
+        auditor_encryption_key: quote_blob[1].encryption_key,
+        auditor_nonce: quote_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: quote_blob[1].ciphertexts,
+    });
+
+    emit!(BalanceUpdated {
+        balance: balance.key(),
+        controller: balance.controller,
+        mint: balance.mint,
+        encryption_key: balance_blob[0].encryption_key,
+        nonce: balance_blob[0].nonce.to_le_bytes(),
+        ciphertexts: balance_blob[0].ciphertexts,
+        auditor_encryption_key: balance_blob[1].encryption_key,
+        auditor_nonce: balance_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: balance_blob[1].ciphertexts,
+    });
+
+    Ok(())
+}
+
+pub fn init_comp_def_handler(ctx: Context<InitCrankQuoteCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, None, None)?;
+    Ok(())
+}
+
+#[init_computation_definition_accounts("crank_quote", payer)]
+#[derive(Accounts)]
+pub struct InitCrankQuoteCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("crank_quote", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CrankQuote<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// RFQ account (for encrypted state reference - needed for the winning index)
+    pub rfq: Box<Account<'info, RfqAccount>>,
+
+    /// Quote account (for encrypted state reference)
+    #[account(
+        mut,
+        constraint = quote.rfq == rfq.key() @ ErrorCode::RfqMismatch,
+    )]
+    pub quote: Box<Account<'info, QuoteAccount>>,
+
+    /// Maker's BASE token balance (for releasing commitment)
+    #[account(
+        mut,
+        seeds = [b"balance", quote.controller.as_ref(), rfq.base_mint.as_ref()],
+        bump,
+    )]
+    pub maker_balance: Box<Account<'info, BalanceAccount>>,
+
+    // --- Arcium accounts ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("crank_quote")]
+#[derive(Accounts)]
+pub struct CrankQuoteCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub quote: Box<Account<'info, QuoteAccount>>,
+    #[account(mut)]
+    pub maker_balance: Box<Account<'info, BalanceAccount>>,
+}
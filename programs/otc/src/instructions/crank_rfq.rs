@@ -0,0 +1,284 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::{CircuitError, ErrorCode};
+use crate::state::{BalanceAccount, Delegate, DelegatePermission, RfqAccount, RfqStatus};
+use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
+use crate::state::{RFQ_CIPHERTEXT_LENGTH, RFQ_CIPHERTEXT_OFFSET};
+use crate::{BalanceUpdated, RfqSettled};
+
+const COMP_DEF_OFFSET: u32 = comp_def_offset("crank_rfq");
+use crate::{SignerAccount, ID, ID_CONST};
+
+pub fn handler(
+    ctx: Context<CrankRfq>,
+    computation_offset: u64,
+    taker_rfq_blob_nonce: u128,
+    taker_balance_blob_nonce: u128,
+) -> Result<()> {
+    // Capture keys and nonces before mutable borrows
+    let rfq_key = ctx.accounts.rfq.key();
+    let taker_balance_key = ctx.accounts.taker_balance.key();
+    let rfq_nonce = u128::from_le_bytes(ctx.accounts.rfq.nonce);
+    let taker_balance_nonce = u128::from_le_bytes(ctx.accounts.taker_balance.nonce);
+
+    // Validate RFQ is open
+    require!(
+        ctx.accounts.rfq.status == RfqStatus::Open,
+        ErrorCode::RfqNotOpen
+    );
+
+    // Authorization check: anyone can crank after the deadline, only the taker (or a
+    // delegate) before (the taker may accept the best quote received so far)
+    let now = Clock::get()?.unix_timestamp;
+    if ctx.accounts.rfq.deadline > now {
+        Delegate::authorize(
+            &ctx.accounts.payer.key(),
+            &ctx.accounts.rfq.controller,
+            ctx.accounts.delegate.as_deref(),
+            DelegatePermission::CRANK,
+            &ctx.accounts.rfq.base_mint,
+            &ctx.accounts.rfq.quote_mint,
+            now,
+        )?;
+    }
+
+    // Blob copies for the taker's auditor, if they registered one
+    let owner_key = ctx.accounts.rfq.encryption_pubkey;
+    let auditor_key = ctx.accounts.taker_balance.auditor_key_or(owner_key);
+
+    // Build ArgBuilder for crank_rfq instruction:
+    // crank_rfq(rfq_state: Enc<Mxe, &RfqState>, taker_balance: Enc<Mxe, &BalanceState>,
+    //           taker_rfq_blob: Shared, taker_balance_blob: Shared, auditor: ArcisX25519Pubkey)
+    let args = ArgBuilder::new()
+        // Enc<Mxe, &RfqState>
+        .plaintext_u128(rfq_nonce)
+        .account(rfq_key, RFQ_CIPHERTEXT_OFFSET, RFQ_CIPHERTEXT_LENGTH)
+        // Enc<Mxe, &BalanceState>
+        .plaintext_u128(taker_balance_nonce)
+        .account(
+            taker_balance_key,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        )
+        // Shared marker for RFQ blob
+        .x25519_pubkey(ctx.accounts.rfq.encryption_pubkey)
+        .plaintext_u128(taker_rfq_blob_nonce)
+        // Shared marker for balance blob
+        .x25519_pubkey(ctx.accounts.rfq.encryption_pubkey)
+        .plaintext_u128(taker_balance_blob_nonce)
+        // Auditor key for the blob copies
+        .x25519_pubkey(auditor_key)
+        .build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![CrankRfqCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: rfq_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: taker_balance_key,
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn callback_handler(
+    ctx: Context<CrankRfqCallback>,
+    output: SignedComputationOutputs<CrankRfqOutput>,
+) -> Result<()> {
+    // Verify and extract output
+    // The return type is (Enc<Mxe, BalanceState>, [Enc<Shared, RfqSettledBlob>; 2],
+    //                     [Enc<Shared, BalanceUpdatedBlob>; 2], u8, u8)
+    // Blob arrays hold [owner, auditor] copies
+    let tuple_output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(CrankRfqOutput { field_0 }) => field_0,
+        Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    // Reject the computation if the circuit hit an arithmetic error
+    CircuitError::check(tuple_output.field_4)?;
+
+    let balance_state = &tuple_output.field_0;
+    let rfq_blob = &tuple_output.field_1;
+    let balance_blob = &tuple_output.field_2;
+
+    let rfq = &mut ctx.accounts.rfq;
+    rfq.status = RfqStatus::from_circuit(tuple_output.field_3)?;
+
+    // Update taker's balance MXE state
+    let balance = &mut ctx.accounts.taker_balance;
+    balance.nonce = balance_state.nonce.to_le_bytes();
+    balance.ciphertexts = balance_state.ciphertexts;
+
+    emit!(RfqSettled {
+        rfq: rfq.key(),
+        status: rfq.status,
+        settled_at: Clock::get()?.unix_timestamp,
+        encryption_key: rfq_blob[0].encryption_key,
+        nonce: rfq_blob[0].nonce.to_le_bytes(),
+        ciphertexts: rfq_blob[0].ciphertexts,
+        auditor_encryption_key: rfq_blob[1].encryption_key,
+        auditor_nonce: rfq_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: rfq_blob[1].ciphertexts,
+    });
+
+    emit!(BalanceUpdated {
+        balance: balance.key(),
+        controller: balance.controller,
+        mint: balance.mint,
+        encryption_key: balance_blob[0].encryption_key,
+        nonce: balance_blob[0].nonce.to_le_bytes(),
+        ciphertexts: balance_blob[0].ciphertexts,
+        auditor_encryption_key: balance_blob[1].encryption_key,
+        auditor_nonce: balance_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: balance_blob[1].ciphertexts,
+    });
+
+    Ok(())
+}
+
+pub fn init_comp_def_handler(ctx: Context<InitCrankRfqCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, None, None)?;
+    Ok(())
+}
+
+#[init_computation_definition_accounts("crank_rfq", payer)]
+#[derive(Accounts)]
+pub struct InitCrankRfqCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    /// Can't check it here as it's not initialized yet.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("crank_rfq", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CrankRfq<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub rfq: Box<Account<'info, RfqAccount>>,
+
+    /// Taker's QUOTE token balance (for releasing commitment and refund)
+    #[account(
+        mut,
+        seeds = [b"balance", rfq.controller.as_ref(), rfq.quote_mint.as_ref()],
+        bump,
+    )]
+    pub taker_balance: Box<Account<'info, BalanceAccount>>,
+
+    /// Delegate of the RFQ's controller; required only for a delegate cranking before the deadline
+    pub delegate: Option<Box<Account<'info, Delegate>>>,
+
+    // --- Arcium accounts (auto-generated pattern) ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("crank_rfq")]
+#[derive(Accounts)]
+pub struct CrankRfqCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account, checked by arcium program via constraints in the callback context.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub rfq: Box<Account<'info, RfqAccount>>,
+    #[account(mut)]
+    pub taker_balance: Box<Account<'info, BalanceAccount>>,
+}
@@ -0,0 +1,339 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::{CircuitError, ErrorCode};
+use crate::state::{
+    pay_crank_bounty, BalanceAccount, DealAccount, DealStatus, Delegate, DelegatePermission,
+};
+use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
+use crate::state::{DEAL_CIPHERTEXT_LENGTH, DEAL_CIPHERTEXT_OFFSET};
+use crate::{BalanceUpdated, CrankBountyPaid, DealSettled};
+
+const COMP_DEF_OFFSET: u32 = comp_def_offset("crank_swap_deal");
+use crate::{SignerAccount, ID, ID_CONST};
+
+/// Settles a swap deal; its offers then settle with crank_swap_offer.
+pub fn handler(
+    ctx: Context<CrankSwapDeal>,
+    computation_offset: u64,
+    creator_deal_blob_nonce: u128,
+    creator_balance_blob_nonce: u128,
+) -> Result<()> {
+    // Capture keys and nonce before mutable borrows
+    let deal_key = ctx.accounts.deal.key();
+    let creator_balance_key = ctx.accounts.creator_balance.key();
+    let deal_nonce = u128::from_le_bytes(ctx.accounts.deal.nonce);
+    let creator_balance_nonce = u128::from_le_bytes(ctx.accounts.creator_balance.nonce);
+
+    // Validate deal is open
+    require!(
+        ctx.accounts.deal.status == DealStatus::Open,
+        ErrorCode::DealNotOpen
+    );
+
+    // Determine if deal has expired
+    let now = Clock::get()?.unix_timestamp;
+    let is_expired = ctx.accounts.deal.expires_at <= now;
+
+    // Authorization check: anyone can crank after expiry, only controller (or a delegate) before
+    if !is_expired {
+        Delegate::authorize(
+            &ctx.accounts.payer.key(),
+            &ctx.accounts.deal.controller,
+            ctx.accounts.delegate.as_deref(),
+            DelegatePermission::CRANK,
+            &ctx.accounts.deal.base_mint,
+            &ctx.accounts.deal.quote_mint,
+            now,
+        )?;
+    }
+
+    // The bounty pays whoever cranks after expiry. Before expiry only the controller (or a
+    // delegate) may crank, so it is refunded to the controller rather than the signer.
+    let bounty_recipient = if is_expired {
+        ctx.accounts.payer.key()
+    } else {
+        ctx.accounts.deal.controller
+    };
+
+    let allow_partial = ctx.accounts.deal.allow_partial;
+
+    // Blob copies for the creator's auditor, if they registered one
+    let owner_key = ctx.accounts.deal.encryption_pubkey;
+    let auditor_key = ctx.accounts.creator_balance.auditor_key_or(owner_key);
+
+    // Build ArgBuilder for crank_swap_deal instruction:
+    // crank_swap_deal(deal_state: Enc<Mxe, &SwapDealState>, creator_balance: Enc<Mxe, &BalanceState>,
+    //                 creator_deal_blob: Shared, creator_balance_blob: Shared, is_expired: bool,
+    //                 allow_partial: bool, auditor: ArcisX25519Pubkey)
+    let args = ArgBuilder::new()
+        // Enc<Mxe, &SwapDealState>
+        .plaintext_u128(deal_nonce)
+        .account(deal_key, DEAL_CIPHERTEXT_OFFSET, DEAL_CIPHERTEXT_LENGTH)
+        // Enc<Mxe, &BalanceState>
+        .plaintext_u128(creator_balance_nonce)
+        .account(
+            creator_balance_key,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        )
+        // Shared marker for deal blob
+        .x25519_pubkey(ctx.accounts.deal.encryption_pubkey)
+        .plaintext_u128(creator_deal_blob_nonce)
+        // Shared marker for balance blob
+        .x25519_pubkey(ctx.accounts.deal.encryption_pubkey)
+        .plaintext_u128(creator_balance_blob_nonce)
+        // Plaintext booleans
+        .plaintext_bool(is_expired)
+        .plaintext_bool(allow_partial)
+        // Auditor key for the blob copies
+        .x25519_pubkey(auditor_key)
+        .build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![CrankSwapDealCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: deal_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: creator_balance_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: bounty_recipient,
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn callback_handler(
+    ctx: Context<CrankSwapDealCallback>,
+    output: SignedComputationOutputs<CrankSwapDealOutput>,
+) -> Result<()> {
+    // Verify and extract output
+    // The return type is (Enc<Mxe, BalanceState>, [Enc<Shared, DealSettledBlob>; 2],
+    //                     [Enc<Shared, BalanceUpdatedBlob>; 2], u8, u8)
+    // Blob arrays hold [owner, auditor] copies
+    let tuple_output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(CrankSwapDealOutput { field_0 }) => field_0,
+        Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    // Reject the computation if the circuit hit an arithmetic error
+    CircuitError::check(tuple_output.field_4)?;
+
+    // Access tuple elements via generated struct fields
+    let balance_state = &tuple_output.field_0;
+    let deal_blob = &tuple_output.field_1;
+    let balance_blob = &tuple_output.field_2;
+    let status = DealStatus::from_circuit(tuple_output.field_3)?;
+
+    // Only update if status changed (Executed or Expired)
+    if status != DealStatus::Open {
+        let deal = &mut ctx.accounts.deal;
+        deal.status = status;
+
+        // Update creator's balance MXE state
+        let balance = &mut ctx.accounts.creator_balance;
+        balance.nonce = balance_state.nonce.to_le_bytes();
+        balance.ciphertexts = balance_state.ciphertexts;
+
+        // Emit DealSettled event with shared blob for creator
+        emit!(DealSettled {
+            deal: deal.key(),
+            status,
+            settled_at: Clock::get()?.unix_timestamp,
+            encryption_key: deal_blob[0].encryption_key,
+            nonce: deal_blob[0].nonce.to_le_bytes(),
+            ciphertexts: deal_blob[0].ciphertexts,
+            auditor_encryption_key: deal_blob[1].encryption_key,
+            auditor_nonce: deal_blob[1].nonce.to_le_bytes(),
+            auditor_ciphertexts: deal_blob[1].ciphertexts,
+        });
+
+        // Emit BalanceUpdated event for creator
+        emit!(BalanceUpdated {
+            balance: balance.key(),
+            controller: balance.controller,
+            mint: balance.mint,
+            encryption_key: balance_blob[0].encryption_key,
+            nonce: balance_blob[0].nonce.to_le_bytes(),
+            ciphertexts: balance_blob[0].ciphertexts,
+            auditor_encryption_key: balance_blob[1].encryption_key,
+            auditor_nonce: balance_blob[1].nonce.to_le_bytes(),
+            auditor_ciphertexts: balance_blob[1].ciphertexts,
+        });
+
+        // Pay the crank bounty (a refund to the controller when cranked before expiry)
+        let deal = &mut ctx.accounts.deal;
+        let bounty = deal.crank_bounty;
+        if bounty > 0 {
+            deal.crank_bounty = 0;
+            pay_crank_bounty(
+                &deal.to_account_info(),
+                &ctx.accounts.bounty_recipient.to_account_info(),
+                bounty,
+            )?;
+            emit!(CrankBountyPaid {
+                account: deal.key(),
+                recipient: ctx.accounts.bounty_recipient.key(),
+                amount: bounty,
+                refund: ctx.accounts.bounty_recipient.key() == deal.controller,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn init_comp_def_handler(ctx: Context<InitCrankSwapDealCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, None, None)?;
+    Ok(())
+}
+
+#[init_computation_definition_accounts("crank_swap_deal", payer)]
+#[derive(Accounts)]
+pub struct InitCrankSwapDealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    /// Can't check it here as it's not initialized yet.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("crank_swap_deal", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CrankSwapDeal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = deal.swap @ ErrorCode::SwapDealMismatch,
+    )]
+    pub deal: Box<Account<'info, DealAccount>>,
+
+    /// Creator's BASE token balance (for releasing commitment and refund)
+    #[account(
+        mut,
+        seeds = [b"balance", deal.controller.as_ref(), deal.base_mint.as_ref()],
+        bump,
+    )]
+    pub creator_balance: Box<Account<'info, BalanceAccount>>,
+
+    /// Delegate of the deal's controller; required only for a delegate cranking before expiry
+    pub delegate: Option<Box<Account<'info, Delegate>>>,
+
+    // --- Arcium accounts (auto-generated pattern) ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("crank_swap_deal")]
+#[derive(Accounts)]
+pub struct CrankSwapDealCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account, checked by arcium program via constraints in the callback context.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub deal: Box<Account<'info, DealAccount>>,
+    #[account(mut)]
+    pub creator_balance: Box<Account<'info, BalanceAccount>>,
+    /// CHECK: receives the crank bounty: the cranker after expiry, otherwise the deal's
+    /// controller; fixed by the queueing instruction
+    #[account(mut)]
+    pub bounty_recipient: UncheckedAccount<'info>,
+}
@@ -0,0 +1,430 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::{CircuitError, ErrorCode};
+use crate::state::{
+    deposit_crank_bounty, load_leg_balances, pay_crank_bounty, price_scale, BalanceAccount,
+    BasketLegs, CounterpartyRestriction, DealAccount, DealStatus, Delegate, DelegatePermission,
+    Proposal, DEAL_VERSION, MAX_BASKET_LEGS,
+};
+use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
+use crate::{BasketDealCreated, DealRejected};
+
+const COMP_DEF_OFFSET: u32 = comp_def_offset("create_basket_deal");
+use crate::{SignerAccount, ID, ID_CONST};
+
+/// Basket outputs (~1 KiB with the auditor copy) do not fit in a single callback transaction
+const BASKET_CALLBACK_TXS: u8 = 2;
+
+/// Creates a basket deal selling `units` baskets of up to MAX_BASKET_LEGS base tokens.
+///
+/// remaining_accounts: the controller's BASE balance of each leg, in leg order.
+#[allow(clippy::too_many_arguments)]
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateBasketDeal<'info>>,
+    computation_offset: u64,
+    controller: Pubkey,
+    encryption_pubkey: [u8; 32],
+    nonce: u128,
+    expires_at: i64,
+    allow_partial: bool,
+    encrypted_units: [u8; 32],
+    encrypted_price: [u8; 32],
+    encrypted_leg_amounts: [[u8; 32]; MAX_BASKET_LEGS],
+    crank_bounty: u64,
+) -> Result<()> {
+    let deal_key = ctx.accounts.deal.key();
+    let quote_mint_key = ctx.accounts.quote_mint.key();
+
+    // Every leg balance belongs to the controller and holds a distinct base mint
+    let legs = load_leg_balances(&controller, &quote_mint_key, None, ctx.remaining_accounts)?;
+    let leg_mints: Vec<Pubkey> = legs.iter().map(|leg| leg.mint).collect();
+
+    // The controller or one of its delegates (holding every leg's pair) must sign;
+    // anyone may execute a multisig controller's approved proposal
+    let now = Clock::get()?.unix_timestamp;
+    let max_notional = if let Some(proposal) = ctx.accounts.proposal.as_deref_mut() {
+        proposal.consume(&controller, &ctx.accounts.instructions_sysvar)?;
+        u64::MAX
+    } else {
+        Delegate::authorize_legs(
+            &ctx.accounts.authority.key(),
+            &controller,
+            ctx.accounts.delegate.as_deref(),
+            DelegatePermission::CREATE_DEAL,
+            &leg_mints,
+            &quote_mint_key,
+            now,
+        )?
+    };
+
+    // Basket units carry no decimals; prices are whole quote per basket unit
+    let quote_decimals = ctx.accounts.quote_mint.decimals;
+    let (price_scale_up, price_scale_down) = price_scale(0, quote_decimals)?;
+
+    // Blob copy for the creator's auditor, taken from the first leg's balance
+    let auditor_key = Account::<BalanceAccount>::try_from(&ctx.remaining_accounts[0])?
+        .auditor_key_or(encryption_pubkey);
+
+    // Initialize DealAccount plaintext fields; the deal counts basket units
+    let deal = &mut ctx.accounts.deal;
+    deal.version = DEAL_VERSION;
+    deal.create_key = ctx.accounts.create_key.key();
+    deal.controller = controller;
+    deal.encryption_pubkey = encryption_pubkey;
+    deal.base_mint = Pubkey::default();
+    deal.quote_mint = quote_mint_key;
+    deal.base_decimals = 0;
+    deal.quote_decimals = quote_decimals;
+    deal.tick_size = 1;
+    deal.lot_size = 1;
+    deal.price_feed = Pubkey::default();
+    deal.band_bps = 0;
+    deal.created_at = 0; // Will be set in callback
+    deal.expires_at = expires_at;
+    deal.status = DealStatus::Open;
+    deal.allow_partial = allow_partial;
+    deal.num_offers = 0;
+    deal.counterparty_kind = CounterpartyRestriction::NONE;
+    deal.counterparty = [0u8; 32];
+    deal.crank_bounty = crank_bounty;
+    deal.basket_legs = legs.len() as u8;
+    deal.swap = false;
+    deal.bump = ctx.bumps.deal;
+
+    let basket = &mut ctx.accounts.basket;
+    basket.deal = deal_key;
+    basket.num_legs = legs.len() as u8;
+    basket.leg_mints = [Pubkey::default(); MAX_BASKET_LEGS];
+    basket.leg_mints[..legs.len()].copy_from_slice(&leg_mints);
+    basket.bump = ctx.bumps.basket;
+
+    // Escrow the optional crank bounty on the deal account
+    deposit_crank_bounty(
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.deal.to_account_info(),
+        crank_bounty,
+    )?;
+
+    // Build ArgBuilder for create_basket_deal instruction:
+    // create_basket_deal(deal_data: Enc<Shared, BasketDealInput>,
+    //                    leg_balances: [Enc<Mxe, &BalanceState>; 4], num_legs: u8,
+    //                    price_scale_up: u128, price_scale_down: u128, max_notional: u64,
+    //                    auditor: ArcisX25519Pubkey)
+    let mut args = ArgBuilder::new()
+        // Enc<Shared, BasketDealInput>
+        .x25519_pubkey(encryption_pubkey)
+        .plaintext_u128(nonce)
+        .encrypted_u64(encrypted_units)
+        .encrypted_u128(encrypted_price);
+    for encrypted_leg_amount in encrypted_leg_amounts {
+        args = args.encrypted_u64(encrypted_leg_amount);
+    }
+    // [Enc<Mxe, &BalanceState>; 4] - unused slots repeat leg 0
+    for i in 0..MAX_BASKET_LEGS {
+        let leg = &legs[if i < legs.len() { i } else { 0 }];
+        args = args.plaintext_u128(leg.nonce).account(
+            leg.balance,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        );
+    }
+    let args = args
+        .plaintext_u8(legs.len() as u8)
+        // Plaintext price scale from the quote mint's decimals
+        .plaintext_u128(price_scale_up)
+        .plaintext_u128(price_scale_down)
+        // Plaintext notional limit of the signing delegate
+        .plaintext_u64(max_notional)
+        // Auditor key for the blob copy
+        .x25519_pubkey(auditor_key)
+        .build();
+
+    // Callback accounts: deal, basket, payer, then the leg balances in leg order
+    let mut callback_accounts = vec![
+        CallbackAccount {
+            pubkey: deal_key,
+            is_writable: true,
+        },
+        CallbackAccount {
+            pubkey: ctx.accounts.basket.key(),
+            is_writable: true,
+        },
+        CallbackAccount {
+            pubkey: controller,
+            is_writable: true,
+        },
+    ];
+    callback_accounts.extend(legs.iter().map(|leg| CallbackAccount {
+        pubkey: leg.balance,
+        is_writable: true,
+    }));
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![CreateBasketDealCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &callback_accounts,
+        )?],
+        BASKET_CALLBACK_TXS,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn callback_handler(
+    ctx: Context<CreateBasketDealCallback>,
+    output: SignedComputationOutputs<CreateBasketDealOutput>,
+) -> Result<()> {
+    // Verify and extract output
+    // The return type is (Enc<Mxe, DealState>, Enc<Mxe, BasketLegsState>,
+    //                     [Enc<Mxe, BalanceState>; 4], [Enc<Shared, BasketDealCreatedBlob>; 2], u8)
+    // The blob array holds [owner, auditor] copies
+    let tuple_output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(CreateBasketDealOutput { field_0 }) => field_0,
+        Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    // A notional over the delegate's limit or oversized inputs reject the deal: nothing was
+    // locked, so only the status changes
+    let error = tuple_output.field_4;
+    if CircuitError::is_rejection(error) {
+        let deal = &mut ctx.accounts.deal;
+        deal.status = DealStatus::Rejected;
+        deal.created_at = Clock::get()?.unix_timestamp;
+
+        // Return the escrowed crank bounty to the controller, the deal can never be cranked
+        let bounty = deal.crank_bounty;
+        if bounty > 0 {
+            deal.crank_bounty = 0;
+            pay_crank_bounty(
+                &deal.to_account_info(),
+                &ctx.accounts.controller.to_account_info(),
+                bounty,
+            )?;
+        }
+
+        emit!(DealRejected {
+            deal: deal.key(),
+            controller: deal.controller,
+            error,
+        });
+        return Ok(());
+    }
+
+    // Reject the computation if the circuit hit an arithmetic error
+    CircuitError::check(error)?;
+
+    let mxe_deal_state = &tuple_output.field_0;
+    let mxe_legs_state = &tuple_output.field_1;
+    let balance_states = &tuple_output.field_2;
+    let deal_blob = &tuple_output.field_3;
+
+    // Store MXE-encrypted state in the deal and basket accounts
+    let deal = &mut ctx.accounts.deal;
+    deal.nonce = mxe_deal_state.nonce.to_le_bytes();
+    deal.ciphertexts = mxe_deal_state.ciphertexts;
+    deal.created_at = Clock::get()?.unix_timestamp;
+
+    let basket = &mut ctx.accounts.basket;
+    basket.nonce = mxe_legs_state.nonce.to_le_bytes();
+    basket.ciphertexts = mxe_legs_state.ciphertexts;
+
+    // Lock every leg's commitment in the same callback
+    // Accounts were validated when queued and are fixed by the callback instruction
+    for (balance_info, balance_state) in ctx.remaining_accounts.iter().zip(balance_states) {
+        let mut balance =
+            BalanceAccount::try_deserialize(&mut &balance_info.try_borrow_data()?[..])?;
+        balance.nonce = balance_state.nonce.to_le_bytes();
+        balance.ciphertexts = balance_state.ciphertexts;
+        balance.try_serialize(&mut &mut balance_info.try_borrow_mut_data()?[..])?;
+    }
+
+    emit!(BasketDealCreated {
+        deal: deal.key(),
+        leg_mints: basket.mints().to_vec(),
+        quote_mint: deal.quote_mint,
+        expires_at: deal.expires_at,
+        allow_partial: deal.allow_partial,
+        created_at: deal.created_at,
+        encryption_key: deal_blob[0].encryption_key,
+        nonce: deal_blob[0].nonce.to_le_bytes(),
+        ciphertexts: deal_blob[0].ciphertexts,
+        auditor_encryption_key: deal_blob[1].encryption_key,
+        auditor_nonce: deal_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: deal_blob[1].ciphertexts,
+    });
+
+    Ok(())
+}
+
+pub fn init_comp_def_handler(ctx: Context<InitCreateBasketDealCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, None, None)?;
+    Ok(())
+}
+
+#[init_computation_definition_accounts("create_basket_deal", payer)]
+#[derive(Accounts)]
+pub struct InitCreateBasketDealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    /// Can't check it here as it's not initialized yet.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("create_basket_deal", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, controller: Pubkey)]
+pub struct CreateBasketDeal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The controller, or a delegate acting for it
+    pub authority: Signer<'info>,
+
+    /// Delegate of the controller; required only when `authority` is not the controller
+    pub delegate: Option<Box<Account<'info, Delegate>>>,
+
+    /// Approved proposal of a multisig controller; present only for multisig calls
+    #[account(
+        mut,
+        seeds = [b"proposal", controller.as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    /// Ephemeral signer for PDA uniqueness
+    pub create_key: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DealAccount::INIT_SPACE,
+        seeds = [b"deal", create_key.key().as_ref()],
+        bump,
+    )]
+    pub deal: Box<Account<'info, DealAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + BasketLegs::INIT_SPACE,
+        seeds = [b"basket", deal.key().as_ref()],
+        bump,
+    )]
+    pub basket: Box<Account<'info, BasketLegs>>,
+
+    pub quote_mint: Box<Account<'info, Mint>>,
+
+    // --- Arcium accounts (auto-generated pattern) ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    // remaining_accounts: the controller's BASE balance of each leg, in leg order
+}
+
+#[callback_accounts("create_basket_deal")]
+#[derive(Accounts)]
+pub struct CreateBasketDealCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account, checked by arcium program via constraints in the callback context.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub deal: Box<Account<'info, DealAccount>>,
+    #[account(mut)]
+    pub basket: Box<Account<'info, BasketLegs>>,
+    /// CHECK: controller of the deal, refunded the crank bounty if the deal is rejected;
+    /// fixed by the queueing instruction
+    #[account(mut)]
+    pub controller: UncheckedAccount<'info>,
+    // remaining_accounts: leg balances in leg order
+}
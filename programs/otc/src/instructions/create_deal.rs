@@ -3,14 +3,19 @@ use anchor_spl::token::Mint;
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
-use crate::error::ErrorCode;
-use crate::state::{BalanceAccount, DealAccount, DealStatus};
+use crate::error::{CircuitError, ErrorCode};
+use crate::state::{
+    deposit_crank_bounty, pay_crank_bounty, price_band, price_scale, BalanceAccount,
+    CounterpartyRestriction, DealAccount, DealStatus, Delegate, DelegatePermission, PairConfig,
+    PriceFeed, Proposal, RelayNonce, DEAL_VERSION,
+};
 use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
-use crate::{BalanceUpdated, DealCreated};
+use crate::{BalanceUpdated, DealCreated, DealRejected};
 
 const COMP_DEF_OFFSET: u32 = comp_def_offset("create_deal");
 use crate::{SignerAccount, ID, ID_CONST};
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<CreateDeal>,
     computation_offset: u64,
@@ -22,6 +27,9 @@ pub fn handler(
     allow_partial: bool,
     encrypted_amount: [u8; 32],
     encrypted_price: [u8; 32],
+    counterparty_kind: u8,
+    counterparty: [u8; 32],
+    crank_bounty: u64,
 ) -> Result<()> {
     // Capture keys and nonce before mutable borrows
     let deal_key = ctx.accounts.deal.key();
@@ -34,22 +42,97 @@ pub fn handler(
         ErrorCode::ControllerMismatch
     );
 
+    // The controller or one of its delegates must sign; delegates are capped in the circuit.
+    // A relayer may sign instead when it carries the controller's ed25519 signature, and
+    // anyone may execute a multisig controller's approved proposal.
+    let now = Clock::get()?.unix_timestamp;
+    let max_notional = if let Some(proposal) = ctx.accounts.proposal.as_deref_mut() {
+        proposal.consume(&controller, &ctx.accounts.instructions_sysvar)?;
+        u64::MAX
+    } else if let Some(relay_nonce) = ctx.accounts.relay_nonce.as_deref_mut() {
+        relay_nonce.consume(&controller, &ctx.accounts.instructions_sysvar)?;
+        u64::MAX
+    } else {
+        Delegate::authorize(
+            &ctx.accounts.authority.key(),
+            &controller,
+            ctx.accounts.delegate.as_deref(),
+            DelegatePermission::CREATE_DEAL,
+            &ctx.accounts.base_mint.key(),
+            &ctx.accounts.quote_mint.key(),
+            now,
+        )?
+    };
+
+    // Validate counterparty restriction
+    require!(
+        counterparty_kind <= CounterpartyRestriction::MERKLE_ROOT,
+        ErrorCode::InvalidCounterpartyRestriction
+    );
+    let counterparty = if counterparty_kind == CounterpartyRestriction::NONE {
+        [0u8; 32]
+    } else {
+        counterparty
+    };
+
+    // Prices are entered in human units; the circuit scales them by the mints' decimals
+    let base_decimals = ctx.accounts.base_mint.decimals;
+    let quote_decimals = ctx.accounts.quote_mint.decimals;
+    let (price_scale_up, price_scale_down) = price_scale(base_decimals, quote_decimals)?;
+
+    // Snapshot the pair's increments and feed so later config changes don't affect this deal
+    let pair = PairConfig::read(&ctx.accounts.pair_config)?;
+    let (tick_size, lot_size) = (pair.tick_size, pair.lot_size);
+
+    // Bound the encrypted price by the oracle; the circuit only reveals whether it fits
+    let (min_price, max_price) = price_band(
+        &pair.price_feed,
+        pair.band_bps,
+        ctx.accounts.price_feed.as_deref(),
+        now,
+    )?;
+
+    // Blob copies for the creator's auditor, if they registered one
+    let auditor_key = ctx.accounts.creator_balance.auditor_key_or(encryption_pubkey);
+
     // Initialize DealAccount plaintext fields
     let deal = &mut ctx.accounts.deal;
+    deal.version = DEAL_VERSION;
     deal.create_key = ctx.accounts.create_key.key();
     deal.controller = controller;
     deal.encryption_pubkey = encryption_pubkey;
     deal.base_mint = ctx.accounts.base_mint.key();
     deal.quote_mint = ctx.accounts.quote_mint.key();
+    deal.base_decimals = base_decimals;
+    deal.quote_decimals = quote_decimals;
+    deal.tick_size = tick_size;
+    deal.lot_size = lot_size;
+    deal.price_feed = pair.price_feed;
+    deal.band_bps = pair.band_bps;
     deal.created_at = 0; // Will be set in callback
     deal.expires_at = expires_at;
-    deal.status = DealStatus::OPEN;
+    deal.status = DealStatus::Open;
     deal.allow_partial = allow_partial;
     deal.num_offers = 0;
+    deal.counterparty_kind = counterparty_kind;
+    deal.counterparty = counterparty;
+    deal.crank_bounty = crank_bounty;
+    deal.basket_legs = 0;
+    deal.swap = false;
     deal.bump = ctx.bumps.deal;
 
+    // Escrow the optional crank bounty on the deal account
+    deposit_crank_bounty(
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.deal.to_account_info(),
+        crank_bounty,
+    )?;
+
     // Build ArgBuilder for create_deal instruction:
-    // create_deal(deal_data: Enc<Shared, DealInput>, creator_balance: Enc<Mxe, &BalanceState>, creator: Shared)
+    // create_deal(deal_data: Enc<Shared, DealInput>, creator_balance: Enc<Mxe, &BalanceState>, creator: Shared,
+    //             price_scale_up: u128, price_scale_down: u128, tick_size: u128, lot_size: u64,
+    //             min_price: u128, max_price: u128, max_notional: u64, auditor: ArcisX25519Pubkey)
     //
     // Enc<Shared, DealInput>: x25519_pubkey + nonce + encrypted fields
     // Enc<Mxe, &BalanceState>: nonce + account reference
@@ -70,6 +153,19 @@ pub fn handler(
         // Shared marker for balance blob
         .x25519_pubkey(encryption_pubkey)
         .plaintext_u128(balance_blob_nonce)
+        // Plaintext price scale from the mints' decimals
+        .plaintext_u128(price_scale_up)
+        .plaintext_u128(price_scale_down)
+        // Plaintext pair increments
+        .plaintext_u128(tick_size)
+        .plaintext_u64(lot_size)
+        // Plaintext oracle price band
+        .plaintext_u128(min_price)
+        .plaintext_u128(max_price)
+        // Plaintext notional limit of the signing delegate
+        .plaintext_u64(max_notional)
+        // Auditor key for the blob copies
+        .x25519_pubkey(auditor_key)
         .build();
 
     ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
@@ -91,6 +187,10 @@ pub fn handler(
                     pubkey: creator_balance_key,
                     is_writable: true,
                 },
+                CallbackAccount {
+                    pubkey: controller,
+                    is_writable: true,
+                },
             ],
         )?],
         1,
@@ -105,7 +205,9 @@ pub fn callback_handler(
     output: SignedComputationOutputs<CreateDealOutput>,
 ) -> Result<()> {
     // Verify and extract output
-    // The return type is (Enc<Mxe, DealState>, Enc<Mxe, BalanceState>, Enc<Shared, DealCreatedBlob>, Enc<Shared, BalanceUpdatedBlob>)
+    // The return type is (Enc<Mxe, DealState>, Enc<Mxe, BalanceState>, [Enc<Shared, DealCreatedBlob>; 2],
+    //                     [Enc<Shared, BalanceUpdatedBlob>; 2], u8)
+    // Blob arrays hold [owner, auditor] copies
     let tuple_output = match output.verify_output(
         &ctx.accounts.cluster_account,
         &ctx.accounts.computation_account,
@@ -114,6 +216,36 @@ pub fn callback_handler(
         Err(_) => return Err(ErrorCode::AbortedComputation.into()),
     };
 
+    // Off-tick/lot, out-of-band or oversized inputs reject the deal: nothing was locked,
+    // so only the status changes
+    let error = tuple_output.field_4;
+    if CircuitError::is_rejection(error) {
+        let deal = &mut ctx.accounts.deal;
+        deal.status = DealStatus::Rejected;
+        deal.created_at = Clock::get()?.unix_timestamp;
+
+        // Return the escrowed crank bounty to the controller, the deal can never be cranked
+        let bounty = deal.crank_bounty;
+        if bounty > 0 {
+            deal.crank_bounty = 0;
+            pay_crank_bounty(
+                &deal.to_account_info(),
+                &ctx.accounts.controller.to_account_info(),
+                bounty,
+            )?;
+        }
+
+        emit!(DealRejected {
+            deal: deal.key(),
+            controller: deal.controller,
+            error,
+        });
+        return Ok(());
+    }
+
+    // Reject the computation if the circuit hit an arithmetic error
+    CircuitError::check(error)?;
+
     // Access tuple elements via generated struct fields
     let mxe_deal_state = &tuple_output.field_0;
     let mxe_balance_state = &tuple_output.field_1;
@@ -141,9 +273,12 @@ pub fn callback_handler(
         expires_at: deal.expires_at,
         allow_partial: deal.allow_partial,
         created_at: deal.created_at,
-        encryption_key: deal_blob.encryption_key,
-        nonce: deal_blob.nonce.to_le_bytes(),
-        ciphertexts: deal_blob.ciphertexts,
+        encryption_key: deal_blob[0].encryption_key,
+        nonce: deal_blob[0].nonce.to_le_bytes(),
+        ciphertexts: deal_blob[0].ciphertexts,
+        auditor_encryption_key: deal_blob[1].encryption_key,
+        auditor_nonce: deal_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: deal_blob[1].ciphertexts,
     });
 
     // Emit BalanceUpdated event for creator
@@ -151,9 +286,12 @@ pub fn callback_handler(
         balance: balance.key(),
         controller: balance.controller,
         mint: balance.mint,
-        encryption_key: balance_blob.encryption_key,
-        nonce: balance_blob.nonce.to_le_bytes(),
-        ciphertexts: balance_blob.ciphertexts,
+        encryption_key: balance_blob[0].encryption_key,
+        nonce: balance_blob[0].nonce.to_le_bytes(),
+        ciphertexts: balance_blob[0].ciphertexts,
+        auditor_encryption_key: balance_blob[1].encryption_key,
+        auditor_nonce: balance_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: balance_blob[1].ciphertexts,
     });
 
     Ok(())
@@ -189,6 +327,32 @@ pub struct CreateDeal<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    /// The controller, or a delegate acting for it
+    pub authority: Signer<'info>,
+
+    /// Delegate of the controller; required only when `authority` is not the controller
+    pub delegate: Option<Box<Account<'info, Delegate>>>,
+
+    /// Controller's relay nonce; present only for relayed (controller-signed message) calls
+    #[account(
+        mut,
+        seeds = [b"relay_nonce", controller.as_ref()],
+        bump = relay_nonce.bump,
+    )]
+    pub relay_nonce: Option<Box<Account<'info, RelayNonce>>>,
+
+    /// Approved proposal of a multisig controller; present only for multisig calls
+    #[account(
+        mut,
+        seeds = [b"proposal", controller.as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+
     /// Ephemeral signer for PDA uniqueness
     pub create_key: Signer<'info>,
 
@@ -212,6 +376,13 @@ pub struct CreateDeal<'info> {
     pub base_mint: Box<Account<'info, Mint>>,
     pub quote_mint: Box<Account<'info, Mint>>,
 
+    /// CHECK: optional PairConfig; defaults apply if it does not exist
+    #[account(seeds = [b"pair", base_mint.key().as_ref(), quote_mint.key().as_ref()], bump)]
+    pub pair_config: UncheckedAccount<'info>,
+
+    /// Oracle for the pair's price band; required only if the pair config sets one
+    pub price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
     // --- Arcium accounts (auto-generated pattern) ---
     #[account(
         init_if_needed,
@@ -291,4 +462,8 @@ pub struct CreateDealCallback<'info> {
     pub deal: Box<Account<'info, DealAccount>>,
     #[account(mut)]
     pub creator_balance: Box<Account<'info, BalanceAccount>>,
+    /// CHECK: controller of the deal, refunded the crank bounty if the deal is rejected;
+    /// fixed by the queueing instruction
+    #[account(mut)]
+    pub controller: UncheckedAccount<'info>,
 }
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state::{Delegate, DelegatePair, DelegatePermission, MAX_DELEGATE_PAIRS};
+
+pub fn handler(
+    ctx: Context<CreateDelegate>,
+    permissions: u8,
+    pairs: Vec<DelegatePair>,
+    max_notional: u64,
+    expires_at: i64,
+) -> Result<()> {
+    require!(
+        permissions != 0 && permissions & !DelegatePermission::ALL == 0,
+        ErrorCode::InvalidDelegate
+    );
+    require!(
+        pairs.len() <= MAX_DELEGATE_PAIRS,
+        ErrorCode::InvalidDelegate
+    );
+    require!(
+        expires_at > Clock::get()?.unix_timestamp,
+        ErrorCode::InvalidDelegate
+    );
+
+    let delegate = &mut ctx.accounts.delegate;
+    delegate.controller = ctx.accounts.controller.key();
+    delegate.delegate = ctx.accounts.session_key.key();
+    delegate.permissions = permissions;
+    delegate.pairs = pairs;
+    delegate.max_notional = max_notional;
+    delegate.expires_at = expires_at;
+    delegate.bump = ctx.bumps.delegate;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateDelegate<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Controller granting the permissions
+    pub controller: Signer<'info>,
+
+    /// CHECK: session key being authorized; it does not need to sign
+    #[account(constraint = session_key.key() != controller.key() @ ErrorCode::InvalidDelegate)]
+    pub session_key: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Delegate::INIT_SPACE,
+        seeds = [b"delegate", controller.key().as_ref(), session_key.key().as_ref()],
+        bump,
+    )]
+    pub delegate: Account<'info, Delegate>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state::{Multisig, MAX_MULTISIG_MEMBERS};
+
+pub fn handler(ctx: Context<CreateMultisig>, threshold: u8, members: Vec<Pubkey>) -> Result<()> {
+    require!(
+        !members.is_empty() && members.len() <= MAX_MULTISIG_MEMBERS,
+        ErrorCode::InvalidMultisig
+    );
+    require!(
+        members
+            .iter()
+            .enumerate()
+            .all(|(i, member)| !members[..i].contains(member)),
+        ErrorCode::InvalidMultisig
+    );
+    require!(
+        threshold >= 1 && usize::from(threshold) <= members.len(),
+        ErrorCode::InvalidMultisig
+    );
+
+    let multisig = &mut ctx.accounts.multisig;
+    multisig.create_key = ctx.accounts.create_key.key();
+    multisig.threshold = threshold;
+    multisig.members = members;
+    multisig.proposal_count = 0;
+    multisig.bump = ctx.bumps.multisig;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Ephemeral signer used to derive a unique multisig PDA
+    pub create_key: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Multisig::INIT_SPACE,
+        seeds = [b"multisig", create_key.key().as_ref()],
+        bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Multisig, Proposal, ProposalStatus};
+
+pub fn handler(ctx: Context<CreateProposal>, instruction_digest: [u8; 32]) -> Result<()> {
+    let multisig = &mut ctx.accounts.multisig;
+    let proposer_bit = multisig.member_bit(&ctx.accounts.proposer.key())?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.multisig = multisig.key();
+    proposal.index = multisig.proposal_count;
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.instruction_digest = instruction_digest;
+    proposal.approvals = 0;
+    proposal.status = ProposalStatus::Active;
+    proposal.bump = ctx.bumps.proposal;
+
+    // Creating a proposal counts as the proposer's approval
+    proposal.approve(proposer_bit, multisig.threshold)?;
+
+    multisig.proposal_count += 1;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    /// Member proposing the instruction; pays for the proposal account
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &multisig.proposal_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
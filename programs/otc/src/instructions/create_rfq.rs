@@ -0,0 +1,327 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::{CircuitError, ErrorCode};
+use crate::state::{BalanceAccount, CounterpartyRestriction, RfqAccount, RfqStatus};
+use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
+use crate::{BalanceUpdated, RfqCreated, RfqRejected};
+
+const COMP_DEF_OFFSET: u32 = comp_def_offset("create_rfq");
+use crate::{SignerAccount, ID, ID_CONST};
+
+#[allow(clippy::too_many_arguments)]
+pub fn handler(
+    ctx: Context<CreateRfq>,
+    computation_offset: u64,
+    controller: Pubkey,
+    encryption_pubkey: [u8; 32],
+    nonce: u128,
+    balance_blob_nonce: u128,
+    deadline: i64,
+    encrypted_size: [u8; 32],
+    encrypted_limit_price: [u8; 32],
+    counterparty_kind: u8,
+    counterparty: [u8; 32],
+) -> Result<()> {
+    // Capture keys and nonce before mutable borrows
+    let rfq_key = ctx.accounts.rfq.key();
+    let taker_balance_key = ctx.accounts.taker_balance.key();
+    let taker_balance_nonce = u128::from_le_bytes(ctx.accounts.taker_balance.nonce);
+
+    // Verify the balance controller matches
+    require!(
+        ctx.accounts.taker_balance.controller == controller,
+        ErrorCode::ControllerMismatch
+    );
+
+    // Validate counterparty restriction (selects which makers may quote)
+    require!(
+        counterparty_kind <= CounterpartyRestriction::MERKLE_ROOT,
+        ErrorCode::InvalidCounterpartyRestriction
+    );
+    let counterparty = if counterparty_kind == CounterpartyRestriction::NONE {
+        [0u8; 32]
+    } else {
+        counterparty
+    };
+
+    // Initialize RfqAccount plaintext fields
+    let rfq = &mut ctx.accounts.rfq;
+    rfq.create_key = ctx.accounts.create_key.key();
+    rfq.controller = controller;
+    rfq.encryption_pubkey = encryption_pubkey;
+    rfq.base_mint = ctx.accounts.base_mint.key();
+    rfq.quote_mint = ctx.accounts.quote_mint.key();
+    rfq.created_at = 0; // Will be set in callback
+    rfq.deadline = deadline;
+    rfq.status = RfqStatus::Open;
+    rfq.num_quotes = 0;
+    rfq.counterparty_kind = counterparty_kind;
+    rfq.counterparty = counterparty;
+    rfq.bump = ctx.bumps.rfq;
+
+    // Build ArgBuilder for create_rfq instruction:
+    // create_rfq(rfq_data: Enc<Shared, RfqInput>, taker_balance: Enc<Mxe, &BalanceState>, taker: Shared,
+    //            auditor: ArcisX25519Pubkey)
+    let args = ArgBuilder::new()
+        // Enc<Shared, RfqInput> - fields ordered as in struct: size (u64), limit_price (u128)
+        .x25519_pubkey(encryption_pubkey)
+        .plaintext_u128(nonce)
+        .encrypted_u64(encrypted_size)
+        .encrypted_u128(encrypted_limit_price)
+        // Enc<Mxe, &BalanceState>
+        .plaintext_u128(taker_balance_nonce)
+        .account(
+            taker_balance_key,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        )
+        // Shared marker for balance blob
+        .x25519_pubkey(encryption_pubkey)
+        .plaintext_u128(balance_blob_nonce)
+        // Auditor key for the blob copies
+        .x25519_pubkey(ctx.accounts.taker_balance.auditor_key_or(encryption_pubkey))
+        .build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![CreateRfqCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: rfq_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: taker_balance_key,
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn callback_handler(
+    ctx: Context<CreateRfqCallback>,
+    output: SignedComputationOutputs<CreateRfqOutput>,
+) -> Result<()> {
+    // Verify and extract output
+    // The return type is (Enc<Mxe, RfqState>, Enc<Mxe, BalanceState>, [Enc<Shared, RfqCreatedBlob>; 2],
+    //                     [Enc<Shared, BalanceUpdatedBlob>; 2], u8)
+    // Blob arrays hold [owner, auditor] copies
+    let tuple_output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(CreateRfqOutput { field_0 }) => field_0,
+        Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    // A size too large to lock rejects the RFQ. The circuit left the balance unchanged and
+    // produced an empty RFQ, so all state is still written.
+    let error = tuple_output.field_4;
+    if !CircuitError::is_rejection(error) {
+        // Reject the computation if the circuit hit an arithmetic error
+        CircuitError::check(error)?;
+    }
+
+    let mxe_rfq_state = &tuple_output.field_0;
+    let mxe_balance_state = &tuple_output.field_1;
+    let rfq_blob = &tuple_output.field_2;
+    let balance_blob = &tuple_output.field_3;
+
+    // Store MXE-encrypted state in RFQ account
+    let rfq = &mut ctx.accounts.rfq;
+    rfq.nonce = mxe_rfq_state.nonce.to_le_bytes();
+    rfq.ciphertexts = mxe_rfq_state.ciphertexts;
+    rfq.created_at = Clock::get()?.unix_timestamp;
+
+    // Store MXE-encrypted state in taker balance account
+    let balance = &mut ctx.accounts.taker_balance;
+    balance.nonce = mxe_balance_state.nonce.to_le_bytes();
+    balance.ciphertexts = mxe_balance_state.ciphertexts;
+
+    if CircuitError::is_rejection(error) {
+        rfq.status = RfqStatus::Rejected;
+        emit!(RfqRejected {
+            rfq: rfq.key(),
+            controller: rfq.controller,
+            error,
+        });
+        return Ok(());
+    }
+
+    emit!(RfqCreated {
+        rfq: rfq.key(),
+        base_mint: rfq.base_mint,
+        quote_mint: rfq.quote_mint,
+        deadline: rfq.deadline,
+        created_at: rfq.created_at,
+        encryption_key: rfq_blob[0].encryption_key,
+        nonce: rfq_blob[0].nonce.to_le_bytes(),
+        ciphertexts: rfq_blob[0].ciphertexts,
+        auditor_encryption_key: rfq_blob[1].encryption_key,
+        auditor_nonce: rfq_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: rfq_blob[1].ciphertexts,
+    });
+
+    emit!(BalanceUpdated {
+        balance: balance.key(),
+        controller: balance.controller,
+        mint: balance.mint,
+        encryption_key: balance_blob[0].encryption_key,
+        nonce: balance_blob[0].nonce.to_le_bytes(),
+        ciphertexts: balance_blob[0].ciphertexts,
+        auditor_encryption_key: balance_blob[1].encryption_key,
+        auditor_nonce: balance_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: balance_blob[1].ciphertexts,
+    });
+
+    Ok(())
+}
+
+pub fn init_comp_def_handler(ctx: Context<InitCreateRfqCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, None, None)?;
+    Ok(())
+}
+
+#[init_computation_definition_accounts("create_rfq", payer)]
+#[derive(Accounts)]
+pub struct InitCreateRfqCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    /// Can't check it here as it's not initialized yet.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("create_rfq", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, controller: Pubkey)]
+pub struct CreateRfq<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Ephemeral signer for RFQ PDA uniqueness
+    pub create_key: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RfqAccount::INIT_SPACE,
+        seeds = [b"rfq", create_key.key().as_ref()],
+        bump,
+    )]
+    pub rfq: Box<Account<'info, RfqAccount>>,
+
+    /// Taker's QUOTE token balance (must exist and have sufficient funds)
+    #[account(
+        mut,
+        seeds = [b"balance", controller.as_ref(), quote_mint.key().as_ref()],
+        bump,
+    )]
+    pub taker_balance: Box<Account<'info, BalanceAccount>>,
+
+    pub base_mint: Box<Account<'info, Mint>>,
+    pub quote_mint: Box<Account<'info, Mint>>,
+
+    // --- Arcium accounts (auto-generated pattern) ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("create_rfq")]
+#[derive(Accounts)]
+pub struct CreateRfqCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account, checked by arcium program via constraints in the callback context.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub rfq: Box<Account<'info, RfqAccount>>,
+    #[account(mut)]
+    pub taker_balance: Box<Account<'info, BalanceAccount>>,
+}
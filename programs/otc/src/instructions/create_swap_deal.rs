@@ -0,0 +1,439 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::{CircuitError, ErrorCode};
+use crate::state::{
+    deposit_crank_bounty, pay_crank_bounty, BalanceAccount, CounterpartyRestriction, DealAccount,
+    DealStatus, Delegate, DelegatePermission, Proposal, RelayNonce, DEAL_VERSION,
+};
+use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
+use crate::{BalanceUpdated, DealCreated, DealRejected};
+
+const COMP_DEF_OFFSET: u32 = comp_def_offset("create_swap_deal");
+use crate::{SignerAccount, ID, ID_CONST};
+
+/// Creates a swap deal giving `give_amount` BASE for `want_amount` QUOTE.
+#[allow(clippy::too_many_arguments)]
+pub fn handler(
+    ctx: Context<CreateSwapDeal>,
+    computation_offset: u64,
+    controller: Pubkey,
+    encryption_pubkey: [u8; 32],
+    nonce: u128,
+    balance_blob_nonce: u128,
+    expires_at: i64,
+    allow_partial: bool,
+    encrypted_give_amount: [u8; 32],
+    encrypted_want_amount: [u8; 32],
+    counterparty_kind: u8,
+    counterparty: [u8; 32],
+    crank_bounty: u64,
+) -> Result<()> {
+    // Capture keys and nonce before mutable borrows
+    let deal_key = ctx.accounts.deal.key();
+    let creator_balance_key = ctx.accounts.creator_balance.key();
+    let creator_balance_nonce = u128::from_le_bytes(ctx.accounts.creator_balance.nonce);
+
+    // Verify the balance controller matches
+    require!(
+        ctx.accounts.creator_balance.controller == controller,
+        ErrorCode::ControllerMismatch
+    );
+
+    // The controller or one of its delegates must sign; delegates are capped in the circuit.
+    // A relayer may sign instead when it carries the controller's ed25519 signature, and
+    // anyone may execute a multisig controller's approved proposal.
+    let now = Clock::get()?.unix_timestamp;
+    let max_notional = if let Some(proposal) = ctx.accounts.proposal.as_deref_mut() {
+        proposal.consume(&controller, &ctx.accounts.instructions_sysvar)?;
+        u64::MAX
+    } else if let Some(relay_nonce) = ctx.accounts.relay_nonce.as_deref_mut() {
+        relay_nonce.consume(&controller, &ctx.accounts.instructions_sysvar)?;
+        u64::MAX
+    } else {
+        Delegate::authorize(
+            &ctx.accounts.authority.key(),
+            &controller,
+            ctx.accounts.delegate.as_deref(),
+            DelegatePermission::CREATE_DEAL,
+            &ctx.accounts.base_mint.key(),
+            &ctx.accounts.quote_mint.key(),
+            now,
+        )?
+    };
+
+    // Validate counterparty restriction
+    require!(
+        counterparty_kind <= CounterpartyRestriction::MERKLE_ROOT,
+        ErrorCode::InvalidCounterpartyRestriction
+    );
+    let counterparty = if counterparty_kind == CounterpartyRestriction::NONE {
+        [0u8; 32]
+    } else {
+        counterparty
+    };
+
+    // Blob copies for the creator's auditor, if they registered one
+    let auditor_key = ctx
+        .accounts
+        .creator_balance
+        .auditor_key_or(encryption_pubkey);
+
+    // Initialize DealAccount plaintext fields; swaps carry no price, so the pair's
+    // increments and oracle band do not apply
+    let deal = &mut ctx.accounts.deal;
+    deal.version = DEAL_VERSION;
+    deal.create_key = ctx.accounts.create_key.key();
+    deal.controller = controller;
+    deal.encryption_pubkey = encryption_pubkey;
+    deal.base_mint = ctx.accounts.base_mint.key();
+    deal.quote_mint = ctx.accounts.quote_mint.key();
+    deal.base_decimals = ctx.accounts.base_mint.decimals;
+    deal.quote_decimals = ctx.accounts.quote_mint.decimals;
+    deal.tick_size = 1;
+    deal.lot_size = 1;
+    deal.price_feed = Pubkey::default();
+    deal.band_bps = 0;
+    deal.created_at = 0; // Will be set in callback
+    deal.expires_at = expires_at;
+    deal.status = DealStatus::Open;
+    deal.allow_partial = allow_partial;
+    deal.num_offers = 0;
+    deal.counterparty_kind = counterparty_kind;
+    deal.counterparty = counterparty;
+    deal.crank_bounty = crank_bounty;
+    deal.basket_legs = 0;
+    deal.swap = true;
+    deal.bump = ctx.bumps.deal;
+
+    // Escrow the optional crank bounty on the deal account
+    deposit_crank_bounty(
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.deal.to_account_info(),
+        crank_bounty,
+    )?;
+
+    // Build ArgBuilder for create_swap_deal instruction:
+    // create_swap_deal(deal_data: Enc<Shared, SwapDealInput>, creator_balance: Enc<Mxe, &BalanceState>,
+    //                  creator: Shared, max_notional: u64, auditor: ArcisX25519Pubkey)
+    //
+    // Enc<Shared, SwapDealInput>: x25519_pubkey + nonce + encrypted fields
+    // Enc<Mxe, &BalanceState>: nonce + account reference
+    // Shared marker: x25519_pubkey + nonce
+    let args = ArgBuilder::new()
+        // Enc<Shared, SwapDealInput>
+        .x25519_pubkey(encryption_pubkey)
+        .plaintext_u128(nonce)
+        .encrypted_u64(encrypted_give_amount)
+        .encrypted_u64(encrypted_want_amount)
+        // Enc<Mxe, &BalanceState>
+        .plaintext_u128(creator_balance_nonce)
+        .account(
+            creator_balance_key,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        )
+        // Shared marker for balance blob
+        .x25519_pubkey(encryption_pubkey)
+        .plaintext_u128(balance_blob_nonce)
+        // Plaintext notional limit of the signing delegate
+        .plaintext_u64(max_notional)
+        // Auditor key for the blob copies
+        .x25519_pubkey(auditor_key)
+        .build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![CreateSwapDealCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: deal_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: creator_balance_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: controller,
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn callback_handler(
+    ctx: Context<CreateSwapDealCallback>,
+    output: SignedComputationOutputs<CreateSwapDealOutput>,
+) -> Result<()> {
+    // Verify and extract output
+    // The return type is (Enc<Mxe, SwapDealState>, Enc<Mxe, BalanceState>,
+    //                     [Enc<Shared, SwapDealCreatedBlob>; 2], [Enc<Shared, BalanceUpdatedBlob>; 2], u8)
+    // Blob arrays hold [owner, auditor] copies
+    let tuple_output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(CreateSwapDealOutput { field_0 }) => field_0,
+        Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    // A want amount over the delegate's limit or oversized inputs reject the deal: nothing
+    // was locked, so only the status changes
+    let error = tuple_output.field_4;
+    if CircuitError::is_rejection(error) {
+        let deal = &mut ctx.accounts.deal;
+        deal.status = DealStatus::Rejected;
+        deal.created_at = Clock::get()?.unix_timestamp;
+
+        // Return the escrowed crank bounty to the controller, the deal can never be cranked
+        let bounty = deal.crank_bounty;
+        if bounty > 0 {
+            deal.crank_bounty = 0;
+            pay_crank_bounty(
+                &deal.to_account_info(),
+                &ctx.accounts.controller.to_account_info(),
+                bounty,
+            )?;
+        }
+
+        emit!(DealRejected {
+            deal: deal.key(),
+            controller: deal.controller,
+            error,
+        });
+        return Ok(());
+    }
+
+    // Reject the computation if the circuit hit an arithmetic error
+    CircuitError::check(error)?;
+
+    // Access tuple elements via generated struct fields
+    let mxe_deal_state = &tuple_output.field_0;
+    let mxe_balance_state = &tuple_output.field_1;
+    let deal_blob = &tuple_output.field_2;
+    let balance_blob = &tuple_output.field_3;
+
+    // Store MXE-encrypted state in deal account
+    let deal = &mut ctx.accounts.deal;
+    deal.nonce = mxe_deal_state.nonce.to_le_bytes();
+    deal.ciphertexts = mxe_deal_state.ciphertexts;
+
+    // Set created_at timestamp
+    deal.created_at = Clock::get()?.unix_timestamp;
+
+    // Store MXE-encrypted state in creator balance account
+    let balance = &mut ctx.accounts.creator_balance;
+    balance.nonce = mxe_balance_state.nonce.to_le_bytes();
+    balance.ciphertexts = mxe_balance_state.ciphertexts;
+
+    // Emit DealCreated event with shared blob for creator (a SwapDealCreatedBlob)
+    emit!(DealCreated {
+        deal: deal.key(),
+        base_mint: deal.base_mint,
+        quote_mint: deal.quote_mint,
+        expires_at: deal.expires_at,
+        allow_partial: deal.allow_partial,
+        created_at: deal.created_at,
+        encryption_key: deal_blob[0].encryption_key,
+        nonce: deal_blob[0].nonce.to_le_bytes(),
+        ciphertexts: deal_blob[0].ciphertexts,
+        auditor_encryption_key: deal_blob[1].encryption_key,
+        auditor_nonce: deal_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: deal_blob[1].ciphertexts,
+    });
+
+    // Emit BalanceUpdated event for creator
+    emit!(BalanceUpdated {
+        balance: balance.key(),
+        controller: balance.controller,
+        mint: balance.mint,
+        encryption_key: balance_blob[0].encryption_key,
+        nonce: balance_blob[0].nonce.to_le_bytes(),
+        ciphertexts: balance_blob[0].ciphertexts,
+        auditor_encryption_key: balance_blob[1].encryption_key,
+        auditor_nonce: balance_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: balance_blob[1].ciphertexts,
+    });
+
+    Ok(())
+}
+
+pub fn init_comp_def_handler(ctx: Context<InitCreateSwapDealCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, None, None)?;
+    Ok(())
+}
+
+#[init_computation_definition_accounts("create_swap_deal", payer)]
+#[derive(Accounts)]
+pub struct InitCreateSwapDealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    /// Can't check it here as it's not initialized yet.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("create_swap_deal", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, controller: Pubkey)]
+pub struct CreateSwapDeal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The controller, or a delegate acting for it
+    pub authority: Signer<'info>,
+
+    /// Delegate of the controller; required only when `authority` is not the controller
+    pub delegate: Option<Box<Account<'info, Delegate>>>,
+
+    /// Controller's relay nonce; present only for relayed (controller-signed message) calls
+    #[account(
+        mut,
+        seeds = [b"relay_nonce", controller.as_ref()],
+        bump = relay_nonce.bump,
+    )]
+    pub relay_nonce: Option<Box<Account<'info, RelayNonce>>>,
+
+    /// Approved proposal of a multisig controller; present only for multisig calls
+    #[account(
+        mut,
+        seeds = [b"proposal", controller.as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    /// Ephemeral signer for PDA uniqueness
+    pub create_key: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DealAccount::INIT_SPACE,
+        seeds = [b"deal", create_key.key().as_ref()],
+        bump,
+    )]
+    pub deal: Account<'info, DealAccount>,
+
+    /// Creator's BASE token balance, the asset given (must exist and have sufficient funds)
+    #[account(
+        mut,
+        seeds = [b"balance", controller.as_ref(), base_mint.key().as_ref()],
+        bump,
+    )]
+    pub creator_balance: Box<Account<'info, BalanceAccount>>,
+
+    pub base_mint: Box<Account<'info, Mint>>,
+    pub quote_mint: Box<Account<'info, Mint>>,
+
+    // --- Arcium accounts (auto-generated pattern) ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("create_swap_deal")]
+#[derive(Accounts)]
+pub struct CreateSwapDealCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account, checked by arcium program via constraints in the callback context.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub deal: Box<Account<'info, DealAccount>>,
+    #[account(mut)]
+    pub creator_balance: Box<Account<'info, BalanceAccount>>,
+    /// CHECK: controller of the deal, refunded the crank bounty if the deal is rejected;
+    /// fixed by the queueing instruction
+    #[account(mut)]
+    pub controller: UncheckedAccount<'info>,
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::state::RelayNonce;
+
+pub fn handler(ctx: Context<InitRelayNonce>, controller: Pubkey) -> Result<()> {
+    let relay_nonce = &mut ctx.accounts.relay_nonce;
+    relay_nonce.controller = controller;
+    relay_nonce.nonce = 0;
+    relay_nonce.bump = ctx.bumps.relay_nonce;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(controller: Pubkey)]
+pub struct InitRelayNonce<'info> {
+    /// Anyone may open the nonce account (typically the relayer); it only orders
+    /// messages the controller signs
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RelayNonce::INIT_SPACE,
+        seeds = [b"relay_nonce", controller.as_ref()],
+        bump,
+    )]
+    pub relay_nonce: Account<'info, RelayNonce>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::state::ControllerLink;
+
+pub fn handler(ctx: Context<LinkController>) -> Result<()> {
+    let link = &mut ctx.accounts.link;
+    link.controller = ctx.accounts.controller.key();
+    link.group = ctx.accounts.group_authority.key();
+    link.bump = ctx.bumps.link;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct LinkController<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Controller joining the group; must consent to the link
+    pub controller: Signer<'info>,
+
+    /// Desk authority identifying the group; must consent to the link
+    pub group_authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ControllerLink::INIT_SPACE,
+        seeds = [b"link", controller.key().as_ref()],
+        bump,
+    )]
+    pub link: Account<'info, ControllerLink>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{migrate_layout, BalanceAccountV0, BALANCE_VERSION};
+use crate::AccountMigrated;
+
+pub fn handler(ctx: Context<MigrateBalance>) -> Result<()> {
+    migrate_layout(
+        &ctx.accounts.balance.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        |legacy: BalanceAccountV0| Ok(legacy.upgrade()),
+    )?;
+
+    emit!(AccountMigrated {
+        account: ctx.accounts.balance.key(),
+        version: BALANCE_VERSION,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateBalance<'info> {
+    /// Anyone may migrate an account; the layout change preserves every field
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, owner = crate::ID)]
+    /// CHECK: legacy BalanceAccount that no longer deserializes; migrate_layout checks the
+    /// discriminator and length
+    pub balance: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::state::{migrate_layout, DealAccount, DealAccountV0, DEAL_VERSION};
+use crate::AccountMigrated;
+
+pub fn handler(ctx: Context<MigrateDeal>) -> Result<()> {
+    let base_mint = &ctx.accounts.base_mint;
+    let quote_mint = &ctx.accounts.quote_mint;
+    migrate_layout(
+        &ctx.accounts.deal.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        |legacy: DealAccountV0| -> Result<DealAccount> {
+            // Version 0 did not record decimals; read them from the deal's own mints
+            require_keys_eq!(legacy.base_mint, base_mint.key(), ErrorCode::MintMismatch);
+            require_keys_eq!(legacy.quote_mint, quote_mint.key(), ErrorCode::MintMismatch);
+            legacy.upgrade(base_mint.decimals, quote_mint.decimals)
+        },
+    )?;
+
+    emit!(AccountMigrated {
+        account: ctx.accounts.deal.key(),
+        version: DEAL_VERSION,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateDeal<'info> {
+    /// Anyone may migrate an account; the layout change preserves every field
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, owner = crate::ID)]
+    /// CHECK: legacy DealAccount that no longer deserializes; migrate_layout checks the
+    /// discriminator and length
+    pub deal: UncheckedAccount<'info>,
+
+    /// The legacy deal's base mint, for its decimals
+    pub base_mint: Box<Account<'info, Mint>>,
+    /// The legacy deal's quote mint, for its decimals
+    pub quote_mint: Box<Account<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
+}
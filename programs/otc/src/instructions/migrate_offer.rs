@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{migrate_layout, OfferAccountV0, OFFER_VERSION};
+use crate::AccountMigrated;
+
+pub fn handler(ctx: Context<MigrateOffer>) -> Result<()> {
+    migrate_layout(
+        &ctx.accounts.offer.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        OfferAccountV0::upgrade,
+    )?;
+
+    emit!(AccountMigrated {
+        account: ctx.accounts.offer.key(),
+        version: OFFER_VERSION,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateOffer<'info> {
+    /// Anyone may migrate an account; the layout change preserves every field
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, owner = crate::ID)]
+    /// CHECK: legacy OfferAccount that no longer deserializes; migrate_layout checks the
+    /// discriminator and length
+    pub offer: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
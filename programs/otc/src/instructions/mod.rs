@@ -1,52 +1,161 @@
+#[cfg(feature = "demo")]
 pub mod add_together;
 pub mod announce_balance;
+pub mod approve_proposal;
+pub mod attest_balance;
+pub mod crank_basket_deal;
 pub mod crank_deal;
 pub mod crank_offer;
+pub mod crank_offers_batch;
+pub mod crank_quote;
+pub mod crank_rfq;
+pub mod crank_swap_deal;
+pub mod crank_swap_offer;
+pub mod create_basket_deal;
 pub mod create_deal;
+pub mod create_delegate;
+pub mod create_multisig;
+pub mod create_proposal;
+pub mod create_rfq;
+pub mod create_swap_deal;
+#[cfg(all(feature = "idl-build", not(feature = "demo")))]
+mod demo_idl_stubs;
+#[cfg(feature = "demo")]
 pub mod get_counter;
+#[cfg(feature = "demo")]
 pub mod increment_counter;
+#[cfg(feature = "demo")]
 pub mod init_counter;
+pub mod init_relay_nonce;
+pub mod link_controller;
+pub mod migrate_balance;
+pub mod migrate_deal;
+pub mod migrate_offer;
+pub mod revoke_delegate;
+pub mod rotate_balance_encryption_key;
+pub mod rotate_deal_encryption_key;
+pub mod rotate_offer_encryption_key;
+pub mod set_auditor;
+pub mod set_pair_config;
+pub mod settle_deal;
 pub mod submit_offer;
+pub mod submit_quote;
+pub mod submit_swap_offer;
 pub mod top_up;
+pub mod transfer;
+pub mod unlink_controller;
+pub mod update_price_feed;
 
 // Re-export account structs and events (not handlers to avoid name conflicts)
+#[cfg(feature = "demo")]
 pub use add_together::{
     AddTogether, AddTogetherCallback, AddTogetherOutput, InitAddTogetherCompDef, SumEvent,
 };
 pub use announce_balance::{
     AnnounceBalance, AnnounceBalanceCallback, AnnounceBalanceOutput, InitAnnounceBalanceCompDef,
 };
+pub use approve_proposal::ApproveProposal;
+pub use attest_balance::{
+    AttestBalance, AttestBalanceCallback, AttestBalanceOutput, InitAttestBalanceCompDef,
+};
+pub use crank_basket_deal::{
+    CrankBasketDeal, CrankBasketDealCallback, CrankBasketDealOutput, InitCrankBasketDealCompDef,
+};
 pub use crank_deal::{
     CrankDeal, CrankDealCallback, CrankDealOutput, InitCrankDealCompDef,
 };
 pub use crank_offer::{
     CrankOffer, CrankOfferCallback, CrankOfferOutput, InitCrankOfferCompDef,
 };
+pub use crank_offers_batch::{
+    CrankOffersBatch, CrankOffersBatchCallback, CrankOffersBatchOutput, InitCrankOffersBatchCompDef,
+};
+pub use crank_quote::{
+    CrankQuote, CrankQuoteCallback, CrankQuoteOutput, InitCrankQuoteCompDef,
+};
+pub use crank_rfq::{
+    CrankRfq, CrankRfqCallback, CrankRfqOutput, InitCrankRfqCompDef,
+};
+pub use crank_swap_deal::{
+    CrankSwapDeal, CrankSwapDealCallback, CrankSwapDealOutput, InitCrankSwapDealCompDef,
+};
+pub use crank_swap_offer::{
+    CrankSwapOffer, CrankSwapOfferCallback, CrankSwapOfferOutput, InitCrankSwapOfferCompDef,
+};
+pub use create_basket_deal::{
+    CreateBasketDeal, CreateBasketDealCallback, CreateBasketDealOutput, InitCreateBasketDealCompDef,
+};
 pub use create_deal::{
     CreateDeal, CreateDealCallback, CreateDealOutput, InitCreateDealCompDef,
 };
+pub use create_delegate::CreateDelegate;
+pub use create_multisig::CreateMultisig;
+pub use create_proposal::CreateProposal;
+pub use create_rfq::{
+    CreateRfq, CreateRfqCallback, CreateRfqOutput, InitCreateRfqCompDef,
+};
+pub use create_swap_deal::{
+    CreateSwapDeal, CreateSwapDealCallback, CreateSwapDealOutput, InitCreateSwapDealCompDef,
+};
+#[cfg(all(feature = "idl-build", not(feature = "demo")))]
+pub use demo_idl_stubs::*;
+#[cfg(feature = "demo")]
 pub use get_counter::{
     CounterValueEvent, GetCounter, GetCounterCallback, GetCounterOutput, InitGetCounterCompDef,
 };
+#[cfg(feature = "demo")]
 pub use increment_counter::{
     IncrementCounter, IncrementCounterCallback, IncrementCounterOutput,
     InitIncrementCounterCompDef,
 };
+#[cfg(feature = "demo")]
 pub use init_counter::{
     InitCounter, InitCounterCallback, InitCounterOutput, InitInitCounterCompDef,
 };
+pub use init_relay_nonce::InitRelayNonce;
+pub use link_controller::LinkController;
+pub use migrate_balance::MigrateBalance;
+pub use migrate_deal::MigrateDeal;
+pub use migrate_offer::MigrateOffer;
+pub use revoke_delegate::RevokeDelegate;
+pub use rotate_balance_encryption_key::RotateBalanceEncryptionKey;
+pub use rotate_deal_encryption_key::{
+    AnnounceDealCallback, AnnounceDealOutput, InitAnnounceDealCompDef, RotateDealEncryptionKey,
+};
+pub use rotate_offer_encryption_key::{
+    AnnounceOfferCallback, AnnounceOfferOutput, InitAnnounceOfferCompDef, RotateOfferEncryptionKey,
+};
+pub use set_auditor::SetAuditor;
+pub use set_pair_config::SetPairConfig;
+pub use settle_deal::{
+    InitSettleDealCompDef, SettleDeal, SettleDealCallback, SettleDealOutput,
+};
 pub use submit_offer::{
     InitSubmitOfferCompDef, SubmitOffer, SubmitOfferCallback, SubmitOfferOutput,
 };
+pub use submit_quote::{
+    InitSubmitQuoteCompDef, SubmitQuote, SubmitQuoteCallback, SubmitQuoteOutput,
+};
+pub use submit_swap_offer::{
+    InitSubmitSwapOfferCompDef, SubmitSwapOffer, SubmitSwapOfferCallback, SubmitSwapOfferOutput,
+};
 pub use top_up::{
     InitTopUpCompDef, TopUp, TopUpCallback, TopUpOutput,
 };
+pub use transfer::{
+    InitTransferCompDef, Transfer, TransferCallback, TransferOutput,
+};
+pub use unlink_controller::UnlinkController;
+pub use update_price_feed::UpdatePriceFeed;
 
 // Re-export the hidden __client_accounts_* modules that anchor's #[program] macro expects at crate root
+#[cfg(feature = "demo")]
 #[doc(hidden)]
 pub(crate) use add_together::__client_accounts_add_together;
+#[cfg(feature = "demo")]
 #[doc(hidden)]
 pub(crate) use add_together::__client_accounts_add_together_callback;
+#[cfg(feature = "demo")]
 #[doc(hidden)]
 pub(crate) use add_together::__client_accounts_init_add_together_comp_def;
 #[doc(hidden)]
@@ -56,6 +165,20 @@ pub(crate) use announce_balance::__client_accounts_announce_balance_callback;
 #[doc(hidden)]
 pub(crate) use announce_balance::__client_accounts_init_announce_balance_comp_def;
 #[doc(hidden)]
+pub(crate) use approve_proposal::__client_accounts_approve_proposal;
+#[doc(hidden)]
+pub(crate) use attest_balance::__client_accounts_attest_balance;
+#[doc(hidden)]
+pub(crate) use attest_balance::__client_accounts_attest_balance_callback;
+#[doc(hidden)]
+pub(crate) use attest_balance::__client_accounts_init_attest_balance_comp_def;
+#[doc(hidden)]
+pub(crate) use crank_basket_deal::__client_accounts_crank_basket_deal;
+#[doc(hidden)]
+pub(crate) use crank_basket_deal::__client_accounts_crank_basket_deal_callback;
+#[doc(hidden)]
+pub(crate) use crank_basket_deal::__client_accounts_init_crank_basket_deal_comp_def;
+#[doc(hidden)]
 pub(crate) use crank_deal::__client_accounts_crank_deal;
 #[doc(hidden)]
 pub(crate) use crank_deal::__client_accounts_crank_deal_callback;
@@ -68,38 +191,159 @@ pub(crate) use crank_offer::__client_accounts_crank_offer_callback;
 #[doc(hidden)]
 pub(crate) use crank_offer::__client_accounts_init_crank_offer_comp_def;
 #[doc(hidden)]
+pub(crate) use crank_offers_batch::__client_accounts_crank_offers_batch;
+#[doc(hidden)]
+pub(crate) use crank_offers_batch::__client_accounts_crank_offers_batch_callback;
+#[doc(hidden)]
+pub(crate) use crank_offers_batch::__client_accounts_init_crank_offers_batch_comp_def;
+#[doc(hidden)]
+pub(crate) use crank_quote::__client_accounts_crank_quote;
+#[doc(hidden)]
+pub(crate) use crank_quote::__client_accounts_crank_quote_callback;
+#[doc(hidden)]
+pub(crate) use crank_quote::__client_accounts_init_crank_quote_comp_def;
+#[doc(hidden)]
+pub(crate) use crank_rfq::__client_accounts_crank_rfq;
+#[doc(hidden)]
+pub(crate) use crank_rfq::__client_accounts_crank_rfq_callback;
+#[doc(hidden)]
+pub(crate) use crank_rfq::__client_accounts_init_crank_rfq_comp_def;
+#[doc(hidden)]
+pub(crate) use crank_swap_deal::__client_accounts_crank_swap_deal;
+#[doc(hidden)]
+pub(crate) use crank_swap_deal::__client_accounts_crank_swap_deal_callback;
+#[doc(hidden)]
+pub(crate) use crank_swap_deal::__client_accounts_init_crank_swap_deal_comp_def;
+#[doc(hidden)]
+pub(crate) use crank_swap_offer::__client_accounts_crank_swap_offer;
+#[doc(hidden)]
+pub(crate) use crank_swap_offer::__client_accounts_crank_swap_offer_callback;
+#[doc(hidden)]
+pub(crate) use crank_swap_offer::__client_accounts_init_crank_swap_offer_comp_def;
+#[doc(hidden)]
+pub(crate) use create_basket_deal::__client_accounts_create_basket_deal;
+#[doc(hidden)]
+pub(crate) use create_basket_deal::__client_accounts_create_basket_deal_callback;
+#[doc(hidden)]
+pub(crate) use create_basket_deal::__client_accounts_init_create_basket_deal_comp_def;
+#[doc(hidden)]
 pub(crate) use create_deal::__client_accounts_create_deal;
 #[doc(hidden)]
 pub(crate) use create_deal::__client_accounts_create_deal_callback;
 #[doc(hidden)]
 pub(crate) use create_deal::__client_accounts_init_create_deal_comp_def;
 #[doc(hidden)]
+pub(crate) use create_delegate::__client_accounts_create_delegate;
+#[doc(hidden)]
+pub(crate) use create_multisig::__client_accounts_create_multisig;
+#[doc(hidden)]
+pub(crate) use create_proposal::__client_accounts_create_proposal;
+#[doc(hidden)]
+pub(crate) use create_rfq::__client_accounts_create_rfq;
+#[doc(hidden)]
+pub(crate) use create_rfq::__client_accounts_create_rfq_callback;
+#[doc(hidden)]
+pub(crate) use create_rfq::__client_accounts_init_create_rfq_comp_def;
+#[doc(hidden)]
+pub(crate) use create_swap_deal::__client_accounts_create_swap_deal;
+#[doc(hidden)]
+pub(crate) use create_swap_deal::__client_accounts_create_swap_deal_callback;
+#[doc(hidden)]
+pub(crate) use create_swap_deal::__client_accounts_init_create_swap_deal_comp_def;
+#[cfg(feature = "demo")]
+#[doc(hidden)]
 pub(crate) use get_counter::__client_accounts_get_counter;
+#[cfg(feature = "demo")]
 #[doc(hidden)]
 pub(crate) use get_counter::__client_accounts_get_counter_callback;
+#[cfg(feature = "demo")]
 #[doc(hidden)]
 pub(crate) use get_counter::__client_accounts_init_get_counter_comp_def;
+#[cfg(feature = "demo")]
 #[doc(hidden)]
 pub(crate) use increment_counter::__client_accounts_increment_counter;
+#[cfg(feature = "demo")]
 #[doc(hidden)]
 pub(crate) use increment_counter::__client_accounts_increment_counter_callback;
+#[cfg(feature = "demo")]
 #[doc(hidden)]
 pub(crate) use increment_counter::__client_accounts_init_increment_counter_comp_def;
+#[cfg(feature = "demo")]
 #[doc(hidden)]
 pub(crate) use init_counter::__client_accounts_init_counter;
+#[cfg(feature = "demo")]
 #[doc(hidden)]
 pub(crate) use init_counter::__client_accounts_init_counter_callback;
+#[cfg(feature = "demo")]
 #[doc(hidden)]
 pub(crate) use init_counter::__client_accounts_init_init_counter_comp_def;
 #[doc(hidden)]
+pub(crate) use init_relay_nonce::__client_accounts_init_relay_nonce;
+#[doc(hidden)]
+pub(crate) use link_controller::__client_accounts_link_controller;
+#[doc(hidden)]
+pub(crate) use migrate_balance::__client_accounts_migrate_balance;
+#[doc(hidden)]
+pub(crate) use migrate_deal::__client_accounts_migrate_deal;
+#[doc(hidden)]
+pub(crate) use migrate_offer::__client_accounts_migrate_offer;
+#[doc(hidden)]
+pub(crate) use revoke_delegate::__client_accounts_revoke_delegate;
+#[doc(hidden)]
+pub(crate) use rotate_balance_encryption_key::__client_accounts_rotate_balance_encryption_key;
+#[doc(hidden)]
+pub(crate) use rotate_deal_encryption_key::__client_accounts_rotate_deal_encryption_key;
+#[doc(hidden)]
+pub(crate) use rotate_deal_encryption_key::__client_accounts_announce_deal_callback;
+#[doc(hidden)]
+pub(crate) use rotate_deal_encryption_key::__client_accounts_init_announce_deal_comp_def;
+#[doc(hidden)]
+pub(crate) use rotate_offer_encryption_key::__client_accounts_rotate_offer_encryption_key;
+#[doc(hidden)]
+pub(crate) use rotate_offer_encryption_key::__client_accounts_announce_offer_callback;
+#[doc(hidden)]
+pub(crate) use rotate_offer_encryption_key::__client_accounts_init_announce_offer_comp_def;
+#[doc(hidden)]
+pub(crate) use set_auditor::__client_accounts_set_auditor;
+#[doc(hidden)]
+pub(crate) use set_pair_config::__client_accounts_set_pair_config;
+#[doc(hidden)]
+pub(crate) use settle_deal::__client_accounts_settle_deal;
+#[doc(hidden)]
+pub(crate) use settle_deal::__client_accounts_settle_deal_callback;
+#[doc(hidden)]
+pub(crate) use settle_deal::__client_accounts_init_settle_deal_comp_def;
+#[doc(hidden)]
 pub(crate) use submit_offer::__client_accounts_submit_offer;
 #[doc(hidden)]
 pub(crate) use submit_offer::__client_accounts_submit_offer_callback;
 #[doc(hidden)]
 pub(crate) use submit_offer::__client_accounts_init_submit_offer_comp_def;
 #[doc(hidden)]
+pub(crate) use submit_quote::__client_accounts_submit_quote;
+#[doc(hidden)]
+pub(crate) use submit_quote::__client_accounts_submit_quote_callback;
+#[doc(hidden)]
+pub(crate) use submit_quote::__client_accounts_init_submit_quote_comp_def;
+#[doc(hidden)]
+pub(crate) use submit_swap_offer::__client_accounts_submit_swap_offer;
+#[doc(hidden)]
+pub(crate) use submit_swap_offer::__client_accounts_submit_swap_offer_callback;
+#[doc(hidden)]
+pub(crate) use submit_swap_offer::__client_accounts_init_submit_swap_offer_comp_def;
+#[doc(hidden)]
 pub(crate) use top_up::__client_accounts_top_up;
 #[doc(hidden)]
 pub(crate) use top_up::__client_accounts_top_up_callback;
 #[doc(hidden)]
 pub(crate) use top_up::__client_accounts_init_top_up_comp_def;
+#[doc(hidden)]
+pub(crate) use transfer::__client_accounts_transfer;
+#[doc(hidden)]
+pub(crate) use transfer::__client_accounts_transfer_callback;
+#[doc(hidden)]
+pub(crate) use transfer::__client_accounts_init_transfer_comp_def;
+#[doc(hidden)]
+pub(crate) use unlink_controller::__client_accounts_unlink_controller;
+#[doc(hidden)]
+pub(crate) use update_price_feed::__client_accounts_update_price_feed;
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Delegate;
+
+pub fn handler(_ctx: Context<RevokeDelegate>) -> Result<()> {
+    // Account is closed by the `close` constraint
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegate<'info> {
+    /// Controller that granted the delegate; receives the rent refund
+    #[account(mut)]
+    pub controller: Signer<'info>,
+
+    #[account(
+        mut,
+        close = controller,
+        seeds = [b"delegate", controller.key().as_ref(), delegate.delegate.as_ref()],
+        bump = delegate.bump,
+    )]
+    pub delegate: Account<'info, Delegate>,
+}
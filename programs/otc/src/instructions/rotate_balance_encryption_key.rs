@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::ErrorCode;
+use crate::instructions::announce_balance::AnnounceBalanceCallback;
+use crate::state::{BalanceAccount, BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
+
+// Re-emission reuses the announce_balance circuit and callback
+const COMP_DEF_OFFSET: u32 = comp_def_offset("announce_balance");
+use crate::{SignerAccount, ID, ID_CONST};
+
+/// Re-seals a balance to a new encryption key and re-announces it to that key.
+pub fn handler(
+    ctx: Context<RotateBalanceEncryptionKey>,
+    computation_offset: u64,
+    controller: Pubkey,
+    encryption_pubkey: [u8; 32],
+    owner_nonce: u128,
+) -> Result<()> {
+    let balance_key = ctx.accounts.balance.key();
+    let balance_nonce = u128::from_le_bytes(ctx.accounts.balance.nonce);
+
+    // Verify the controller matches
+    require!(
+        ctx.accounts.balance.controller == controller,
+        ErrorCode::ControllerMismatch
+    );
+
+    // Every later blob (top_up, cranks, transfers) is sealed to the stored key
+    ctx.accounts.balance.encryption_pubkey = encryption_pubkey;
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    // Blob copy for the owner's auditor, if they registered one
+    let auditor_key = ctx.accounts.balance.auditor_key_or(encryption_pubkey);
+
+    // Build ArgBuilder for announce_balance instruction:
+    // announce_balance(balance_state: Enc<Mxe, &BalanceState>, owner: Shared,
+    //                  auditor: ArcisX25519Pubkey)
+    let args = ArgBuilder::new()
+        // Enc<Mxe, &BalanceState> - nonce then account reference
+        .plaintext_u128(balance_nonce)
+        .account(
+            balance_key,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        )
+        // Shared marker - the new key then nonce
+        .x25519_pubkey(encryption_pubkey)
+        .plaintext_u128(owner_nonce)
+        // Auditor key for the blob copy
+        .x25519_pubkey(auditor_key)
+        .build();
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![AnnounceBalanceCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[CallbackAccount {
+                pubkey: balance_key,
+                is_writable: false,
+            }],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+#[queue_computation_accounts("announce_balance", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, controller: Pubkey)]
+pub struct RotateBalanceEncryptionKey<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The controller signer (derived from wallet signature)
+    pub controller_signer: Signer<'info>,
+
+    /// Balance account whose key is rotated
+    #[account(
+        mut,
+        seeds = [b"balance", controller.as_ref(), balance.mint.as_ref()],
+        bump,
+        constraint = controller_signer.key() == controller @ ErrorCode::ControllerMismatch,
+    )]
+    pub balance: Account<'info, BalanceAccount>,
+
+    // --- Arcium accounts ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
@@ -0,0 +1,219 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::ErrorCode;
+use crate::state::{BalanceAccount, DealAccount, DEAL_CIPHERTEXT_LENGTH, DEAL_CIPHERTEXT_OFFSET};
+use crate::DealAnnounced;
+
+const COMP_DEF_OFFSET: u32 = comp_def_offset("announce_deal");
+use crate::{SignerAccount, ID, ID_CONST};
+
+/// Re-seals a deal to a new encryption key and re-announces its state to that key.
+pub fn handler(
+    ctx: Context<RotateDealEncryptionKey>,
+    computation_offset: u64,
+    encryption_pubkey: [u8; 32],
+    owner_nonce: u128,
+) -> Result<()> {
+    let deal_key = ctx.accounts.deal.key();
+    let deal_nonce = u128::from_le_bytes(ctx.accounts.deal.nonce);
+
+    // Later settlement blobs (crank_deal, settle_deal) are sealed to the stored key
+    ctx.accounts.deal.encryption_pubkey = encryption_pubkey;
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    // Blob copy for the creator's auditor, if they registered one
+    let auditor_key = ctx
+        .accounts
+        .creator_balance
+        .auditor_key_or(encryption_pubkey);
+
+    // Build ArgBuilder for announce_deal instruction:
+    // announce_deal(deal_state: Enc<Mxe, &DealState>, owner: Shared, auditor: ArcisX25519Pubkey)
+    let args = ArgBuilder::new()
+        // Enc<Mxe, &DealState>
+        .plaintext_u128(deal_nonce)
+        .account(deal_key, DEAL_CIPHERTEXT_OFFSET, DEAL_CIPHERTEXT_LENGTH)
+        // Shared marker - the new key then nonce
+        .x25519_pubkey(encryption_pubkey)
+        .plaintext_u128(owner_nonce)
+        // Auditor key for the blob copy
+        .x25519_pubkey(auditor_key)
+        .build();
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![AnnounceDealCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[CallbackAccount {
+                pubkey: deal_key,
+                is_writable: false,
+            }],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn callback_handler(
+    ctx: Context<AnnounceDealCallback>,
+    output: SignedComputationOutputs<AnnounceDealOutput>,
+) -> Result<()> {
+    // Verify and extract output
+    // Return type is [Enc<Shared, DealAnnouncedBlob>; 2]
+    // Blob arrays hold [owner, auditor] copies
+    let shared_blob = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(AnnounceDealOutput { field_0 }) => field_0,
+        Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    let deal = &ctx.accounts.deal;
+    emit!(DealAnnounced {
+        deal: deal.key(),
+        controller: deal.controller,
+        encryption_key: shared_blob[0].encryption_key,
+        nonce: shared_blob[0].nonce.to_le_bytes(),
+        ciphertexts: shared_blob[0].ciphertexts,
+        auditor_encryption_key: shared_blob[1].encryption_key,
+        auditor_nonce: shared_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: shared_blob[1].ciphertexts,
+    });
+
+    Ok(())
+}
+
+pub fn init_comp_def_handler(ctx: Context<InitAnnounceDealCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, None, None)?;
+    Ok(())
+}
+
+#[init_computation_definition_accounts("announce_deal", payer)]
+#[derive(Accounts)]
+pub struct InitAnnounceDealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    /// Can't check it here as it's not initialized yet.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("announce_deal", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RotateDealEncryptionKey<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The controller signer (derived from wallet signature)
+    pub controller_signer: Signer<'info>,
+
+    /// Deal whose key is rotated
+    #[account(
+        mut,
+        constraint = deal.controller == controller_signer.key() @ ErrorCode::ControllerMismatch,
+    )]
+    pub deal: Box<Account<'info, DealAccount>>,
+
+    /// Creator's BASE token balance (for the auditor key)
+    #[account(
+        seeds = [b"balance", deal.controller.as_ref(), deal.base_mint.as_ref()],
+        bump,
+    )]
+    pub creator_balance: Box<Account<'info, BalanceAccount>>,
+
+    // --- Arcium accounts ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("announce_deal")]
+#[derive(Accounts)]
+pub struct AnnounceDealCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account, checked by arcium program via constraints in the callback context.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub deal: Box<Account<'info, DealAccount>>,
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::state::BalanceAccount;
+
+pub fn handler(ctx: Context<SetAuditor>, auditor_pubkey: [u8; 32]) -> Result<()> {
+    // A zeroed key removes the auditor; later blobs are only sealed to the owner
+    ctx.accounts.balance.auditor_pubkey = auditor_pubkey;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetAuditor<'info> {
+    /// Balance owner; only they can choose who audits it
+    pub controller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"balance", controller.key().as_ref(), balance.mint.as_ref()],
+        bump = balance.bump,
+    )]
+    pub balance: Account<'info, BalanceAccount>,
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::program::Otc;
+use crate::state::{PairConfig, BPS_DENOMINATOR};
+
+pub fn handler(
+    ctx: Context<SetPairConfig>,
+    tick_size: u128,
+    lot_size: u64,
+    price_feed: Pubkey,
+    band_bps: u16,
+) -> Result<()> {
+    require!(
+        tick_size > 0 && lot_size > 0,
+        ErrorCode::InvalidTickOrLotSize
+    );
+    require!(
+        band_bps as u128 <= BPS_DENOMINATOR,
+        ErrorCode::InvalidPriceBand
+    );
+
+    // Existing deals keep the sizes and feed they were created with
+    let config = &mut ctx.accounts.pair_config;
+    config.base_mint = ctx.accounts.base_mint.key();
+    config.quote_mint = ctx.accounts.quote_mint.key();
+    config.tick_size = tick_size;
+    config.lot_size = lot_size;
+    config.price_feed = price_feed;
+    config.band_bps = band_bps;
+    config.bump = ctx.bumps.pair_config;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPairConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Program upgrade authority; the only account allowed to set pair increments and price bands
+    pub authority: Signer<'info>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Otc>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ ErrorCode::NotAuthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub base_mint: Box<Account<'info, Mint>>,
+    pub quote_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PairConfig::INIT_SPACE,
+        seeds = [b"pair", base_mint.key().as_ref(), quote_mint.key().as_ref()],
+        bump,
+    )]
+    pub pair_config: Account<'info, PairConfig>,
+
+    pub system_program: Program<'info, System>,
+}
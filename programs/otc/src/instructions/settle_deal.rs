@@ -0,0 +1,339 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::{CircuitError, ErrorCode};
+use crate::instructions::crank_offers_batch::{
+    apply_offer_settlements, load_offer_slots, offer_slot_callback_accounts, push_offer_slot_args,
+    BATCH_CALLBACK_TXS, CRANK_BATCH_SIZE,
+};
+use crate::state::{
+    pay_crank_bounty, BalanceAccount, DealAccount, DealStatus, Delegate, DelegatePermission,
+};
+use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
+use crate::state::{DEAL_CIPHERTEXT_LENGTH, DEAL_CIPHERTEXT_OFFSET};
+use crate::{CrankBountyPaid, DealSettled};
+
+const COMP_DEF_OFFSET: u32 = comp_def_offset("settle_deal");
+use crate::{SignerAccount, ID, ID_CONST};
+
+/// Settles a deal and every one of its offers in a single computation.
+///
+/// remaining_accounts: [offer_0, offeror_balance_0, offer_1, offeror_balance_1, ...]
+/// covering all `deal.num_offers` offers. Rejected offers are counted but take no slot.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleDeal<'info>>,
+    computation_offset: u64,
+    creator_deal_blob_nonce: u128,
+) -> Result<()> {
+    // Capture keys and nonce before mutable borrows
+    let deal_key = ctx.accounts.deal.key();
+    let creator_balance_key = ctx.accounts.creator_balance.key();
+    let deal_nonce = u128::from_le_bytes(ctx.accounts.deal.nonce);
+    let creator_balance_nonce = u128::from_le_bytes(ctx.accounts.creator_balance.nonce);
+    let creator_auditor_key = ctx
+        .accounts
+        .creator_balance
+        .auditor_key_or(ctx.accounts.deal.encryption_pubkey);
+
+    // Validate deal is open
+    require!(
+        ctx.accounts.deal.status == DealStatus::Open,
+        ErrorCode::DealNotOpen
+    );
+
+    // Determine if deal has expired
+    let now = Clock::get()?.unix_timestamp;
+    let is_expired = ctx.accounts.deal.expires_at <= now;
+
+    // Authorization check: anyone can settle after expiry, only controller (or a delegate) before
+    if !is_expired {
+        Delegate::authorize(
+            &ctx.accounts.payer.key(),
+            &ctx.accounts.deal.controller,
+            ctx.accounts.delegate.as_deref(),
+            DelegatePermission::CRANK,
+            &ctx.accounts.deal.base_mint,
+            &ctx.accounts.deal.quote_mint,
+            now,
+        )?;
+    }
+
+    // Every offer must be part of the settlement, otherwise balances diverge again
+    let (slots, rejected) = load_offer_slots(
+        deal_key,
+        ctx.accounts.deal.quote_mint,
+        ctx.remaining_accounts,
+    )?;
+    require!(
+        slots.len() + rejected == ctx.accounts.deal.num_offers as usize,
+        ErrorCode::IncompleteOfferSet
+    );
+
+    // The bounty pays whoever cranks after expiry. Before expiry only the controller (or a
+    // delegate) may settle, so it is refunded to the controller rather than the signer.
+    let bounty_recipient = if is_expired {
+        ctx.accounts.payer.key()
+    } else {
+        ctx.accounts.deal.controller
+    };
+
+    // Hold the deal until the callback lands so no offer or crank races the settlement
+    ctx.accounts.deal.status = DealStatus::Settling;
+
+    let allow_partial = ctx.accounts.deal.allow_partial;
+
+    // Build ArgBuilder for settle_deal instruction:
+    // settle_deal(deal_state: Enc<Mxe, &DealState>, creator_balance: Enc<Mxe, &BalanceState>,
+    //             creator_deal_blob: Shared, creator_auditor: ArcisX25519Pubkey,
+    //             is_expired: bool, allow_partial: bool,
+    //             offer_states: [Enc<Mxe, &OfferState>; 8],
+    //             offeror_balances: [Enc<Mxe, &BalanceState>; 8],
+    //             offeror_keys: [ArcisX25519Pubkey; 8], auditor_keys: [ArcisX25519Pubkey; 8],
+    //             floor_locked: [bool; 8])
+    let args = ArgBuilder::new()
+        // Enc<Mxe, &DealState>
+        .plaintext_u128(deal_nonce)
+        .account(deal_key, DEAL_CIPHERTEXT_OFFSET, DEAL_CIPHERTEXT_LENGTH)
+        // Enc<Mxe, &BalanceState>
+        .plaintext_u128(creator_balance_nonce)
+        .account(
+            creator_balance_key,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        )
+        // Shared marker for deal blob
+        .x25519_pubkey(ctx.accounts.deal.encryption_pubkey)
+        .plaintext_u128(creator_deal_blob_nonce)
+        // Auditor key for the deal blob copy
+        .x25519_pubkey(creator_auditor_key)
+        // Plaintext booleans
+        .plaintext_bool(is_expired)
+        .plaintext_bool(allow_partial);
+    let args = push_offer_slot_args(args, &slots).build();
+
+    // Callback accounts: deal, creator balance, cranker, deal bounty recipient, then the
+    // (offer, offeror_balance) pairs
+    let mut callback_accounts = vec![
+        CallbackAccount {
+            pubkey: deal_key,
+            is_writable: true,
+        },
+        CallbackAccount {
+            pubkey: creator_balance_key,
+            is_writable: true,
+        },
+        CallbackAccount {
+            pubkey: ctx.accounts.payer.key(),
+            is_writable: true,
+        },
+        CallbackAccount {
+            pubkey: bounty_recipient,
+            is_writable: true,
+        },
+    ];
+    callback_accounts.extend(offer_slot_callback_accounts(&slots));
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![SettleDealCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &callback_accounts,
+        )?],
+        BATCH_CALLBACK_TXS,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn callback_handler(
+    ctx: Context<SettleDealCallback>,
+    output: SignedComputationOutputs<SettleDealOutput>,
+) -> Result<()> {
+    // Verify and extract output
+    // The return type is ([Enc<Shared, DealSettledBlob>; 2], u8,
+    //                     [Enc<Mxe, BalanceState>; 9], [Enc<Shared, OfferSettledBlob>; 16], u8)
+    // where balance slot 8 is the creator's and slots 0..8 are the offerors'.
+    // Blob arrays hold the owner copies first, then the auditor copies.
+    // An aborted computation reopens the deal instead of failing, which would leave it
+    // Settling for good
+    let tuple_output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(SettleDealOutput { field_0 }) => field_0,
+        Err(_) => {
+            ctx.accounts.deal.status = DealStatus::Open;
+            return Ok(());
+        }
+    };
+
+    let deal_blob = &tuple_output.field_0;
+    let balance_states = &tuple_output.field_2;
+    let status = DealStatus::from_circuit(tuple_output.field_1)?;
+
+    // Nothing is applied unless the deal can settle (status still Open) and the circuit
+    // hit no arithmetic error; either way the deal is reopened.
+    if status == DealStatus::Open || tuple_output.field_4 != CircuitError::NONE {
+        ctx.accounts.deal.status = DealStatus::Open;
+        return Ok(());
+    }
+
+    let settled_at = Clock::get()?.unix_timestamp;
+    let cranker = ctx.accounts.cranker.to_account_info();
+
+    let deal = &mut ctx.accounts.deal;
+    deal.status = status;
+
+    // Update creator's balance MXE state
+    let balance = &mut ctx.accounts.creator_balance;
+    balance.nonce = balance_states[CRANK_BATCH_SIZE].nonce.to_le_bytes();
+    balance.ciphertexts = balance_states[CRANK_BATCH_SIZE].ciphertexts;
+
+    emit!(DealSettled {
+        deal: deal.key(),
+        status,
+        settled_at,
+        encryption_key: deal_blob[0].encryption_key,
+        nonce: deal_blob[0].nonce.to_le_bytes(),
+        ciphertexts: deal_blob[0].ciphertexts,
+        auditor_encryption_key: deal_blob[1].encryption_key,
+        auditor_nonce: deal_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: deal_blob[1].ciphertexts,
+    });
+
+    // Pay the deal's crank bounty (a refund to the controller when settled before expiry)
+    let bounty = deal.crank_bounty;
+    if bounty > 0 {
+        deal.crank_bounty = 0;
+        let recipient = ctx.accounts.bounty_recipient.to_account_info();
+        pay_crank_bounty(&deal.to_account_info(), &recipient, bounty)?;
+        emit!(CrankBountyPaid {
+            account: deal.key(),
+            recipient: recipient.key(),
+            amount: bounty,
+            refund: recipient.key() == deal.controller,
+        });
+    }
+
+    // Settle every offer in the same callback
+    apply_offer_settlements(
+        ctx.remaining_accounts,
+        &balance_states[..CRANK_BATCH_SIZE],
+        &tuple_output.field_3,
+        &cranker,
+    )
+}
+
+pub fn init_comp_def_handler(ctx: Context<InitSettleDealCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, None, None)?;
+    Ok(())
+}
+
+#[init_computation_definition_accounts("settle_deal", payer)]
+#[derive(Accounts)]
+pub struct InitSettleDealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("settle_deal", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SettleDeal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = deal.basket_legs == 0 @ ErrorCode::BasketDealMismatch,
+        constraint = !deal.swap @ ErrorCode::SwapDealMismatch,
+    )]
+    pub deal: Box<Account<'info, DealAccount>>,
+
+    /// Creator's BASE token balance (for releasing commitment and refund)
+    #[account(
+        mut,
+        seeds = [b"balance", deal.controller.as_ref(), deal.base_mint.as_ref()],
+        bump,
+    )]
+    pub creator_balance: Box<Account<'info, BalanceAccount>>,
+
+    /// Delegate of the deal's controller; required only for a delegate cranking before expiry
+    pub delegate: Option<Box<Account<'info, Delegate>>>,
+
+    // --- Arcium accounts ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("settle_deal")]
+#[derive(Accounts)]
+pub struct SettleDealCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub deal: Box<Account<'info, DealAccount>>,
+    #[account(mut)]
+    pub creator_balance: Box<Account<'info, BalanceAccount>>,
+    /// CHECK: cranker, receives the crank bounties; fixed by the queueing instruction
+    #[account(mut)]
+    pub cranker: UncheckedAccount<'info>,
+    /// CHECK: receives the deal's crank bounty: the cranker after expiry, otherwise the
+    /// deal's controller; fixed by the queueing instruction
+    #[account(mut)]
+    pub bounty_recipient: UncheckedAccount<'info>,
+    // remaining_accounts: (offer, offeror_balance) pairs in slot order
+}
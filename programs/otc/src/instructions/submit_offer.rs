@@ -2,15 +2,20 @@ use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
-use crate::error::ErrorCode;
-use crate::state::{BalanceAccount, DealAccount, DealStatus, OfferAccount, OfferStatus};
+use crate::error::{CircuitError, ErrorCode};
+use crate::state::{
+    deposit_crank_bounty, is_allowed_counterparty, pay_crank_bounty, price_band, BalanceAccount,
+    ControllerLink, DealAccount, DealStatus, Delegate, DelegatePermission, OfferAccount,
+    OfferStatus, PriceFeed, RelayNonce, OFFER_VERSION,
+};
 use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
 use crate::state::{DEAL_CIPHERTEXT_LENGTH, DEAL_CIPHERTEXT_OFFSET};
-use crate::OfferCreated;
+use crate::{OfferCreated, OfferRejected};
 
 const COMP_DEF_OFFSET: u32 = comp_def_offset("submit_offer");
 use crate::{SignerAccount, ID, ID_CONST};
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<SubmitOffer>,
     computation_offset: u64,
@@ -19,6 +24,8 @@ pub fn handler(
     nonce: u128,
     encrypted_price: [u8; 32],
     encrypted_amount: [u8; 32],
+    counterparty_proof: Vec<[u8; 32]>,
+    crank_bounty: u64,
 ) -> Result<()> {
     // Capture keys and nonce before mutable borrows to avoid borrow checker issues
     let deal_key = ctx.accounts.deal.key();
@@ -29,7 +36,7 @@ pub fn handler(
 
     // Validate deal is open
     require!(
-        ctx.accounts.deal.status == DealStatus::OPEN,
+        ctx.accounts.deal.status == DealStatus::Open,
         ErrorCode::DealNotOpen
     );
 
@@ -43,26 +50,97 @@ pub fn handler(
         ErrorCode::ControllerMismatch
     );
 
+    // The controller or one of its delegates must sign; delegates are capped in the circuit.
+    // A relayer may sign instead when it carries the controller's ed25519 signature.
+    let max_notional = match ctx.accounts.relay_nonce.as_deref_mut() {
+        Some(relay_nonce) => {
+            relay_nonce.consume(&controller, &ctx.accounts.instructions_sysvar)?;
+            u64::MAX
+        }
+        None => Delegate::authorize(
+            &ctx.accounts.authority.key(),
+            &controller,
+            ctx.accounts.delegate.as_deref(),
+            DelegatePermission::SUBMIT_OFFER,
+            &ctx.accounts.deal.base_mint,
+            &ctx.accounts.deal.quote_mint,
+            now,
+        )?,
+    };
+
+    // Reject wash trades against your own deal or a linked sub-account
+    require!(
+        controller != ctx.accounts.deal.controller,
+        ErrorCode::SelfTrade
+    );
+    require!(
+        !ControllerLink::are_linked(&ctx.accounts.offeror_link, &ctx.accounts.creator_link)?,
+        ErrorCode::LinkedControllers
+    );
+
+    // Verify the offeror is an allowed counterparty (no-op for unrestricted deals)
+    require!(
+        is_allowed_counterparty(
+            ctx.accounts.deal.counterparty_kind,
+            &ctx.accounts.deal.counterparty,
+            &controller,
+            &counterparty_proof,
+        ),
+        ErrorCode::CounterpartyNotAllowed
+    );
+
     // Initialize OfferAccount plaintext fields
     {
         let deal = &ctx.accounts.deal;
         let offer = &mut ctx.accounts.offer;
+        offer.version = OFFER_VERSION;
         offer.create_key = ctx.accounts.create_key.key();
         offer.controller = controller;
         offer.encryption_pubkey = encryption_pubkey;
         offer.deal = deal_key;
         offer.submitted_at = 0; // Will be set in callback
         offer.offer_index = deal.num_offers;
-        offer.status = OfferStatus::OPEN;
+        offer.status = OfferStatus::Open;
+        offer.crank_bounty = crank_bounty;
+        offer.floor_locked = false;
         offer.bump = ctx.bumps.offer;
     }
 
+    // Escrow the optional crank bounty on the offer account
+    deposit_crank_bounty(
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.offer.to_account_info(),
+        crank_bounty,
+    )?;
+
     // Increment offer counter
     ctx.accounts.deal.num_offers += 1;
 
+    // Offer prices use the deal's human units and increments
+    let (price_scale_up, price_scale_down) = ctx.accounts.deal.price_scale()?;
+    let tick_size = ctx.accounts.deal.tick_size;
+    let lot_size = ctx.accounts.deal.lot_size;
+
+    // Blob copy for the offeror's auditor, if they registered one
+    let auditor_key = ctx
+        .accounts
+        .offeror_balance
+        .auditor_key_or(encryption_pubkey);
+
+    // Bound the encrypted price by the deal's oracle at submission time
+    let (min_price, max_price) = price_band(
+        &ctx.accounts.deal.price_feed,
+        ctx.accounts.deal.band_bps,
+        ctx.accounts.price_feed.as_deref(),
+        now,
+    )?;
+
     // Build ArgBuilder for submit_offer instruction:
     // submit_offer(deal_state: Enc<Mxe, &DealState>, offer_data: Enc<Shared, OfferInput>,
-    //              offeror_balance: Enc<Mxe, &BalanceState>)
+    //              offeror_balance: Enc<Mxe, &BalanceState>, price_scale_up: u128,
+    //              price_scale_down: u128, tick_size: u128, lot_size: u64, min_price: u128,
+    //              max_price: u128, max_notional: u64, auditor: ArcisX25519Pubkey)
     let args = ArgBuilder::new()
         // Enc<Mxe, &DealState>
         .plaintext_u128(deal_nonce)
@@ -79,6 +157,19 @@ pub fn handler(
             BALANCE_CIPHERTEXT_OFFSET,
             BALANCE_CIPHERTEXT_LENGTH,
         )
+        // Plaintext price scale from the deal's mint decimals
+        .plaintext_u128(price_scale_up)
+        .plaintext_u128(price_scale_down)
+        // Plaintext deal increments
+        .plaintext_u128(tick_size)
+        .plaintext_u64(lot_size)
+        // Plaintext oracle price band
+        .plaintext_u128(min_price)
+        .plaintext_u128(max_price)
+        // Plaintext notional limit of the signing delegate
+        .plaintext_u64(max_notional)
+        // Auditor key for the blob copy
+        .x25519_pubkey(auditor_key)
         .build();
 
     ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
@@ -104,6 +195,10 @@ pub fn handler(
                     pubkey: offeror_balance_key,
                     is_writable: true,
                 },
+                CallbackAccount {
+                    pubkey: controller,
+                    is_writable: true,
+                },
             ],
         )?],
         1,
@@ -119,7 +214,8 @@ pub fn callback_handler(
 ) -> Result<()> {
     // Verify and extract output
     // The return type is (Enc<Mxe, DealState>, Enc<Mxe, OfferState>, Enc<Mxe, BalanceState>,
-    //                     Enc<Shared, OfferCreatedBlob>)
+    //                     [Enc<Shared, OfferCreatedBlob>; 2], u8)
+    // Blob arrays hold [owner, auditor] copies
     let tuple_output = match output.verify_output(
         &ctx.accounts.cluster_account,
         &ctx.accounts.computation_account,
@@ -128,6 +224,14 @@ pub fn callback_handler(
         Err(_) => return Err(ErrorCode::AbortedComputation.into()),
     };
 
+    // Off-tick/lot, out-of-band or oversized inputs reject the offer. The circuit left the
+    // deal and balance unchanged and produced an empty offer, so all state is still written.
+    let error = tuple_output.field_4;
+    if !CircuitError::is_rejection(error) {
+        // Reject the computation if the circuit hit an arithmetic error
+        CircuitError::check(error)?;
+    }
+
     // Access tuple elements via generated struct fields
     let updated_deal = &tuple_output.field_0;
     let offer_state = &tuple_output.field_1;
@@ -152,15 +256,41 @@ pub fn callback_handler(
     balance.nonce = balance_state.nonce.to_le_bytes();
     balance.ciphertexts = balance_state.ciphertexts;
 
+    if CircuitError::is_rejection(error) {
+        offer.status = OfferStatus::Rejected;
+
+        // Return the escrowed crank bounty to the controller, the offer can never be cranked
+        let bounty = offer.crank_bounty;
+        if bounty > 0 {
+            offer.crank_bounty = 0;
+            pay_crank_bounty(
+                &offer.to_account_info(),
+                &ctx.accounts.controller.to_account_info(),
+                bounty,
+            )?;
+        }
+
+        emit!(OfferRejected {
+            deal: deal.key(),
+            offer: offer.key(),
+            offer_index: offer.offer_index,
+            error,
+        });
+        return Ok(());
+    }
+
     // Emit OfferCreated event with shared blob for offeror
     emit!(OfferCreated {
         deal: deal.key(),
         offer: offer.key(),
         offer_index: offer.offer_index,
         submitted_at: offer.submitted_at,
-        encryption_key: offer_blob.encryption_key,
-        nonce: offer_blob.nonce.to_le_bytes(),
-        ciphertexts: offer_blob.ciphertexts,
+        encryption_key: offer_blob[0].encryption_key,
+        nonce: offer_blob[0].nonce.to_le_bytes(),
+        ciphertexts: offer_blob[0].ciphertexts,
+        auditor_encryption_key: offer_blob[1].encryption_key,
+        auditor_nonce: offer_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: offer_blob[1].ciphertexts,
     });
 
     Ok(())
@@ -196,10 +326,31 @@ pub struct SubmitOffer<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    /// The offeror's controller, or a delegate acting for it
+    pub authority: Signer<'info>,
+
+    /// Delegate of the controller; required only when `authority` is not the controller
+    pub delegate: Option<Box<Account<'info, Delegate>>>,
+
+    /// Controller's relay nonce; present only for relayed (controller-signed message) calls
+    #[account(
+        mut,
+        seeds = [b"relay_nonce", controller.as_ref()],
+        bump = relay_nonce.bump,
+    )]
+    pub relay_nonce: Option<Box<Account<'info, RelayNonce>>>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+
     /// Ephemeral signer for offer PDA uniqueness
     pub create_key: Signer<'info>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = !deal.swap @ ErrorCode::SwapDealMismatch,
+    )]
     pub deal: Box<Account<'info, DealAccount>>,
 
     #[account(
@@ -219,6 +370,17 @@ pub struct SubmitOffer<'info> {
     )]
     pub offeror_balance: Box<Account<'info, BalanceAccount>>,
 
+    /// CHECK: Offeror's ControllerLink PDA; may be uninitialized if the controller is unlinked
+    #[account(seeds = [b"link", controller.as_ref()], bump)]
+    pub offeror_link: UncheckedAccount<'info>,
+
+    /// CHECK: Deal creator's ControllerLink PDA; may be uninitialized if the controller is unlinked
+    #[account(seeds = [b"link", deal.controller.as_ref()], bump)]
+    pub creator_link: UncheckedAccount<'info>,
+
+    /// Oracle for the deal's price band; required only if the deal snapshotted one
+    pub price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
     // --- Arcium accounts (auto-generated pattern) ---
     #[account(
         init_if_needed,
@@ -300,4 +462,8 @@ pub struct SubmitOfferCallback<'info> {
     pub offer: Box<Account<'info, OfferAccount>>,
     #[account(mut)]
     pub offeror_balance: Box<Account<'info, BalanceAccount>>,
+    /// CHECK: controller of the offer, refunded the crank bounty if the offer is rejected;
+    /// fixed by the queueing instruction
+    #[account(mut)]
+    pub controller: UncheckedAccount<'info>,
 }
@@ -0,0 +1,356 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::{CircuitError, ErrorCode};
+use crate::state::{
+    is_allowed_counterparty, BalanceAccount, ControllerLink, QuoteAccount, QuoteStatus, RfqAccount,
+    RfqStatus,
+};
+use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
+use crate::state::{RFQ_CIPHERTEXT_LENGTH, RFQ_CIPHERTEXT_OFFSET};
+use crate::{QuoteCreated, QuoteRejected};
+
+const COMP_DEF_OFFSET: u32 = comp_def_offset("submit_quote");
+use crate::{SignerAccount, ID, ID_CONST};
+
+pub fn handler(
+    ctx: Context<SubmitQuote>,
+    computation_offset: u64,
+    controller: Pubkey,
+    encryption_pubkey: [u8; 32],
+    nonce: u128,
+    encrypted_price: [u8; 32],
+    counterparty_proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    // Capture keys and nonces before mutable borrows
+    let rfq_key = ctx.accounts.rfq.key();
+    let quote_key = ctx.accounts.quote.key();
+    let maker_balance_key = ctx.accounts.maker_balance.key();
+    let rfq_nonce = u128::from_le_bytes(ctx.accounts.rfq.nonce);
+    let maker_balance_nonce = u128::from_le_bytes(ctx.accounts.maker_balance.nonce);
+
+    // Validate RFQ is open and before its deadline
+    require!(
+        ctx.accounts.rfq.status == RfqStatus::Open,
+        ErrorCode::RfqNotOpen
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(ctx.accounts.rfq.deadline > now, ErrorCode::RfqExpired);
+
+    // Verify the balance controller matches
+    require!(
+        ctx.accounts.maker_balance.controller == controller,
+        ErrorCode::ControllerMismatch
+    );
+
+    // Reject wash trades against your own RFQ or a linked sub-account
+    require!(
+        controller != ctx.accounts.rfq.controller,
+        ErrorCode::SelfTrade
+    );
+    require!(
+        !ControllerLink::are_linked(&ctx.accounts.maker_link, &ctx.accounts.taker_link)?,
+        ErrorCode::LinkedControllers
+    );
+
+    // Only makers selected by the taker may quote
+    require!(
+        is_allowed_counterparty(
+            ctx.accounts.rfq.counterparty_kind,
+            &ctx.accounts.rfq.counterparty,
+            &controller,
+            &counterparty_proof,
+        ),
+        ErrorCode::CounterpartyNotAllowed
+    );
+
+    // Initialize QuoteAccount plaintext fields
+    let quote_index = ctx.accounts.rfq.num_quotes;
+    {
+        let quote = &mut ctx.accounts.quote;
+        quote.create_key = ctx.accounts.create_key.key();
+        quote.controller = controller;
+        quote.encryption_pubkey = encryption_pubkey;
+        quote.rfq = rfq_key;
+        quote.submitted_at = 0; // Will be set in callback
+        quote.quote_index = quote_index;
+        quote.status = QuoteStatus::Open;
+        quote.bump = ctx.bumps.quote;
+    }
+
+    // Increment quote counter
+    ctx.accounts.rfq.num_quotes += 1;
+
+    // Build ArgBuilder for submit_quote instruction:
+    // submit_quote(rfq_state: Enc<Mxe, &RfqState>, quote_data: Enc<Shared, QuoteInput>,
+    //              maker_balance: Enc<Mxe, &BalanceState>, quote_index: u32,
+    //              auditor: ArcisX25519Pubkey)
+    let args = ArgBuilder::new()
+        // Enc<Mxe, &RfqState>
+        .plaintext_u128(rfq_nonce)
+        .account(rfq_key, RFQ_CIPHERTEXT_OFFSET, RFQ_CIPHERTEXT_LENGTH)
+        // Enc<Shared, QuoteInput>
+        .x25519_pubkey(encryption_pubkey)
+        .plaintext_u128(nonce)
+        .encrypted_u128(encrypted_price)
+        // Enc<Mxe, &BalanceState>
+        .plaintext_u128(maker_balance_nonce)
+        .account(
+            maker_balance_key,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        )
+        // Plaintext u32: quote_index
+        .plaintext_u32(quote_index)
+        // Auditor key for the blob copy
+        .x25519_pubkey(ctx.accounts.maker_balance.auditor_key_or(encryption_pubkey))
+        .build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![SubmitQuoteCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: rfq_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: quote_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: maker_balance_key,
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn callback_handler(
+    ctx: Context<SubmitQuoteCallback>,
+    output: SignedComputationOutputs<SubmitQuoteOutput>,
+) -> Result<()> {
+    // Verify and extract output
+    // The return type is (Enc<Mxe, RfqState>, Enc<Mxe, QuoteState>, Enc<Mxe, BalanceState>,
+    //                     [Enc<Shared, QuoteCreatedBlob>; 2], u8)
+    // Blob arrays hold [owner, auditor] copies
+    let tuple_output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(SubmitQuoteOutput { field_0 }) => field_0,
+        Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    // A size too large to lock rejects the quote. The circuit left the RFQ and balance
+    // unchanged and produced an empty quote, so all state is still written.
+    let error = tuple_output.field_4;
+    if !CircuitError::is_rejection(error) {
+        // Reject the computation if the circuit hit an arithmetic error
+        CircuitError::check(error)?;
+    }
+
+    let updated_rfq = &tuple_output.field_0;
+    let quote_state = &tuple_output.field_1;
+    let balance_state = &tuple_output.field_2;
+    let quote_blob = &tuple_output.field_3;
+
+    // Update RFQ's MXE state (best quote tracking)
+    let rfq = &mut ctx.accounts.rfq;
+    rfq.nonce = updated_rfq.nonce.to_le_bytes();
+    rfq.ciphertexts = updated_rfq.ciphertexts;
+
+    // Store quote's MXE state
+    let quote = &mut ctx.accounts.quote;
+    quote.nonce = quote_state.nonce.to_le_bytes();
+    quote.ciphertexts = quote_state.ciphertexts;
+    quote.submitted_at = Clock::get()?.unix_timestamp;
+
+    // Store maker's balance MXE state
+    let balance = &mut ctx.accounts.maker_balance;
+    balance.nonce = balance_state.nonce.to_le_bytes();
+    balance.ciphertexts = balance_state.ciphertexts;
+
+    if CircuitError::is_rejection(error) {
+        quote.status = QuoteStatus::Rejected;
+        emit!(QuoteRejected {
+            rfq: rfq.key(),
+            quote: quote.key(),
+            quote_index: quote.quote_index,
+            error,
+        });
+        return Ok(());
+    }
+
+    emit!(QuoteCreated {
+        rfq: rfq.key(),
+        quote: quote.key(),
+        quote_index: quote.quote_index,
+        submitted_at: quote.submitted_at,
+        encryption_key: quote_blob[0].encryption_key,
+        nonce: quote_blob[0].nonce.to_le_bytes(),
+        ciphertexts: quote_blob[0].ciphertexts,
+        auditor_encryption_key: quote_blob[1].encryption_key,
+        auditor_nonce: quote_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: quote_blob[1].ciphertexts,
+    });
+
+    Ok(())
+}
+
+pub fn init_comp_def_handler(ctx: Context<InitSubmitQuoteCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, None, None)?;
+    Ok(())
+}
+
+#[init_computation_definition_accounts("submit_quote", payer)]
+#[derive(Accounts)]
+pub struct InitSubmitQuoteCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    /// Can't check it here as it's not initialized yet.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("submit_quote", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, controller: Pubkey)]
+pub struct SubmitQuote<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Ephemeral signer for quote PDA uniqueness
+    pub create_key: Signer<'info>,
+
+    #[account(mut)]
+    pub rfq: Box<Account<'info, RfqAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + QuoteAccount::INIT_SPACE,
+        seeds = [b"quote", rfq.key().as_ref(), create_key.key().as_ref()],
+        bump,
+    )]
+    pub quote: Box<Account<'info, QuoteAccount>>,
+
+    /// Maker's BASE token balance (must exist and have sufficient funds)
+    #[account(
+        mut,
+        seeds = [b"balance", controller.as_ref(), rfq.base_mint.as_ref()],
+        bump,
+    )]
+    pub maker_balance: Box<Account<'info, BalanceAccount>>,
+
+    /// CHECK: Maker's ControllerLink PDA; may be uninitialized if the controller is unlinked
+    #[account(seeds = [b"link", controller.as_ref()], bump)]
+    pub maker_link: UncheckedAccount<'info>,
+
+    /// CHECK: Taker's ControllerLink PDA; may be uninitialized if the controller is unlinked
+    #[account(seeds = [b"link", rfq.controller.as_ref()], bump)]
+    pub taker_link: UncheckedAccount<'info>,
+
+    // --- Arcium accounts (auto-generated pattern) ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("submit_quote")]
+#[derive(Accounts)]
+pub struct SubmitQuoteCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account, checked by arcium program via constraints in the callback context.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub rfq: Box<Account<'info, RfqAccount>>,
+    #[account(mut)]
+    pub quote: Box<Account<'info, QuoteAccount>>,
+    #[account(mut)]
+    pub maker_balance: Box<Account<'info, BalanceAccount>>,
+}
@@ -0,0 +1,445 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::{CircuitError, ErrorCode};
+use crate::state::{
+    deposit_crank_bounty, is_allowed_counterparty, pay_crank_bounty, BalanceAccount,
+    ControllerLink, DealAccount, DealStatus, Delegate, DelegatePermission, OfferAccount,
+    OfferStatus, RelayNonce, OFFER_VERSION,
+};
+use crate::state::{BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
+use crate::state::{DEAL_CIPHERTEXT_LENGTH, DEAL_CIPHERTEXT_OFFSET};
+use crate::{OfferCreated, OfferRejected};
+
+const COMP_DEF_OFFSET: u32 = comp_def_offset("submit_swap_offer");
+use crate::{SignerAccount, ID, ID_CONST};
+
+/// Submits an offer giving `give_amount` QUOTE for up to `want_amount` BASE of a swap deal.
+#[allow(clippy::too_many_arguments)]
+pub fn handler(
+    ctx: Context<SubmitSwapOffer>,
+    computation_offset: u64,
+    controller: Pubkey,
+    encryption_pubkey: [u8; 32],
+    nonce: u128,
+    encrypted_give_amount: [u8; 32],
+    encrypted_want_amount: [u8; 32],
+    counterparty_proof: Vec<[u8; 32]>,
+    crank_bounty: u64,
+) -> Result<()> {
+    // Capture keys and nonce before mutable borrows to avoid borrow checker issues
+    let deal_key = ctx.accounts.deal.key();
+    let offer_key = ctx.accounts.offer.key();
+    let offeror_balance_key = ctx.accounts.offeror_balance.key();
+    let deal_nonce = u128::from_le_bytes(ctx.accounts.deal.nonce);
+    let offeror_balance_nonce = u128::from_le_bytes(ctx.accounts.offeror_balance.nonce);
+
+    // Validate deal is open
+    require!(
+        ctx.accounts.deal.status == DealStatus::Open,
+        ErrorCode::DealNotOpen
+    );
+
+    // Validate deal has not expired
+    let now = Clock::get()?.unix_timestamp;
+    require!(ctx.accounts.deal.expires_at > now, ErrorCode::DealExpired);
+
+    // Verify the balance controller matches
+    require!(
+        ctx.accounts.offeror_balance.controller == controller,
+        ErrorCode::ControllerMismatch
+    );
+
+    // The controller or one of its delegates must sign; delegates are capped in the circuit.
+    // A relayer may sign instead when it carries the controller's ed25519 signature.
+    let max_notional = match ctx.accounts.relay_nonce.as_deref_mut() {
+        Some(relay_nonce) => {
+            relay_nonce.consume(&controller, &ctx.accounts.instructions_sysvar)?;
+            u64::MAX
+        }
+        None => Delegate::authorize(
+            &ctx.accounts.authority.key(),
+            &controller,
+            ctx.accounts.delegate.as_deref(),
+            DelegatePermission::SUBMIT_OFFER,
+            &ctx.accounts.deal.base_mint,
+            &ctx.accounts.deal.quote_mint,
+            now,
+        )?,
+    };
+
+    // Reject wash trades against your own deal or a linked sub-account
+    require!(
+        controller != ctx.accounts.deal.controller,
+        ErrorCode::SelfTrade
+    );
+    require!(
+        !ControllerLink::are_linked(&ctx.accounts.offeror_link, &ctx.accounts.creator_link)?,
+        ErrorCode::LinkedControllers
+    );
+
+    // Verify the offeror is an allowed counterparty (no-op for unrestricted deals)
+    require!(
+        is_allowed_counterparty(
+            ctx.accounts.deal.counterparty_kind,
+            &ctx.accounts.deal.counterparty,
+            &controller,
+            &counterparty_proof,
+        ),
+        ErrorCode::CounterpartyNotAllowed
+    );
+
+    // Initialize OfferAccount plaintext fields
+    {
+        let deal = &ctx.accounts.deal;
+        let offer = &mut ctx.accounts.offer;
+        offer.version = OFFER_VERSION;
+        offer.create_key = ctx.accounts.create_key.key();
+        offer.controller = controller;
+        offer.encryption_pubkey = encryption_pubkey;
+        offer.deal = deal_key;
+        offer.submitted_at = 0; // Will be set in callback
+        offer.offer_index = deal.num_offers;
+        offer.status = OfferStatus::Open;
+        offer.crank_bounty = crank_bounty;
+        offer.floor_locked = false;
+        offer.bump = ctx.bumps.offer;
+    }
+
+    // Escrow the optional crank bounty on the offer account
+    deposit_crank_bounty(
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.offer.to_account_info(),
+        crank_bounty,
+    )?;
+
+    // Increment offer counter
+    ctx.accounts.deal.num_offers += 1;
+
+    // Blob copy for the offeror's auditor, if they registered one
+    let auditor_key = ctx
+        .accounts
+        .offeror_balance
+        .auditor_key_or(encryption_pubkey);
+
+    // Build ArgBuilder for submit_swap_offer instruction:
+    // submit_swap_offer(deal_state: Enc<Mxe, &SwapDealState>, offer_data: Enc<Shared, SwapOfferInput>,
+    //                   offeror_balance: Enc<Mxe, &BalanceState>, max_notional: u64,
+    //                   auditor: ArcisX25519Pubkey)
+    let args = ArgBuilder::new()
+        // Enc<Mxe, &SwapDealState>
+        .plaintext_u128(deal_nonce)
+        .account(deal_key, DEAL_CIPHERTEXT_OFFSET, DEAL_CIPHERTEXT_LENGTH)
+        // Enc<Shared, SwapOfferInput> - fields ordered as in struct: give_amount, want_amount
+        .x25519_pubkey(encryption_pubkey)
+        .plaintext_u128(nonce)
+        .encrypted_u64(encrypted_give_amount)
+        .encrypted_u64(encrypted_want_amount)
+        // Enc<Mxe, &BalanceState>
+        .plaintext_u128(offeror_balance_nonce)
+        .account(
+            offeror_balance_key,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        )
+        // Plaintext notional limit of the signing delegate
+        .plaintext_u64(max_notional)
+        // Auditor key for the blob copy
+        .x25519_pubkey(auditor_key)
+        .build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![SubmitSwapOfferCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: deal_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: offer_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: offeror_balance_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: controller,
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn callback_handler(
+    ctx: Context<SubmitSwapOfferCallback>,
+    output: SignedComputationOutputs<SubmitSwapOfferOutput>,
+) -> Result<()> {
+    // Verify and extract output
+    // The return type is (Enc<Mxe, SwapDealState>, Enc<Mxe, SwapOfferState>, Enc<Mxe, BalanceState>,
+    //                     [Enc<Shared, SwapOfferCreatedBlob>; 2], u8)
+    // Blob arrays hold [owner, auditor] copies
+    let tuple_output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(SubmitSwapOfferOutput { field_0 }) => field_0,
+        Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    // A give amount over the delegate's limit or oversized inputs reject the offer. The
+    // circuit left the deal and balance unchanged and produced an empty offer, so all state
+    // is still written.
+    let error = tuple_output.field_4;
+    if !CircuitError::is_rejection(error) {
+        // Reject the computation if the circuit hit an arithmetic error
+        CircuitError::check(error)?;
+    }
+
+    // Access tuple elements via generated struct fields
+    let updated_deal = &tuple_output.field_0;
+    let offer_state = &tuple_output.field_1;
+    let balance_state = &tuple_output.field_2;
+    let offer_blob = &tuple_output.field_3;
+
+    // Update deal's MXE state
+    let deal = &mut ctx.accounts.deal;
+    deal.nonce = updated_deal.nonce.to_le_bytes();
+    deal.ciphertexts = updated_deal.ciphertexts;
+
+    // Store offer's MXE state
+    let offer = &mut ctx.accounts.offer;
+    offer.nonce = offer_state.nonce.to_le_bytes();
+    offer.ciphertexts = offer_state.ciphertexts;
+
+    // Set submitted_at timestamp
+    offer.submitted_at = Clock::get()?.unix_timestamp;
+
+    // Store offeror's balance MXE state
+    let balance = &mut ctx.accounts.offeror_balance;
+    balance.nonce = balance_state.nonce.to_le_bytes();
+    balance.ciphertexts = balance_state.ciphertexts;
+
+    if CircuitError::is_rejection(error) {
+        offer.status = OfferStatus::Rejected;
+
+        // Return the escrowed crank bounty to the controller, the offer can never be cranked
+        let bounty = offer.crank_bounty;
+        if bounty > 0 {
+            offer.crank_bounty = 0;
+            pay_crank_bounty(
+                &offer.to_account_info(),
+                &ctx.accounts.controller.to_account_info(),
+                bounty,
+            )?;
+        }
+
+        emit!(OfferRejected {
+            deal: deal.key(),
+            offer: offer.key(),
+            offer_index: offer.offer_index,
+            error,
+        });
+        return Ok(());
+    }
+
+    // Emit OfferCreated event with shared blob for offeror (a SwapOfferCreatedBlob)
+    emit!(OfferCreated {
+        deal: deal.key(),
+        offer: offer.key(),
+        offer_index: offer.offer_index,
+        submitted_at: offer.submitted_at,
+        encryption_key: offer_blob[0].encryption_key,
+        nonce: offer_blob[0].nonce.to_le_bytes(),
+        ciphertexts: offer_blob[0].ciphertexts,
+        auditor_encryption_key: offer_blob[1].encryption_key,
+        auditor_nonce: offer_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: offer_blob[1].ciphertexts,
+    });
+
+    Ok(())
+}
+
+pub fn init_comp_def_handler(ctx: Context<InitSubmitSwapOfferCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, None, None)?;
+    Ok(())
+}
+
+#[init_computation_definition_accounts("submit_swap_offer", payer)]
+#[derive(Accounts)]
+pub struct InitSubmitSwapOfferCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    /// Can't check it here as it's not initialized yet.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("submit_swap_offer", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, controller: Pubkey)]
+pub struct SubmitSwapOffer<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The offeror's controller, or a delegate acting for it
+    pub authority: Signer<'info>,
+
+    /// Delegate of the controller; required only when `authority` is not the controller
+    pub delegate: Option<Box<Account<'info, Delegate>>>,
+
+    /// Controller's relay nonce; present only for relayed (controller-signed message) calls
+    #[account(
+        mut,
+        seeds = [b"relay_nonce", controller.as_ref()],
+        bump = relay_nonce.bump,
+    )]
+    pub relay_nonce: Option<Box<Account<'info, RelayNonce>>>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    /// Ephemeral signer for offer PDA uniqueness
+    pub create_key: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = deal.swap @ ErrorCode::SwapDealMismatch,
+    )]
+    pub deal: Box<Account<'info, DealAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + OfferAccount::INIT_SPACE,
+        seeds = [b"offer", deal.key().as_ref(), create_key.key().as_ref()],
+        bump,
+    )]
+    pub offer: Box<Account<'info, OfferAccount>>,
+
+    /// Offeror's QUOTE token balance, the asset given (must exist and have sufficient funds)
+    #[account(
+        mut,
+        seeds = [b"balance", controller.as_ref(), deal.quote_mint.as_ref()],
+        bump,
+    )]
+    pub offeror_balance: Box<Account<'info, BalanceAccount>>,
+
+    /// CHECK: Offeror's ControllerLink PDA; may be uninitialized if the controller is unlinked
+    #[account(seeds = [b"link", controller.as_ref()], bump)]
+    pub offeror_link: UncheckedAccount<'info>,
+
+    /// CHECK: Deal creator's ControllerLink PDA; may be uninitialized if the controller is unlinked
+    #[account(seeds = [b"link", deal.controller.as_ref()], bump)]
+    pub creator_link: UncheckedAccount<'info>,
+
+    // --- Arcium accounts (auto-generated pattern) ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("submit_swap_offer")]
+#[derive(Accounts)]
+pub struct SubmitSwapOfferCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account, checked by arcium program via constraints in the callback context.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub deal: Box<Account<'info, DealAccount>>,
+    #[account(mut)]
+    pub offer: Box<Account<'info, OfferAccount>>,
+    #[account(mut)]
+    pub offeror_balance: Box<Account<'info, BalanceAccount>>,
+    /// CHECK: controller of the offer, refunded the crank bounty if the offer is rejected;
+    /// fixed by the queueing instruction
+    #[account(mut)]
+    pub controller: UncheckedAccount<'info>,
+}
@@ -3,8 +3,11 @@ use anchor_spl::token::Mint;
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
-use crate::error::ErrorCode;
-use crate::state::{BalanceAccount, BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET};
+use crate::error::{CircuitError, ErrorCode};
+use crate::state::{
+    BalanceAccount, Proposal, BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET,
+    BALANCE_VERSION,
+};
 use crate::BalanceUpdated;
 
 const COMP_DEF_OFFSET: u32 = comp_def_offset("top_up");
@@ -18,6 +21,16 @@ pub fn handler(
     owner_nonce: u128,
     amount: u64,
 ) -> Result<()> {
+    // The controller signs, or anyone executes a multisig controller's approved proposal
+    match ctx.accounts.proposal.as_deref_mut() {
+        Some(proposal) => proposal.consume(&controller, &ctx.accounts.instructions_sysvar)?,
+        None => require_keys_eq!(
+            ctx.accounts.controller_signer.key(),
+            controller,
+            ErrorCode::ControllerMismatch
+        ),
+    }
+
     // Extract keys before mutable borrow
     let balance_key = ctx.accounts.balance.key();
     let mint_key = ctx.accounts.mint.key();
@@ -38,6 +51,7 @@ pub fn handler(
 
         if is_new {
             // Initialize plaintext fields for new balance
+            balance.version = BALANCE_VERSION;
             balance.controller = controller;
             balance.encryption_pubkey = encryption_pubkey;
             balance.mint = mint_key;
@@ -53,8 +67,12 @@ pub fn handler(
 
     ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
+    // Blob copy for the owner's auditor, if they registered one
+    let auditor_key = ctx.accounts.balance.auditor_key_or(encryption_pubkey);
+
     // Build ArgBuilder for top_up instruction:
-    // top_up(balance_state: Enc<Mxe, &BalanceState>, owner: Shared, amount: u64, is_new: bool)
+    // top_up(balance_state: Enc<Mxe, &BalanceState>, owner: Shared, amount: u64, is_new: bool,
+    //        auditor: ArcisX25519Pubkey)
     //
     // For Enc<Mxe, &T> by reference:
     // - plaintext_u128(nonce) for the account's encryption nonce
@@ -84,6 +102,8 @@ pub fn handler(
         .plaintext_u64(amount)
         // Plaintext bool is_new
         .plaintext_bool(is_new)
+        // Auditor key for the blob copy
+        .x25519_pubkey(auditor_key)
         .build();
 
     queue_computation(
@@ -111,7 +131,8 @@ pub fn callback_handler(
     output: SignedComputationOutputs<TopUpOutput>,
 ) -> Result<()> {
     // Verify and extract output
-    // Return type is (Enc<Mxe, BalanceState>, Enc<Shared, BalanceUpdatedBlob>)
+    // Return type is (Enc<Mxe, BalanceState>, [Enc<Shared, BalanceUpdatedBlob>; 2], u8)
+    // Blob arrays hold [owner, auditor] copies
     let tuple_output = match output.verify_output(
         &ctx.accounts.cluster_account,
         &ctx.accounts.computation_account,
@@ -120,6 +141,9 @@ pub fn callback_handler(
         Err(_) => return Err(ErrorCode::AbortedComputation.into()),
     };
 
+    // Reject the computation if the circuit hit an arithmetic error
+    CircuitError::check(tuple_output.field_2)?;
+
     // Access tuple elements via generated struct fields
     let mxe_state = &tuple_output.field_0;
     let shared_blob = &tuple_output.field_1;
@@ -134,9 +158,12 @@ pub fn callback_handler(
         balance: balance.key(),
         controller: balance.controller,
         mint: balance.mint,
-        encryption_key: shared_blob.encryption_key,
-        nonce: shared_blob.nonce.to_le_bytes(),
-        ciphertexts: shared_blob.ciphertexts,
+        encryption_key: shared_blob[0].encryption_key,
+        nonce: shared_blob[0].nonce.to_le_bytes(),
+        ciphertexts: shared_blob[0].ciphertexts,
+        auditor_encryption_key: shared_blob[1].encryption_key,
+        auditor_nonce: shared_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: shared_blob[1].ciphertexts,
     });
 
     Ok(())
@@ -172,9 +199,22 @@ pub struct TopUp<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    /// The controller signer (derived from wallet signature)
+    /// The controller signer (derived from wallet signature), or the executor of a
+    /// multisig proposal
     pub controller_signer: Signer<'info>,
 
+    /// Approved proposal of a multisig controller; present only for multisig calls
+    #[account(
+        mut,
+        seeds = [b"proposal", controller.as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+
     /// Token mint for this balance
     pub mint: Account<'info, Mint>,
 
@@ -184,7 +224,6 @@ pub struct TopUp<'info> {
         space = 8 + BalanceAccount::INIT_SPACE,
         seeds = [b"balance", controller.as_ref(), mint.key().as_ref()],
         bump,
-        constraint = controller_signer.key() == controller @ ErrorCode::ControllerMismatch,
     )]
     pub balance: Account<'info, BalanceAccount>,
 
@@ -0,0 +1,325 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::{CircuitError, ErrorCode};
+use crate::state::{
+    BalanceAccount, Proposal, BALANCE_CIPHERTEXT_LENGTH, BALANCE_CIPHERTEXT_OFFSET,
+};
+use crate::{BalanceTransferred, BalanceUpdated};
+
+const COMP_DEF_OFFSET: u32 = comp_def_offset("transfer");
+use crate::{SignerAccount, ID, ID_CONST};
+
+#[allow(clippy::too_many_arguments)]
+pub fn handler(
+    ctx: Context<Transfer>,
+    computation_offset: u64,
+    controller: Pubkey,
+    encryption_pubkey: [u8; 32],
+    nonce: u128,
+    recipient_blob_nonce: u128,
+    encrypted_amount: [u8; 32],
+) -> Result<()> {
+    // The controller signs, or anyone executes a multisig controller's approved proposal
+    match ctx.accounts.proposal.as_deref_mut() {
+        Some(proposal) => proposal.consume(&controller, &ctx.accounts.instructions_sysvar)?,
+        None => require_keys_eq!(
+            ctx.accounts.controller_signer.key(),
+            controller,
+            ErrorCode::ControllerMismatch
+        ),
+    }
+
+    // Extract keys and nonces before mutable borrow
+    let sender_balance_key = ctx.accounts.sender_balance.key();
+    let recipient_balance_key = ctx.accounts.recipient_balance.key();
+    let sender_balance_nonce = u128::from_le_bytes(ctx.accounts.sender_balance.nonce);
+    let recipient_balance_nonce = u128::from_le_bytes(ctx.accounts.recipient_balance.nonce);
+    let recipient_encryption_pubkey = ctx.accounts.recipient_balance.encryption_pubkey;
+    let sender_auditor_key = ctx
+        .accounts
+        .sender_balance
+        .auditor_key_or(encryption_pubkey);
+    let recipient_auditor_key = ctx
+        .accounts
+        .recipient_balance
+        .auditor_key_or(recipient_encryption_pubkey);
+
+    // Verify the sending controller matches
+    require!(
+        ctx.accounts.sender_balance.controller == controller,
+        ErrorCode::ControllerMismatch
+    );
+    require_keys_neq!(
+        sender_balance_key,
+        recipient_balance_key,
+        ErrorCode::SelfTransfer
+    );
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    // Build ArgBuilder for transfer instruction:
+    // transfer(transfer_data: Enc<Shared, TransferInput>, sender_balance: Enc<Mxe, &BalanceState>,
+    //          recipient_balance: Enc<Mxe, &BalanceState>, recipient_balance_blob: Shared,
+    //          sender_auditor: ArcisX25519Pubkey, recipient_auditor: ArcisX25519Pubkey)
+    let args = ArgBuilder::new()
+        // Enc<Shared, TransferInput>
+        .x25519_pubkey(encryption_pubkey)
+        .plaintext_u128(nonce)
+        .encrypted_u64(encrypted_amount)
+        // Enc<Mxe, &BalanceState> - sender
+        .plaintext_u128(sender_balance_nonce)
+        .account(
+            sender_balance_key,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        )
+        // Enc<Mxe, &BalanceState> - recipient
+        .plaintext_u128(recipient_balance_nonce)
+        .account(
+            recipient_balance_key,
+            BALANCE_CIPHERTEXT_OFFSET,
+            BALANCE_CIPHERTEXT_LENGTH,
+        )
+        // Shared marker for the recipient's balance blob
+        .x25519_pubkey(recipient_encryption_pubkey)
+        .plaintext_u128(recipient_blob_nonce)
+        // Auditor keys for the sender and recipient blob copies
+        .x25519_pubkey(sender_auditor_key)
+        .x25519_pubkey(recipient_auditor_key)
+        .build();
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![TransferCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: sender_balance_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: recipient_balance_key,
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn callback_handler(
+    ctx: Context<TransferCallback>,
+    output: SignedComputationOutputs<TransferOutput>,
+) -> Result<()> {
+    // Verify and extract output
+    // Return type is (Enc<Mxe, BalanceState>, Enc<Mxe, BalanceState>,
+    //                 [Enc<Shared, BalanceUpdatedBlob>; 2], [Enc<Shared, BalanceUpdatedBlob>; 2], u8)
+    // Blob arrays hold [owner, auditor] copies
+    let tuple_output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(TransferOutput { field_0 }) => field_0,
+        Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    // A failed transfer left both balances unchanged; only publish the outcome
+    let success = tuple_output.field_4 == CircuitError::NONE;
+    emit!(BalanceTransferred {
+        sender_balance: ctx.accounts.sender_balance.key(),
+        recipient_balance: ctx.accounts.recipient_balance.key(),
+        mint: ctx.accounts.sender_balance.mint,
+        success,
+    });
+    if !success {
+        return Ok(());
+    }
+
+    // Access tuple elements via generated struct fields
+    let sender_state = &tuple_output.field_0;
+    let recipient_state = &tuple_output.field_1;
+    let sender_blob = &tuple_output.field_2;
+    let recipient_blob = &tuple_output.field_3;
+
+    let sender = &mut ctx.accounts.sender_balance;
+    sender.nonce = sender_state.nonce.to_le_bytes();
+    sender.ciphertexts = sender_state.ciphertexts;
+    emit!(BalanceUpdated {
+        balance: sender.key(),
+        controller: sender.controller,
+        mint: sender.mint,
+        encryption_key: sender_blob[0].encryption_key,
+        nonce: sender_blob[0].nonce.to_le_bytes(),
+        ciphertexts: sender_blob[0].ciphertexts,
+        auditor_encryption_key: sender_blob[1].encryption_key,
+        auditor_nonce: sender_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: sender_blob[1].ciphertexts,
+    });
+
+    let recipient = &mut ctx.accounts.recipient_balance;
+    recipient.nonce = recipient_state.nonce.to_le_bytes();
+    recipient.ciphertexts = recipient_state.ciphertexts;
+    emit!(BalanceUpdated {
+        balance: recipient.key(),
+        controller: recipient.controller,
+        mint: recipient.mint,
+        encryption_key: recipient_blob[0].encryption_key,
+        nonce: recipient_blob[0].nonce.to_le_bytes(),
+        ciphertexts: recipient_blob[0].ciphertexts,
+        auditor_encryption_key: recipient_blob[1].encryption_key,
+        auditor_nonce: recipient_blob[1].nonce.to_le_bytes(),
+        auditor_ciphertexts: recipient_blob[1].ciphertexts,
+    });
+
+    Ok(())
+}
+
+pub fn init_comp_def_handler(ctx: Context<InitTransferCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, None, None)?;
+    Ok(())
+}
+
+#[init_computation_definition_accounts("transfer", payer)]
+#[derive(Accounts)]
+pub struct InitTransferCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    /// Can't check it here as it's not initialized yet.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("transfer", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, controller: Pubkey)]
+pub struct Transfer<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The sending controller signer (derived from wallet signature), or the executor
+    /// of a multisig proposal
+    pub controller_signer: Signer<'info>,
+
+    /// Approved proposal of a multisig controller; present only for multisig calls
+    #[account(
+        mut,
+        seeds = [b"proposal", controller.as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    /// Sender's balance, debited by the transferred amount
+    #[account(
+        seeds = [b"balance", controller.as_ref(), sender_balance.mint.as_ref()],
+        bump,
+    )]
+    pub sender_balance: Box<Account<'info, BalanceAccount>>,
+
+    /// Recipient's existing balance for the same mint
+    #[account(
+        seeds = [b"balance", recipient_balance.controller.as_ref(), sender_balance.mint.as_ref()],
+        bump,
+    )]
+    pub recipient_balance: Box<Account<'info, BalanceAccount>>,
+
+    // --- Arcium accounts ---
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: mempool_account, checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: executing_pool, checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("transfer")]
+#[derive(Accounts)]
+pub struct TransferCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account, checked by arcium program via constraints in the callback context.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub sender_balance: Box<Account<'info, BalanceAccount>>,
+    #[account(mut)]
+    pub recipient_balance: Box<Account<'info, BalanceAccount>>,
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state::ControllerLink;
+
+pub fn handler(_ctx: Context<UnlinkController>) -> Result<()> {
+    // Account is closed by the `close` constraint
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnlinkController<'info> {
+    /// Group authority that created the link; receives the rent refund
+    #[account(mut)]
+    pub group_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = group_authority,
+        seeds = [b"link", link.controller.as_ref()],
+        bump = link.bump,
+        constraint = link.group == group_authority.key() @ ErrorCode::NotAuthorized,
+    )]
+    pub link: Account<'info, ControllerLink>,
+}
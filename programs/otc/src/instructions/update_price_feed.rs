@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::state::PriceFeed;
+
+pub fn handler(ctx: Context<UpdatePriceFeed>, price: u128) -> Result<()> {
+    let feed = &mut ctx.accounts.price_feed;
+    feed.authority = ctx.accounts.authority.key();
+    feed.base_mint = ctx.accounts.base_mint.key();
+    feed.quote_mint = ctx.accounts.quote_mint.key();
+    feed.price = price;
+    feed.updated_at = Clock::get()?.unix_timestamp;
+    feed.bump = ctx.bumps.price_feed;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdatePriceFeed<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Feed owner; the PDA is derived from it, so only it can write this feed
+    pub authority: Signer<'info>,
+
+    pub base_mint: Box<Account<'info, Mint>>,
+    pub quote_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PriceFeed::INIT_SPACE,
+        seeds = [
+            b"price_feed",
+            authority.key().as_ref(),
+            base_mint.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -17,11 +17,13 @@ declare_id!("otchkwgH55zjvvFp8xGXYArzrmm2cCBKfPmgUfn6cE6");
 pub mod otc {
     use super::*;
 
-    // Add Together
+    // Add Together (demo)
+    #[cfg(feature = "demo")]
     pub fn init_add_together_comp_def(ctx: Context<InitAddTogetherCompDef>) -> Result<()> {
         instructions::add_together::init_comp_def_handler(ctx)
     }
 
+    #[cfg(feature = "demo")]
     pub fn add_together(
         ctx: Context<AddTogether>,
         computation_offset: u64,
@@ -40,6 +42,7 @@ pub mod otc {
         )
     }
 
+    #[cfg(feature = "demo")]
     #[arcium_callback(encrypted_ix = "add_together")]
     pub fn add_together_callback(
         ctx: Context<AddTogetherCallback>,
@@ -48,11 +51,13 @@ pub mod otc {
         instructions::add_together::callback_handler(ctx, output)
     }
 
-    // Init Counter
+    // Init Counter (demo)
+    #[cfg(feature = "demo")]
     pub fn init_init_counter_comp_def(ctx: Context<InitInitCounterCompDef>) -> Result<()> {
         instructions::init_counter::init_comp_def_handler(ctx)
     }
 
+    #[cfg(feature = "demo")]
     pub fn init_counter(
         ctx: Context<InitCounter>,
         computation_offset: u64,
@@ -61,6 +66,7 @@ pub mod otc {
         instructions::init_counter::handler(ctx, computation_offset, nonce)
     }
 
+    #[cfg(feature = "demo")]
     #[arcium_callback(encrypted_ix = "init_counter")]
     pub fn init_counter_callback(
         ctx: Context<InitCounterCallback>,
@@ -69,17 +75,20 @@ pub mod otc {
         instructions::init_counter::callback_handler(ctx, output)
     }
 
-    // Increment Counter
+    // Increment Counter (demo)
+    #[cfg(feature = "demo")]
     pub fn init_increment_counter_comp_def(
         ctx: Context<InitIncrementCounterCompDef>,
     ) -> Result<()> {
         instructions::increment_counter::init_comp_def_handler(ctx)
     }
 
+    #[cfg(feature = "demo")]
     pub fn increment_counter(ctx: Context<IncrementCounter>, computation_offset: u64) -> Result<()> {
         instructions::increment_counter::handler(ctx, computation_offset)
     }
 
+    #[cfg(feature = "demo")]
     #[arcium_callback(encrypted_ix = "increment_counter")]
     pub fn increment_counter_callback(
         ctx: Context<IncrementCounterCallback>,
@@ -88,11 +97,13 @@ pub mod otc {
         instructions::increment_counter::callback_handler(ctx, output)
     }
 
-    // Get Counter
+    // Get Counter (demo)
+    #[cfg(feature = "demo")]
     pub fn init_get_counter_comp_def(ctx: Context<InitGetCounterCompDef>) -> Result<()> {
         instructions::get_counter::init_comp_def_handler(ctx)
     }
 
+    #[cfg(feature = "demo")]
     pub fn get_counter(
         ctx: Context<GetCounter>,
         computation_offset: u64,
@@ -111,6 +122,7 @@ pub mod otc {
         )
     }
 
+    #[cfg(feature = "demo")]
     #[arcium_callback(encrypted_ix = "get_counter")]
     pub fn get_counter_callback(
         ctx: Context<GetCounterCallback>,
@@ -124,6 +136,7 @@ pub mod otc {
         instructions::create_deal::init_comp_def_handler(ctx)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn create_deal(
         ctx: Context<CreateDeal>,
         computation_offset: u64,
@@ -135,6 +148,9 @@ pub mod otc {
         allow_partial: bool,
         encrypted_amount: [u8; 32],
         encrypted_price: [u8; 32],
+        counterparty_kind: u8,
+        counterparty: [u8; 32],
+        crank_bounty: u64,
     ) -> Result<()> {
         instructions::create_deal::handler(
             ctx,
@@ -147,6 +163,9 @@ pub mod otc {
             allow_partial,
             encrypted_amount,
             encrypted_price,
+            counterparty_kind,
+            counterparty,
+            crank_bounty,
         )
     }
 
@@ -163,6 +182,7 @@ pub mod otc {
         instructions::submit_offer::init_comp_def_handler(ctx)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn submit_offer(
         ctx: Context<SubmitOffer>,
         computation_offset: u64,
@@ -171,6 +191,8 @@ pub mod otc {
         nonce: u128,
         encrypted_price: [u8; 32],
         encrypted_amount: [u8; 32],
+        counterparty_proof: Vec<[u8; 32]>,
+        crank_bounty: u64,
     ) -> Result<()> {
         instructions::submit_offer::handler(
             ctx,
@@ -180,6 +202,8 @@ pub mod otc {
             nonce,
             encrypted_price,
             encrypted_amount,
+            counterparty_proof,
+            crank_bounty,
         )
     }
 
@@ -220,6 +244,75 @@ pub mod otc {
         instructions::announce_balance::callback_handler(ctx, output)
     }
 
+    // Rotate Encryption Keys
+    pub fn rotate_balance_encryption_key(
+        ctx: Context<RotateBalanceEncryptionKey>,
+        computation_offset: u64,
+        controller: Pubkey,
+        encryption_pubkey: [u8; 32],
+        owner_nonce: u128,
+    ) -> Result<()> {
+        instructions::rotate_balance_encryption_key::handler(
+            ctx,
+            computation_offset,
+            controller,
+            encryption_pubkey,
+            owner_nonce,
+        )
+    }
+
+    pub fn init_announce_deal_comp_def(ctx: Context<InitAnnounceDealCompDef>) -> Result<()> {
+        instructions::rotate_deal_encryption_key::init_comp_def_handler(ctx)
+    }
+
+    pub fn rotate_deal_encryption_key(
+        ctx: Context<RotateDealEncryptionKey>,
+        computation_offset: u64,
+        encryption_pubkey: [u8; 32],
+        owner_nonce: u128,
+    ) -> Result<()> {
+        instructions::rotate_deal_encryption_key::handler(
+            ctx,
+            computation_offset,
+            encryption_pubkey,
+            owner_nonce,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "announce_deal")]
+    pub fn announce_deal_callback(
+        ctx: Context<AnnounceDealCallback>,
+        output: SignedComputationOutputs<AnnounceDealOutput>,
+    ) -> Result<()> {
+        instructions::rotate_deal_encryption_key::callback_handler(ctx, output)
+    }
+
+    pub fn init_announce_offer_comp_def(ctx: Context<InitAnnounceOfferCompDef>) -> Result<()> {
+        instructions::rotate_offer_encryption_key::init_comp_def_handler(ctx)
+    }
+
+    pub fn rotate_offer_encryption_key(
+        ctx: Context<RotateOfferEncryptionKey>,
+        computation_offset: u64,
+        encryption_pubkey: [u8; 32],
+        owner_nonce: u128,
+    ) -> Result<()> {
+        instructions::rotate_offer_encryption_key::handler(
+            ctx,
+            computation_offset,
+            encryption_pubkey,
+            owner_nonce,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "announce_offer")]
+    pub fn announce_offer_callback(
+        ctx: Context<AnnounceOfferCallback>,
+        output: SignedComputationOutputs<AnnounceOfferOutput>,
+    ) -> Result<()> {
+        instructions::rotate_offer_encryption_key::callback_handler(ctx, output)
+    }
+
     // Crank Deal
     pub fn init_crank_deal_comp_def(ctx: Context<InitCrankDealCompDef>) -> Result<()> {
         instructions::crank_deal::init_comp_def_handler(ctx)
@@ -294,4 +387,529 @@ pub mod otc {
     ) -> Result<()> {
         instructions::top_up::callback_handler(ctx, output)
     }
+
+    // Create RFQ
+    pub fn init_create_rfq_comp_def(ctx: Context<InitCreateRfqCompDef>) -> Result<()> {
+        instructions::create_rfq::init_comp_def_handler(ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_rfq(
+        ctx: Context<CreateRfq>,
+        computation_offset: u64,
+        controller: Pubkey,
+        encryption_pubkey: [u8; 32],
+        nonce: u128,
+        balance_blob_nonce: u128,
+        deadline: i64,
+        encrypted_size: [u8; 32],
+        encrypted_limit_price: [u8; 32],
+        counterparty_kind: u8,
+        counterparty: [u8; 32],
+    ) -> Result<()> {
+        instructions::create_rfq::handler(
+            ctx,
+            computation_offset,
+            controller,
+            encryption_pubkey,
+            nonce,
+            balance_blob_nonce,
+            deadline,
+            encrypted_size,
+            encrypted_limit_price,
+            counterparty_kind,
+            counterparty,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "create_rfq")]
+    pub fn create_rfq_callback(
+        ctx: Context<CreateRfqCallback>,
+        output: SignedComputationOutputs<CreateRfqOutput>,
+    ) -> Result<()> {
+        instructions::create_rfq::callback_handler(ctx, output)
+    }
+
+    // Submit Quote
+    pub fn init_submit_quote_comp_def(ctx: Context<InitSubmitQuoteCompDef>) -> Result<()> {
+        instructions::submit_quote::init_comp_def_handler(ctx)
+    }
+
+    pub fn submit_quote(
+        ctx: Context<SubmitQuote>,
+        computation_offset: u64,
+        controller: Pubkey,
+        encryption_pubkey: [u8; 32],
+        nonce: u128,
+        encrypted_price: [u8; 32],
+        counterparty_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::submit_quote::handler(
+            ctx,
+            computation_offset,
+            controller,
+            encryption_pubkey,
+            nonce,
+            encrypted_price,
+            counterparty_proof,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "submit_quote")]
+    pub fn submit_quote_callback(
+        ctx: Context<SubmitQuoteCallback>,
+        output: SignedComputationOutputs<SubmitQuoteOutput>,
+    ) -> Result<()> {
+        instructions::submit_quote::callback_handler(ctx, output)
+    }
+
+    // Crank RFQ
+    pub fn init_crank_rfq_comp_def(ctx: Context<InitCrankRfqCompDef>) -> Result<()> {
+        instructions::crank_rfq::init_comp_def_handler(ctx)
+    }
+
+    pub fn crank_rfq(
+        ctx: Context<CrankRfq>,
+        computation_offset: u64,
+        taker_rfq_blob_nonce: u128,
+        taker_balance_blob_nonce: u128,
+    ) -> Result<()> {
+        instructions::crank_rfq::handler(ctx, computation_offset, taker_rfq_blob_nonce, taker_balance_blob_nonce)
+    }
+
+    #[arcium_callback(encrypted_ix = "crank_rfq")]
+    pub fn crank_rfq_callback(
+        ctx: Context<CrankRfqCallback>,
+        output: SignedComputationOutputs<CrankRfqOutput>,
+    ) -> Result<()> {
+        instructions::crank_rfq::callback_handler(ctx, output)
+    }
+
+    // Crank Quote
+    pub fn init_crank_quote_comp_def(ctx: Context<InitCrankQuoteCompDef>) -> Result<()> {
+        instructions::crank_quote::init_comp_def_handler(ctx)
+    }
+
+    pub fn crank_quote(
+        ctx: Context<CrankQuote>,
+        computation_offset: u64,
+        maker_quote_blob_nonce: u128,
+        maker_balance_blob_nonce: u128,
+    ) -> Result<()> {
+        instructions::crank_quote::handler(ctx, computation_offset, maker_quote_blob_nonce, maker_balance_blob_nonce)
+    }
+
+    #[arcium_callback(encrypted_ix = "crank_quote")]
+    pub fn crank_quote_callback(
+        ctx: Context<CrankQuoteCallback>,
+        output: SignedComputationOutputs<CrankQuoteOutput>,
+    ) -> Result<()> {
+        instructions::crank_quote::callback_handler(ctx, output)
+    }
+
+    // Link Controller
+    pub fn link_controller(ctx: Context<LinkController>) -> Result<()> {
+        instructions::link_controller::handler(ctx)
+    }
+
+    // Unlink Controller
+    pub fn unlink_controller(ctx: Context<UnlinkController>) -> Result<()> {
+        instructions::unlink_controller::handler(ctx)
+    }
+
+    // Create Delegate
+    pub fn create_delegate(
+        ctx: Context<CreateDelegate>,
+        permissions: u8,
+        pairs: Vec<DelegatePair>,
+        max_notional: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::create_delegate::handler(ctx, permissions, pairs, max_notional, expires_at)
+    }
+
+    // Revoke Delegate
+    pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+        instructions::revoke_delegate::handler(ctx)
+    }
+
+    // Init Relay Nonce
+    pub fn init_relay_nonce(ctx: Context<InitRelayNonce>, controller: Pubkey) -> Result<()> {
+        instructions::init_relay_nonce::handler(ctx, controller)
+    }
+
+    // Create Multisig
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        threshold: u8,
+        members: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::create_multisig::handler(ctx, threshold, members)
+    }
+
+    // Create Proposal
+    pub fn create_proposal(ctx: Context<CreateProposal>, instruction_digest: [u8; 32]) -> Result<()> {
+        instructions::create_proposal::handler(ctx, instruction_digest)
+    }
+
+    // Approve Proposal
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        instructions::approve_proposal::handler(ctx)
+    }
+
+    // Create Basket Deal
+    pub fn init_create_basket_deal_comp_def(
+        ctx: Context<InitCreateBasketDealCompDef>,
+    ) -> Result<()> {
+        instructions::create_basket_deal::init_comp_def_handler(ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_basket_deal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateBasketDeal<'info>>,
+        computation_offset: u64,
+        controller: Pubkey,
+        encryption_pubkey: [u8; 32],
+        nonce: u128,
+        expires_at: i64,
+        allow_partial: bool,
+        encrypted_units: [u8; 32],
+        encrypted_price: [u8; 32],
+        encrypted_leg_amounts: [[u8; 32]; 4],
+        crank_bounty: u64,
+    ) -> Result<()> {
+        instructions::create_basket_deal::handler(
+            ctx,
+            computation_offset,
+            controller,
+            encryption_pubkey,
+            nonce,
+            expires_at,
+            allow_partial,
+            encrypted_units,
+            encrypted_price,
+            encrypted_leg_amounts,
+            crank_bounty,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "create_basket_deal")]
+    pub fn create_basket_deal_callback(
+        ctx: Context<CreateBasketDealCallback>,
+        output: SignedComputationOutputs<CreateBasketDealOutput>,
+    ) -> Result<()> {
+        instructions::create_basket_deal::callback_handler(ctx, output)
+    }
+
+    // Crank Basket Deal
+    pub fn init_crank_basket_deal_comp_def(
+        ctx: Context<InitCrankBasketDealCompDef>,
+    ) -> Result<()> {
+        instructions::crank_basket_deal::init_comp_def_handler(ctx)
+    }
+
+    pub fn crank_basket_deal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CrankBasketDeal<'info>>,
+        computation_offset: u64,
+        creator_deal_blob_nonce: u128,
+    ) -> Result<()> {
+        instructions::crank_basket_deal::handler(ctx, computation_offset, creator_deal_blob_nonce)
+    }
+
+    #[arcium_callback(encrypted_ix = "crank_basket_deal")]
+    pub fn crank_basket_deal_callback(
+        ctx: Context<CrankBasketDealCallback>,
+        output: SignedComputationOutputs<CrankBasketDealOutput>,
+    ) -> Result<()> {
+        instructions::crank_basket_deal::callback_handler(ctx, output)
+    }
+
+    // Create Swap Deal
+    pub fn init_create_swap_deal_comp_def(ctx: Context<InitCreateSwapDealCompDef>) -> Result<()> {
+        instructions::create_swap_deal::init_comp_def_handler(ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_swap_deal(
+        ctx: Context<CreateSwapDeal>,
+        computation_offset: u64,
+        controller: Pubkey,
+        encryption_pubkey: [u8; 32],
+        nonce: u128,
+        balance_blob_nonce: u128,
+        expires_at: i64,
+        allow_partial: bool,
+        encrypted_give_amount: [u8; 32],
+        encrypted_want_amount: [u8; 32],
+        counterparty_kind: u8,
+        counterparty: [u8; 32],
+        crank_bounty: u64,
+    ) -> Result<()> {
+        instructions::create_swap_deal::handler(
+            ctx,
+            computation_offset,
+            controller,
+            encryption_pubkey,
+            nonce,
+            balance_blob_nonce,
+            expires_at,
+            allow_partial,
+            encrypted_give_amount,
+            encrypted_want_amount,
+            counterparty_kind,
+            counterparty,
+            crank_bounty,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "create_swap_deal")]
+    pub fn create_swap_deal_callback(
+        ctx: Context<CreateSwapDealCallback>,
+        output: SignedComputationOutputs<CreateSwapDealOutput>,
+    ) -> Result<()> {
+        instructions::create_swap_deal::callback_handler(ctx, output)
+    }
+
+    // Submit Swap Offer
+    pub fn init_submit_swap_offer_comp_def(
+        ctx: Context<InitSubmitSwapOfferCompDef>,
+    ) -> Result<()> {
+        instructions::submit_swap_offer::init_comp_def_handler(ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn submit_swap_offer(
+        ctx: Context<SubmitSwapOffer>,
+        computation_offset: u64,
+        controller: Pubkey,
+        encryption_pubkey: [u8; 32],
+        nonce: u128,
+        encrypted_give_amount: [u8; 32],
+        encrypted_want_amount: [u8; 32],
+        counterparty_proof: Vec<[u8; 32]>,
+        crank_bounty: u64,
+    ) -> Result<()> {
+        instructions::submit_swap_offer::handler(
+            ctx,
+            computation_offset,
+            controller,
+            encryption_pubkey,
+            nonce,
+            encrypted_give_amount,
+            encrypted_want_amount,
+            counterparty_proof,
+            crank_bounty,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "submit_swap_offer")]
+    pub fn submit_swap_offer_callback(
+        ctx: Context<SubmitSwapOfferCallback>,
+        output: SignedComputationOutputs<SubmitSwapOfferOutput>,
+    ) -> Result<()> {
+        instructions::submit_swap_offer::callback_handler(ctx, output)
+    }
+
+    // Crank Swap Deal
+    pub fn init_crank_swap_deal_comp_def(ctx: Context<InitCrankSwapDealCompDef>) -> Result<()> {
+        instructions::crank_swap_deal::init_comp_def_handler(ctx)
+    }
+
+    pub fn crank_swap_deal(
+        ctx: Context<CrankSwapDeal>,
+        computation_offset: u64,
+        creator_deal_blob_nonce: u128,
+        creator_balance_blob_nonce: u128,
+    ) -> Result<()> {
+        instructions::crank_swap_deal::handler(
+            ctx,
+            computation_offset,
+            creator_deal_blob_nonce,
+            creator_balance_blob_nonce,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "crank_swap_deal")]
+    pub fn crank_swap_deal_callback(
+        ctx: Context<CrankSwapDealCallback>,
+        output: SignedComputationOutputs<CrankSwapDealOutput>,
+    ) -> Result<()> {
+        instructions::crank_swap_deal::callback_handler(ctx, output)
+    }
+
+    // Crank Swap Offer
+    pub fn init_crank_swap_offer_comp_def(ctx: Context<InitCrankSwapOfferCompDef>) -> Result<()> {
+        instructions::crank_swap_offer::init_comp_def_handler(ctx)
+    }
+
+    pub fn crank_swap_offer(
+        ctx: Context<CrankSwapOffer>,
+        computation_offset: u64,
+        offeror_offer_blob_nonce: u128,
+        offeror_balance_blob_nonce: u128,
+    ) -> Result<()> {
+        instructions::crank_swap_offer::handler(
+            ctx,
+            computation_offset,
+            offeror_offer_blob_nonce,
+            offeror_balance_blob_nonce,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "crank_swap_offer")]
+    pub fn crank_swap_offer_callback(
+        ctx: Context<CrankSwapOfferCallback>,
+        output: SignedComputationOutputs<CrankSwapOfferOutput>,
+    ) -> Result<()> {
+        instructions::crank_swap_offer::callback_handler(ctx, output)
+    }
+
+    // Migrate Deal
+    pub fn migrate_deal(ctx: Context<MigrateDeal>) -> Result<()> {
+        instructions::migrate_deal::handler(ctx)
+    }
+
+    // Migrate Offer
+    pub fn migrate_offer(ctx: Context<MigrateOffer>) -> Result<()> {
+        instructions::migrate_offer::handler(ctx)
+    }
+
+    // Migrate Balance
+    pub fn migrate_balance(ctx: Context<MigrateBalance>) -> Result<()> {
+        instructions::migrate_balance::handler(ctx)
+    }
+
+    // Set Auditor
+    pub fn set_auditor(ctx: Context<SetAuditor>, auditor_pubkey: [u8; 32]) -> Result<()> {
+        instructions::set_auditor::handler(ctx, auditor_pubkey)
+    }
+
+    // Set Pair Config
+    pub fn set_pair_config(
+        ctx: Context<SetPairConfig>,
+        tick_size: u128,
+        lot_size: u64,
+        price_feed: Pubkey,
+        band_bps: u16,
+    ) -> Result<()> {
+        instructions::set_pair_config::handler(ctx, tick_size, lot_size, price_feed, band_bps)
+    }
+
+    // Update Price Feed
+    pub fn update_price_feed(ctx: Context<UpdatePriceFeed>, price: u128) -> Result<()> {
+        instructions::update_price_feed::handler(ctx, price)
+    }
+
+    // Crank Offers Batch
+    pub fn init_crank_offers_batch_comp_def(
+        ctx: Context<InitCrankOffersBatchCompDef>,
+    ) -> Result<()> {
+        instructions::crank_offers_batch::init_comp_def_handler(ctx)
+    }
+
+    pub fn crank_offers_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CrankOffersBatch<'info>>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        instructions::crank_offers_batch::handler(ctx, computation_offset)
+    }
+
+    #[arcium_callback(encrypted_ix = "crank_offers_batch")]
+    pub fn crank_offers_batch_callback(
+        ctx: Context<CrankOffersBatchCallback>,
+        output: SignedComputationOutputs<CrankOffersBatchOutput>,
+    ) -> Result<()> {
+        instructions::crank_offers_batch::callback_handler(ctx, output)
+    }
+
+    // Settle Deal
+    pub fn init_settle_deal_comp_def(ctx: Context<InitSettleDealCompDef>) -> Result<()> {
+        instructions::settle_deal::init_comp_def_handler(ctx)
+    }
+
+    pub fn settle_deal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleDeal<'info>>,
+        computation_offset: u64,
+        creator_deal_blob_nonce: u128,
+    ) -> Result<()> {
+        instructions::settle_deal::handler(ctx, computation_offset, creator_deal_blob_nonce)
+    }
+
+    #[arcium_callback(encrypted_ix = "settle_deal")]
+    pub fn settle_deal_callback(
+        ctx: Context<SettleDealCallback>,
+        output: SignedComputationOutputs<SettleDealOutput>,
+    ) -> Result<()> {
+        instructions::settle_deal::callback_handler(ctx, output)
+    }
+
+    // Transfer
+    pub fn init_transfer_comp_def(ctx: Context<InitTransferCompDef>) -> Result<()> {
+        instructions::transfer::init_comp_def_handler(ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer(
+        ctx: Context<Transfer>,
+        computation_offset: u64,
+        controller: Pubkey,
+        encryption_pubkey: [u8; 32],
+        nonce: u128,
+        recipient_blob_nonce: u128,
+        encrypted_amount: [u8; 32],
+    ) -> Result<()> {
+        instructions::transfer::handler(
+            ctx,
+            computation_offset,
+            controller,
+            encryption_pubkey,
+            nonce,
+            recipient_blob_nonce,
+            encrypted_amount,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "transfer")]
+    pub fn transfer_callback(
+        ctx: Context<TransferCallback>,
+        output: SignedComputationOutputs<TransferOutput>,
+    ) -> Result<()> {
+        instructions::transfer::callback_handler(ctx, output)
+    }
+
+    // Attest Balance
+    pub fn init_attest_balance_comp_def(ctx: Context<InitAttestBalanceCompDef>) -> Result<()> {
+        instructions::attest_balance::init_comp_def_handler(ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn attest_balance(
+        ctx: Context<AttestBalance>,
+        computation_offset: u64,
+        controller: Pubkey,
+        encryption_pubkey: [u8; 32],
+        nonce: u128,
+        encrypted_threshold: [u8; 32],
+        counterparty_pubkey: [u8; 32],
+        counterparty_nonce: u128,
+    ) -> Result<()> {
+        instructions::attest_balance::handler(
+            ctx,
+            computation_offset,
+            controller,
+            encryption_pubkey,
+            nonce,
+            encrypted_threshold,
+            counterparty_pubkey,
+            counterparty_nonce,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "attest_balance")]
+    pub fn attest_balance_callback(
+        ctx: Context<AttestBalanceCallback>,
+        output: SignedComputationOutputs<AttestBalanceOutput>,
+    ) -> Result<()> {
+        instructions::attest_balance::callback_handler(ctx, output)
+    }
 }
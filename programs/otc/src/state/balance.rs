@@ -4,31 +4,70 @@ use anchor_lang::prelude::*;
 // MXE-encrypted fields FIRST for stable offsets:
 //   nonce: [u8; 16] at offset 8
 //   ciphertexts: [[u8; 32]; 2] at offset 24
+//   version: u8 at offset 88
 // Then plaintext fields follow.
 // BalanceState has 2 fields: amount (u64), committed_amount (u64)
 // For account references, we pass just the ciphertext portion
 pub const BALANCE_CIPHERTEXT_OFFSET: u32 = 24; // discriminator (8) + nonce (16)
 pub const BALANCE_CIPHERTEXT_LENGTH: u32 = 64; // 2 x 32 bytes
 
-/// BalanceAccount represents a user's encrypted balance for a specific mint.
-///
-/// PDA seeds: ["balance", controller, mint]
-#[account]
-#[derive(InitSpace)]
-pub struct BalanceAccount {
-    // === MXE-encrypted (raw bytes) - MUST BE FIRST for stable offsets ===
-    /// Nonce for MXE encryption
-    pub nonce: [u8; 16],
-    /// 2 encrypted fields: amount (u64), committed_amount (u64)
-    pub ciphertexts: [[u8; 32]; 2],
-
-    // === Public (plaintext) ===
-    /// Derived ed25519 pubkey (signing authority)
-    pub controller: Pubkey,
-    /// Derived x25519 pubkey (for event routing/encryption)
-    pub encryption_pubkey: [u8; 32],
-    /// Token mint for this balance
-    pub mint: Pubkey,
-    /// PDA bump seed
-    pub bump: u8,
+/// Offset of the layout version byte, right after the ciphertexts
+pub const BALANCE_VERSION_OFFSET: usize = 88;
+/// Current BalanceAccount layout version (accounts created before versioning are 0)
+pub const BALANCE_VERSION: u8 = 1;
+
+// Computations and migrations rely on these offsets; check them against the
+// serialized layout of BalanceAccount (see account_layout!).
+const _: () = {
+    assert!(BALANCE_CIPHERTEXT_OFFSET as usize == balance_layout::ciphertexts.0);
+    assert!(BALANCE_CIPHERTEXT_LENGTH as usize == balance_layout::ciphertexts.1);
+    assert!(BALANCE_VERSION_OFFSET == balance_layout::version.0);
+    assert!(balance_layout::bump.0 + balance_layout::bump.1 == 8 + BalanceAccount::INIT_SPACE);
+};
+
+account_layout! {
+    balance_layout;
+
+    /// BalanceAccount represents a user's encrypted balance for a specific mint.
+    ///
+    /// PDA seeds: ["balance", controller, mint]
+    #[account]
+    #[derive(InitSpace)]
+    pub struct BalanceAccount {
+        // === MXE-encrypted (raw bytes) - MUST BE FIRST for stable offsets ===
+        /// Nonce for MXE encryption
+        pub nonce: [u8; 16],
+        /// 2 encrypted fields: amount (u64), committed_amount (u64)
+        pub ciphertexts: [[u8; 32]; 2],
+
+        // === Layout version - fixed offset, see BALANCE_VERSION_OFFSET ===
+        /// Layout version (see BALANCE_VERSION and migrate_balance)
+        pub version: u8,
+
+        // === Public (plaintext) ===
+        /// Derived ed25519 pubkey (signing authority)
+        pub controller: Pubkey,
+        /// Derived x25519 pubkey (for event routing/encryption)
+        pub encryption_pubkey: [u8; 32],
+        /// Token mint for this balance
+        pub mint: Pubkey,
+        /// Optional auditor x25519 pubkey receiving a copy of every blob (zeroed when unset)
+        pub auditor_pubkey: [u8; 32],
+        /// PDA bump seed
+        pub bump: u8,
+    }
+}
+
+impl BalanceAccount {
+    /// Key the auditor copy of a blob is sealed to.
+    ///
+    /// Circuits always emit the copy; without a registered auditor it goes to
+    /// `owner_key`, so it reveals nothing the owner's own blob doesn't.
+    pub fn auditor_key_or(&self, owner_key: [u8; 32]) -> [u8; 32] {
+        if self.auditor_pubkey == [0u8; 32] {
+            owner_key
+        } else {
+            self.auditor_pubkey
+        }
+    }
 }
@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state::BalanceAccount;
+
+// BasketLegs data layout (after 8-byte discriminator):
+// MXE-encrypted fields FIRST for stable offsets:
+//   nonce: [u8; 16] at offset 8
+//   ciphertexts: [[u8; 32]; 4] at offset 24
+// Then plaintext fields follow.
+// BasketLegsState has 1 field: leg_amounts ([u64; 4])
+// For account references, we pass just the ciphertext portion
+pub const BASKET_CIPHERTEXT_OFFSET: u32 = 24; // discriminator (8) + nonce (16)
+pub const BASKET_CIPHERTEXT_LENGTH: u32 = 128; // 4 x 32 bytes
+
+/// Number of leg slots in the basket circuits
+pub const MAX_BASKET_LEGS: usize = 4;
+
+/// BasketLegs holds the base legs of a basket deal.
+///
+/// The deal's own encrypted state counts basket units and their price, so offers
+/// work unchanged; each unit holds the per-leg amounts stored here.
+///
+/// PDA seeds: ["basket", deal]
+#[account]
+#[derive(InitSpace)]
+pub struct BasketLegs {
+    // === MXE-encrypted (raw bytes) - MUST BE FIRST for stable offsets ===
+    /// Nonce for MXE encryption
+    pub nonce: [u8; 16],
+    /// 4 encrypted fields: raw base amount of each leg per basket unit
+    pub ciphertexts: [[u8; 32]; 4],
+
+    // === Public (plaintext) ===
+    /// Deal these legs belong to
+    pub deal: Pubkey,
+    /// Number of legs in use
+    pub num_legs: u8,
+    /// Base mint of each leg (`Pubkey::default()` past `num_legs`)
+    pub leg_mints: [Pubkey; 4],
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl BasketLegs {
+    /// Base mints of the legs in use.
+    pub fn mints(&self) -> &[Pubkey] {
+        &self.leg_mints[..self.num_legs as usize]
+    }
+}
+
+/// A validated leg balance, captured at queue time.
+pub struct LegBalance {
+    pub balance: Pubkey,
+    pub nonce: u128,
+    pub mint: Pubkey,
+}
+
+/// Validates the controller's leg balances passed as remaining accounts.
+///
+/// With `leg_mints` the balances must follow those mints in order; otherwise they
+/// define the legs and must hold 2 to MAX_BASKET_LEGS distinct mints other than
+/// `quote_mint`.
+pub fn load_leg_balances<'info>(
+    controller: &Pubkey,
+    quote_mint: &Pubkey,
+    leg_mints: Option<&[Pubkey]>,
+    accounts: &'info [AccountInfo<'info>],
+) -> Result<Vec<LegBalance>> {
+    require!(
+        (2..=MAX_BASKET_LEGS).contains(&accounts.len()),
+        ErrorCode::InvalidBasketLegs
+    );
+
+    let mut legs: Vec<LegBalance> = Vec::with_capacity(MAX_BASKET_LEGS);
+    for (i, info) in accounts.iter().enumerate() {
+        require!(info.is_writable, ErrorCode::InvalidBasketLegs);
+        let balance = Account::<BalanceAccount>::try_from(info)?;
+        require!(
+            balance.controller == *controller,
+            ErrorCode::ControllerMismatch
+        );
+        if let Some(mints) = leg_mints {
+            require!(
+                mints.get(i) == Some(&balance.mint),
+                ErrorCode::InvalidBasketLegs
+            );
+        }
+        // Two legs of the same mint would write the same balance twice
+        require!(
+            balance.mint != *quote_mint && !legs.iter().any(|leg| leg.mint == balance.mint),
+            ErrorCode::InvalidBasketLegs
+        );
+        legs.push(LegBalance {
+            balance: balance.key(),
+            nonce: u128::from_le_bytes(balance.nonce),
+            mint: balance.mint,
+        });
+    }
+    if let Some(mints) = leg_mints {
+        require!(legs.len() == mints.len(), ErrorCode::InvalidBasketLegs);
+    }
+    Ok(legs)
+}
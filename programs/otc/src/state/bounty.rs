@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+/// Deposits a crank bounty from `payer` into a program-owned account.
+///
+/// The lamports sit on top of the account's rent-exempt minimum until the
+/// account is cranked.
+pub fn deposit_crank_bounty<'info>(
+    system_program: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    holder: &AccountInfo<'info>,
+    bounty: u64,
+) -> Result<()> {
+    if bounty == 0 {
+        return Ok(());
+    }
+    transfer(
+        CpiContext::new(
+            system_program.clone(),
+            Transfer {
+                from: payer.clone(),
+                to: holder.clone(),
+            },
+        ),
+        bounty,
+    )
+}
+
+/// Pays a crank bounty held on a program-owned account to the cranker.
+pub fn pay_crank_bounty(holder: &AccountInfo, cranker: &AccountInfo, bounty: u64) -> Result<()> {
+    if bounty == 0 {
+        return Ok(());
+    }
+    **holder.try_borrow_mut_lamports()? -= bounty;
+    **cranker.try_borrow_mut_lamports()? += bounty;
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+
+// Merkle allowlist layout:
+//   leaf = sha256(0x00 || controller)
+//   node = sha256(0x01 || min(a, b) || max(a, b))
+// Pairs are sorted before hashing, so a proof is just the list of sibling hashes
+// from leaf to root. Only the root is ever stored on-chain.
+const LEAF_PREFIX: &[u8] = &[0x00];
+const NODE_PREFIX: &[u8] = &[0x01];
+
+/// Counterparty restriction constants for DealAccount
+pub struct CounterpartyRestriction;
+
+impl CounterpartyRestriction {
+    /// Any controller may submit offers
+    pub const NONE: u8 = 0;
+    /// Only a single controller may submit offers (`counterparty` holds its pubkey)
+    pub const CONTROLLER: u8 = 1;
+    /// Only controllers in a Merkle allowlist may submit offers (`counterparty` holds the root)
+    pub const MERKLE_ROOT: u8 = 2;
+}
+
+/// Computes the allowlist leaf for a controller.
+pub fn counterparty_leaf(controller: &Pubkey) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, controller.as_ref()]).to_bytes()
+}
+
+/// Verifies that `controller` is allowed to trade against a deal with the given restriction.
+pub fn is_allowed_counterparty(
+    kind: u8,
+    counterparty: &[u8; 32],
+    controller: &Pubkey,
+    proof: &[[u8; 32]],
+) -> bool {
+    match kind {
+        CounterpartyRestriction::NONE => true,
+        CounterpartyRestriction::CONTROLLER => controller.to_bytes() == *counterparty,
+        CounterpartyRestriction::MERKLE_ROOT => {
+            let computed = proof
+                .iter()
+                .fold(counterparty_leaf(controller), |acc, sibling| {
+                    let (left, right) = if acc <= *sibling {
+                        (acc, *sibling)
+                    } else {
+                        (*sibling, acc)
+                    };
+                    hashv(&[NODE_PREFIX, &left, &right]).to_bytes()
+                });
+            computed == *counterparty
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        hashv(&[&[0x01], left, right]).to_bytes()
+    }
+
+    /// Four-leaf allowlist: returns the members, the root and each member's proof.
+    fn tree() -> ([Pubkey; 4], [u8; 32], [Vec<[u8; 32]>; 4]) {
+        let members = [0u8, 1, 2, 3].map(|i| Pubkey::new_from_array([i + 1; 32]));
+        let leaves = members.map(|member| counterparty_leaf(&member));
+        let left = node(&leaves[0], &leaves[1]);
+        let right = node(&leaves[2], &leaves[3]);
+        let root = node(&left, &right);
+        let proofs = [
+            vec![leaves[1], right],
+            vec![leaves[0], right],
+            vec![leaves[3], left],
+            vec![leaves[2], left],
+        ];
+        (members, root, proofs)
+    }
+
+    #[test]
+    fn leaves_and_nodes_are_domain_separated() {
+        let controller = Pubkey::new_from_array([7; 32]);
+        let leaf = counterparty_leaf(&controller);
+        assert_eq!(leaf, hashv(&[&[0x00], controller.as_ref()]).to_bytes());
+        assert_ne!(leaf, hashv(&[controller.as_ref()]).to_bytes());
+
+        // A node hash can't pass as a leaf of the same bytes
+        let (a, b) = ([1u8; 32], [2u8; 32]);
+        let mut pair = [0u8; 64];
+        pair[..32].copy_from_slice(&a);
+        pair[32..].copy_from_slice(&b);
+        assert_ne!(node(&a, &b), hashv(&[&[0x00], &pair]).to_bytes());
+    }
+
+    #[test]
+    fn pairs_are_sorted_before_hashing() {
+        let (a, b) = ([1u8; 32], [2u8; 32]);
+        assert_eq!(node(&a, &b), node(&b, &a));
+        assert_eq!(node(&b, &a), hashv(&[&[0x01], &a, &b]).to_bytes());
+    }
+
+    #[test]
+    fn members_prove_inclusion() {
+        let (members, root, proofs) = tree();
+        for (member, proof) in members.iter().zip(&proofs) {
+            assert!(is_allowed_counterparty(
+                CounterpartyRestriction::MERKLE_ROOT,
+                &root,
+                member,
+                proof
+            ));
+        }
+    }
+
+    #[test]
+    fn invalid_proofs_are_rejected() {
+        let (members, root, proofs) = tree();
+        let kind = CounterpartyRestriction::MERKLE_ROOT;
+
+        // Another member's proof
+        assert!(!is_allowed_counterparty(
+            kind,
+            &root,
+            &members[0],
+            &proofs[2]
+        ));
+        // Truncated proof
+        assert!(!is_allowed_counterparty(
+            kind,
+            &root,
+            &members[0],
+            &proofs[0][..1]
+        ));
+        assert!(!is_allowed_counterparty(kind, &root, &members[0], &[]));
+        // Non-member reusing a member's proof
+        let outsider = Pubkey::new_from_array([9; 32]);
+        for proof in &proofs {
+            assert!(!is_allowed_counterparty(kind, &root, &outsider, proof));
+        }
+        // An inner node is not a leaf
+        let inner = Pubkey::new_from_array(node(&counterparty_leaf(&members[0]), &proofs[0][0]));
+        assert!(!is_allowed_counterparty(
+            kind,
+            &root,
+            &inner,
+            &proofs[0][1..]
+        ));
+    }
+
+    #[test]
+    fn single_controller_and_unrestricted_deals() {
+        let controller = Pubkey::new_from_array([7; 32]);
+        let other = Pubkey::new_from_array([8; 32]);
+        let kind = CounterpartyRestriction::CONTROLLER;
+        assert!(is_allowed_counterparty(
+            kind,
+            &controller.to_bytes(),
+            &controller,
+            &[]
+        ));
+        assert!(!is_allowed_counterparty(
+            kind,
+            &controller.to_bytes(),
+            &other,
+            &[]
+        ));
+        assert!(is_allowed_counterparty(
+            CounterpartyRestriction::NONE,
+            &[0; 32],
+            &other,
+            &[]
+        ));
+        assert!(!is_allowed_counterparty(3, &other.to_bytes(), &other, &[]));
+    }
+}
@@ -1,48 +1,122 @@
 use anchor_lang::prelude::*;
 
+use crate::error::ErrorCode;
+use crate::state::DealStatus;
+
 // DealAccount data layout (after 8-byte discriminator):
 // MXE-encrypted fields FIRST for stable offsets:
 //   nonce: [u8; 16] at offset 8
 //   ciphertexts: [[u8; 32]; 3] at offset 24
+//   version: u8 at offset 120
 // Then plaintext fields follow.
 // DealState has 3 fields: amount (u64), price (u128), fill_amount (u64)
 // For account references, we pass just the ciphertext portion
 pub const DEAL_CIPHERTEXT_OFFSET: u32 = 24; // discriminator (8) + nonce (16)
 pub const DEAL_CIPHERTEXT_LENGTH: u32 = 96; // 3 x 32 bytes
 
-/// DealAccount represents an OTC deal created by a seller.
-///
-/// PDA seeds: ["deal", create_key]
-#[account]
-#[derive(InitSpace)]
-pub struct DealAccount {
-    // === MXE-encrypted (raw bytes) - MUST BE FIRST for stable offsets ===
-    /// Nonce for MXE encryption
-    pub nonce: [u8; 16],
-    /// 3 encrypted fields: amount (u64), price (u128), fill_amount (u64)
-    pub ciphertexts: [[u8; 32]; 3],
-
-    // === Public (plaintext) ===
-    /// Ephemeral signer used for PDA uniqueness
-    pub create_key: Pubkey,
-    /// Derived ed25519 pubkey (signing authority)
-    pub controller: Pubkey,
-    /// Derived x25519 pubkey (for event routing/encryption)
-    pub encryption_pubkey: [u8; 32],
-    /// Token the creator is selling (base asset)
-    pub base_mint: Pubkey,
-    /// Token the creator receives (quote asset)
-    pub quote_mint: Pubkey,
-    /// Unix timestamp when deal was created (set at callback)
-    pub created_at: i64,
-    /// Unix timestamp when deal expires
-    pub expires_at: i64,
-    /// Deal status (see DealStatus)
-    pub status: u8,
-    /// Whether to allow partial fills at expiry
-    pub allow_partial: bool,
-    /// Counter for offers made on this deal
-    pub num_offers: u32,
-    /// PDA bump seed
-    pub bump: u8,
+/// Offset of the layout version byte, right after the ciphertexts
+pub const DEAL_VERSION_OFFSET: usize = 120;
+/// Current DealAccount layout version (accounts created before versioning are 0)
+pub const DEAL_VERSION: u8 = 1;
+
+// Computations and migrations rely on these offsets; check them against the
+// serialized layout of DealAccount (see account_layout!).
+const _: () = {
+    assert!(DEAL_CIPHERTEXT_OFFSET as usize == deal_layout::ciphertexts.0);
+    assert!(DEAL_CIPHERTEXT_LENGTH as usize == deal_layout::ciphertexts.1);
+    assert!(DEAL_VERSION_OFFSET == deal_layout::version.0);
+    assert!(deal_layout::bump.0 + deal_layout::bump.1 == 8 + DealAccount::INIT_SPACE);
+};
+
+/// Largest supported difference between base and quote mint decimals.
+/// Keeps the price scale factor well inside the circuits' overflow bound.
+pub const MAX_DECIMALS_GAP: u8 = 18;
+
+account_layout! {
+    deal_layout;
+
+    /// DealAccount represents an OTC deal created by a seller.
+    ///
+    /// PDA seeds: ["deal", create_key]
+    #[account]
+    #[derive(InitSpace)]
+    pub struct DealAccount {
+        // === MXE-encrypted (raw bytes) - MUST BE FIRST for stable offsets ===
+        /// Nonce for MXE encryption
+        pub nonce: [u8; 16],
+        /// 3 encrypted fields: amount (u64), price (u128), fill_amount (u64)
+        pub ciphertexts: [[u8; 32]; 3],
+
+        // === Layout version - fixed offset, see DEAL_VERSION_OFFSET ===
+        /// Layout version (see DEAL_VERSION and migrate_deal)
+        pub version: u8,
+
+        // === Public (plaintext) ===
+        /// Ephemeral signer used for PDA uniqueness
+        pub create_key: Pubkey,
+        /// Derived ed25519 pubkey (signing authority)
+        pub controller: Pubkey,
+        /// Derived x25519 pubkey (for event routing/encryption)
+        pub encryption_pubkey: [u8; 32],
+        /// Token the creator is selling (base asset)
+        pub base_mint: Pubkey,
+        /// Token the creator receives (quote asset)
+        pub quote_mint: Pubkey,
+        /// Decimals of the base mint, recorded at creation
+        pub base_decimals: u8,
+        /// Decimals of the quote mint, recorded at creation
+        pub quote_decimals: u8,
+        /// Price increment from the pair config at creation (X64.64, human units)
+        pub tick_size: u128,
+        /// Amount increment from the pair config at creation (raw base units)
+        pub lot_size: u64,
+        /// PriceFeed from the pair config at creation (`Pubkey::default()` for none)
+        pub price_feed: Pubkey,
+        /// Price band from the pair config at creation, in basis points of the feed price
+        pub band_bps: u16,
+        /// Unix timestamp when deal was created (set at callback)
+        pub created_at: i64,
+        /// Unix timestamp when deal expires
+        pub expires_at: i64,
+        /// Deal status
+        pub status: DealStatus,
+        /// Whether to allow partial fills at expiry
+        pub allow_partial: bool,
+        /// Counter for offers made on this deal
+        pub num_offers: u32,
+        /// Counterparty restriction (see CounterpartyRestriction)
+        pub counterparty_kind: u8,
+        /// Allowed controller or Merkle root of allowed controllers (zeroed when unrestricted)
+        pub counterparty: [u8; 32],
+        /// Lamports paid to whoever cranks the deal (held on this account until then)
+        pub crank_bounty: u64,
+        /// Number of base legs of a basket deal (0 for a single-asset deal, see BasketLegs)
+        pub basket_legs: u8,
+        /// Whether this is a swap deal (ciphertexts hold SwapDealState, see create_swap_deal)
+        pub swap: bool,
+        /// PDA bump seed
+        pub bump: u8,
+    }
+}
+
+impl DealAccount {
+    /// Plaintext factors the circuits use to turn a human-unit price (whole quote
+    /// per whole base) into raw token units: `raw = price * scale_up / scale_down`.
+    /// One of the two is always 1.
+    pub fn price_scale(&self) -> Result<(u128, u128)> {
+        price_scale(self.base_decimals, self.quote_decimals)
+    }
+}
+
+/// Computes `(scale_up, scale_down)` for a base/quote decimals pair.
+pub fn price_scale(base_decimals: u8, quote_decimals: u8) -> Result<(u128, u128)> {
+    require!(
+        base_decimals.abs_diff(quote_decimals) <= MAX_DECIMALS_GAP,
+        ErrorCode::UnsupportedDecimals
+    );
+    Ok(if quote_decimals >= base_decimals {
+        (10u128.pow((quote_decimals - base_decimals) as u32), 1)
+    } else {
+        (1, 10u128.pow((base_decimals - quote_decimals) as u32))
+    })
 }
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+/// Maximum number of pairs a delegate can be scoped to
+pub const MAX_DELEGATE_PAIRS: usize = 4;
+
+/// Permission bits for Delegate
+///
+/// There is no cancel permission: offers fill against the deal when they are submitted
+/// and stay locked until settlement, so neither the controller nor a delegate can
+/// cancel one.
+pub struct DelegatePermission;
+
+impl DelegatePermission {
+    /// May create deals for the controller
+    pub const CREATE_DEAL: u8 = 1;
+    /// May submit offers for the controller
+    pub const SUBMIT_OFFER: u8 = 2;
+    /// May crank or settle the controller's deals and RFQs before expiry
+    pub const CRANK: u8 = 4;
+    /// Every permission
+    pub const ALL: u8 = Self::CREATE_DEAL | Self::SUBMIT_OFFER | Self::CRANK;
+}
+
+/// A base/quote pair a delegate may trade
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct DelegatePair {
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+}
+
+/// Delegate lets a session key trade on behalf of a controller without the
+/// controller key signing every action. Revoked by closing the account.
+///
+/// PDA seeds: ["delegate", controller, delegate]
+#[account]
+#[derive(InitSpace)]
+pub struct Delegate {
+    /// Controller granting the permissions
+    pub controller: Pubkey,
+    /// Session key allowed to sign for the controller
+    pub delegate: Pubkey,
+    /// DelegatePermission bits
+    pub permissions: u8,
+    /// Pairs the delegate may trade (empty for any pair)
+    #[max_len(MAX_DELEGATE_PAIRS)]
+    pub pairs: Vec<DelegatePair>,
+    /// Maximum notional per deal or offer, in raw quote units (0 for no limit)
+    pub max_notional: u64,
+    /// Unix timestamp after which the delegate can no longer act
+    pub expires_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl Delegate {
+    /// Checks that `signer` may act for `controller` on the given pair.
+    ///
+    /// The controller itself is always authorized. Anyone else must present a live
+    /// delegate of that controller holding `permission` for the pair. Returns the
+    /// notional limit the circuits enforce (`u64::MAX` when unlimited).
+    pub fn authorize(
+        signer: &Pubkey,
+        controller: &Pubkey,
+        delegate: Option<&Account<Delegate>>,
+        permission: u8,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        now: i64,
+    ) -> Result<u64> {
+        if signer == controller {
+            return Ok(u64::MAX);
+        }
+        let delegate = delegate.ok_or(ErrorCode::NotAuthorized)?;
+        require!(
+            delegate.controller == *controller && delegate.delegate == *signer,
+            ErrorCode::NotAuthorized
+        );
+        require!(now < delegate.expires_at, ErrorCode::DelegateExpired);
+        require!(
+            delegate.permissions & permission == permission,
+            ErrorCode::DelegatePermissionDenied
+        );
+        require!(
+            delegate.pairs.is_empty()
+                || delegate
+                    .pairs
+                    .iter()
+                    .any(|pair| { pair.base_mint == *base_mint && pair.quote_mint == *quote_mint }),
+            ErrorCode::DelegatePairNotAllowed
+        );
+        Ok(if delegate.max_notional == 0 {
+            u64::MAX
+        } else {
+            delegate.max_notional
+        })
+    }
+
+    /// `authorize` for a basket deal: the delegate must hold every leg's pair.
+    pub fn authorize_legs(
+        signer: &Pubkey,
+        controller: &Pubkey,
+        delegate: Option<&Account<Delegate>>,
+        permission: u8,
+        leg_mints: &[Pubkey],
+        quote_mint: &Pubkey,
+        now: i64,
+    ) -> Result<u64> {
+        let mut max_notional = u64::MAX;
+        for leg_mint in leg_mints {
+            max_notional = Self::authorize(
+                signer, controller, delegate, permission, leg_mint, quote_mint, now,
+            )?;
+        }
+        Ok(max_notional)
+    }
+}
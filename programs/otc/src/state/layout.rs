@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{DealStatus, OfferStatus};
+
+/// Serialized size of an account field. Anchor's `Space` only covers types deriving
+/// `InitSpace`, so primitives and arrays are listed here.
+pub trait FieldSpace {
+    const SPACE: usize;
+}
+
+macro_rules! field_space {
+    ($($ty:ty => $space:expr),* $(,)?) => {
+        $(impl FieldSpace for $ty {
+            const SPACE: usize = $space;
+        })*
+    };
+}
+
+field_space!(
+    bool => 1,
+    u8 => 1,
+    u16 => 2,
+    u32 => 4,
+    u64 => 8,
+    i64 => 8,
+    u128 => 16,
+    Pubkey => 32,
+    DealStatus => DealStatus::INIT_SPACE,
+    OfferStatus => OfferStatus::INIT_SPACE,
+);
+
+impl<T: FieldSpace, const N: usize> FieldSpace for [T; N] {
+    const SPACE: usize = T::SPACE * N;
+}
+
+/// Declares an account struct together with a `$layout` module holding the serialized
+/// `(offset, len)` of each field, counted from the start of the account data (so the
+/// first field sits at 8, after the discriminator).
+///
+/// Offsets follow the declaration order and each field's `Space`, so constants that
+/// computations and migrations rely on can be asserted against the struct itself:
+/// retyping, inserting or reordering a field changes them.
+///
+/// Field types are matched as single token trees (a name or an array) so that derives
+/// such as `InitSpace` still see plain types, and must implement `FieldSpace`.
+macro_rules! account_layout {
+    (
+        $layout:ident;
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_attr:meta])* $field_vis:vis $field:ident : $ty:tt),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name {
+            $($(#[$field_attr])* $field_vis $field: $ty),*
+        }
+
+        /// Serialized `(offset, len)` of each field, see `account_layout!`
+        #[allow(non_upper_case_globals)]
+        pub mod $layout {
+            use super::*;
+
+            account_layout!(@fields 8; $($field: $ty),*);
+        }
+    };
+    (@fields $offset:expr;) => {};
+    (@fields $offset:expr; $field:ident : $ty:tt $(, $rest:ident : $rest_ty:tt)*) => {
+        pub const $field: (usize, usize) = ($offset, <$ty as $crate::state::FieldSpace>::SPACE);
+        account_layout!(@fields $offset + <$ty as $crate::state::FieldSpace>::SPACE; $($rest: $rest_ty),*);
+    };
+}
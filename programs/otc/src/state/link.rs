@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+/// ControllerLink declares that a controller belongs to a desk (group) of
+/// sub-accounts that must never trade against each other.
+///
+/// PDA seeds: ["link", controller]
+#[account]
+#[derive(InitSpace)]
+pub struct ControllerLink {
+    /// Linked controller
+    pub controller: Pubkey,
+    /// Group authority shared by every controller of the desk
+    pub group: Pubkey,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl ControllerLink {
+    /// Reads the group from an optional link account.
+    ///
+    /// Controllers without a link PDA are unlinked, so a missing (uninitialized)
+    /// account yields `None` rather than an error.
+    pub fn group_of(info: &AccountInfo) -> Result<Option<Pubkey>> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(None);
+        }
+        let link = ControllerLink::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(Some(link.group))
+    }
+
+    /// Returns true if both link accounts place their controllers in the same group.
+    pub fn are_linked(a: &AccountInfo, b: &AccountInfo) -> Result<bool> {
+        Ok(match (Self::group_of(a)?, Self::group_of(b)?) {
+            (Some(group_a), Some(group_b)) => group_a == group_b,
+            _ => false,
+        })
+    }
+}
@@ -1,11 +1,41 @@
+#[macro_use]
+mod layout;
+
 mod balance;
+mod basket;
+mod bounty;
+#[cfg(feature = "demo")]
 mod counter;
+mod counterparty;
 mod deal;
+mod delegate;
+mod link;
+mod multisig;
 mod offer;
+mod pair;
+mod price_feed;
+mod quote;
+mod relay;
+mod rfq;
 mod status;
+mod version;
 
 pub use balance::*;
+pub use basket::*;
+pub use bounty::*;
+#[cfg(feature = "demo")]
 pub use counter::*;
+pub use counterparty::*;
 pub use deal::*;
+pub use delegate::*;
+pub use layout::FieldSpace;
+pub use link::*;
+pub use multisig::*;
 pub use offer::*;
+pub use pair::*;
+pub use price_feed::*;
+pub use quote::*;
+pub use relay::*;
+pub use rfq::*;
 pub use status::*;
+pub use version::*;
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+use crate::error::ErrorCode;
+use crate::state::{instruction_digest, ProposalStatus};
+
+/// Maximum number of members in a multisig (approvals are a u16 bitmap)
+pub const MAX_MULTISIG_MEMBERS: usize = 10;
+
+/// Multisig is an M-of-N controller. Its PDA is used as the `controller` of
+/// balances and deals; actions on them run through approved proposals.
+///
+/// PDA seeds: ["multisig", create_key]
+#[account]
+#[derive(InitSpace)]
+pub struct Multisig {
+    /// Ephemeral key used to derive the PDA
+    pub create_key: Pubkey,
+    /// Approvals required to execute a proposal
+    pub threshold: u8,
+    /// Member keys; a member's position is its bit in `Proposal::approvals`
+    #[max_len(MAX_MULTISIG_MEMBERS)]
+    pub members: Vec<Pubkey>,
+    /// Index the next proposal will be created with
+    pub proposal_count: u64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl Multisig {
+    /// Approval bit of `member`, or NotMultisigMember.
+    pub fn member_bit(&self, member: &Pubkey) -> Result<u16> {
+        let index = self
+            .members
+            .iter()
+            .position(|key| key == member)
+            .ok_or(ErrorCode::NotMultisigMember)?;
+        Ok(1u16 << index)
+    }
+}
+
+/// Proposal approves one exact instruction for a multisig controller.
+///
+/// The instruction is identified by its `instruction_digest` (program, data and
+/// non-signer accounts), so whoever executes it supplies only the fee payer and
+/// other signers.
+///
+/// PDA seeds: ["proposal", multisig, index (u64 LE)]
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    /// Multisig this proposal acts for
+    pub multisig: Pubkey,
+    /// Sequential index within the multisig
+    pub index: u64,
+    /// Member who created the proposal
+    pub proposer: Pubkey,
+    /// Digest of the instruction to execute
+    pub instruction_digest: [u8; 32],
+    /// Bitmap of members who approved, indexed like `Multisig::members`
+    pub approvals: u16,
+    /// Proposal status
+    pub status: ProposalStatus,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl Proposal {
+    /// Records `bit`'s approval and marks the proposal approved at the threshold.
+    pub fn approve(&mut self, bit: u16, threshold: u8) -> Result<()> {
+        require!(
+            self.status == ProposalStatus::Active,
+            ErrorCode::ProposalNotActive
+        );
+        require!(
+            self.approvals & bit == 0,
+            ErrorCode::ProposalAlreadyApproved
+        );
+        self.approvals |= bit;
+        if self.approvals.count_ones() >= u32::from(threshold) {
+            self.status = ProposalStatus::Approved;
+        }
+        Ok(())
+    }
+
+    /// Verifies that the current instruction is the one approved for `controller`
+    /// and marks the proposal executed.
+    pub fn consume(
+        &mut self,
+        controller: &Pubkey,
+        instructions_sysvar: &AccountInfo,
+    ) -> Result<()> {
+        require_keys_eq!(self.multisig, *controller, ErrorCode::ControllerMismatch);
+        require!(
+            self.status == ProposalStatus::Approved,
+            ErrorCode::ProposalNotApproved
+        );
+
+        let current_index = load_current_index_checked(instructions_sysvar)?;
+        let current = load_instruction_at_checked(current_index as usize, instructions_sysvar)?;
+        require!(
+            instruction_digest(&current) == self.instruction_digest,
+            ErrorCode::ProposalDigestMismatch
+        );
+
+        self.status = ProposalStatus::Executed;
+        Ok(())
+    }
+}
@@ -1,42 +1,72 @@
 use anchor_lang::prelude::*;
 
+use crate::state::OfferStatus;
+
 // OfferAccount data layout (after 8-byte discriminator):
 // MXE-encrypted fields FIRST for stable offsets:
 //   nonce: [u8; 16] at offset 8
 //   ciphertexts: [[u8; 32]; 3] at offset 24
+//   version: u8 at offset 120
 // Then plaintext fields follow.
 // OfferState has 3 fields: price (u128), amount (u64), amt_to_execute (u64)
 // For account references, we pass just the ciphertext portion
 pub const OFFER_CIPHERTEXT_OFFSET: u32 = 24; // discriminator (8) + nonce (16)
 pub const OFFER_CIPHERTEXT_LENGTH: u32 = 96; // 3 x 32 bytes
 
-/// OfferAccount represents an offer made on an OTC deal.
-///
-/// PDA seeds: ["offer", deal, create_key]
-#[account]
-#[derive(InitSpace)]
-pub struct OfferAccount {
-    // === MXE-encrypted (raw bytes) - MUST BE FIRST for stable offsets ===
-    /// Nonce for MXE encryption
-    pub nonce: [u8; 16],
-    /// 3 encrypted fields: price (u128), amount (u64), amt_to_execute (u64)
-    pub ciphertexts: [[u8; 32]; 3],
-
-    // === Public (plaintext) ===
-    /// Ephemeral signer used for PDA uniqueness
-    pub create_key: Pubkey,
-    /// Derived ed25519 pubkey (signing authority)
-    pub controller: Pubkey,
-    /// Derived x25519 pubkey (for event routing/encryption)
-    pub encryption_pubkey: [u8; 32],
-    /// The deal this offer targets
-    pub deal: Pubkey,
-    /// Unix timestamp when offer was submitted (set at callback)
-    pub submitted_at: i64,
-    /// FIFO sequence number for this offer
-    pub offer_index: u32,
-    /// Offer status (see OfferStatus)
-    pub status: u8,
-    /// PDA bump seed
-    pub bump: u8,
+/// Offset of the layout version byte, right after the ciphertexts
+pub const OFFER_VERSION_OFFSET: usize = 120;
+/// Current OfferAccount layout version (accounts created before versioning are 0)
+pub const OFFER_VERSION: u8 = 1;
+
+// Computations and migrations rely on these offsets; check them against the
+// serialized layout of OfferAccount (see account_layout!).
+const _: () = {
+    assert!(OFFER_CIPHERTEXT_OFFSET as usize == offer_layout::ciphertexts.0);
+    assert!(OFFER_CIPHERTEXT_LENGTH as usize == offer_layout::ciphertexts.1);
+    assert!(OFFER_VERSION_OFFSET == offer_layout::version.0);
+    assert!(offer_layout::bump.0 + offer_layout::bump.1 == 8 + OfferAccount::INIT_SPACE);
+};
+
+account_layout! {
+    offer_layout;
+
+    /// OfferAccount represents an offer made on an OTC deal.
+    ///
+    /// PDA seeds: ["offer", deal, create_key]
+    #[account]
+    #[derive(InitSpace)]
+    pub struct OfferAccount {
+        // === MXE-encrypted (raw bytes) - MUST BE FIRST for stable offsets ===
+        /// Nonce for MXE encryption
+        pub nonce: [u8; 16],
+        /// 3 encrypted fields: price (u128), amount (u64), amt_to_execute (u64)
+        pub ciphertexts: [[u8; 32]; 3],
+
+        // === Layout version - fixed offset, see OFFER_VERSION_OFFSET ===
+        /// Layout version (see OFFER_VERSION and migrate_offer)
+        pub version: u8,
+
+        // === Public (plaintext) ===
+        /// Ephemeral signer used for PDA uniqueness
+        pub create_key: Pubkey,
+        /// Derived ed25519 pubkey (signing authority)
+        pub controller: Pubkey,
+        /// Derived x25519 pubkey (for event routing/encryption)
+        pub encryption_pubkey: [u8; 32],
+        /// The deal this offer targets
+        pub deal: Pubkey,
+        /// Unix timestamp when offer was submitted (set at callback)
+        pub submitted_at: i64,
+        /// FIFO sequence number for this offer
+        pub offer_index: u32,
+        /// Offer status
+        pub status: OfferStatus,
+        /// Lamports paid to whoever cranks the offer (held on this account until then)
+        pub crank_bounty: u64,
+        /// The quote commitment was locked rounded down rather than up (offers submitted
+        /// before version 1); settlement releases the same amount
+        pub floor_locked: bool,
+        /// PDA bump seed
+        pub bump: u8,
+    }
 }
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+/// PairConfig sets the price and amount increments for a base/quote pair, and
+/// optionally an oracle price band.
+///
+/// Pairs without a config accept any price and amount (tick and lot of 1, no band).
+///
+/// PDA seeds: ["pair", base_mint, quote_mint]
+#[account]
+#[derive(InitSpace)]
+pub struct PairConfig {
+    /// Token being sold (base asset)
+    pub base_mint: Pubkey,
+    /// Token being paid (quote asset)
+    pub quote_mint: Pubkey,
+    /// Price increment, X64.64 in human units (whole quote per whole base)
+    pub tick_size: u128,
+    /// Amount increment, in raw base units
+    pub lot_size: u64,
+    /// PriceFeed bounding deal and offer prices (`Pubkey::default()` for none)
+    pub price_feed: Pubkey,
+    /// Allowed deviation from the feed price, in basis points
+    pub band_bps: u16,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl PairConfig {
+    /// Tick size used when a pair has no config (any X64.64 price)
+    pub const DEFAULT_TICK_SIZE: u128 = 1;
+    /// Lot size used when a pair has no config (any raw amount)
+    pub const DEFAULT_LOT_SIZE: u64 = 1;
+
+    /// Reads an optional pair config account.
+    ///
+    /// A missing (uninitialized) account yields the defaults rather than an error.
+    pub fn read(info: &AccountInfo) -> Result<PairConfig> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(PairConfig {
+                base_mint: Pubkey::default(),
+                quote_mint: Pubkey::default(),
+                tick_size: Self::DEFAULT_TICK_SIZE,
+                lot_size: Self::DEFAULT_LOT_SIZE,
+                price_feed: Pubkey::default(),
+                band_bps: 0,
+                bump: 0,
+            });
+        }
+        PairConfig::try_deserialize(&mut &info.try_borrow_data()?[..])
+    }
+}
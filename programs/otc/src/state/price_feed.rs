@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+/// Oldest price feed update (in seconds) accepted when checking a price band.
+pub const MAX_PRICE_FEED_AGE: i64 = 300;
+
+/// Basis points in 100%.
+pub const BPS_DENOMINATOR: u128 = 10_000;
+
+/// PriceFeed is a minimal program-owned oracle: its authority writes a plaintext
+/// reference price that pair configs can use to bound encrypted prices.
+///
+/// PDA seeds: ["price_feed", authority, base_mint, quote_mint]
+#[account]
+#[derive(InitSpace)]
+pub struct PriceFeed {
+    /// Only signer allowed to update the price
+    pub authority: Pubkey,
+    /// Token being sold (base asset)
+    pub base_mint: Pubkey,
+    /// Token being paid (quote asset)
+    pub quote_mint: Pubkey,
+    /// Reference price, X64.64 in human units (whole quote per whole base)
+    pub price: u128,
+    /// Unix timestamp of the last update
+    pub updated_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Computes the `(min_price, max_price)` band passed to the circuits.
+///
+/// Without a configured feed (`Pubkey::default()`) every price is accepted. Otherwise
+/// `feed` must be the configured account and have been updated within
+/// `MAX_PRICE_FEED_AGE` seconds; the band is the feed price +/- `band_bps`.
+pub fn price_band(
+    configured_feed: &Pubkey,
+    band_bps: u16,
+    feed: Option<&Account<PriceFeed>>,
+    now: i64,
+) -> Result<(u128, u128)> {
+    if *configured_feed == Pubkey::default() {
+        return Ok((0, u128::MAX));
+    }
+    let feed = feed.ok_or(ErrorCode::PriceFeedMismatch)?;
+    require_keys_eq!(feed.key(), *configured_feed, ErrorCode::PriceFeedMismatch);
+    require!(
+        now.saturating_sub(feed.updated_at) <= MAX_PRICE_FEED_AGE,
+        ErrorCode::StalePriceFeed
+    );
+
+    // price * bps / 10_000 without overflowing u128
+    let bps = band_bps as u128;
+    let width =
+        feed.price / BPS_DENOMINATOR * bps + feed.price % BPS_DENOMINATOR * bps / BPS_DENOMINATOR;
+    Ok((
+        feed.price.saturating_sub(width),
+        feed.price.saturating_add(width),
+    ))
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::state::QuoteStatus;
+
+// QuoteAccount data layout (after 8-byte discriminator):
+// MXE-encrypted fields FIRST for stable offsets:
+//   nonce: [u8; 16] at offset 8
+//   ciphertexts: [[u8; 32]; 2] at offset 24
+// Then plaintext fields follow.
+// QuoteState has 2 fields: price (u128), committed (u64)
+// For account references, we pass just the ciphertext portion
+pub const QUOTE_CIPHERTEXT_OFFSET: u32 = 24; // discriminator (8) + nonce (16)
+pub const QUOTE_CIPHERTEXT_LENGTH: u32 = 64; // 2 x 32 bytes
+
+/// QuoteAccount represents a maker's quote in response to an RFQ.
+///
+/// PDA seeds: ["quote", rfq, create_key]
+#[account]
+#[derive(InitSpace)]
+pub struct QuoteAccount {
+    // === MXE-encrypted (raw bytes) - MUST BE FIRST for stable offsets ===
+    /// Nonce for MXE encryption
+    pub nonce: [u8; 16],
+    /// 2 encrypted fields: price (u128), committed (u64)
+    pub ciphertexts: [[u8; 32]; 2],
+
+    // === Public (plaintext) ===
+    /// Ephemeral signer used for PDA uniqueness
+    pub create_key: Pubkey,
+    /// Derived ed25519 pubkey (signing authority)
+    pub controller: Pubkey,
+    /// Derived x25519 pubkey (for event routing/encryption)
+    pub encryption_pubkey: [u8; 32],
+    /// The RFQ this quote responds to
+    pub rfq: Pubkey,
+    /// Unix timestamp when quote was submitted (set at callback)
+    pub submitted_at: i64,
+    /// Sequence number for this quote (earlier quotes win price ties)
+    pub quote_index: u32,
+    /// Quote status
+    pub status: QuoteStatus,
+    /// PDA bump seed
+    pub bump: u8,
+}
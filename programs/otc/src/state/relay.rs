@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use solana_sdk_ids::ed25519_program;
+use solana_sha256_hasher::hashv;
+
+use crate::error::ErrorCode;
+
+// Relayed authorization layout:
+//   digest  = sha256(program_id || instruction data || non-signer account keys, in order)
+//   message = RELAY_DOMAIN || nonce (u64 LE) || digest
+// The controller signs `message` off-chain; the relayer places an ed25519 program
+// instruction verifying that signature immediately before the relayed instruction.
+// Signer accounts (fee payer, relayer authority, ephemeral create keys) are left out of
+// the digest so the relayer can supply them.
+pub const RELAY_DOMAIN: &[u8] = b"otc-relay-v1";
+
+// ed25519 program instruction data: a 2-byte header, then 14-byte offset records
+const ED25519_HEADER_LEN: usize = 2;
+const ED25519_OFFSETS_LEN: usize = 14;
+
+/// RelayNonce orders a controller's relayed instructions so a signed message
+/// can only be submitted once.
+///
+/// Only `create_deal`, `create_swap_deal`, `submit_offer` and `submit_swap_offer`
+/// accept a relayed signature. Every other controller-signed instruction (top-ups,
+/// transfers, announcements, early cranks and settlements, delegate and multisig
+/// management) still needs the controller, or where supported a delegate or an
+/// approved multisig proposal, to sign the transaction.
+///
+/// PDA seeds: ["relay_nonce", controller]
+#[account]
+#[derive(InitSpace)]
+pub struct RelayNonce {
+    /// Controller whose signatures this nonce protects
+    pub controller: Pubkey,
+    /// Nonce the next relayed message must carry
+    pub nonce: u64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl RelayNonce {
+    /// Verifies that the controller signed the current instruction and consumes the nonce.
+    ///
+    /// The ed25519 program instruction must directly precede the current one and verify
+    /// a single signature over `RELAY_DOMAIN || nonce || digest` whose data is inline.
+    pub fn consume(
+        &mut self,
+        controller: &Pubkey,
+        instructions_sysvar: &AccountInfo,
+    ) -> Result<()> {
+        require_keys_eq!(self.controller, *controller, ErrorCode::ControllerMismatch);
+
+        let current_index = load_current_index_checked(instructions_sysvar)?;
+        require!(current_index > 0, ErrorCode::MissingRelaySignature);
+        let current = load_instruction_at_checked(current_index as usize, instructions_sysvar)?;
+        let verify = load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+        require_keys_eq!(
+            verify.program_id,
+            ed25519_program::ID,
+            ErrorCode::MissingRelaySignature
+        );
+
+        let (signer, message) = ed25519_signed_message(&verify.data)?;
+        require_keys_eq!(signer, *controller, ErrorCode::InvalidRelaySignature);
+
+        let expected = [
+            RELAY_DOMAIN,
+            &self.nonce.to_le_bytes(),
+            &instruction_digest(&current),
+        ]
+        .concat();
+        require!(message == expected, ErrorCode::InvalidRelaySignature);
+
+        self.nonce = self
+            .nonce
+            .checked_add(1)
+            .ok_or(ErrorCode::InvalidRelaySignature)?;
+        Ok(())
+    }
+}
+
+/// Digest of an instruction: its program, data and every non-signer account.
+///
+/// Shared by relayed signatures and multisig proposals to bind an approval to one
+/// exact instruction while leaving fee payers and other signers open.
+pub fn instruction_digest(ix: &Instruction) -> [u8; 32] {
+    let mut parts: Vec<&[u8]> = vec![ix.program_id.as_ref(), &ix.data];
+    parts.extend(
+        ix.accounts
+            .iter()
+            .filter(|meta| !meta.is_signer)
+            .map(|meta| meta.pubkey.as_ref()),
+    );
+    hashv(&parts).to_bytes()
+}
+
+/// Extracts the (pubkey, message) of a single-signature ed25519 instruction whose
+/// signature, pubkey and message all live in its own data.
+fn ed25519_signed_message(data: &[u8]) -> Result<(Pubkey, Vec<u8>)> {
+    require!(
+        data.len() >= ED25519_HEADER_LEN + ED25519_OFFSETS_LEN && data[0] == 1,
+        ErrorCode::InvalidRelaySignature
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let offsets = ED25519_HEADER_LEN;
+    let signature_ix = read_u16(offsets + 2);
+    let pubkey_offset = read_u16(offsets + 4) as usize;
+    let pubkey_ix = read_u16(offsets + 6);
+    let message_offset = read_u16(offsets + 8) as usize;
+    let message_len = read_u16(offsets + 10) as usize;
+    let message_ix = read_u16(offsets + 12);
+
+    // u16::MAX points at the ed25519 instruction itself
+    require!(
+        signature_ix == u16::MAX && pubkey_ix == u16::MAX && message_ix == u16::MAX,
+        ErrorCode::InvalidRelaySignature
+    );
+    let pubkey: [u8; 32] = data
+        .get(pubkey_offset..pubkey_offset + 32)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(ErrorCode::InvalidRelaySignature)?;
+    let message = data
+        .get(message_offset..message_offset + message_len)
+        .ok_or(ErrorCode::InvalidRelaySignature)?;
+    Ok((Pubkey::new_from_array(pubkey), message.to_vec()))
+}
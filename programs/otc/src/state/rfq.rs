@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::state::RfqStatus;
+
+// RfqAccount data layout (after 8-byte discriminator):
+// MXE-encrypted fields FIRST for stable offsets:
+//   nonce: [u8; 16] at offset 8
+//   ciphertexts: [[u8; 32]; 5] at offset 24
+// Then plaintext fields follow.
+// RfqState has 5 fields: size (u64), limit_price (u128), best_price (u128), best_index (u32), has_quote (bool)
+// For account references, we pass just the ciphertext portion
+pub const RFQ_CIPHERTEXT_OFFSET: u32 = 24; // discriminator (8) + nonce (16)
+pub const RFQ_CIPHERTEXT_LENGTH: u32 = 160; // 5 x 32 bytes
+
+/// RfqAccount represents a taker's request-for-quote that makers respond to.
+///
+/// Unlike deals, RFQs do not record mint decimals: the limit price and every quote
+/// price are X64.64 in raw units (quote base units per base base unit), as entered.
+///
+/// PDA seeds: ["rfq", create_key]
+#[account]
+#[derive(InitSpace)]
+pub struct RfqAccount {
+    // === MXE-encrypted (raw bytes) - MUST BE FIRST for stable offsets ===
+    /// Nonce for MXE encryption
+    pub nonce: [u8; 16],
+    /// 5 encrypted fields: size (u64), limit_price (u128), best_price (u128), best_index (u32), has_quote (bool)
+    pub ciphertexts: [[u8; 32]; 5],
+
+    // === Public (plaintext) ===
+    /// Ephemeral signer used for PDA uniqueness
+    pub create_key: Pubkey,
+    /// Derived ed25519 pubkey (signing authority)
+    pub controller: Pubkey,
+    /// Derived x25519 pubkey (for event routing/encryption)
+    pub encryption_pubkey: [u8; 32],
+    /// Token the taker is buying (base asset)
+    pub base_mint: Pubkey,
+    /// Token the taker pays with (quote asset)
+    pub quote_mint: Pubkey,
+    /// Unix timestamp when RFQ was created (set at callback)
+    pub created_at: i64,
+    /// Unix timestamp after which no quotes are accepted and anyone may crank
+    pub deadline: i64,
+    /// RFQ status
+    pub status: RfqStatus,
+    /// Counter for quotes made on this RFQ
+    pub num_quotes: u32,
+    /// Counterparty restriction on makers (see CounterpartyRestriction)
+    pub counterparty_kind: u8,
+    /// Allowed controller or Merkle root of allowed controllers (zeroed when unrestricted)
+    pub counterparty: [u8; 32],
+    /// PDA bump seed
+    pub bump: u8,
+}
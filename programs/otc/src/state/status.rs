@@ -1,21 +1,133 @@
-/// Status constants for DealAccount
-pub struct DealStatus;
+use anchor_lang::prelude::*;
 
-impl DealStatus {
+use crate::error::ErrorCode;
+
+/// Lifecycle of a DealAccount.
+///
+/// Serialized as a single byte (the variant index), so the values match the
+/// statuses revealed by the settlement circuits.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DealStatus {
     /// Deal is open and accepting offers
-    pub const OPEN: u8 = 0;
+    Open,
     /// Deal has been fully executed
-    pub const EXECUTED: u8 = 1;
+    Executed,
     /// Deal has expired (may have partial fills)
-    pub const EXPIRED: u8 = 2;
+    Expired,
+    /// Deal was rejected at creation (invalid or oversized inputs, see CircuitError)
+    Rejected,
+    /// `settle_deal` is in flight: the deal takes no offers and cannot be cranked until
+    /// its callback settles or reopens it
+    Settling,
 }
 
-/// Status constants for OfferAccount
-pub struct OfferStatus;
+impl DealStatus {
+    /// Converts the status revealed by a deal settlement circuit:
+    /// 0 = still open, 1 = executed, 2 = expired.
+    ///
+    /// Circuits never reveal `Rejected` or `Settling`; any other value fails the
+    /// callback instead of being stored.
+    pub fn from_circuit(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Open),
+            1 => Ok(Self::Executed),
+            2 => Ok(Self::Expired),
+            _ => err!(ErrorCode::InvalidCircuitStatus),
+        }
+    }
+
+    /// True once the deal has executed or expired, so its offers can be settled.
+    pub fn is_settled(self) -> bool {
+        matches!(self, Self::Executed | Self::Expired)
+    }
+}
 
-impl OfferStatus {
+/// Lifecycle of an OfferAccount
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OfferStatus {
     /// Offer is open and pending settlement
-    pub const OPEN: u8 = 0;
+    Open,
     /// Offer has been settled (executed, partial, or refunded)
-    pub const SETTLED: u8 = 1;
+    Settled,
+    /// Offer was rejected at submission; it locked nothing and is never settled
+    Rejected,
+}
+
+/// Outcome of a settled offer, sealed in `OfferSettledBlob.outcome`.
+///
+/// The program never sees the outcome; clients decode the decrypted byte
+/// with `from_circuit`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OfferOutcome {
+    /// The full offer amount executed
+    Executed,
+    /// Part of the offer executed and the rest was refunded
+    Partial,
+    /// Nothing executed and the full commitment was refunded
+    Failed,
+}
+
+impl OfferOutcome {
+    /// Converts a decrypted `OfferSettledBlob.outcome`:
+    /// 0 = executed, 1 = partial, 2 = failed.
+    pub fn from_circuit(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Executed),
+            1 => Ok(Self::Partial),
+            2 => Ok(Self::Failed),
+            _ => err!(ErrorCode::InvalidCircuitStatus),
+        }
+    }
+}
+
+/// Lifecycle of an RfqAccount.
+///
+/// Serialized as a single byte (the variant index), so the values match the
+/// statuses revealed by `crank_rfq`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RfqStatus {
+    /// RFQ is open and accepting quotes
+    Open,
+    /// RFQ was filled by the best quote
+    Filled,
+    /// RFQ closed without any acceptable quote
+    Expired,
+    /// RFQ was rejected at creation (size too large to lock, see CircuitError)
+    Rejected,
+}
+
+impl RfqStatus {
+    /// Converts the status revealed by `crank_rfq`: 1 = filled, 2 = expired.
+    ///
+    /// Settlement always closes the RFQ and circuits never reveal `Rejected`;
+    /// any other value fails the callback instead of being stored.
+    pub fn from_circuit(value: u8) -> Result<Self> {
+        match value {
+            1 => Ok(Self::Filled),
+            2 => Ok(Self::Expired),
+            _ => err!(ErrorCode::InvalidCircuitStatus),
+        }
+    }
+}
+
+/// Lifecycle of a QuoteAccount
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuoteStatus {
+    /// Quote is open and pending settlement
+    Open,
+    /// Quote has been settled (executed or released)
+    Settled,
+    /// Quote was rejected at submission; it locked nothing and is never settled
+    Rejected,
+}
+
+/// Lifecycle of a multisig Proposal
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProposalStatus {
+    /// Proposal is collecting member approvals
+    Active,
+    /// Proposal reached the threshold and may be executed
+    Approved,
+    /// Proposal's instruction has been executed
+    Executed,
 }
@@ -0,0 +1,224 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+
+use crate::error::ErrorCode;
+use crate::state::{
+    balance_layout, deal_layout, offer_layout, BalanceAccount, DealAccount, DealStatus,
+    OfferAccount, OfferStatus, PairConfig, BALANCE_VERSION, DEAL_VERSION, OFFER_VERSION,
+};
+
+account_layout! {
+    deal_v0_layout;
+
+    /// DealAccount as written before layout versioning (version 0).
+    ///
+    /// Shares the discriminator of `DealAccount`, so it is read with plain borsh after the
+    /// discriminator rather than as an `#[account]`.
+    #[derive(AnchorDeserialize, InitSpace)]
+    pub struct DealAccountV0 {
+        pub nonce: [u8; 16],
+        pub ciphertexts: [[u8; 32]; 3],
+        pub create_key: Pubkey,
+        pub controller: Pubkey,
+        pub encryption_pubkey: [u8; 32],
+        pub base_mint: Pubkey,
+        pub quote_mint: Pubkey,
+        pub created_at: i64,
+        pub expires_at: i64,
+        pub status: u8,
+        pub allow_partial: bool,
+        pub num_offers: u32,
+        pub bump: u8,
+    }
+}
+
+impl DealAccountV0 {
+    /// Current layout of the deal. Decimals come from the mints, which version 0 did
+    /// not record; the remaining new fields get the values of an unrestricted,
+    /// single-asset deal on a pair without config.
+    pub fn upgrade(self, base_decimals: u8, quote_decimals: u8) -> Result<DealAccount> {
+        let status = match self.status {
+            0 => DealStatus::Open,
+            1 => DealStatus::Executed,
+            2 => DealStatus::Expired,
+            _ => return err!(ErrorCode::InvalidAccountLayout),
+        };
+        Ok(DealAccount {
+            nonce: self.nonce,
+            ciphertexts: self.ciphertexts,
+            version: DEAL_VERSION,
+            create_key: self.create_key,
+            controller: self.controller,
+            encryption_pubkey: self.encryption_pubkey,
+            base_mint: self.base_mint,
+            quote_mint: self.quote_mint,
+            base_decimals,
+            quote_decimals,
+            tick_size: PairConfig::DEFAULT_TICK_SIZE,
+            lot_size: PairConfig::DEFAULT_LOT_SIZE,
+            price_feed: Pubkey::default(),
+            band_bps: 0,
+            created_at: self.created_at,
+            expires_at: self.expires_at,
+            status,
+            allow_partial: self.allow_partial,
+            num_offers: self.num_offers,
+            counterparty_kind: 0,
+            counterparty: [0u8; 32],
+            crank_bounty: 0,
+            basket_legs: 0,
+            swap: false,
+            bump: self.bump,
+        })
+    }
+}
+
+account_layout! {
+    offer_v0_layout;
+
+    /// OfferAccount as written before layout versioning (version 0).
+    #[derive(AnchorDeserialize, InitSpace)]
+    pub struct OfferAccountV0 {
+        pub nonce: [u8; 16],
+        pub ciphertexts: [[u8; 32]; 3],
+        pub create_key: Pubkey,
+        pub controller: Pubkey,
+        pub encryption_pubkey: [u8; 32],
+        pub deal: Pubkey,
+        pub submitted_at: i64,
+        pub offer_index: u32,
+        pub status: u8,
+        pub bump: u8,
+    }
+}
+
+impl OfferAccountV0 {
+    /// Current layout of the offer, without a crank bounty. Version 0 locked the offer's
+    /// quote rounded down, so it is released that way.
+    pub fn upgrade(self) -> Result<OfferAccount> {
+        let status = match self.status {
+            0 => OfferStatus::Open,
+            1 => OfferStatus::Settled,
+            _ => return err!(ErrorCode::InvalidAccountLayout),
+        };
+        Ok(OfferAccount {
+            nonce: self.nonce,
+            ciphertexts: self.ciphertexts,
+            version: OFFER_VERSION,
+            create_key: self.create_key,
+            controller: self.controller,
+            encryption_pubkey: self.encryption_pubkey,
+            deal: self.deal,
+            submitted_at: self.submitted_at,
+            offer_index: self.offer_index,
+            status,
+            crank_bounty: 0,
+            floor_locked: true,
+            bump: self.bump,
+        })
+    }
+}
+
+account_layout! {
+    balance_v0_layout;
+
+    /// BalanceAccount as written before layout versioning (version 0).
+    #[derive(AnchorDeserialize, InitSpace)]
+    pub struct BalanceAccountV0 {
+        pub nonce: [u8; 16],
+        pub ciphertexts: [[u8; 32]; 2],
+        pub controller: Pubkey,
+        pub encryption_pubkey: [u8; 32],
+        pub mint: Pubkey,
+        pub bump: u8,
+    }
+}
+
+impl BalanceAccountV0 {
+    /// Current layout of the balance, without an auditor.
+    pub fn upgrade(self) -> BalanceAccount {
+        BalanceAccount {
+            nonce: self.nonce,
+            ciphertexts: self.ciphertexts,
+            version: BALANCE_VERSION,
+            controller: self.controller,
+            encryption_pubkey: self.encryption_pubkey,
+            mint: self.mint,
+            auditor_pubkey: [0u8; 32],
+            bump: self.bump,
+        }
+    }
+}
+
+// Migration leaves the nonce and ciphertexts in place, so version 0 must store them at
+// the same offsets as the current layouts.
+const _: () = {
+    assert!(deal_v0_layout::nonce.0 == deal_layout::nonce.0);
+    assert!(deal_v0_layout::ciphertexts.0 == deal_layout::ciphertexts.0);
+    assert!(deal_v0_layout::ciphertexts.1 == deal_layout::ciphertexts.1);
+    assert!(offer_v0_layout::nonce.0 == offer_layout::nonce.0);
+    assert!(offer_v0_layout::ciphertexts.0 == offer_layout::ciphertexts.0);
+    assert!(offer_v0_layout::ciphertexts.1 == offer_layout::ciphertexts.1);
+    assert!(balance_v0_layout::nonce.0 == balance_layout::nonce.0);
+    assert!(balance_v0_layout::ciphertexts.0 == balance_layout::ciphertexts.0);
+    assert!(balance_v0_layout::ciphertexts.1 == balance_layout::ciphertexts.1);
+};
+
+/// Rewrites a legacy program account in its current layout.
+///
+/// The account must carry `T`'s discriminator and be exactly as long as the legacy
+/// layout `L`; a current-length account fails with `AccountAlreadyMigrated` and
+/// anything else with `InvalidAccountLayout`. `upgrade` maps the legacy fields onto
+/// `T`, the account grows to `T`'s space and `T` is written over it. Both layouts start
+/// with the nonce and ciphertexts, so the ciphertext offsets passed to computations
+/// remain valid.
+///
+/// `payer` funds the extra rent; lamports already held on the account (such as an
+/// escrowed crank bounty) are left untouched.
+pub fn migrate_layout<'info, L, T>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    upgrade: impl FnOnce(L) -> Result<T>,
+) -> Result<()>
+where
+    L: AnchorDeserialize + Space,
+    T: AccountSerialize + Discriminator + Space,
+{
+    let legacy_len = 8 + L::INIT_SPACE;
+    let len = 8 + T::INIT_SPACE;
+    let current = {
+        let data = account.try_borrow_data()?;
+        require!(
+            data.starts_with(T::DISCRIMINATOR),
+            ErrorCode::InvalidAccountLayout
+        );
+        require!(data.len() != len, ErrorCode::AccountAlreadyMigrated);
+        require!(data.len() == legacy_len, ErrorCode::InvalidAccountLayout);
+        let legacy =
+            L::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::InvalidAccountLayout))?;
+        upgrade(legacy)?
+    };
+
+    let rent = Rent::get()?;
+    let extra_rent = rent
+        .minimum_balance(len)
+        .saturating_sub(rent.minimum_balance(legacy_len));
+    if extra_rent > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            extra_rent,
+        )?;
+    }
+
+    account.resize(len)?;
+    let mut data = account.try_borrow_mut_data()?;
+    current.try_serialize(&mut &mut data[..])
+}
@@ -3,6 +3,7 @@ import { randomBytes } from "crypto";
 import { expect } from "chai";
 import {
   getTestHarness,
+  hasDemoInstructions,
   getMXEPublicKeyWithRetry,
   awaitEvent,
   awaitComputationFinalization,
@@ -21,6 +22,11 @@ describe("Add Together", () => {
   const { program, provider, owner, arciumEnv, clusterAccount } =
     getTestHarness();
 
+  before(function () {
+    // The examples only exist in the demo build
+    if (!hasDemoInstructions(program)) this.skip();
+  });
+
   it("adds two encrypted values and returns correct sum", async () => {
     const mxePublicKey = await getMXEPublicKeyWithRetry(
       provider,
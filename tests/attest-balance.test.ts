@@ -0,0 +1,165 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitEvent,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getBalanceAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+describe("Balance Attestation", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  async function topUp(
+    controller: Keypair,
+    encryptionPubkey: Uint8Array,
+    mint: PublicKey,
+    amount: number
+  ): Promise<PublicKey> {
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPubkey),
+        randomU128(),
+        new anchor.BN(amount)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        ...arciumAccounts(computationOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    return balance;
+  }
+
+  it("attests proof of funds to a third party without revealing the balance", async () => {
+    // ==========================================
+    // SETUP: Owner with 1000, counterparty key
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const mint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    const ownerPrivateKey = x25519.utils.randomSecretKey();
+    const ownerPublicKey = x25519.getPublicKey(ownerPrivateKey);
+    const ownerCipher = new RescueCipher(
+      x25519.getSharedSecret(ownerPrivateKey, mxePublicKey)
+    );
+    const balance = await topUp(owner, ownerPublicKey, mint, 1000);
+
+    const counterpartyPrivateKey = x25519.utils.randomSecretKey();
+    const counterpartyPublicKey = x25519.getPublicKey(counterpartyPrivateKey);
+    const counterpartyCipher = new RescueCipher(
+      x25519.getSharedSecret(counterpartyPrivateKey, mxePublicKey)
+    );
+
+    async function attest(threshold: bigint) {
+      const nonce = randomBytes(16);
+      const ciphertext = ownerCipher.encrypt([threshold], nonce);
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      const attestedPromise = awaitEvent(program, "balanceAttested");
+      await program.methods
+        .attestBalance(
+          computationOffset,
+          owner.publicKey,
+          Array.from(ownerPublicKey),
+          new anchor.BN(deserializeLE(nonce).toString()),
+          Array.from(ciphertext[0]),
+          Array.from(counterpartyPublicKey),
+          randomU128()
+        )
+        .accountsPartial({
+          controllerSigner: owner.publicKey,
+          balance,
+          ...arciumAccounts(computationOffset, "attest_balance"),
+        })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(
+        provider,
+        computationOffset,
+        program.programId,
+        "confirmed"
+      );
+      const attested = await attestedPromise;
+      expect(attested.balance.toBase58()).to.equal(balance.toBase58());
+      expect(Buffer.from(attested.encryptionKey)).to.deep.equal(
+        Buffer.from(counterpartyPublicKey)
+      );
+      // AttestationBlob: threshold (u64), covered (bool)
+      return counterpartyCipher.decrypt(
+        attested.ciphertexts,
+        Uint8Array.from(attested.nonce)
+      );
+    }
+
+    // ==========================================
+    // STEP 1: 1000 covers 800
+    // ==========================================
+    const [threshold, covered] = await attest(BigInt(800));
+    expect(threshold).to.equal(BigInt(800));
+    expect(covered).to.equal(BigInt(1));
+
+    // ==========================================
+    // STEP 2: 1000 does not cover 1500
+    // ==========================================
+    const [, notCovered] = await attest(BigInt(1500));
+    expect(notCovered).to.equal(BigInt(0));
+  });
+});
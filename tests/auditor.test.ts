@@ -0,0 +1,159 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitEvent,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getBalanceAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+describe("Auditor View Keys", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  async function topUp(
+    controller: Keypair,
+    encryptionPubkey: Uint8Array,
+    mint: PublicKey,
+    amount: number
+  ): Promise<PublicKey> {
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPubkey),
+        randomU128(),
+        new anchor.BN(amount)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        ...arciumAccounts(computationOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    return balance;
+  }
+
+  it("copies balance blobs to the configured auditor key", async () => {
+    // ==========================================
+    // SETUP: Owner with 1000, auditor key
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const mint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    const ownerPrivateKey = x25519.utils.randomSecretKey();
+    const ownerPublicKey = x25519.getPublicKey(ownerPrivateKey);
+    const ownerCipher = new RescueCipher(
+      x25519.getSharedSecret(ownerPrivateKey, mxePublicKey)
+    );
+    const balance = await topUp(owner, ownerPublicKey, mint, 1000);
+
+    const auditorPrivateKey = x25519.utils.randomSecretKey();
+    const auditorPublicKey = x25519.getPublicKey(auditorPrivateKey);
+    const auditorCipher = new RescueCipher(
+      x25519.getSharedSecret(auditorPrivateKey, mxePublicKey)
+    );
+
+    // ==========================================
+    // STEP 1: Only the controller can set the auditor
+    // ==========================================
+    const stranger = Keypair.generate();
+    try {
+      await program.methods
+        .setAuditor(Array.from(auditorPublicKey))
+        .accountsPartial({ controller: stranger.publicKey, balance })
+        .signers([stranger])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("stranger should not be able to set the auditor");
+    } catch (error: any) {
+      expect(error.toString()).to.include("ConstraintSeeds");
+    }
+
+    await program.methods
+      .setAuditor(Array.from(auditorPublicKey))
+      .accountsPartial({ controller: owner.publicKey, balance })
+      .rpc({ commitment: "confirmed" });
+    const balanceAccount = await program.account.balanceAccount.fetch(balance);
+    expect(Buffer.from(balanceAccount.auditorPubkey)).to.deep.equal(
+      Buffer.from(auditorPublicKey)
+    );
+
+    // ==========================================
+    // STEP 2: The next update is readable by owner and auditor
+    // ==========================================
+    const updatedPromise = awaitEvent(program, "balanceUpdated");
+    await topUp(owner, ownerPublicKey, mint, 500);
+    const updated = await updatedPromise;
+
+    expect(Buffer.from(updated.auditorEncryptionKey)).to.deep.equal(
+      Buffer.from(auditorPublicKey)
+    );
+    const [ownerAmount] = ownerCipher.decrypt(
+      updated.ciphertexts,
+      Uint8Array.from(updated.nonce)
+    );
+    const [auditorAmount, auditorCommitted] = auditorCipher.decrypt(
+      updated.auditorCiphertexts,
+      Uint8Array.from(updated.auditorNonce)
+    );
+    expect(ownerAmount).to.equal(BigInt(1500));
+    expect(auditorAmount).to.equal(BigInt(1500));
+    expect(auditorCommitted).to.equal(BigInt(0));
+  });
+});
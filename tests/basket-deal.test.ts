@@ -0,0 +1,299 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitEvent,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getDealAddress,
+  getOfferAddress,
+  getBalanceAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+describe("Basket Deals", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  function getBasketAddress(deal: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("basket"), deal.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  function legAccounts(balances: PublicKey[]) {
+    return balances.map((pubkey) => ({
+      pubkey,
+      isWritable: true,
+      isSigner: false,
+    }));
+  }
+
+  async function finalize(computationOffset: anchor.BN) {
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+  }
+
+  async function topUp(
+    controller: Keypair,
+    encryptionPubkey: Uint8Array,
+    mint: PublicKey,
+    amount: number
+  ): Promise<PublicKey> {
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPubkey),
+        randomU128(),
+        new anchor.BN(amount)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        ...arciumAccounts(computationOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(computationOffset);
+    return balance;
+  }
+
+  it("sells a three-leg basket and settles every leg in one crank", async () => {
+    // ==========================================
+    // SETUP: Three base legs and a whole-unit quote mint
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const legMints: PublicKey[] = [];
+    for (let i = 0; i < 3; i++) {
+      legMints.push(
+        await createMint(provider.connection, owner, owner.publicKey, null, 6)
+      );
+    }
+    const quoteMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      0
+    );
+
+    const creatorPrivateKey = x25519.utils.randomSecretKey();
+    const creatorPublicKey = x25519.getPublicKey(creatorPrivateKey);
+    const creatorCipher = new RescueCipher(
+      x25519.getSharedSecret(creatorPrivateKey, mxePublicKey)
+    );
+    const creator = Keypair.generate();
+    const legBalances: PublicKey[] = [];
+    for (const mint of legMints) {
+      legBalances.push(await topUp(creator, creatorPublicKey, mint, 10000));
+    }
+
+    // ==========================================
+    // STEP 1: Create a basket of 10 units at 5 quote each
+    // ==========================================
+    const units = BigInt(10);
+    const price = BigInt(5) << BigInt(64); // X64.64: 5.0
+    const legAmounts = [BigInt(100), BigInt(200), BigInt(300), BigInt(0)];
+    const dealNonce = randomBytes(16);
+    const dealCiphertext = creatorCipher.encrypt(
+      [units, price, ...legAmounts],
+      dealNonce
+    );
+
+    const createKey = Keypair.generate();
+    const deal = getDealAddress(program, createKey.publicKey);
+    const basket = getBasketAddress(deal);
+    const createOffset = new anchor.BN(randomBytes(8), "hex");
+    const createBasketDeal = (balances: PublicKey[], key: Keypair) =>
+      program.methods
+        .createBasketDeal(
+          createOffset,
+          creator.publicKey,
+          Array.from(creatorPublicKey),
+          new anchor.BN(deserializeLE(dealNonce).toString()),
+          new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+          false,
+          Array.from(dealCiphertext[0]),
+          Array.from(dealCiphertext[1]),
+          dealCiphertext.slice(2).map((ciphertext) => Array.from(ciphertext)),
+          new anchor.BN(0) // crank_bounty: none
+        )
+        .accountsPartial({
+          authority: creator.publicKey,
+          createKey: key.publicKey,
+          deal: getDealAddress(program, key.publicKey),
+          basket: getBasketAddress(getDealAddress(program, key.publicKey)),
+          quoteMint,
+          ...arciumAccounts(createOffset, "create_basket_deal"),
+        })
+        .remainingAccounts(legAccounts(balances))
+        .signers([creator, key]);
+
+    // The same leg twice would lock one balance twice
+    try {
+      const duplicateKey = Keypair.generate();
+      await createBasketDeal(
+        [legBalances[0], legBalances[0]],
+        duplicateKey
+      ).rpc({ commitment: "confirmed" });
+      expect.fail("duplicate legs should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("InvalidBasketLegs");
+    }
+
+    const createdPromise = awaitEvent(program, "basketDealCreated");
+    await createBasketDeal(legBalances, createKey).rpc({
+      skipPreflight: true,
+      commitment: "confirmed",
+    });
+    await finalize(createOffset);
+    const created = await createdPromise;
+    expect(created.deal.toBase58()).to.equal(deal.toBase58());
+    expect(created.legMints.map((mint: PublicKey) => mint.toBase58())).to.eql(
+      legMints.map((mint) => mint.toBase58())
+    );
+    const createdBlob = creatorCipher.decrypt(
+      created.ciphertexts,
+      Uint8Array.from(created.nonce)
+    );
+    expect(createdBlob).to.eql([units, price, ...legAmounts]);
+
+    const dealAccount = await program.account.dealAccount.fetch(deal);
+    expect(dealAccount.basketLegs).to.equal(3);
+    const basketAccount = await program.account.basketLegs.fetch(basket);
+    expect(basketAccount.numLegs).to.equal(3);
+
+    // ==========================================
+    // STEP 2: A buyer takes the whole basket with a regular offer
+    // ==========================================
+    const offeror = Keypair.generate();
+    const offerorPrivateKey = x25519.utils.randomSecretKey();
+    const offerorPublicKey = x25519.getPublicKey(offerorPrivateKey);
+    const offerorCipher = new RescueCipher(
+      x25519.getSharedSecret(offerorPrivateKey, mxePublicKey)
+    );
+    const offerorBalance = await topUp(
+      offeror,
+      offerorPublicKey,
+      quoteMint,
+      10000
+    );
+
+    const offerNonce = randomBytes(16);
+    const offerCiphertext = offerorCipher.encrypt([price, units], offerNonce);
+    const offerCreateKey = Keypair.generate();
+    const offerOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .submitOffer(
+        offerOffset,
+        offeror.publicKey,
+        Array.from(offerorPublicKey),
+        new anchor.BN(deserializeLE(offerNonce).toString()),
+        Array.from(offerCiphertext[0]),
+        Array.from(offerCiphertext[1]),
+        [], // counterparty_proof
+        new anchor.BN(0) // crank_bounty: none
+      )
+      .accountsPartial({
+        authority: offeror.publicKey,
+        createKey: offerCreateKey.publicKey,
+        deal,
+        offer: getOfferAddress(program, deal, offerCreateKey.publicKey),
+        offerorBalance,
+        ...arciumAccounts(offerOffset, "submit_offer"),
+      })
+      .signers([offerCreateKey, offeror])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(offerOffset);
+
+    // ==========================================
+    // STEP 3: The creator cranks; every leg settles together
+    // ==========================================
+    const crankOffset = new anchor.BN(randomBytes(8), "hex");
+    const settledPromise = awaitEvent(program, "basketDealSettled");
+    await program.methods
+      .crankBasketDeal(crankOffset, randomU128())
+      .accountsPartial({
+        payer: creator.publicKey,
+        deal,
+        basket,
+        ...arciumAccounts(crankOffset, "crank_basket_deal"),
+      })
+      .remainingAccounts(legAccounts(legBalances))
+      .signers([creator])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(crankOffset);
+
+    const settled = await settledPromise;
+    expect(settled.status).to.eql({ executed: {} });
+    // BasketDealSettledBlob: [total_filled, creator_receives, leg_sold x4, leg_refund x4]
+    const settledBlob = creatorCipher.decrypt(
+      settled.ciphertexts,
+      Uint8Array.from(settled.nonce)
+    );
+    expect(settledBlob[0]).to.equal(BigInt(10));
+    expect(settledBlob[1]).to.equal(BigInt(50));
+    expect(settledBlob.slice(2, 6)).to.eql([
+      BigInt(1000),
+      BigInt(2000),
+      BigInt(3000),
+      BigInt(0),
+    ]);
+    expect(settledBlob.slice(6)).to.eql([
+      BigInt(0),
+      BigInt(0),
+      BigInt(0),
+      BigInt(0),
+    ]);
+
+    const settledDeal = await program.account.dealAccount.fetch(deal);
+    expect(settledDeal.status).to.eql({ executed: {} });
+  });
+});
@@ -3,6 +3,7 @@ import { randomBytes } from "crypto";
 import { expect } from "chai";
 import {
   getTestHarness,
+  hasDemoInstructions,
   getMXEPublicKeyWithRetry,
   awaitEvents,
   awaitComputationFinalization,
@@ -22,6 +23,11 @@ describe("Counter", () => {
   const { program, provider, owner, arciumEnv, clusterAccount } =
     getTestHarness();
 
+  before(function () {
+    // The examples only exist in the demo build
+    if (!hasDemoInstructions(program)) this.skip();
+  });
+
   it("initializes, increments, and reads a counter", async () => {
     const mxePublicKey = await getMXEPublicKeyWithRetry(
       provider,
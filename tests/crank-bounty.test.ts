@@ -0,0 +1,296 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitEvent,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getDealAddress,
+  getOfferAddress,
+  getBalanceAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+// DelegatePermission bit for cranking and settling
+const CRANK = 4;
+const BOUNTY = 1_000_000;
+
+describe("Crank Bounty", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  function getDelegateAddress(
+    controller: PublicKey,
+    sessionKey: PublicKey
+  ): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("delegate"), controller.toBuffer(), sessionKey.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  async function finalize(computationOffset: anchor.BN) {
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+  }
+
+  async function fund(keypair: Keypair) {
+    const airdropSig = await provider.connection.requestAirdrop(
+      keypair.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdropSig, "confirmed");
+  }
+
+  async function topUp(
+    controller: Keypair,
+    encryptionPubkey: Uint8Array,
+    mint: PublicKey,
+    amount: number
+  ): Promise<PublicKey> {
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPubkey),
+        randomU128(),
+        new anchor.BN(amount)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        ...arciumAccounts(computationOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(computationOffset);
+    return balance;
+  }
+
+  it("pays third-party crankers and refunds the controller", async () => {
+    // ==========================================
+    // SETUP: Mints, creator and offeror balances
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const baseMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+    const quoteMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    const creatorPrivateKey = x25519.utils.randomSecretKey();
+    const creatorPublicKey = x25519.getPublicKey(creatorPrivateKey);
+    const creatorCipher = new RescueCipher(
+      x25519.getSharedSecret(creatorPrivateKey, mxePublicKey)
+    );
+    const creatorBalance = await topUp(
+      owner,
+      creatorPublicKey,
+      baseMint,
+      10000
+    );
+
+    const offeror = Keypair.generate();
+    const offerorPrivateKey = x25519.utils.randomSecretKey();
+    const offerorPublicKey = x25519.getPublicKey(offerorPrivateKey);
+    const offerorCipher = new RescueCipher(
+      x25519.getSharedSecret(offerorPrivateKey, mxePublicKey)
+    );
+    const offerorBalance = await topUp(
+      offeror,
+      offerorPublicKey,
+      quoteMint,
+      10000
+    );
+
+    // 1000 at 2.0 with a bounty, expiring at `expiresAt`
+    async function createDeal(expiresAt: number): Promise<PublicKey> {
+      const dealNonce = randomBytes(16);
+      const dealCiphertext = creatorCipher.encrypt(
+        [BigInt(1000), BigInt(2) << BigInt(64)],
+        dealNonce
+      );
+      const dealCreateKey = Keypair.generate();
+      const deal = getDealAddress(program, dealCreateKey.publicKey);
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .createDeal(
+          computationOffset,
+          owner.publicKey,
+          Array.from(creatorPublicKey),
+          new anchor.BN(deserializeLE(dealNonce).toString()),
+          randomU128(),
+          new anchor.BN(expiresAt),
+          false,
+          Array.from(dealCiphertext[0]),
+          Array.from(dealCiphertext[1]),
+          0, // counterparty_kind: unrestricted
+          Array.from(new Uint8Array(32)), // counterparty
+          new anchor.BN(BOUNTY)
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          createKey: dealCreateKey.publicKey,
+          deal,
+          creatorBalance,
+          baseMint,
+          quoteMint,
+          ...arciumAccounts(computationOffset, "create_deal"),
+        })
+        .signers([dealCreateKey])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await finalize(computationOffset);
+      return deal;
+    }
+
+    async function crankDeal(
+      deal: PublicKey,
+      payer: Keypair,
+      delegate: PublicKey | null
+    ) {
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .crankDeal(computationOffset, randomU128(), randomU128())
+        .accountsPartial({
+          payer: payer.publicKey,
+          deal,
+          creatorBalance,
+          delegate,
+          ...arciumAccounts(computationOffset, "crank_deal"),
+        })
+        .signers([payer])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await finalize(computationOffset);
+    }
+
+    // ==========================================
+    // STEP 1: A third party cranks an expired deal and keeps the bounty
+    // ==========================================
+    const cranker = Keypair.generate();
+    await fund(cranker);
+
+    const expiredDeal = await createDeal(Math.floor(Date.now() / 1000) - 60);
+    const paidPromise = awaitEvent(program, "crankBountyPaid");
+    await crankDeal(expiredDeal, cranker, null);
+    const paid = await paidPromise;
+    expect(paid.account.toBase58()).to.equal(expiredDeal.toBase58());
+    expect(paid.recipient.toBase58()).to.equal(cranker.publicKey.toBase58());
+    expect(paid.amount.toNumber()).to.equal(BOUNTY);
+    expect(paid.refund).to.equal(false);
+
+    // ==========================================
+    // STEP 2: The controller's session key cranks a filled deal early,
+    // so the bounty goes back to the controller, not the session key
+    // ==========================================
+    const filledDeal = await createDeal(Math.floor(Date.now() / 1000) + 3600);
+
+    const offerNonce = randomBytes(16);
+    const offerCiphertext = offerorCipher.encrypt(
+      [BigInt(2) << BigInt(64), BigInt(1000)],
+      offerNonce
+    );
+    const offerCreateKey = Keypair.generate();
+    const offerOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .submitOffer(
+        offerOffset,
+        offeror.publicKey,
+        Array.from(offerorPublicKey),
+        new anchor.BN(deserializeLE(offerNonce).toString()),
+        Array.from(offerCiphertext[0]),
+        Array.from(offerCiphertext[1]),
+        [], // counterparty_proof
+        new anchor.BN(0) // crank_bounty: none
+      )
+      .accountsPartial({
+        authority: offeror.publicKey,
+        createKey: offerCreateKey.publicKey,
+        deal: filledDeal,
+        offer: getOfferAddress(program, filledDeal, offerCreateKey.publicKey),
+        offerorBalance,
+        ...arciumAccounts(offerOffset, "submit_offer"),
+      })
+      .signers([offerCreateKey, offeror])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(offerOffset);
+
+    const sessionKey = Keypair.generate();
+    await fund(sessionKey);
+    const delegate = getDelegateAddress(owner.publicKey, sessionKey.publicKey);
+    await program.methods
+      .createDelegate(
+        CRANK,
+        [],
+        new anchor.BN(0),
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+      )
+      .accountsPartial({
+        controller: owner.publicKey,
+        sessionKey: sessionKey.publicKey,
+        delegate,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const refundPromise = awaitEvent(program, "crankBountyPaid");
+    await crankDeal(filledDeal, sessionKey, delegate);
+    const refund = await refundPromise;
+    expect(refund.account.toBase58()).to.equal(filledDeal.toBase58());
+    expect(refund.recipient.toBase58()).to.equal(owner.publicKey.toBase58());
+    expect(refund.amount.toNumber()).to.equal(BOUNTY);
+    expect(refund.refund).to.equal(true);
+  });
+});
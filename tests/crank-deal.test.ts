@@ -146,9 +146,13 @@ describe("Crank Deal", () => {
         expiresAt,
         allowPartial,
         Array.from(dealCiphertext[0]),
-        Array.from(dealCiphertext[1])
+        Array.from(dealCiphertext[1]),
+        0, // counterparty_kind: unrestricted
+        Array.from(new Uint8Array(32)), // counterparty
+        new anchor.BN(0) // crank_bounty: none
       )
       .accountsPartial({
+        authority: owner.publicKey,
         createKey: dealCreateKey.publicKey,
         deal: dealAddress,
         creatorBalance: creatorBalanceAddress,
@@ -185,6 +189,8 @@ describe("Crank Deal", () => {
     // ==========================================
     // STEP 3: Top up offeror's QUOTE balance
     // ==========================================
+    // Offeror must be a different controller than the deal creator (self-trade is rejected)
+    const offeror = Keypair.generate();
     console.log("\n--- Top Up Offeror Balance ---");
 
     const offerorPrivateKey = x25519.utils.randomSecretKey();
@@ -197,7 +203,7 @@ describe("Crank Deal", () => {
 
     const offerorBalanceAddress = getBalanceAddress(
       program,
-      owner.publicKey,
+      offeror.publicKey,
       quoteMint
     );
     const offerorTopUpNonce = randomBytes(16);
@@ -206,13 +212,13 @@ describe("Crank Deal", () => {
     await program.methods
       .topUp(
         offerorTopUpComputationOffset,
-        owner.publicKey,
+        offeror.publicKey,
         Array.from(offerorPublicKey),
         new anchor.BN(deserializeLE(offerorTopUpNonce).toString()),
         new anchor.BN(10000)
       )
       .accountsPartial({
-        controllerSigner: owner.publicKey,
+        controllerSigner: offeror.publicKey,
         mint: quoteMint,
         balance: offerorBalanceAddress,
         computationAccount: getComputationAccAddress(
@@ -230,6 +236,7 @@ describe("Crank Deal", () => {
           Buffer.from(getCompDefAccOffset("top_up")).readUInt32LE()
         ),
       })
+      .signers([offeror])
       .rpc({ skipPreflight: true, commitment: "confirmed" });
 
     await awaitComputationFinalization(
@@ -266,13 +273,16 @@ describe("Crank Deal", () => {
     await program.methods
       .submitOffer(
         offerComputationOffset,
-        owner.publicKey,
+        offeror.publicKey,
         Array.from(offerorPublicKey),
         new anchor.BN(deserializeLE(offerNonce).toString()),
         Array.from(offerCiphertext[0]),
-        Array.from(offerCiphertext[1])
+        Array.from(offerCiphertext[1]),
+        [], // counterparty_proof
+        new anchor.BN(0) // crank_bounty: none
       )
       .accountsPartial({
+        authority: offeror.publicKey,
         createKey: offerCreateKey.publicKey,
         deal: dealAddress,
         offer: offerAddress,
@@ -292,7 +302,7 @@ describe("Crank Deal", () => {
           Buffer.from(getCompDefAccOffset("submit_offer")).readUInt32LE()
         ),
       })
-      .signers([offerCreateKey])
+      .signers([offerCreateKey, offeror])
       .rpc({ skipPreflight: true, commitment: "confirmed" });
 
     await awaitComputationFinalization(
@@ -360,7 +370,7 @@ describe("Crank Deal", () => {
     console.log("DealSettled event received");
 
     expect(dealSettledEvent.deal.toBase58()).to.equal(dealAddress.toBase58());
-    expect(dealSettledEvent.status).to.equal(1); // EXECUTED
+    expect(dealSettledEvent.status).to.eql({ executed: {} });
     expect(dealSettledEvent.settledAt.toNumber()).to.be.greaterThan(0);
 
     // Decrypt the settlement blob using creator's private key
@@ -388,7 +398,7 @@ describe("Crank Deal", () => {
     // STEP 7: Verify DealAccount state
     // ==========================================
     const dealAccount = await program.account.dealAccount.fetch(dealAddress);
-    expect(dealAccount.status).to.equal(1); // EXECUTED
+    expect(dealAccount.status).to.eql({ executed: {} });
     console.log("Deal status verified: EXECUTED (1)");
   });
 });
@@ -144,9 +144,13 @@ describe("Crank Offer", () => {
         expiresAt,
         allowPartial,
         Array.from(dealCiphertext[0]),
-        Array.from(dealCiphertext[1])
+        Array.from(dealCiphertext[1]),
+        0, // counterparty_kind: unrestricted
+        Array.from(new Uint8Array(32)), // counterparty
+        new anchor.BN(0) // crank_bounty: none
       )
       .accountsPartial({
+        authority: owner.publicKey,
         createKey: dealCreateKey.publicKey,
         deal: dealAddress,
         creatorBalance: creatorBalanceAddress,
@@ -183,6 +187,8 @@ describe("Crank Offer", () => {
     // ==========================================
     // STEP 3: Top up offeror's QUOTE balance
     // ==========================================
+    // Offeror must be a different controller than the deal creator (self-trade is rejected)
+    const offeror = Keypair.generate();
     console.log("\n--- Top Up Offeror Balance ---");
 
     const offerorPrivateKey = x25519.utils.randomSecretKey();
@@ -195,7 +201,7 @@ describe("Crank Offer", () => {
 
     const offerorBalanceAddress = getBalanceAddress(
       program,
-      owner.publicKey,
+      offeror.publicKey,
       quoteMint
     );
     const offerorTopUpNonce = randomBytes(16);
@@ -204,13 +210,13 @@ describe("Crank Offer", () => {
     await program.methods
       .topUp(
         offerorTopUpComputationOffset,
-        owner.publicKey,
+        offeror.publicKey,
         Array.from(offerorPublicKey),
         new anchor.BN(deserializeLE(offerorTopUpNonce).toString()),
         new anchor.BN(10000)
       )
       .accountsPartial({
-        controllerSigner: owner.publicKey,
+        controllerSigner: offeror.publicKey,
         mint: quoteMint,
         balance: offerorBalanceAddress,
         computationAccount: getComputationAccAddress(
@@ -228,6 +234,7 @@ describe("Crank Offer", () => {
           Buffer.from(getCompDefAccOffset("top_up")).readUInt32LE()
         ),
       })
+      .signers([offeror])
       .rpc({ skipPreflight: true, commitment: "confirmed" });
 
     await awaitComputationFinalization(
@@ -263,13 +270,16 @@ describe("Crank Offer", () => {
     await program.methods
       .submitOffer(
         offerComputationOffset,
-        owner.publicKey,
+        offeror.publicKey,
         Array.from(offerorPublicKey),
         new anchor.BN(deserializeLE(offerNonce).toString()),
         Array.from(offerCiphertext[0]),
-        Array.from(offerCiphertext[1])
+        Array.from(offerCiphertext[1]),
+        [], // counterparty_proof
+        new anchor.BN(1_000_000) // crank_bounty: paid to whoever cranks the offer
       )
       .accountsPartial({
+        authority: offeror.publicKey,
         createKey: offerCreateKey.publicKey,
         deal: dealAddress,
         offer: offerAddress,
@@ -289,7 +299,7 @@ describe("Crank Offer", () => {
           Buffer.from(getCompDefAccOffset("submit_offer")).readUInt32LE()
         ),
       })
-      .signers([offerCreateKey])
+      .signers([offerCreateKey, offeror])
       .rpc({ skipPreflight: true, commitment: "confirmed" });
 
     await awaitComputationFinalization(
@@ -353,13 +363,13 @@ describe("Crank Offer", () => {
     const dealSettledEvent = await dealSettledEventPromise;
     console.log("DealSettled event received");
 
-    expect(dealSettledEvent.status).to.equal(1); // EXECUTED
+    expect(dealSettledEvent.status).to.eql({ executed: {} });
 
     // Verify deal status is EXECUTED before cranking offer
     const dealAccountBefore = await program.account.dealAccount.fetch(
       dealAddress
     );
-    expect(dealAccountBefore.status).to.equal(1); // EXECUTED
+    expect(dealAccountBefore.status).to.eql({ executed: {} });
     console.log("Deal status verified: EXECUTED (1)");
 
     // ==========================================
@@ -371,7 +381,7 @@ describe("Crank Offer", () => {
     const offerAccountBefore = await program.account.offerAccount.fetch(
       offerAddress
     );
-    expect(offerAccountBefore.status).to.equal(0); // OPEN
+    expect(offerAccountBefore.status).to.eql({ open: {} });
     console.log("Offer status before crank: OPEN (0)");
 
     const crankOfferComputationOffset = new anchor.BN(randomBytes(8), "hex");
@@ -379,6 +389,7 @@ describe("Crank Offer", () => {
     const crankOfferBalanceBlobNonce = randomBytes(16);
 
     const offerSettledEventPromise = awaitEvent(program, "offerSettled");
+    const crankBountyPaidEventPromise = awaitEvent(program, "crankBountyPaid");
 
     await program.methods
       .crankOffer(
@@ -430,6 +441,16 @@ describe("Crank Offer", () => {
     );
     expect(offerSettledEvent.offerIndex).to.equal(0);
 
+    // Crank bounty goes to the (non-offeror) cranker
+    const crankBountyPaidEvent = await crankBountyPaidEventPromise;
+    expect(crankBountyPaidEvent.account.toBase58()).to.equal(
+      offerAddress.toBase58()
+    );
+    expect(crankBountyPaidEvent.recipient.toBase58()).to.equal(
+      owner.publicKey.toBase58()
+    );
+    expect(crankBountyPaidEvent.amount.toNumber()).to.equal(1_000_000);
+
     // Decrypt the settlement blob using offeror's private key
     const decrypted = offerorCipher.decrypt(
       offerSettledEvent.ciphertexts,
@@ -461,7 +482,7 @@ describe("Crank Offer", () => {
     const offerAccountAfter = await program.account.offerAccount.fetch(
       offerAddress
     );
-    expect(offerAccountAfter.status).to.equal(1); // SETTLED
+    expect(offerAccountAfter.status).to.eql({ settled: {} });
     console.log("Offer status verified: SETTLED (1)");
   });
 });
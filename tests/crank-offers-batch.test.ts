@@ -0,0 +1,315 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitEvent,
+  awaitEvents,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getDealAddress,
+  getOfferAddress,
+  getBalanceAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+describe("Crank Offers Batch", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  async function topUp(
+    controller: Keypair,
+    encryptionPubkey: Uint8Array,
+    mint: PublicKey,
+    amount: number
+  ): Promise<PublicKey> {
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPubkey),
+        randomU128(),
+        new anchor.BN(amount)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        ...arciumAccounts(computationOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    return balance;
+  }
+
+  it("settles several offers in one computation", async () => {
+    // ==========================================
+    // SETUP: Mints, creator balance and deal (sell 1000 BASE at 2.0)
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const baseMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+    const quoteMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    const creatorPrivateKey = x25519.utils.randomSecretKey();
+    const creatorPublicKey = x25519.getPublicKey(creatorPrivateKey);
+    const creatorCipher = new RescueCipher(
+      x25519.getSharedSecret(creatorPrivateKey, mxePublicKey)
+    );
+    const creatorBalanceAddress = await topUp(
+      owner,
+      creatorPublicKey,
+      baseMint,
+      10000
+    );
+
+    const dealNonce = randomBytes(16);
+    const dealCiphertext = creatorCipher.encrypt(
+      [BigInt(1000), BigInt(2) << BigInt(64)],
+      dealNonce
+    );
+    const dealCreateKey = Keypair.generate();
+    const dealAddress = getDealAddress(program, dealCreateKey.publicKey);
+    const createDealOffset = new anchor.BN(randomBytes(8), "hex");
+
+    await program.methods
+      .createDeal(
+        createDealOffset,
+        owner.publicKey,
+        Array.from(creatorPublicKey),
+        new anchor.BN(deserializeLE(dealNonce).toString()),
+        randomU128(),
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+        true,
+        Array.from(dealCiphertext[0]),
+        Array.from(dealCiphertext[1]),
+        0, // counterparty_kind: unrestricted
+        Array.from(new Uint8Array(32)), // counterparty
+        new anchor.BN(0) // crank_bounty: none
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        createKey: dealCreateKey.publicKey,
+        deal: dealAddress,
+        creatorBalance: creatorBalanceAddress,
+        baseMint,
+        quoteMint,
+        ...arciumAccounts(createDealOffset, "create_deal"),
+      })
+      .signers([dealCreateKey])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      createDealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    // ==========================================
+    // STEP 1: Two offerors each buy 500 at 2.5
+    // ==========================================
+    const offerors = [Keypair.generate(), Keypair.generate()].map(
+      (controller) => {
+        const privateKey = x25519.utils.randomSecretKey();
+        const publicKey = x25519.getPublicKey(privateKey);
+        const cipher = new RescueCipher(
+          x25519.getSharedSecret(privateKey, mxePublicKey)
+        );
+        return { controller, publicKey, cipher };
+      }
+    );
+
+    const offerAddresses: PublicKey[] = [];
+    const offerorBalanceAddresses: PublicKey[] = [];
+    for (const offeror of offerors) {
+      const balance = await topUp(
+        offeror.controller,
+        offeror.publicKey,
+        quoteMint,
+        10000
+      );
+
+      const offerNonce = randomBytes(16);
+      const offerCiphertext = offeror.cipher.encrypt(
+        [BigInt(5) << BigInt(63), BigInt(500)],
+        offerNonce
+      );
+      const offerCreateKey = Keypair.generate();
+      const offerAddress = getOfferAddress(
+        program,
+        dealAddress,
+        offerCreateKey.publicKey
+      );
+      const submitOfferOffset = new anchor.BN(randomBytes(8), "hex");
+
+      await program.methods
+        .submitOffer(
+          submitOfferOffset,
+          offeror.controller.publicKey,
+          Array.from(offeror.publicKey),
+          new anchor.BN(deserializeLE(offerNonce).toString()),
+          Array.from(offerCiphertext[0]),
+          Array.from(offerCiphertext[1]),
+          [], // counterparty_proof
+          new anchor.BN(0) // crank_bounty: none
+        )
+        .accountsPartial({
+          authority: offeror.controller.publicKey,
+          createKey: offerCreateKey.publicKey,
+          deal: dealAddress,
+          offer: offerAddress,
+          offerorBalance: balance,
+          ...arciumAccounts(submitOfferOffset, "submit_offer"),
+        })
+        .signers([offerCreateKey, offeror.controller])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(
+        provider,
+        submitOfferOffset,
+        program.programId,
+        "confirmed"
+      );
+
+      offerAddresses.push(offerAddress);
+      offerorBalanceAddresses.push(balance);
+    }
+
+    // ==========================================
+    // STEP 2: Creator settles the fully filled deal
+    // ==========================================
+    const crankDealOffset = new anchor.BN(randomBytes(8), "hex");
+    const dealSettledEventPromise = awaitEvent(program, "dealSettled");
+
+    await program.methods
+      .crankDeal(crankDealOffset, randomU128(), randomU128())
+      .accountsPartial({
+        payer: owner.publicKey,
+        deal: dealAddress,
+        creatorBalance: creatorBalanceAddress,
+        ...arciumAccounts(crankDealOffset, "crank_deal"),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      crankDealOffset,
+      program.programId,
+      "confirmed"
+    );
+    expect((await dealSettledEventPromise).status).to.eql({ executed: {} });
+
+    // ==========================================
+    // STEP 3: Settle both offers in a single batch
+    // ==========================================
+    const batchOffset = new anchor.BN(randomBytes(8), "hex");
+    const offerSettledEventsPromise = awaitEvents(
+      program,
+      "offerSettled",
+      offerAddresses.length
+    );
+
+    await program.methods
+      .crankOffersBatch(batchOffset)
+      .accountsPartial({
+        payer: owner.publicKey,
+        deal: dealAddress,
+        ...arciumAccounts(batchOffset, "crank_offers_batch"),
+      })
+      .remainingAccounts(
+        offerAddresses.flatMap((offer, i) => [
+          { pubkey: offer, isSigner: false, isWritable: true },
+          {
+            pubkey: offerorBalanceAddresses[i],
+            isSigner: false,
+            isWritable: true,
+          },
+        ])
+      )
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      batchOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    // One OfferSettled per offer, each decryptable only by its offeror
+    const offerSettledEvents = await offerSettledEventsPromise;
+    for (const [i, offerAddress] of offerAddresses.entries()) {
+      const event = offerSettledEvents.find(
+        (e) => e.offer.toBase58() === offerAddress.toBase58()
+      );
+      expect(event).to.not.be.undefined;
+
+      // OfferSettledBlob: [outcome, executed_amt, quote_paid, quote_refund]
+      const settled = offerors[i].cipher.decrypt(
+        event!.ciphertexts,
+        Uint8Array.from(event!.nonce)
+      );
+      expect(settled[0]).to.equal(BigInt(0)); // EXECUTED
+      expect(settled[1]).to.equal(BigInt(500));
+      expect(settled[2]).to.equal(BigInt(1000)); // 500 * 2.0
+      expect(settled[3]).to.equal(BigInt(250)); // committed at 2.5
+
+      const offerAccount = await program.account.offerAccount.fetch(
+        offerAddress
+      );
+      expect(offerAccount.status).to.eql({ settled: {} });
+    }
+  });
+});
@@ -141,9 +141,13 @@ describe("Create Deal", () => {
         expiresAt,
         allowPartial,
         Array.from(ciphertext[0]),
-        Array.from(ciphertext[1])
+        Array.from(ciphertext[1]),
+        0, // counterparty_kind: unrestricted
+        Array.from(new Uint8Array(32)), // counterparty
+        new anchor.BN(0) // crank_bounty: none
       )
       .accountsPartial({
+        authority: owner.publicKey,
         createKey: createKey.publicKey,
         deal: dealAddress,
         creatorBalance: creatorBalanceAddress,
@@ -216,7 +220,7 @@ describe("Create Deal", () => {
     expect(dealAccount.baseMint.toBase58()).to.equal(baseMint.toBase58());
     expect(dealAccount.quoteMint.toBase58()).to.equal(quoteMint.toBase58());
     expect(dealAccount.expiresAt.toNumber()).to.equal(expiresAt.toNumber());
-    expect(dealAccount.status).to.equal(0); // OPEN
+    expect(dealAccount.status).to.eql({ open: {} });
     expect(dealAccount.allowPartial).to.equal(allowPartial);
     expect(dealAccount.createdAt.toNumber()).to.be.greaterThan(0);
     expect(dealAccount.numOffers).to.equal(0);
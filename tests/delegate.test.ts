@@ -0,0 +1,343 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitEvent,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getDealAddress,
+  getOfferAddress,
+  getBalanceAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+// DelegatePermission bit for submitting offers
+const SUBMIT_OFFER = 2;
+
+describe("Delegated Session Keys", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  function getDelegateAddress(
+    controller: PublicKey,
+    sessionKey: PublicKey
+  ): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("delegate"), controller.toBuffer(), sessionKey.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  async function finalize(computationOffset: anchor.BN) {
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+  }
+
+  async function topUp(
+    controller: Keypair,
+    encryptionPubkey: Uint8Array,
+    mint: PublicKey,
+    amount: number
+  ): Promise<PublicKey> {
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPubkey),
+        randomU128(),
+        new anchor.BN(amount)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        ...arciumAccounts(computationOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(computationOffset);
+    return balance;
+  }
+
+  it("lets a scoped session key submit offers for a controller", async () => {
+    // ==========================================
+    // SETUP: Deal by owner, offeror balance, offeror session key
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const baseMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+    const quoteMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    const creatorPrivateKey = x25519.utils.randomSecretKey();
+    const creatorPublicKey = x25519.getPublicKey(creatorPrivateKey);
+    const creatorCipher = new RescueCipher(
+      x25519.getSharedSecret(creatorPrivateKey, mxePublicKey)
+    );
+    const creatorBalance = await topUp(
+      owner,
+      creatorPublicKey,
+      baseMint,
+      10000
+    );
+
+    const dealNonce = randomBytes(16);
+    const dealCiphertext = creatorCipher.encrypt(
+      [BigInt(1000), BigInt(2) << BigInt(64)],
+      dealNonce
+    );
+    const dealCreateKey = Keypair.generate();
+    const deal = getDealAddress(program, dealCreateKey.publicKey);
+    const dealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createDeal(
+        dealOffset,
+        owner.publicKey,
+        Array.from(creatorPublicKey),
+        new anchor.BN(deserializeLE(dealNonce).toString()),
+        randomU128(),
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+        true,
+        Array.from(dealCiphertext[0]),
+        Array.from(dealCiphertext[1]),
+        0, // counterparty_kind: unrestricted
+        Array.from(new Uint8Array(32)), // counterparty
+        new anchor.BN(0) // crank_bounty: none
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        createKey: dealCreateKey.publicKey,
+        deal,
+        creatorBalance,
+        baseMint,
+        quoteMint,
+        ...arciumAccounts(dealOffset, "create_deal"),
+      })
+      .signers([dealCreateKey])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(dealOffset);
+
+    const offeror = Keypair.generate();
+    const offerorPrivateKey = x25519.utils.randomSecretKey();
+    const offerorPublicKey = x25519.getPublicKey(offerorPrivateKey);
+    const offerorCipher = new RescueCipher(
+      x25519.getSharedSecret(offerorPrivateKey, mxePublicKey)
+    );
+    const offerorBalance = await topUp(
+      offeror,
+      offerorPublicKey,
+      quoteMint,
+      10000
+    );
+
+    // Session key may only submit offers on this pair, up to 1000 quote per offer
+    const sessionKey = Keypair.generate();
+    const delegate = getDelegateAddress(
+      offeror.publicKey,
+      sessionKey.publicKey
+    );
+    await program.methods
+      .createDelegate(
+        SUBMIT_OFFER,
+        [{ baseMint, quoteMint }],
+        new anchor.BN(1000),
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+      )
+      .accountsPartial({
+        controller: offeror.publicKey,
+        sessionKey: sessionKey.publicKey,
+        delegate,
+      })
+      .signers([offeror])
+      .rpc({ commitment: "confirmed" });
+
+    async function submitOffer(amount: bigint, signer: Keypair) {
+      const offerNonce = randomBytes(16);
+      const offerCiphertext = offerorCipher.encrypt(
+        [BigInt(2) << BigInt(64), amount],
+        offerNonce
+      );
+      const offerCreateKey = Keypair.generate();
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .submitOffer(
+          computationOffset,
+          offeror.publicKey,
+          Array.from(offerorPublicKey),
+          new anchor.BN(deserializeLE(offerNonce).toString()),
+          Array.from(offerCiphertext[0]),
+          Array.from(offerCiphertext[1]),
+          [], // counterparty_proof
+          new anchor.BN(0) // crank_bounty: none
+        )
+        .accountsPartial({
+          authority: signer.publicKey,
+          delegate,
+          createKey: offerCreateKey.publicKey,
+          deal,
+          offer: getOfferAddress(program, deal, offerCreateKey.publicKey),
+          offerorBalance,
+          ...arciumAccounts(computationOffset, "submit_offer"),
+        })
+        .signers([offerCreateKey, signer])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await finalize(computationOffset);
+    }
+
+    // ==========================================
+    // STEP 1: Offer within the limit (400 at 2.0 = 800 quote)
+    // ==========================================
+    const createdPromise = awaitEvent(program, "offerCreated");
+    await submitOffer(BigInt(400), sessionKey);
+    const created = await createdPromise;
+    expect(created.deal.toBase58()).to.equal(deal.toBase58());
+
+    // ==========================================
+    // STEP 2: Offer over the limit (600 at 2.0 = 1200 quote) is rejected
+    // ==========================================
+    const rejectedPromise = awaitEvent(program, "offerRejected");
+    await submitOffer(BigInt(600), sessionKey);
+    const rejected = await rejectedPromise;
+    expect(rejected.error).to.equal(5); // NOTIONAL_EXCEEDED
+
+    // ==========================================
+    // STEP 3: Unlisted permission is denied
+    // ==========================================
+    const offerorBaseBalance = await topUp(
+      offeror,
+      offerorPublicKey,
+      baseMint,
+      1000
+    );
+    const ownDealNonce = randomBytes(16);
+    const ownDealCiphertext = offerorCipher.encrypt(
+      [BigInt(100), BigInt(2) << BigInt(64)],
+      ownDealNonce
+    );
+    const ownDealKey = Keypair.generate();
+    const ownDealOffset = new anchor.BN(randomBytes(8), "hex");
+    try {
+      await program.methods
+        .createDeal(
+          ownDealOffset,
+          offeror.publicKey,
+          Array.from(offerorPublicKey),
+          new anchor.BN(deserializeLE(ownDealNonce).toString()),
+          randomU128(),
+          new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+          true,
+          Array.from(ownDealCiphertext[0]),
+          Array.from(ownDealCiphertext[1]),
+          0, // counterparty_kind: unrestricted
+          Array.from(new Uint8Array(32)), // counterparty
+          new anchor.BN(0) // crank_bounty: none
+        )
+        .accountsPartial({
+          authority: sessionKey.publicKey,
+          delegate,
+          createKey: ownDealKey.publicKey,
+          deal: getDealAddress(program, ownDealKey.publicKey),
+          creatorBalance: offerorBaseBalance,
+          baseMint,
+          quoteMint,
+          ...arciumAccounts(ownDealOffset, "create_deal"),
+        })
+        .signers([ownDealKey, sessionKey])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("delegate without CREATE_DEAL should not create deals");
+    } catch (error: any) {
+      expect(error.toString()).to.include("DelegatePermissionDenied");
+    }
+
+    // ==========================================
+    // STEP 4: Revoked session keys can no longer act
+    // ==========================================
+    await program.methods
+      .revokeDelegate()
+      .accountsPartial({ controller: offeror.publicKey, delegate })
+      .signers([offeror])
+      .rpc({ commitment: "confirmed" });
+
+    try {
+      await program.methods
+        .submitOffer(
+          new anchor.BN(randomBytes(8), "hex"),
+          offeror.publicKey,
+          Array.from(offerorPublicKey),
+          randomU128(),
+          Array.from(new Uint8Array(32)),
+          Array.from(new Uint8Array(32)),
+          [], // counterparty_proof
+          new anchor.BN(0) // crank_bounty: none
+        )
+        .accountsPartial({
+          authority: sessionKey.publicKey,
+          delegate: null,
+          createKey: ownDealKey.publicKey,
+          deal,
+          offer: getOfferAddress(program, deal, ownDealKey.publicKey),
+          offerorBalance,
+          ...arciumAccounts(ownDealOffset, "submit_offer"),
+        })
+        .signers([ownDealKey, sessionKey])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("revoked session key should not submit offers");
+    } catch (error: any) {
+      expect(error.toString()).to.include("NotAuthorized");
+    }
+  });
+});
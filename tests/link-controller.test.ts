@@ -0,0 +1,356 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getDealAddress,
+  getOfferAddress,
+  getRfqAddress,
+  getQuoteAddress,
+  getBalanceAddress,
+  getLinkAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+describe("Linked Controllers", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  async function finalize(computationOffset: anchor.BN) {
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+  }
+
+  async function topUp(
+    controller: Keypair,
+    encryptionPubkey: Uint8Array,
+    mint: PublicKey,
+    amount: number
+  ): Promise<PublicKey> {
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPubkey),
+        randomU128(),
+        new anchor.BN(amount)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        ...arciumAccounts(computationOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(computationOffset);
+    return balance;
+  }
+
+  async function link(controller: Keypair, desk: Keypair) {
+    await program.methods
+      .linkController()
+      .accountsPartial({
+        controller: controller.publicKey,
+        groupAuthority: desk.publicKey,
+        link: getLinkAddress(program, controller.publicKey),
+      })
+      .signers([controller, desk])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function unlink(controller: Keypair, desk: Keypair) {
+    await program.methods
+      .unlinkController()
+      .accountsPartial({
+        groupAuthority: desk.publicKey,
+        link: getLinkAddress(program, controller.publicKey),
+      })
+      .signers([desk])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("links and unlinks desk sub-accounts", async () => {
+    const desk = Keypair.generate();
+    const subAccountA = Keypair.generate();
+    const subAccountB = Keypair.generate();
+
+    // Both the controller and the desk authority must sign the link
+    for (const controller of [subAccountA, subAccountB]) {
+      await link(controller, desk);
+    }
+
+    const linkA = await program.account.controllerLink.fetch(
+      getLinkAddress(program, subAccountA.publicKey)
+    );
+    const linkB = await program.account.controllerLink.fetch(
+      getLinkAddress(program, subAccountB.publicKey)
+    );
+    expect(linkA.controller.toBase58()).to.equal(
+      subAccountA.publicKey.toBase58()
+    );
+    expect(linkA.group.toBase58()).to.equal(desk.publicKey.toBase58());
+    expect(linkB.group.toBase58()).to.equal(linkA.group.toBase58());
+
+    // Desk authority removes sub-account A from the group
+    await unlink(subAccountA, desk);
+
+    const closed = await program.provider.connection.getAccountInfo(
+      getLinkAddress(program, subAccountA.publicKey)
+    );
+    expect(closed).to.be.null;
+  });
+
+  it("blocks trades within a controller or desk", async () => {
+    // ==========================================
+    // SETUP: Two desk sub-accounts holding BASE and QUOTE
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const baseMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+    const quoteMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    const desk = Keypair.generate();
+    const creator = Keypair.generate();
+    const sibling = Keypair.generate();
+    const privateKey = x25519.utils.randomSecretKey();
+    const publicKey = x25519.getPublicKey(privateKey);
+    const cipher = new RescueCipher(
+      x25519.getSharedSecret(privateKey, mxePublicKey)
+    );
+    for (const controller of [creator, sibling]) {
+      for (const mint of [baseMint, quoteMint]) {
+        await topUp(controller, publicKey, mint, 10000);
+      }
+    }
+
+    // ==========================================
+    // STEP 1: Creator opens a deal and an RFQ (size 1000 at 2.0)
+    // ==========================================
+    const terms = [BigInt(1000), BigInt(2) << BigInt(64)];
+    const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+
+    const dealNonce = randomBytes(16);
+    const dealCiphertext = cipher.encrypt(terms, dealNonce);
+    const dealCreateKey = Keypair.generate();
+    const dealAddress = getDealAddress(program, dealCreateKey.publicKey);
+    const createDealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createDeal(
+        createDealOffset,
+        creator.publicKey,
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(dealNonce).toString()),
+        randomU128(),
+        expiresAt,
+        true,
+        Array.from(dealCiphertext[0]),
+        Array.from(dealCiphertext[1]),
+        0, // counterparty_kind: unrestricted
+        Array.from(new Uint8Array(32)), // counterparty
+        new anchor.BN(0) // crank_bounty: none
+      )
+      .accountsPartial({
+        authority: creator.publicKey,
+        createKey: dealCreateKey.publicKey,
+        deal: dealAddress,
+        creatorBalance: getBalanceAddress(program, creator.publicKey, baseMint),
+        baseMint,
+        quoteMint,
+        ...arciumAccounts(createDealOffset, "create_deal"),
+      })
+      .signers([dealCreateKey, creator])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(createDealOffset);
+
+    const rfqNonce = randomBytes(16);
+    const rfqCiphertext = cipher.encrypt(terms, rfqNonce);
+    const rfqCreateKey = Keypair.generate();
+    const rfqAddress = getRfqAddress(program, rfqCreateKey.publicKey);
+    const createRfqOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createRfq(
+        createRfqOffset,
+        creator.publicKey,
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(rfqNonce).toString()),
+        randomU128(),
+        expiresAt,
+        Array.from(rfqCiphertext[0]),
+        Array.from(rfqCiphertext[1]),
+        0, // counterparty_kind: any maker
+        Array.from(new Uint8Array(32)) // counterparty
+      )
+      .accountsPartial({
+        createKey: rfqCreateKey.publicKey,
+        rfq: rfqAddress,
+        takerBalance: getBalanceAddress(program, creator.publicKey, quoteMint),
+        baseMint,
+        quoteMint,
+        ...arciumAccounts(createRfqOffset, "create_rfq"),
+      })
+      .signers([rfqCreateKey])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(createRfqOffset);
+
+    async function submitOffer(offeror: Keypair) {
+      const offerNonce = randomBytes(16);
+      const offerCiphertext = cipher.encrypt(
+        [BigInt(2) << BigInt(64), BigInt(100)],
+        offerNonce
+      );
+      const offerCreateKey = Keypair.generate();
+      const submitOfferOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .submitOffer(
+          submitOfferOffset,
+          offeror.publicKey,
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(offerNonce).toString()),
+          Array.from(offerCiphertext[0]),
+          Array.from(offerCiphertext[1]),
+          [], // counterparty_proof
+          new anchor.BN(0) // crank_bounty: none
+        )
+        .accountsPartial({
+          authority: offeror.publicKey,
+          createKey: offerCreateKey.publicKey,
+          deal: dealAddress,
+          offer: getOfferAddress(
+            program,
+            dealAddress,
+            offerCreateKey.publicKey
+          ),
+          offerorBalance: getBalanceAddress(
+            program,
+            offeror.publicKey,
+            quoteMint
+          ),
+          ...arciumAccounts(submitOfferOffset, "submit_offer"),
+        })
+        .signers([offerCreateKey, offeror])
+        .rpc({ commitment: "confirmed" });
+      return submitOfferOffset;
+    }
+
+    async function submitQuote(maker: Keypair) {
+      const quoteNonce = randomBytes(16);
+      const quoteCiphertext = cipher.encrypt(
+        [BigInt(2) << BigInt(64)],
+        quoteNonce
+      );
+      const quoteCreateKey = Keypair.generate();
+      const submitQuoteOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .submitQuote(
+          submitQuoteOffset,
+          maker.publicKey,
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(quoteNonce).toString()),
+          Array.from(quoteCiphertext[0]),
+          [] // counterparty_proof
+        )
+        .accountsPartial({
+          createKey: quoteCreateKey.publicKey,
+          rfq: rfqAddress,
+          quote: getQuoteAddress(program, rfqAddress, quoteCreateKey.publicKey),
+          makerBalance: getBalanceAddress(program, maker.publicKey, baseMint),
+          ...arciumAccounts(submitQuoteOffset, "submit_quote"),
+        })
+        .signers([quoteCreateKey])
+        .rpc({ commitment: "confirmed" });
+      return submitQuoteOffset;
+    }
+
+    async function expectBlocked(submit: Promise<unknown>, error: string) {
+      try {
+        await submit;
+        expect.fail(`expected ${error}`);
+      } catch (e: any) {
+        expect(e.toString()).to.include(error);
+      }
+    }
+
+    // ==========================================
+    // STEP 2: The creator cannot trade against itself
+    // ==========================================
+    await expectBlocked(submitOffer(creator), "SelfTrade");
+    await expectBlocked(submitQuote(creator), "SelfTrade");
+
+    // ==========================================
+    // STEP 3: Nor can a sub-account of the same desk
+    // ==========================================
+    await link(creator, desk);
+    await link(sibling, desk);
+    await expectBlocked(submitOffer(sibling), "LinkedControllers");
+    await expectBlocked(submitQuote(sibling), "LinkedControllers");
+
+    // ==========================================
+    // STEP 4: Unlinking the sub-account lifts the block
+    // ==========================================
+    await unlink(sibling, desk);
+    await finalize(await submitOffer(sibling));
+    await finalize(await submitQuote(sibling));
+
+    const deal = await program.account.dealAccount.fetch(dealAddress);
+    expect(deal.numOffers).to.equal(1);
+    const rfq = await program.account.rfqAccount.fetch(rfqAddress);
+    expect(rfq.numQuotes).to.equal(1);
+  });
+});
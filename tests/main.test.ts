@@ -1,5 +1,6 @@
 import {
   getTestHarness,
+  hasDemoInstructions,
   initCompDefIfNeeded,
   initAddTogetherCompDef,
   initInitCounterCompDef,
@@ -11,6 +12,22 @@ import {
   initCrankOfferCompDef,
   initTopUpCompDef,
   initAnnounceBalanceCompDef,
+  initCreateRfqCompDef,
+  initSubmitQuoteCompDef,
+  initCrankRfqCompDef,
+  initCrankQuoteCompDef,
+  initCrankOffersBatchCompDef,
+  initSettleDealCompDef,
+  initTransferCompDef,
+  initAttestBalanceCompDef,
+  initAnnounceDealCompDef,
+  initAnnounceOfferCompDef,
+  initCreateBasketDealCompDef,
+  initCrankBasketDealCompDef,
+  initCreateSwapDealCompDef,
+  initSubmitSwapOfferCompDef,
+  initCrankSwapDealCompDef,
+  initCrankSwapOfferCompDef,
 } from "./harness";
 import { setupTestMints } from "./setup-mints";
 
@@ -22,28 +39,32 @@ describe("OTC Setup", () => {
     console.log("Test mints created:", mints);
   });
 
-  it("initializes add_together comp def", async () => {
+  it("initializes add_together comp def", async function () {
+    if (!hasDemoInstructions(program)) this.skip();
     await initCompDefIfNeeded(
       () => initAddTogetherCompDef(program, provider, owner, false, false),
       "Add Together"
     );
   });
 
-  it("initializes init_counter comp def", async () => {
+  it("initializes init_counter comp def", async function () {
+    if (!hasDemoInstructions(program)) this.skip();
     await initCompDefIfNeeded(
       () => initInitCounterCompDef(program, provider, owner, false, false),
       "Init Counter"
     );
   });
 
-  it("initializes increment_counter comp def", async () => {
+  it("initializes increment_counter comp def", async function () {
+    if (!hasDemoInstructions(program)) this.skip();
     await initCompDefIfNeeded(
       () => initIncrementCounterCompDef(program, provider, owner, false, false),
       "Increment Counter"
     );
   });
 
-  it("initializes get_counter comp def", async () => {
+  it("initializes get_counter comp def", async function () {
+    if (!hasDemoInstructions(program)) this.skip();
     await initCompDefIfNeeded(
       () => initGetCounterCompDef(program, provider, owner, false, false),
       "Get Counter"
@@ -91,4 +112,116 @@ describe("OTC Setup", () => {
       "Announce Balance"
     );
   });
+
+  it("initializes create_rfq comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initCreateRfqCompDef(program, provider, owner, false, false),
+      "Create RFQ"
+    );
+  });
+
+  it("initializes submit_quote comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initSubmitQuoteCompDef(program, provider, owner, false, false),
+      "Submit Quote"
+    );
+  });
+
+  it("initializes crank_rfq comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initCrankRfqCompDef(program, provider, owner, false, false),
+      "Crank RFQ"
+    );
+  });
+
+  it("initializes crank_quote comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initCrankQuoteCompDef(program, provider, owner, false, false),
+      "Crank Quote"
+    );
+  });
+
+  it("initializes crank_offers_batch comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initCrankOffersBatchCompDef(program, provider, owner, false, false),
+      "Crank Offers Batch"
+    );
+  });
+
+  it("initializes settle_deal comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initSettleDealCompDef(program, provider, owner, false, false),
+      "Settle Deal"
+    );
+  });
+
+  it("initializes transfer comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initTransferCompDef(program, provider, owner, false, false),
+      "Transfer"
+    );
+  });
+
+  it("initializes attest_balance comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initAttestBalanceCompDef(program, provider, owner, false, false),
+      "Attest Balance"
+    );
+  });
+
+  it("initializes announce_deal comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initAnnounceDealCompDef(program, provider, owner, false, false),
+      "Announce deal"
+    );
+  });
+
+  it("initializes announce_offer comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initAnnounceOfferCompDef(program, provider, owner, false, false),
+      "Announce offer"
+    );
+  });
+
+  it("initializes create_basket_deal comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initCreateBasketDealCompDef(program, provider, owner, false, false),
+      "CreateBasketDeal"
+    );
+  });
+
+  it("initializes crank_basket_deal comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initCrankBasketDealCompDef(program, provider, owner, false, false),
+      "CrankBasketDeal"
+    );
+  });
+
+  it("initializes create_swap_deal comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initCreateSwapDealCompDef(program, provider, owner, false, false),
+      "Create Swap Deal"
+    );
+  });
+
+  it("initializes submit_swap_offer comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initSubmitSwapOfferCompDef(program, provider, owner, false, false),
+      "Submit Swap Offer"
+    );
+  });
+
+  it("initializes crank_swap_deal comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initCrankSwapDealCompDef(program, provider, owner, false, false),
+      "Crank Swap Deal"
+    );
+  });
+
+  it("initializes crank_swap_offer comp def", async () => {
+    await initCompDefIfNeeded(
+      () => initCrankSwapOfferCompDef(program, provider, owner, false, false),
+      "Crank Swap Offer"
+    );
+  });
 });
@@ -0,0 +1,302 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import * as fs from "fs";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getBalanceAddress,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+describe("Account Migration", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  it("creates versioned accounts and refuses to migrate them again", async () => {
+    // ==========================================
+    // SETUP: A fresh balance created by top_up
+    // ==========================================
+    await getMXEPublicKeyWithRetry(provider, program.programId);
+    const mint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+    const controller = Keypair.generate();
+    const encryptionPublicKey = x25519.getPublicKey(
+      x25519.utils.randomSecretKey()
+    );
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPublicKey),
+        new anchor.BN(deserializeLE(randomBytes(16)).toString()),
+        new anchor.BN(1000)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(
+          arciumEnv.arciumClusterOffset
+        ),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("top_up")).readUInt32LE()
+        ),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const balanceAccount = await program.account.balanceAccount.fetch(balance);
+    expect(balanceAccount.version).to.equal(1);
+
+    // ==========================================
+    // STEP 1: Current accounts are already migrated
+    // ==========================================
+    try {
+      await program.methods
+        .migrateBalance()
+        .accountsPartial({ payer: owner.publicKey, balance })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("migrating a current balance should fail");
+    } catch (error: any) {
+      expect(error.toString()).to.include("AccountAlreadyMigrated");
+    }
+
+    // ==========================================
+    // STEP 2: Other program accounts are not mistaken for legacy layouts
+    // ==========================================
+    const [relayNonce] = PublicKey.findProgramAddressSync(
+      [Buffer.from("relay_nonce"), controller.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .initRelayNonce(controller.publicKey)
+      .accountsPartial({ payer: owner.publicKey, relayNonce })
+      .rpc({ commitment: "confirmed" });
+    try {
+      await program.methods
+        .migrateDeal()
+        .accountsPartial({
+          payer: owner.publicKey,
+          deal: relayNonce,
+          baseMint: mint,
+          quoteMint: mint,
+        })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("migrating a non-deal account should fail");
+    } catch (error: any) {
+      expect(error.toString()).to.include("InvalidAccountLayout");
+    }
+  });
+
+  // Legacy (version 0) accounts are loaded at genesis from tests/fixtures (see
+  // Anchor.toml). Their layout is the nonce and ciphertexts followed by the plaintext
+  // fields, without the version byte or any field added since.
+  const fixture = (name: string) => {
+    const json = JSON.parse(
+      fs.readFileSync(`tests/fixtures/${name}.json`, "utf8")
+    );
+    return {
+      address: new PublicKey(json.pubkey),
+      data: Buffer.from(json.account.data[0], "base64"),
+    };
+  };
+  const keyAt = (data: Buffer, offset: number) =>
+    new PublicKey(data.subarray(offset, offset + 32));
+
+  const expectEncryptedFieldsKept = (
+    before: Buffer,
+    after: Buffer,
+    ciphertextLength: number
+  ) => {
+    // Discriminator, nonce and the ciphertexts at offset 24 stay where they were
+    const end = 24 + ciphertextLength;
+    expect(after.subarray(0, end).equals(before.subarray(0, end))).to.be.true;
+  };
+
+  it("migrates accounts written before versioning", async () => {
+    const legacyDeal = fixture("legacy-deal");
+    const legacyOffer = fixture("legacy-offer");
+    const legacyBalance = fixture("legacy-balance");
+    const baseMint = fixture("legacy-base-mint").address;
+    const quoteMint = fixture("legacy-quote-mint").address;
+
+    // ==========================================
+    // STEP 1: The deal's decimals come from its own mints
+    // ==========================================
+    try {
+      await program.methods
+        .migrateDeal()
+        .accountsPartial({
+          payer: owner.publicKey,
+          deal: legacyDeal.address,
+          baseMint: quoteMint,
+          quoteMint: baseMint,
+        })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("migrating with the wrong mints should fail");
+    } catch (error: any) {
+      expect(error.toString()).to.include("MintMismatch");
+    }
+
+    // ==========================================
+    // STEP 2: Migrate the deal
+    // ==========================================
+    await program.methods
+      .migrateDeal()
+      .accountsPartial({
+        payer: owner.publicKey,
+        deal: legacyDeal.address,
+        baseMint,
+        quoteMint,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const dealInfo = await provider.connection.getAccountInfo(
+      legacyDeal.address,
+      "confirmed"
+    );
+    expect(dealInfo!.data.length).to.equal(program.account.dealAccount.size);
+    expectEncryptedFieldsKept(legacyDeal.data, dealInfo!.data, 96);
+
+    const deal = await program.account.dealAccount.fetch(legacyDeal.address);
+    const d = legacyDeal.data;
+    expect(deal.version).to.equal(1);
+    expect(deal.createKey.equals(keyAt(d, 120))).to.be.true;
+    expect(deal.controller.equals(keyAt(d, 152))).to.be.true;
+    expect(Buffer.from(deal.encryptionPubkey).equals(d.subarray(184, 216))).to
+      .be.true;
+    expect(deal.baseMint.equals(baseMint)).to.be.true;
+    expect(deal.quoteMint.equals(quoteMint)).to.be.true;
+    expect(deal.createdAt.toString()).to.equal(
+      d.readBigInt64LE(280).toString()
+    );
+    expect(deal.expiresAt.toString()).to.equal(
+      d.readBigInt64LE(288).toString()
+    );
+    expect(deal.status).to.deep.equal({ open: {} });
+    expect(deal.allowPartial).to.equal(d[297] === 1);
+    expect(deal.numOffers).to.equal(d.readUInt32LE(298));
+    expect(deal.bump).to.equal(d[302]);
+    // New fields: decimals from the mints, defaults for the rest
+    expect(deal.baseDecimals).to.equal(9);
+    expect(deal.quoteDecimals).to.equal(6);
+    expect(deal.tickSize.toString()).to.equal("1");
+    expect(deal.lotSize.toString()).to.equal("1");
+    expect(deal.priceFeed.equals(PublicKey.default)).to.be.true;
+    expect(deal.counterpartyKind).to.equal(0);
+    expect(deal.crankBounty.toString()).to.equal("0");
+    expect(deal.basketLegs).to.equal(0);
+    expect(deal.swap).to.be.false;
+
+    // ==========================================
+    // STEP 3: Migrate the offer
+    // ==========================================
+    await program.methods
+      .migrateOffer()
+      .accountsPartial({ payer: owner.publicKey, offer: legacyOffer.address })
+      .rpc({ commitment: "confirmed" });
+
+    const offerInfo = await provider.connection.getAccountInfo(
+      legacyOffer.address,
+      "confirmed"
+    );
+    expect(offerInfo!.data.length).to.equal(program.account.offerAccount.size);
+    expectEncryptedFieldsKept(legacyOffer.data, offerInfo!.data, 96);
+
+    const offer = await program.account.offerAccount.fetch(legacyOffer.address);
+    const o = legacyOffer.data;
+    expect(offer.version).to.equal(1);
+    expect(offer.createKey.equals(keyAt(o, 120))).to.be.true;
+    expect(offer.controller.equals(keyAt(o, 152))).to.be.true;
+    expect(Buffer.from(offer.encryptionPubkey).equals(o.subarray(184, 216))).to
+      .be.true;
+    expect(offer.deal.equals(legacyDeal.address)).to.be.true;
+    expect(offer.submittedAt.toString()).to.equal(
+      o.readBigInt64LE(248).toString()
+    );
+    expect(offer.offerIndex).to.equal(o.readUInt32LE(256));
+    expect(offer.status).to.deep.equal({ settled: {} });
+    expect(offer.bump).to.equal(o[261]);
+    expect(offer.crankBounty.toString()).to.equal("0");
+    // Version 0 locked the quote rounded down and is released the same way
+    expect(offer.floorLocked).to.equal(true);
+
+    // ==========================================
+    // STEP 4: Migrate the balance
+    // ==========================================
+    await program.methods
+      .migrateBalance()
+      .accountsPartial({
+        payer: owner.publicKey,
+        balance: legacyBalance.address,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const balanceInfo = await provider.connection.getAccountInfo(
+      legacyBalance.address,
+      "confirmed"
+    );
+    expect(balanceInfo!.data.length).to.equal(
+      program.account.balanceAccount.size
+    );
+    expectEncryptedFieldsKept(legacyBalance.data, balanceInfo!.data, 64);
+
+    const balance = await program.account.balanceAccount.fetch(
+      legacyBalance.address
+    );
+    const b = legacyBalance.data;
+    expect(balance.version).to.equal(1);
+    expect(balance.controller.equals(keyAt(b, 88))).to.be.true;
+    expect(Buffer.from(balance.encryptionPubkey).equals(b.subarray(120, 152)))
+      .to.be.true;
+    expect(balance.mint.equals(baseMint)).to.be.true;
+    expect(balance.bump).to.equal(b[184]);
+    expect(Buffer.from(balance.auditorPubkey).equals(Buffer.alloc(32))).to.be
+      .true;
+
+    // ==========================================
+    // STEP 5: Migrated accounts cannot be migrated again
+    // ==========================================
+    try {
+      await program.methods
+        .migrateOffer()
+        .accountsPartial({ payer: owner.publicKey, offer: legacyOffer.address })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("migrating an offer twice should fail");
+    } catch (error: any) {
+      expect(error.toString()).to.include("AccountAlreadyMigrated");
+    }
+  });
+});
@@ -0,0 +1,284 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey, TransactionInstruction } from "@solana/web3.js";
+import { createHash, randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitEvent,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getDealAddress,
+  getBalanceAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+describe("Multisig Controllers", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  function getMultisigAddress(createKey: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("multisig"), createKey.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  function getProposalAddress(multisig: PublicKey, index: number): PublicKey {
+    const indexBytes = Buffer.alloc(8);
+    indexBytes.writeBigUInt64LE(BigInt(index));
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), multisig.toBuffer(), indexBytes],
+      program.programId
+    )[0];
+  }
+
+  /**
+   * Digest a proposal approves: sha256(program id, data, non-signer account keys).
+   */
+  function instructionDigest(ix: TransactionInstruction): number[] {
+    const digest = createHash("sha256");
+    digest.update(ix.programId.toBuffer());
+    digest.update(ix.data);
+    for (const key of ix.keys.filter((meta) => !meta.isSigner)) {
+      digest.update(key.pubkey.toBuffer());
+    }
+    return Array.from(digest.digest());
+  }
+
+  async function finalize(computationOffset: anchor.BN) {
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+  }
+
+  it("runs top-ups and deals for a 2-of-3 multisig through proposals", async () => {
+    // ==========================================
+    // SETUP: 2-of-3 multisig with a shared encryption key
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const baseMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+    const quoteMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    const secondMember = Keypair.generate();
+    const thirdMember = Keypair.generate();
+    const createKey = Keypair.generate();
+    const multisig = getMultisigAddress(createKey.publicKey);
+    await program.methods
+      .createMultisig(2, [
+        owner.publicKey,
+        secondMember.publicKey,
+        thirdMember.publicKey,
+      ])
+      .accountsPartial({ createKey: createKey.publicKey, multisig })
+      .signers([createKey])
+      .rpc({ commitment: "confirmed" });
+
+    const sharedPrivateKey = x25519.utils.randomSecretKey();
+    const sharedPublicKey = x25519.getPublicKey(sharedPrivateKey);
+    const sharedCipher = new RescueCipher(
+      x25519.getSharedSecret(sharedPrivateKey, mxePublicKey)
+    );
+    const balance = getBalanceAddress(program, multisig, baseMint);
+
+    // ==========================================
+    // STEP 1: Propose a top-up of the multisig's balance
+    // ==========================================
+    const topUpProposal = getProposalAddress(multisig, 0);
+    const topUpOffset = new anchor.BN(randomBytes(8), "hex");
+    const topUp = program.methods
+      .topUp(
+        topUpOffset,
+        multisig,
+        Array.from(sharedPublicKey),
+        randomU128(),
+        new anchor.BN(10000)
+      )
+      .accountsPartial({
+        controllerSigner: owner.publicKey,
+        proposal: topUpProposal,
+        mint: baseMint,
+        balance,
+        ...arciumAccounts(topUpOffset, "top_up"),
+      });
+
+    await program.methods
+      .createProposal(instructionDigest(await topUp.instruction()))
+      .accountsPartial({
+        proposer: owner.publicKey,
+        multisig,
+        proposal: topUpProposal,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    // One approval (the proposer's) is below the threshold
+    try {
+      await topUp.rpc({ commitment: "confirmed" });
+      expect.fail("proposal below threshold should not execute");
+    } catch (error: any) {
+      expect(error.toString()).to.include("ProposalNotApproved");
+    }
+
+    // ==========================================
+    // STEP 2: A second member approves; anyone may execute
+    // ==========================================
+    try {
+      await program.methods
+        .approveProposal()
+        .accountsPartial({
+          member: owner.publicKey,
+          multisig,
+          proposal: topUpProposal,
+        })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("members should not approve twice");
+    } catch (error: any) {
+      expect(error.toString()).to.include("ProposalAlreadyApproved");
+    }
+
+    await program.methods
+      .approveProposal()
+      .accountsPartial({
+        member: secondMember.publicKey,
+        multisig,
+        proposal: topUpProposal,
+      })
+      .signers([secondMember])
+      .rpc({ commitment: "confirmed" });
+
+    const balanceUpdatedPromise = awaitEvent(program, "balanceUpdated");
+    await topUp.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(topUpOffset);
+    const balanceUpdated = await balanceUpdatedPromise;
+    expect(balanceUpdated.controller.toBase58()).to.equal(multisig.toBase58());
+    const [amount] = sharedCipher.decrypt(
+      balanceUpdated.ciphertexts,
+      Uint8Array.from(balanceUpdated.nonce)
+    );
+    expect(amount).to.equal(BigInt(10000));
+
+    // Executed proposals cannot be replayed
+    try {
+      await topUp.rpc({ commitment: "confirmed" });
+      expect.fail("executed proposal should not execute again");
+    } catch (error: any) {
+      expect(error.toString()).to.include("ProposalNotApproved");
+    }
+
+    // ==========================================
+    // STEP 3: Propose, approve and execute a deal
+    // ==========================================
+    const dealProposal = getProposalAddress(multisig, 1);
+    const dealNonce = randomBytes(16);
+    const dealCiphertext = sharedCipher.encrypt(
+      [BigInt(1000), BigInt(2) << BigInt(64)],
+      dealNonce
+    );
+    const dealCreateKey = Keypair.generate();
+    const deal = getDealAddress(program, dealCreateKey.publicKey);
+    const dealOffset = new anchor.BN(randomBytes(8), "hex");
+    const createDeal = program.methods
+      .createDeal(
+        dealOffset,
+        multisig,
+        Array.from(sharedPublicKey),
+        new anchor.BN(deserializeLE(dealNonce).toString()),
+        randomU128(),
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+        true,
+        Array.from(dealCiphertext[0]),
+        Array.from(dealCiphertext[1]),
+        0, // counterparty_kind: unrestricted
+        Array.from(new Uint8Array(32)), // counterparty
+        new anchor.BN(0) // crank_bounty: none
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        proposal: dealProposal,
+        createKey: dealCreateKey.publicKey,
+        deal,
+        creatorBalance: balance,
+        baseMint,
+        quoteMint,
+        ...arciumAccounts(dealOffset, "create_deal"),
+      })
+      .signers([dealCreateKey]);
+
+    await program.methods
+      .createProposal(instructionDigest(await createDeal.instruction()))
+      .accountsPartial({
+        proposer: owner.publicKey,
+        multisig,
+        proposal: dealProposal,
+      })
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .approveProposal()
+      .accountsPartial({
+        member: thirdMember.publicKey,
+        multisig,
+        proposal: dealProposal,
+      })
+      .signers([thirdMember])
+      .rpc({ commitment: "confirmed" });
+
+    const createdPromise = awaitEvent(program, "dealCreated");
+    await createDeal.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(dealOffset);
+    const created = await createdPromise;
+    expect(created.deal.toBase58()).to.equal(deal.toBase58());
+
+    const dealAccount = await program.account.dealAccount.fetch(deal);
+    expect(dealAccount.controller.toBase58()).to.equal(multisig.toBase58());
+  });
+});
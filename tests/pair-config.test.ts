@@ -0,0 +1,279 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitEvent,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getDealAddress,
+  getOfferAddress,
+  getBalanceAddress,
+  getPairConfigAddress,
+  getProgramDataAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+describe("Pair Tick And Lot Sizes", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  async function topUp(
+    controller: Keypair,
+    encryptionPubkey: Uint8Array,
+    mint: PublicKey,
+    amount: number
+  ): Promise<PublicKey> {
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPubkey),
+        randomU128(),
+        new anchor.BN(amount)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        ...arciumAccounts(computationOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    return balance;
+  }
+
+  it("rejects deals and offers off the pair's tick and lot", async () => {
+    // ==========================================
+    // SETUP: Mints with tick 0.5 and lot 100
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const baseMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+    const quoteMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    const pairConfig = getPairConfigAddress(program, baseMint, quoteMint);
+    await program.methods
+      .setPairConfig(
+        new anchor.BN((BigInt(1) << BigInt(63)).toString()), // tick 0.5
+        new anchor.BN(100), // lot 100
+        PublicKey.default, // price_feed: none
+        0 // band_bps
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        program: program.programId,
+        programData: getProgramDataAddress(program),
+        baseMint,
+        quoteMint,
+        pairConfig,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const creatorPrivateKey = x25519.utils.randomSecretKey();
+    const creatorPublicKey = x25519.getPublicKey(creatorPrivateKey);
+    const creatorCipher = new RescueCipher(
+      x25519.getSharedSecret(creatorPrivateKey, mxePublicKey)
+    );
+    const creatorBalanceAddress = await topUp(
+      owner,
+      creatorPublicKey,
+      baseMint,
+      10000
+    );
+
+    async function createDeal(amount: bigint, price: bigint) {
+      const dealNonce = randomBytes(16);
+      const dealCiphertext = creatorCipher.encrypt([amount, price], dealNonce);
+      const dealCreateKey = Keypair.generate();
+      const dealAddress = getDealAddress(program, dealCreateKey.publicKey);
+      const createDealOffset = new anchor.BN(randomBytes(8), "hex");
+
+      await program.methods
+        .createDeal(
+          createDealOffset,
+          owner.publicKey,
+          Array.from(creatorPublicKey),
+          new anchor.BN(deserializeLE(dealNonce).toString()),
+          randomU128(),
+          new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+          true,
+          Array.from(dealCiphertext[0]),
+          Array.from(dealCiphertext[1]),
+          0, // counterparty_kind: unrestricted
+          Array.from(new Uint8Array(32)), // counterparty
+          new anchor.BN(0) // crank_bounty: none
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          createKey: dealCreateKey.publicKey,
+          deal: dealAddress,
+          creatorBalance: creatorBalanceAddress,
+          baseMint,
+          quoteMint,
+          pairConfig,
+          ...arciumAccounts(createDealOffset, "create_deal"),
+        })
+        .signers([dealCreateKey])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(
+        provider,
+        createDealOffset,
+        program.programId,
+        "confirmed"
+      );
+      return dealAddress;
+    }
+
+    // ==========================================
+    // STEP 1: Price 2.25 is off the 0.5 tick
+    // ==========================================
+    const dealRejectedPromise = awaitEvent(program, "dealRejected");
+    const rejectedDeal = await createDeal(
+      BigInt(1000),
+      BigInt(9) << BigInt(62) // 2.25
+    );
+    const dealRejected = await dealRejectedPromise;
+    expect(dealRejected.deal.toBase58()).to.equal(rejectedDeal.toBase58());
+    expect(dealRejected.error).to.equal(3); // OFF_TICK_OR_LOT
+    const rejectedAccount = await program.account.dealAccount.fetch(
+      rejectedDeal
+    );
+    expect(rejectedAccount.status).to.eql({ rejected: {} });
+
+    // ==========================================
+    // STEP 2: Deal at 2.5 for 1000 is accepted
+    // ==========================================
+    const dealCreatedPromise = awaitEvent(program, "dealCreated");
+    const dealAddress = await createDeal(
+      BigInt(1000),
+      BigInt(5) << BigInt(63) // 2.5
+    );
+    await dealCreatedPromise;
+    const dealAccount = await program.account.dealAccount.fetch(dealAddress);
+    expect(dealAccount.status).to.eql({ open: {} });
+    expect(dealAccount.lotSize.toNumber()).to.equal(100);
+
+    // ==========================================
+    // STEP 3: Offer for 150 is off the 100 lot
+    // ==========================================
+    const offeror = Keypair.generate();
+    const offerorPrivateKey = x25519.utils.randomSecretKey();
+    const offerorPublicKey = x25519.getPublicKey(offerorPrivateKey);
+    const offerorCipher = new RescueCipher(
+      x25519.getSharedSecret(offerorPrivateKey, mxePublicKey)
+    );
+    const offerorBalance = await topUp(
+      offeror,
+      offerorPublicKey,
+      quoteMint,
+      10000
+    );
+
+    const offerNonce = randomBytes(16);
+    const offerCiphertext = offerorCipher.encrypt(
+      [BigInt(3) << BigInt(64), BigInt(150)],
+      offerNonce
+    );
+    const offerCreateKey = Keypair.generate();
+    const offerAddress = getOfferAddress(
+      program,
+      dealAddress,
+      offerCreateKey.publicKey
+    );
+    const submitOfferOffset = new anchor.BN(randomBytes(8), "hex");
+    const offerRejectedPromise = awaitEvent(program, "offerRejected");
+
+    await program.methods
+      .submitOffer(
+        submitOfferOffset,
+        offeror.publicKey,
+        Array.from(offerorPublicKey),
+        new anchor.BN(deserializeLE(offerNonce).toString()),
+        Array.from(offerCiphertext[0]),
+        Array.from(offerCiphertext[1]),
+        [], // counterparty_proof
+        new anchor.BN(0) // crank_bounty: none
+      )
+      .accountsPartial({
+        authority: offeror.publicKey,
+        createKey: offerCreateKey.publicKey,
+        deal: dealAddress,
+        offer: offerAddress,
+        offerorBalance,
+        ...arciumAccounts(submitOfferOffset, "submit_offer"),
+      })
+      .signers([offerCreateKey, offeror])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      submitOfferOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const offerRejected = await offerRejectedPromise;
+    expect(offerRejected.offer.toBase58()).to.equal(offerAddress.toBase58());
+    expect(offerRejected.error).to.equal(3); // OFF_TICK_OR_LOT
+    const rejectedOffer = await program.account.offerAccount.fetch(
+      offerAddress
+    );
+    expect(rejectedOffer.status).to.eql({ rejected: {} });
+  });
+});
@@ -0,0 +1,256 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Ed25519Program,
+  Keypair,
+  PublicKey,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { createHash, randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitEvent,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getDealAddress,
+  getBalanceAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+// Domain separator prefixed to every relayed message
+const RELAY_DOMAIN = Buffer.from("otc-relay-v1");
+
+describe("Relayed Instructions", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  function getRelayNonceAddress(controller: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("relay_nonce"), controller.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  /**
+   * Message the controller signs: domain || nonce (u64 LE) || sha256(program id,
+   * data, non-signer account keys).
+   */
+  function relayMessage(ix: TransactionInstruction, nonce: number): Buffer {
+    const digest = createHash("sha256");
+    digest.update(ix.programId.toBuffer());
+    digest.update(ix.data);
+    for (const key of ix.keys.filter((meta) => !meta.isSigner)) {
+      digest.update(key.pubkey.toBuffer());
+    }
+    const nonceBytes = Buffer.alloc(8);
+    nonceBytes.writeBigUInt64LE(BigInt(nonce));
+    return Buffer.concat([RELAY_DOMAIN, nonceBytes, digest.digest()]);
+  }
+
+  it("creates a deal from a controller-signed message sent by a relayer", async () => {
+    // ==========================================
+    // SETUP: Controller balance and relay nonce
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const baseMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+    const quoteMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    const controller = Keypair.generate();
+    const controllerPrivateKey = x25519.utils.randomSecretKey();
+    const controllerPublicKey = x25519.getPublicKey(controllerPrivateKey);
+    const controllerCipher = new RescueCipher(
+      x25519.getSharedSecret(controllerPrivateKey, mxePublicKey)
+    );
+
+    const creatorBalance = getBalanceAddress(
+      program,
+      controller.publicKey,
+      baseMint
+    );
+    const topUpOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        topUpOffset,
+        controller.publicKey,
+        Array.from(controllerPublicKey),
+        randomU128(),
+        new anchor.BN(10000)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint: baseMint,
+        balance: creatorBalance,
+        ...arciumAccounts(topUpOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      topUpOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    // The relayer opens the nonce account; the controller never signs a transaction
+    const relayNonce = getRelayNonceAddress(controller.publicKey);
+    await program.methods
+      .initRelayNonce(controller.publicKey)
+      .accountsPartial({ payer: owner.publicKey, relayNonce })
+      .rpc({ commitment: "confirmed" });
+
+    // ==========================================
+    // STEP 1: Relayer submits the signed create_deal
+    // ==========================================
+    const dealNonce = randomBytes(16);
+    const dealCiphertext = controllerCipher.encrypt(
+      [BigInt(1000), BigInt(2) << BigInt(64)],
+      dealNonce
+    );
+    const dealCreateKey = Keypair.generate();
+    const deal = getDealAddress(program, dealCreateKey.publicKey);
+    const dealOffset = new anchor.BN(randomBytes(8), "hex");
+    const relayedIx = await program.methods
+      .createDeal(
+        dealOffset,
+        controller.publicKey,
+        Array.from(controllerPublicKey),
+        new anchor.BN(deserializeLE(dealNonce).toString()),
+        randomU128(),
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+        true,
+        Array.from(dealCiphertext[0]),
+        Array.from(dealCiphertext[1]),
+        0, // counterparty_kind: unrestricted
+        Array.from(new Uint8Array(32)), // counterparty
+        new anchor.BN(0) // crank_bounty: none
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        relayNonce,
+        createKey: dealCreateKey.publicKey,
+        deal,
+        creatorBalance,
+        baseMint,
+        quoteMint,
+        ...arciumAccounts(dealOffset, "create_deal"),
+      })
+      .instruction();
+
+    const signatureIx = Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: controller.secretKey,
+      message: relayMessage(relayedIx, 0),
+    });
+
+    const createdPromise = awaitEvent(program, "dealCreated");
+    await provider.sendAndConfirm(
+      new Transaction().add(signatureIx, relayedIx),
+      [dealCreateKey],
+      { skipPreflight: true, commitment: "confirmed" }
+    );
+    await awaitComputationFinalization(
+      provider,
+      dealOffset,
+      program.programId,
+      "confirmed"
+    );
+    const created = await createdPromise;
+    expect(created.deal.toBase58()).to.equal(deal.toBase58());
+
+    const nonceAccount = await program.account.relayNonce.fetch(relayNonce);
+    expect(nonceAccount.nonce.toNumber()).to.equal(1);
+
+    // ==========================================
+    // STEP 2: Replaying the same signature fails on the advanced nonce
+    // ==========================================
+    const replayKey = Keypair.generate();
+    const replayOffset = new anchor.BN(randomBytes(8), "hex");
+    const replayIx = await program.methods
+      .createDeal(
+        replayOffset,
+        controller.publicKey,
+        Array.from(controllerPublicKey),
+        new anchor.BN(deserializeLE(dealNonce).toString()),
+        randomU128(),
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+        true,
+        Array.from(dealCiphertext[0]),
+        Array.from(dealCiphertext[1]),
+        0, // counterparty_kind: unrestricted
+        Array.from(new Uint8Array(32)), // counterparty
+        new anchor.BN(0) // crank_bounty: none
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        relayNonce,
+        createKey: replayKey.publicKey,
+        deal: getDealAddress(program, replayKey.publicKey),
+        creatorBalance,
+        baseMint,
+        quoteMint,
+        ...arciumAccounts(replayOffset, "create_deal"),
+      })
+      .instruction();
+
+    try {
+      await provider.sendAndConfirm(
+        new Transaction().add(signatureIx, replayIx),
+        [replayKey],
+        { commitment: "confirmed" }
+      );
+      expect.fail("replayed relay signature should be rejected");
+    } catch (error: any) {
+      // Raw transactions surface the Anchor error name in the program logs
+      const logs = (error.logs ?? []).join("\n");
+      expect(logs).to.include("InvalidRelaySignature");
+    }
+  });
+});
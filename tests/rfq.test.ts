@@ -0,0 +1,448 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitEvent,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getRfqAddress,
+  getQuoteAddress,
+  getBalanceAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+describe("Request For Quote", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  async function topUp(
+    controller: Keypair,
+    encryptionPubkey: Uint8Array,
+    mint: PublicKey,
+    amount: number
+  ): Promise<PublicKey> {
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPubkey),
+        new anchor.BN(deserializeLE(randomBytes(16)).toString()),
+        new anchor.BN(amount)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        ...arciumAccounts(computationOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    return balance;
+  }
+
+  it("fills an RFQ with the best maker quote", async () => {
+    // ==========================================
+    // SETUP: Mints, keys and balances
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+
+    const baseMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+    const quoteMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    // Taker (owner) pays QUOTE, maker sells BASE
+    const takerPrivateKey = x25519.utils.randomSecretKey();
+    const takerPublicKey = x25519.getPublicKey(takerPrivateKey);
+    const takerCipher = new RescueCipher(
+      x25519.getSharedSecret(takerPrivateKey, mxePublicKey)
+    );
+
+    const maker = Keypair.generate();
+    const makerPrivateKey = x25519.utils.randomSecretKey();
+    const makerPublicKey = x25519.getPublicKey(makerPrivateKey);
+    const makerCipher = new RescueCipher(
+      x25519.getSharedSecret(makerPrivateKey, mxePublicKey)
+    );
+
+    const takerBalanceAddress = await topUp(
+      owner,
+      takerPublicKey,
+      quoteMint,
+      10000
+    );
+    const makerBalanceAddress = await topUp(
+      maker,
+      makerPublicKey,
+      baseMint,
+      10000
+    );
+    console.log("Taker and maker balances topped up");
+
+    // ==========================================
+    // STEP 1: Taker publishes RFQ (size 1000, limit 3.0)
+    // ==========================================
+    const rfqNonce = randomBytes(16);
+    const rfqCiphertext = takerCipher.encrypt(
+      [BigInt(1000), BigInt(3) << BigInt(64)],
+      rfqNonce
+    );
+    const rfqCreateKey = Keypair.generate();
+    const rfqAddress = getRfqAddress(program, rfqCreateKey.publicKey);
+    const deadline = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+    const createRfqOffset = new anchor.BN(randomBytes(8), "hex");
+
+    const rfqCreatedEventPromise = awaitEvent(program, "rfqCreated");
+
+    await program.methods
+      .createRfq(
+        createRfqOffset,
+        owner.publicKey,
+        Array.from(takerPublicKey),
+        new anchor.BN(deserializeLE(rfqNonce).toString()),
+        new anchor.BN(deserializeLE(randomBytes(16)).toString()),
+        deadline,
+        Array.from(rfqCiphertext[0]),
+        Array.from(rfqCiphertext[1]),
+        1, // counterparty_kind: single controller
+        Array.from(maker.publicKey.toBytes())
+      )
+      .accountsPartial({
+        createKey: rfqCreateKey.publicKey,
+        rfq: rfqAddress,
+        takerBalance: takerBalanceAddress,
+        baseMint,
+        quoteMint,
+        ...arciumAccounts(createRfqOffset, "create_rfq"),
+      })
+      .signers([rfqCreateKey])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+
+    await awaitComputationFinalization(
+      provider,
+      createRfqOffset,
+      program.programId,
+      "confirmed"
+    );
+    const rfqCreatedEvent = await rfqCreatedEventPromise;
+    expect(rfqCreatedEvent.rfq.toBase58()).to.equal(rfqAddress.toBase58());
+    console.log("RFQ created:", rfqAddress.toBase58());
+
+    // ==========================================
+    // STEP 2: Selected maker quotes 2.0
+    // ==========================================
+    const quoteNonce = randomBytes(16);
+    const quoteCiphertext = makerCipher.encrypt(
+      [BigInt(2) << BigInt(64)],
+      quoteNonce
+    );
+    const quoteCreateKey = Keypair.generate();
+    const quoteAddress = getQuoteAddress(
+      program,
+      rfqAddress,
+      quoteCreateKey.publicKey
+    );
+    const submitQuoteOffset = new anchor.BN(randomBytes(8), "hex");
+
+    const quoteCreatedEventPromise = awaitEvent(program, "quoteCreated");
+
+    await program.methods
+      .submitQuote(
+        submitQuoteOffset,
+        maker.publicKey,
+        Array.from(makerPublicKey),
+        new anchor.BN(deserializeLE(quoteNonce).toString()),
+        Array.from(quoteCiphertext[0]),
+        [] // counterparty_proof (not needed for single controller)
+      )
+      .accountsPartial({
+        createKey: quoteCreateKey.publicKey,
+        rfq: rfqAddress,
+        quote: quoteAddress,
+        makerBalance: makerBalanceAddress,
+        ...arciumAccounts(submitQuoteOffset, "submit_quote"),
+      })
+      .signers([quoteCreateKey])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+
+    await awaitComputationFinalization(
+      provider,
+      submitQuoteOffset,
+      program.programId,
+      "confirmed"
+    );
+    const quoteCreatedEvent = await quoteCreatedEventPromise;
+    expect(quoteCreatedEvent.quoteIndex).to.equal(0);
+
+    // QuoteCreatedBlob: [price: u128, size: u64]
+    const quoteCreated = makerCipher.decrypt(
+      quoteCreatedEvent.ciphertexts,
+      Uint8Array.from(quoteCreatedEvent.nonce)
+    );
+    expect(quoteCreated[0]).to.equal(BigInt(2) << BigInt(64));
+    expect(quoteCreated[1]).to.equal(BigInt(1000));
+
+    // ==========================================
+    // STEP 3: Taker accepts early by cranking the RFQ
+    // ==========================================
+    const crankRfqOffset = new anchor.BN(randomBytes(8), "hex");
+    const rfqSettledEventPromise = awaitEvent(program, "rfqSettled");
+
+    await program.methods
+      .crankRfq(
+        crankRfqOffset,
+        new anchor.BN(deserializeLE(randomBytes(16)).toString()),
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        payer: owner.publicKey,
+        rfq: rfqAddress,
+        takerBalance: takerBalanceAddress,
+        ...arciumAccounts(crankRfqOffset, "crank_rfq"),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+
+    await awaitComputationFinalization(
+      provider,
+      crankRfqOffset,
+      program.programId,
+      "confirmed"
+    );
+    const rfqSettledEvent = await rfqSettledEventPromise;
+    expect(rfqSettledEvent.status).to.eql({ filled: {} });
+
+    // RfqSettledBlob: [executed_amt: u64, price: u128, quote_paid: u64, quote_refund: u64]
+    const rfqSettled = takerCipher.decrypt(
+      rfqSettledEvent.ciphertexts,
+      Uint8Array.from(rfqSettledEvent.nonce)
+    );
+    expect(rfqSettled[0]).to.equal(BigInt(1000));
+    expect(rfqSettled[1]).to.equal(BigInt(2) << BigInt(64));
+    expect(rfqSettled[2]).to.equal(BigInt(2000)); // 1000 * 2.0
+    expect(rfqSettled[3]).to.equal(BigInt(1000)); // committed at 3.0, paid at 2.0
+
+    // ==========================================
+    // STEP 4: Crank the winning quote
+    // ==========================================
+    const crankQuoteOffset = new anchor.BN(randomBytes(8), "hex");
+    const quoteSettledEventPromise = awaitEvent(program, "quoteSettled");
+
+    await program.methods
+      .crankQuote(
+        crankQuoteOffset,
+        new anchor.BN(deserializeLE(randomBytes(16)).toString()),
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        payer: owner.publicKey,
+        rfq: rfqAddress,
+        quote: quoteAddress,
+        makerBalance: makerBalanceAddress,
+        ...arciumAccounts(crankQuoteOffset, "crank_quote"),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+
+    await awaitComputationFinalization(
+      provider,
+      crankQuoteOffset,
+      program.programId,
+      "confirmed"
+    );
+    const quoteSettledEvent = await quoteSettledEventPromise;
+
+    // QuoteSettledBlob: [outcome: u8, executed_amt: u64, quote_receives: u64]
+    const quoteSettled = makerCipher.decrypt(
+      quoteSettledEvent.ciphertexts,
+      Uint8Array.from(quoteSettledEvent.nonce)
+    );
+    expect(quoteSettled[0]).to.equal(BigInt(0)); // EXECUTED
+    expect(quoteSettled[1]).to.equal(BigInt(1000));
+    expect(quoteSettled[2]).to.equal(BigInt(2000));
+
+    const quoteAccount = await program.account.quoteAccount.fetch(
+      quoteAddress
+    );
+    expect(quoteAccount.status).to.eql({ settled: {} });
+  });
+
+  it("rejects an RFQ too large to lock", async () => {
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const quoteMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+    const baseMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    const takerPrivateKey = x25519.utils.randomSecretKey();
+    const takerPublicKey = x25519.getPublicKey(takerPrivateKey);
+    const takerCipher = new RescueCipher(
+      x25519.getSharedSecret(takerPrivateKey, mxePublicKey)
+    );
+    const takerBalanceAddress = await topUp(
+      owner,
+      takerPublicKey,
+      quoteMint,
+      10000
+    );
+
+    // u64::MAX at 2.0 overflows the QUOTE commitment
+    const rfqNonce = randomBytes(16);
+    const rfqCiphertext = takerCipher.encrypt(
+      [(BigInt(1) << BigInt(64)) - BigInt(1), BigInt(2) << BigInt(64)],
+      rfqNonce
+    );
+    const rfqCreateKey = Keypair.generate();
+    const rfqAddress = getRfqAddress(program, rfqCreateKey.publicKey);
+    const createRfqOffset = new anchor.BN(randomBytes(8), "hex");
+    const rfqRejectedPromise = awaitEvent(program, "rfqRejected");
+
+    await program.methods
+      .createRfq(
+        createRfqOffset,
+        owner.publicKey,
+        Array.from(takerPublicKey),
+        new anchor.BN(deserializeLE(rfqNonce).toString()),
+        new anchor.BN(deserializeLE(randomBytes(16)).toString()),
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+        Array.from(rfqCiphertext[0]),
+        Array.from(rfqCiphertext[1]),
+        0, // counterparty_kind: anyone
+        Array.from(new Uint8Array(32))
+      )
+      .accountsPartial({
+        createKey: rfqCreateKey.publicKey,
+        rfq: rfqAddress,
+        takerBalance: takerBalanceAddress,
+        baseMint,
+        quoteMint,
+        ...arciumAccounts(createRfqOffset, "create_rfq"),
+      })
+      .signers([rfqCreateKey])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+
+    await awaitComputationFinalization(
+      provider,
+      createRfqOffset,
+      program.programId,
+      "confirmed"
+    );
+    const rfqRejected = await rfqRejectedPromise;
+    expect(rfqRejected.rfq.toBase58()).to.equal(rfqAddress.toBase58());
+    expect(rfqRejected.error).to.equal(2); // OVERFLOW
+
+    const rfqAccount = await program.account.rfqAccount.fetch(rfqAddress);
+    expect(rfqAccount.status).to.eql({ rejected: {} });
+
+    // A rejected RFQ takes no quotes
+    const maker = Keypair.generate();
+    const makerPrivateKey = x25519.utils.randomSecretKey();
+    const makerPublicKey = x25519.getPublicKey(makerPrivateKey);
+    const makerCipher = new RescueCipher(
+      x25519.getSharedSecret(makerPrivateKey, mxePublicKey)
+    );
+    const makerBalanceAddress = await topUp(
+      maker,
+      makerPublicKey,
+      baseMint,
+      10000
+    );
+    const quoteNonce = randomBytes(16);
+    const quoteCiphertext = makerCipher.encrypt(
+      [BigInt(1) << BigInt(64)],
+      quoteNonce
+    );
+    const quoteCreateKey = Keypair.generate();
+    const submitQuoteOffset = new anchor.BN(randomBytes(8), "hex");
+    try {
+      await program.methods
+        .submitQuote(
+          submitQuoteOffset,
+          maker.publicKey,
+          Array.from(makerPublicKey),
+          new anchor.BN(deserializeLE(quoteNonce).toString()),
+          Array.from(quoteCiphertext[0]),
+          []
+        )
+        .accountsPartial({
+          createKey: quoteCreateKey.publicKey,
+          rfq: rfqAddress,
+          quote: getQuoteAddress(
+            program,
+            rfqAddress,
+            quoteCreateKey.publicKey
+          ),
+          makerBalance: makerBalanceAddress,
+          ...arciumAccounts(submitQuoteOffset, "submit_quote"),
+        })
+        .signers([quoteCreateKey])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("quote on a rejected RFQ should fail");
+    } catch (error: any) {
+      expect(error.message).to.include("RfqNotOpen");
+    }
+  });
+});
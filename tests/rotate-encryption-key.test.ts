@@ -0,0 +1,332 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitEvent,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getDealAddress,
+  getOfferAddress,
+  getBalanceAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+describe("Rotate Encryption Key", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  async function finalize(computationOffset: anchor.BN) {
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+  }
+
+  async function topUp(
+    controller: Keypair,
+    encryptionPubkey: Uint8Array,
+    mint: PublicKey,
+    amount: number
+  ): Promise<PublicKey> {
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPubkey),
+        randomU128(),
+        new anchor.BN(amount)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        ...arciumAccounts(computationOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(computationOffset);
+    return balance;
+  }
+
+  function newKey() {
+    return x25519.utils.randomSecretKey();
+  }
+
+  it("re-seals balances, deals and offers to a rotated key", async () => {
+    // ==========================================
+    // SETUP: Creator and offeror balances, a deal and an offer
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const cipherFor = (privateKey: Uint8Array) =>
+      new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+
+    const baseMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+    const quoteMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    const creatorKey = newKey();
+    const creatorPublicKey = x25519.getPublicKey(creatorKey);
+    const creatorBalance = await topUp(
+      owner,
+      creatorPublicKey,
+      baseMint,
+      10000
+    );
+
+    const offeror = Keypair.generate();
+    const offerorKey = newKey();
+    const offerorPublicKey = x25519.getPublicKey(offerorKey);
+    const offerorBalance = await topUp(
+      offeror,
+      offerorPublicKey,
+      quoteMint,
+      10000
+    );
+
+    const dealNonce = randomBytes(16);
+    const dealCiphertext = cipherFor(creatorKey).encrypt(
+      [BigInt(1000), BigInt(2) << BigInt(64)],
+      dealNonce
+    );
+    const dealCreateKey = Keypair.generate();
+    const deal = getDealAddress(program, dealCreateKey.publicKey);
+    const dealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createDeal(
+        dealOffset,
+        owner.publicKey,
+        Array.from(creatorPublicKey),
+        new anchor.BN(deserializeLE(dealNonce).toString()),
+        randomU128(),
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+        true,
+        Array.from(dealCiphertext[0]),
+        Array.from(dealCiphertext[1]),
+        0, // counterparty_kind: unrestricted
+        Array.from(new Uint8Array(32)), // counterparty
+        new anchor.BN(0) // crank_bounty: none
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        createKey: dealCreateKey.publicKey,
+        deal,
+        creatorBalance,
+        baseMint,
+        quoteMint,
+        ...arciumAccounts(dealOffset, "create_deal"),
+      })
+      .signers([dealCreateKey])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(dealOffset);
+
+    const offerNonce = randomBytes(16);
+    const offerCiphertext = cipherFor(offerorKey).encrypt(
+      [BigInt(2) << BigInt(64), BigInt(400)],
+      offerNonce
+    );
+    const offerCreateKey = Keypair.generate();
+    const offer = getOfferAddress(program, deal, offerCreateKey.publicKey);
+    const offerOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .submitOffer(
+        offerOffset,
+        offeror.publicKey,
+        Array.from(offerorPublicKey),
+        new anchor.BN(deserializeLE(offerNonce).toString()),
+        Array.from(offerCiphertext[0]),
+        Array.from(offerCiphertext[1]),
+        [], // counterparty_proof
+        new anchor.BN(0) // crank_bounty: none
+      )
+      .accountsPartial({
+        authority: offeror.publicKey,
+        createKey: offerCreateKey.publicKey,
+        deal,
+        offer,
+        offerorBalance,
+        ...arciumAccounts(offerOffset, "submit_offer"),
+      })
+      .signers([offerCreateKey, offeror])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(offerOffset);
+
+    // ==========================================
+    // STEP 1: Rotate the creator's balance key
+    // ==========================================
+    const rotatedBalanceKey = newKey();
+    const rotatedBalancePublicKey = x25519.getPublicKey(rotatedBalanceKey);
+    const balanceOffset = new anchor.BN(randomBytes(8), "hex");
+    const balanceUpdatedPromise = awaitEvent(program, "balanceUpdated");
+    await program.methods
+      .rotateBalanceEncryptionKey(
+        balanceOffset,
+        owner.publicKey,
+        Array.from(rotatedBalancePublicKey),
+        randomU128()
+      )
+      .accountsPartial({
+        controllerSigner: owner.publicKey,
+        balance: creatorBalance,
+        ...arciumAccounts(balanceOffset, "announce_balance"),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(balanceOffset);
+
+    const balanceUpdated = await balanceUpdatedPromise;
+    expect(Buffer.from(balanceUpdated.encryptionKey)).to.deep.equal(
+      Buffer.from(rotatedBalancePublicKey)
+    );
+    const [amount, committed] = cipherFor(rotatedBalanceKey).decrypt(
+      balanceUpdated.ciphertexts,
+      Uint8Array.from(balanceUpdated.nonce)
+    );
+    expect(amount).to.equal(BigInt(10000));
+    expect(committed).to.equal(BigInt(1000));
+    const balanceAccount = await program.account.balanceAccount.fetch(
+      creatorBalance
+    );
+    expect(Buffer.from(balanceAccount.encryptionPubkey)).to.deep.equal(
+      Buffer.from(rotatedBalancePublicKey)
+    );
+
+    // ==========================================
+    // STEP 2: Rotate the deal key
+    // ==========================================
+    const rotatedDealKey = newKey();
+    const rotatedDealPublicKey = x25519.getPublicKey(rotatedDealKey);
+    const dealRotateOffset = new anchor.BN(randomBytes(8), "hex");
+    const dealAnnouncedPromise = awaitEvent(program, "dealAnnounced");
+    await program.methods
+      .rotateDealEncryptionKey(
+        dealRotateOffset,
+        Array.from(rotatedDealPublicKey),
+        randomU128()
+      )
+      .accountsPartial({
+        controllerSigner: owner.publicKey,
+        deal,
+        creatorBalance,
+        ...arciumAccounts(dealRotateOffset, "announce_deal"),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(dealRotateOffset);
+
+    const dealAnnounced = await dealAnnouncedPromise;
+    expect(dealAnnounced.deal.toBase58()).to.equal(deal.toBase58());
+    // DealAnnouncedBlob: amount (u64), price (u128), fill_amount (u64)
+    const [dealAmount, , fillAmount] = cipherFor(rotatedDealKey).decrypt(
+      dealAnnounced.ciphertexts,
+      Uint8Array.from(dealAnnounced.nonce)
+    );
+    expect(dealAmount).to.equal(BigInt(1000));
+    expect(fillAmount).to.equal(BigInt(400));
+
+    // ==========================================
+    // STEP 3: Only the offeror can rotate the offer key
+    // ==========================================
+    const rotatedOfferKey = newKey();
+    const rotatedOfferPublicKey = x25519.getPublicKey(rotatedOfferKey);
+    const strangerOffset = new anchor.BN(randomBytes(8), "hex");
+    try {
+      await program.methods
+        .rotateOfferEncryptionKey(
+          strangerOffset,
+          Array.from(rotatedOfferPublicKey),
+          randomU128()
+        )
+        .accountsPartial({
+          controllerSigner: owner.publicKey,
+          offer,
+          deal,
+          offerorBalance,
+          ...arciumAccounts(strangerOffset, "announce_offer"),
+        })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("only the offeror should be able to rotate the offer key");
+    } catch (error: any) {
+      expect(error.toString()).to.include("ControllerMismatch");
+    }
+
+    const offerRotateOffset = new anchor.BN(randomBytes(8), "hex");
+    const offerAnnouncedPromise = awaitEvent(program, "offerAnnounced");
+    await program.methods
+      .rotateOfferEncryptionKey(
+        offerRotateOffset,
+        Array.from(rotatedOfferPublicKey),
+        randomU128()
+      )
+      .accountsPartial({
+        controllerSigner: offeror.publicKey,
+        offer,
+        deal,
+        offerorBalance,
+        ...arciumAccounts(offerRotateOffset, "announce_offer"),
+      })
+      .signers([offeror])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(offerRotateOffset);
+
+    const offerAnnounced = await offerAnnouncedPromise;
+    expect(offerAnnounced.offer.toBase58()).to.equal(offer.toBase58());
+    // OfferAnnouncedBlob: price (u128), amount (u64), amt_to_execute (u64)
+    const [, offerAmount, amtToExecute] = cipherFor(rotatedOfferKey).decrypt(
+      offerAnnounced.ciphertexts,
+      Uint8Array.from(offerAnnounced.nonce)
+    );
+    expect(offerAmount).to.equal(BigInt(400));
+    expect(amtToExecute).to.equal(BigInt(400));
+  });
+});
@@ -1,6 +1,6 @@
 import * as anchor from "@coral-xyz/anchor";
-import { Keypair } from "@solana/web3.js";
-import { randomBytes } from "crypto";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createHash, randomBytes } from "crypto";
 import { createMint } from "@solana/spl-token";
 import { expect } from "chai";
 import {
@@ -26,6 +26,80 @@ describe("Submit Offer", () => {
   const { program, provider, owner, arciumEnv, clusterAccount } =
     getTestHarness();
 
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  async function topUp(
+    controller: Keypair,
+    encryptionPubkey: Uint8Array,
+    mint: PublicKey,
+    amount: number
+  ): Promise<PublicKey> {
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPubkey),
+        randomU128(),
+        new anchor.BN(amount)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        ...arciumAccounts(computationOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    return balance;
+  }
+
+  // Allowlist hashing, as in programs/otc/src/state/counterparty.rs
+  function leaf(controller: PublicKey): Buffer {
+    return createHash("sha256")
+      .update(Buffer.from([0x00]))
+      .update(controller.toBuffer())
+      .digest();
+  }
+
+  function node(a: Buffer, b: Buffer): Buffer {
+    const [left, right] = Buffer.compare(a, b) <= 0 ? [a, b] : [b, a];
+    return createHash("sha256")
+      .update(Buffer.from([0x01]))
+      .update(left)
+      .update(right)
+      .digest();
+  }
+
   it("submits an offer to an existing deal", async () => {
     // ==========================================
     // SETUP: Get MXE public key and create mints
@@ -151,9 +225,13 @@ describe("Submit Offer", () => {
         expiresAt,
         allowPartial,
         Array.from(dealCiphertext[0]),
-        Array.from(dealCiphertext[1])
+        Array.from(dealCiphertext[1]),
+        0, // counterparty_kind: unrestricted
+        Array.from(new Uint8Array(32)), // counterparty
+        new anchor.BN(0) // crank_bounty: none
       )
       .accountsPartial({
+        authority: owner.publicKey,
         createKey: dealCreateKey.publicKey,
         deal: dealAddress,
         creatorBalance: creatorBalanceAddress,
@@ -202,6 +280,8 @@ describe("Submit Offer", () => {
     // ==========================================
     // STEP 3: Top up offeror's QUOTE balance
     // ==========================================
+    // Offeror must be a different controller than the deal creator (self-trade is rejected)
+    const offeror = Keypair.generate();
     console.log("\n--- Top Up Offeror Balance ---");
 
     // Generate offeror's encryption keypair (different from deal creator)
@@ -215,7 +295,7 @@ describe("Submit Offer", () => {
 
     const offerorBalanceAddress = getBalanceAddress(
       program,
-      owner.publicKey,
+      offeror.publicKey,
       quoteMint
     );
     const offerorTopUpNonce = randomBytes(16);
@@ -224,13 +304,13 @@ describe("Submit Offer", () => {
     await program.methods
       .topUp(
         offerorTopUpComputationOffset,
-        owner.publicKey,
+        offeror.publicKey,
         Array.from(offerorPublicKey),
         new anchor.BN(deserializeLE(offerorTopUpNonce).toString()),
         new anchor.BN(10000)
       )
       .accountsPartial({
-        controllerSigner: owner.publicKey,
+        controllerSigner: offeror.publicKey,
         mint: quoteMint,
         balance: offerorBalanceAddress,
         computationAccount: getComputationAccAddress(
@@ -248,6 +328,7 @@ describe("Submit Offer", () => {
           Buffer.from(getCompDefAccOffset("top_up")).readUInt32LE()
         ),
       })
+      .signers([offeror])
       .rpc({ skipPreflight: true, commitment: "confirmed" });
 
     await awaitComputationFinalization(
@@ -289,13 +370,16 @@ describe("Submit Offer", () => {
     const submitOfferSig = await program.methods
       .submitOffer(
         offerComputationOffset,
-        owner.publicKey, // controller
+        offeror.publicKey, // controller
         Array.from(offerorPublicKey),
         new anchor.BN(deserializeLE(offerNonce).toString()),
         Array.from(offerCiphertext[0]), // encrypted price
-        Array.from(offerCiphertext[1]) // encrypted amount
+        Array.from(offerCiphertext[1]), // encrypted amount
+        [], // counterparty_proof
+        new anchor.BN(0) // crank_bounty: none
       )
       .accountsPartial({
+        authority: offeror.publicKey,
         createKey: offerCreateKey.publicKey,
         deal: dealAddress,
         offer: offerAddress,
@@ -315,7 +399,7 @@ describe("Submit Offer", () => {
           Buffer.from(getCompDefAccOffset("submit_offer")).readUInt32LE()
         ),
       })
-      .signers([offerCreateKey])
+      .signers([offerCreateKey, offeror])
       .rpc({ skipPreflight: true, commitment: "confirmed" });
     console.log("Queue submit_offer sig is ", submitOfferSig);
 
@@ -361,14 +445,14 @@ describe("Submit Offer", () => {
       offerCreateKey.publicKey.toBase58()
     );
     expect(offerAccount.controller.toBase58()).to.equal(
-      owner.publicKey.toBase58()
+      offeror.publicKey.toBase58()
     );
     expect(offerAccount.encryptionPubkey).to.deep.equal(
       Array.from(offerorPublicKey)
     );
     expect(offerAccount.deal.toBase58()).to.equal(dealAddress.toBase58());
     expect(offerAccount.offerIndex).to.equal(0);
-    expect(offerAccount.status).to.equal(0); // OPEN
+    expect(offerAccount.status).to.eql({ open: {} });
     expect(offerAccount.submittedAt.toNumber()).to.be.greaterThan(0);
 
     console.log("OfferAccount verified successfully");
@@ -391,4 +475,181 @@ describe("Submit Offer", () => {
       dealAccountAfter.numOffers
     );
   });
+
+  it("rejects controllers outside the deal's restriction", async () => {
+    // ==========================================
+    // SETUP: Mints, creator BASE balance and offeror QUOTE balance
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const baseMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+    const quoteMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    const creatorPrivateKey = x25519.utils.randomSecretKey();
+    const creatorPublicKey = x25519.getPublicKey(creatorPrivateKey);
+    const creatorCipher = new RescueCipher(
+      x25519.getSharedSecret(creatorPrivateKey, mxePublicKey)
+    );
+    const creatorBalanceAddress = await topUp(
+      owner,
+      creatorPublicKey,
+      baseMint,
+      10000
+    );
+
+    const outsider = Keypair.generate();
+    const member = Keypair.generate();
+    const offerorPrivateKey = x25519.utils.randomSecretKey();
+    const offerorPublicKey = x25519.getPublicKey(offerorPrivateKey);
+    const offerorCipher = new RescueCipher(
+      x25519.getSharedSecret(offerorPrivateKey, mxePublicKey)
+    );
+    await topUp(outsider, offerorPublicKey, quoteMint, 10000);
+    await topUp(member, offerorPublicKey, quoteMint, 10000);
+
+    async function createDeal(counterpartyKind: number, counterparty: Buffer) {
+      const dealNonce = randomBytes(16);
+      const dealCiphertext = creatorCipher.encrypt(
+        [BigInt(1000), BigInt(2) << BigInt(64)],
+        dealNonce
+      );
+      const dealCreateKey = Keypair.generate();
+      const dealAddress = getDealAddress(program, dealCreateKey.publicKey);
+      const createDealOffset = new anchor.BN(randomBytes(8), "hex");
+
+      await program.methods
+        .createDeal(
+          createDealOffset,
+          owner.publicKey,
+          Array.from(creatorPublicKey),
+          new anchor.BN(deserializeLE(dealNonce).toString()),
+          randomU128(),
+          new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+          true,
+          Array.from(dealCiphertext[0]),
+          Array.from(dealCiphertext[1]),
+          counterpartyKind,
+          Array.from(counterparty),
+          new anchor.BN(0) // crank_bounty: none
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          createKey: dealCreateKey.publicKey,
+          deal: dealAddress,
+          creatorBalance: creatorBalanceAddress,
+          baseMint,
+          quoteMint,
+          ...arciumAccounts(createDealOffset, "create_deal"),
+        })
+        .signers([dealCreateKey])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(
+        provider,
+        createDealOffset,
+        program.programId,
+        "confirmed"
+      );
+      return dealAddress;
+    }
+
+    async function submitOffer(
+      dealAddress: PublicKey,
+      offeror: Keypair,
+      proof: Buffer[]
+    ) {
+      const offerNonce = randomBytes(16);
+      const offerCiphertext = offerorCipher.encrypt(
+        [BigInt(2) << BigInt(64), BigInt(100)],
+        offerNonce
+      );
+      const offerCreateKey = Keypair.generate();
+      const submitOfferOffset = new anchor.BN(randomBytes(8), "hex");
+
+      await program.methods
+        .submitOffer(
+          submitOfferOffset,
+          offeror.publicKey,
+          Array.from(offerorPublicKey),
+          new anchor.BN(deserializeLE(offerNonce).toString()),
+          Array.from(offerCiphertext[0]),
+          Array.from(offerCiphertext[1]),
+          proof.map((sibling) => Array.from(sibling)),
+          new anchor.BN(0) // crank_bounty: none
+        )
+        .accountsPartial({
+          authority: offeror.publicKey,
+          createKey: offerCreateKey.publicKey,
+          deal: dealAddress,
+          offer: getOfferAddress(
+            program,
+            dealAddress,
+            offerCreateKey.publicKey
+          ),
+          offerorBalance: getBalanceAddress(
+            program,
+            offeror.publicKey,
+            quoteMint
+          ),
+          ...arciumAccounts(submitOfferOffset, "submit_offer"),
+        })
+        .signers([offerCreateKey, offeror])
+        .rpc({ commitment: "confirmed" });
+      return submitOfferOffset;
+    }
+
+    // ==========================================
+    // STEP 1: Single-controller deal (kind 1) rejects everyone else
+    // ==========================================
+    const singleDeal = await createDeal(1, member.publicKey.toBuffer());
+    try {
+      await submitOffer(singleDeal, outsider, []);
+      expect.fail("an unlisted controller should not submit an offer");
+    } catch (error: any) {
+      expect(error.toString()).to.include("CounterpartyNotAllowed");
+    }
+
+    // ==========================================
+    // STEP 2: Allowlist deal (kind 2) rejects non-members
+    // ==========================================
+    const other = Keypair.generate().publicKey;
+    const root = node(leaf(member.publicKey), leaf(other));
+    const allowlistDeal = await createDeal(2, root);
+
+    // Neither an empty proof nor a member's proof admits an outsider
+    for (const proof of [[], [leaf(other)]]) {
+      try {
+        await submitOffer(allowlistDeal, outsider, proof);
+        expect.fail("an unlisted controller should not submit an offer");
+      } catch (error: any) {
+        expect(error.toString()).to.include("CounterpartyNotAllowed");
+      }
+    }
+
+    // A member with a valid proof passes the check
+    const submitOfferOffset = await submitOffer(allowlistDeal, member, [
+      leaf(other),
+    ]);
+    await awaitComputationFinalization(
+      provider,
+      submitOfferOffset,
+      program.programId,
+      "confirmed"
+    );
+    const dealAccount = await program.account.dealAccount.fetch(allowlistDeal);
+    expect(dealAccount.numOffers).to.equal(1);
+  });
 });
@@ -0,0 +1,305 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitEvent,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getDealAddress,
+  getOfferAddress,
+  getBalanceAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+describe("Swap Deals", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  async function finalize(computationOffset: anchor.BN) {
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+  }
+
+  async function topUp(
+    controller: Keypair,
+    encryptionPubkey: Uint8Array,
+    mint: PublicKey,
+    amount: number
+  ): Promise<PublicKey> {
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPubkey),
+        randomU128(),
+        new anchor.BN(amount)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        ...arciumAccounts(computationOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(computationOffset);
+    return balance;
+  }
+
+  it("swaps 1000 base for 40 quote at the creator's ratio", async () => {
+    // ==========================================
+    // SETUP: Two mints and funded balances on each side
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const baseMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      0
+    );
+    const quoteMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      0
+    );
+
+    const creator = Keypair.generate();
+    const creatorPrivateKey = x25519.utils.randomSecretKey();
+    const creatorPublicKey = x25519.getPublicKey(creatorPrivateKey);
+    const creatorCipher = new RescueCipher(
+      x25519.getSharedSecret(creatorPrivateKey, mxePublicKey)
+    );
+    const creatorBalance = await topUp(
+      creator,
+      creatorPublicKey,
+      baseMint,
+      5000
+    );
+
+    const offeror = Keypair.generate();
+    const offerorPrivateKey = x25519.utils.randomSecretKey();
+    const offerorPublicKey = x25519.getPublicKey(offerorPrivateKey);
+    const offerorCipher = new RescueCipher(
+      x25519.getSharedSecret(offerorPrivateKey, mxePublicKey)
+    );
+    const offerorBalance = await topUp(
+      offeror,
+      offerorPublicKey,
+      quoteMint,
+      5000
+    );
+
+    // ==========================================
+    // STEP 1: The creator gives 1000 base and wants 40 quote
+    // ==========================================
+    const dealNonce = randomBytes(16);
+    const dealCiphertext = creatorCipher.encrypt(
+      [BigInt(1000), BigInt(40)],
+      dealNonce
+    );
+    const createKey = Keypair.generate();
+    const deal = getDealAddress(program, createKey.publicKey);
+    const createOffset = new anchor.BN(randomBytes(8), "hex");
+    const createdPromise = awaitEvent(program, "dealCreated");
+    await program.methods
+      .createSwapDeal(
+        createOffset,
+        creator.publicKey,
+        Array.from(creatorPublicKey),
+        new anchor.BN(deserializeLE(dealNonce).toString()),
+        randomU128(),
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+        false,
+        Array.from(dealCiphertext[0]),
+        Array.from(dealCiphertext[1]),
+        0, // counterparty_kind: unrestricted
+        Array.from(new Uint8Array(32)), // counterparty
+        new anchor.BN(0) // crank_bounty: none
+      )
+      .accountsPartial({
+        authority: creator.publicKey,
+        createKey: createKey.publicKey,
+        deal,
+        creatorBalance,
+        baseMint,
+        quoteMint,
+        ...arciumAccounts(createOffset, "create_swap_deal"),
+      })
+      .signers([creator, createKey])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(createOffset);
+
+    const created = await createdPromise;
+    // SwapDealCreatedBlob: [give_amount, want_amount]
+    expect(
+      creatorCipher.decrypt(created.ciphertexts, Uint8Array.from(created.nonce))
+    ).to.eql([BigInt(1000), BigInt(40)]);
+    expect((await program.account.dealAccount.fetch(deal)).swap).to.equal(true);
+
+    // ==========================================
+    // STEP 2: Regular offers cannot target a swap deal
+    // ==========================================
+    const offerNonce = randomBytes(16);
+    const offerCiphertext = offerorCipher.encrypt(
+      [BigInt(50), BigInt(1000)],
+      offerNonce
+    );
+    try {
+      const strayKey = Keypair.generate();
+      const strayOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .submitOffer(
+          strayOffset,
+          offeror.publicKey,
+          Array.from(offerorPublicKey),
+          new anchor.BN(deserializeLE(offerNonce).toString()),
+          Array.from(offerCiphertext[0]),
+          Array.from(offerCiphertext[1]),
+          [], // counterparty_proof
+          new anchor.BN(0) // crank_bounty: none
+        )
+        .accountsPartial({
+          authority: offeror.publicKey,
+          createKey: strayKey.publicKey,
+          deal,
+          offer: getOfferAddress(program, deal, strayKey.publicKey),
+          offerorBalance,
+          ...arciumAccounts(strayOffset, "submit_offer"),
+        })
+        .signers([strayKey, offeror])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("submit_offer should reject swap deals");
+    } catch (error: any) {
+      expect(error.toString()).to.include("SwapDealMismatch");
+    }
+
+    // ==========================================
+    // STEP 3: The offeror gives up to 50 quote for 1000 base
+    // ==========================================
+    const offerCreateKey = Keypair.generate();
+    const offer = getOfferAddress(program, deal, offerCreateKey.publicKey);
+    const offerOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .submitSwapOffer(
+        offerOffset,
+        offeror.publicKey,
+        Array.from(offerorPublicKey),
+        new anchor.BN(deserializeLE(offerNonce).toString()),
+        Array.from(offerCiphertext[0]),
+        Array.from(offerCiphertext[1]),
+        [], // counterparty_proof
+        new anchor.BN(0) // crank_bounty: none
+      )
+      .accountsPartial({
+        authority: offeror.publicKey,
+        createKey: offerCreateKey.publicKey,
+        deal,
+        offer,
+        offerorBalance,
+        ...arciumAccounts(offerOffset, "submit_swap_offer"),
+      })
+      .signers([offerCreateKey, offeror])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(offerOffset);
+
+    // ==========================================
+    // STEP 4: The creator cranks the filled deal
+    // ==========================================
+    const crankDealOffset = new anchor.BN(randomBytes(8), "hex");
+    const dealSettledPromise = awaitEvent(program, "dealSettled");
+    await program.methods
+      .crankSwapDeal(crankDealOffset, randomU128(), randomU128())
+      .accountsPartial({
+        payer: creator.publicKey,
+        deal,
+        creatorBalance,
+        ...arciumAccounts(crankDealOffset, "crank_swap_deal"),
+      })
+      .signers([creator])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(crankDealOffset);
+
+    const dealSettled = await dealSettledPromise;
+    expect(dealSettled.status).to.eql({ executed: {} });
+    // DealSettledBlob: [total_filled, creator_receives, creator_refund]
+    expect(
+      creatorCipher.decrypt(
+        dealSettled.ciphertexts,
+        Uint8Array.from(dealSettled.nonce)
+      )
+    ).to.eql([BigInt(1000), BigInt(40), BigInt(0)]);
+
+    // ==========================================
+    // STEP 5: The offer settles at the creator's ratio, refunding the excess
+    // ==========================================
+    const crankOfferOffset = new anchor.BN(randomBytes(8), "hex");
+    const offerSettledPromise = awaitEvent(program, "offerSettled");
+    await program.methods
+      .crankSwapOffer(crankOfferOffset, randomU128(), randomU128())
+      .accountsPartial({
+        payer: owner.publicKey,
+        deal,
+        offer,
+        offerorBalance,
+        ...arciumAccounts(crankOfferOffset, "crank_swap_offer"),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await finalize(crankOfferOffset);
+
+    const offerSettled = await offerSettledPromise;
+    // OfferSettledBlob: [outcome, executed_amt, quote_paid, quote_refund]
+    expect(
+      offerorCipher.decrypt(
+        offerSettled.ciphertexts,
+        Uint8Array.from(offerSettled.nonce)
+      )
+    ).to.eql([BigInt(0), BigInt(1000), BigInt(40), BigInt(10)]);
+  });
+});
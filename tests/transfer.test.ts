@@ -0,0 +1,195 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  getTestHarness,
+  getMXEPublicKeyWithRetry,
+  awaitEvent,
+  awaitEvents,
+  awaitComputationFinalization,
+  getCompDefAccOffset,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getBalanceAddress,
+  RescueCipher,
+  deserializeLE,
+  x25519,
+} from "./harness";
+
+describe("Private Transfer", () => {
+  const { program, provider, owner, arciumEnv, clusterAccount } =
+    getTestHarness();
+
+  /**
+   * Arcium accounts shared by every queued computation.
+   */
+  function arciumAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+    };
+  }
+
+  function randomU128(): anchor.BN {
+    return new anchor.BN(deserializeLE(randomBytes(16)).toString());
+  }
+
+  async function topUp(
+    controller: Keypair,
+    encryptionPubkey: Uint8Array,
+    mint: PublicKey,
+    amount: number
+  ): Promise<PublicKey> {
+    const balance = getBalanceAddress(program, controller.publicKey, mint);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .topUp(
+        computationOffset,
+        controller.publicKey,
+        Array.from(encryptionPubkey),
+        randomU128(),
+        new anchor.BN(amount)
+      )
+      .accountsPartial({
+        controllerSigner: controller.publicKey,
+        mint,
+        balance,
+        ...arciumAccounts(computationOffset, "top_up"),
+      })
+      .signers([controller])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    return balance;
+  }
+
+  it("moves funds between two controllers without revealing the amount", async () => {
+    // ==========================================
+    // SETUP: Sender with 1000, recipient with 50
+    // ==========================================
+    const mxePublicKey = await getMXEPublicKeyWithRetry(
+      provider,
+      program.programId
+    );
+    const mint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6
+    );
+
+    const senderPrivateKey = x25519.utils.randomSecretKey();
+    const senderPublicKey = x25519.getPublicKey(senderPrivateKey);
+    const senderCipher = new RescueCipher(
+      x25519.getSharedSecret(senderPrivateKey, mxePublicKey)
+    );
+    const senderBalance = await topUp(owner, senderPublicKey, mint, 1000);
+
+    const recipient = Keypair.generate();
+    const recipientPrivateKey = x25519.utils.randomSecretKey();
+    const recipientPublicKey = x25519.getPublicKey(recipientPrivateKey);
+    const recipientCipher = new RescueCipher(
+      x25519.getSharedSecret(recipientPrivateKey, mxePublicKey)
+    );
+    const recipientBalance = await topUp(
+      recipient,
+      recipientPublicKey,
+      mint,
+      50
+    );
+
+    async function transfer(amount: bigint) {
+      const nonce = randomBytes(16);
+      const ciphertext = senderCipher.encrypt([amount], nonce);
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .transfer(
+          computationOffset,
+          owner.publicKey,
+          Array.from(senderPublicKey),
+          new anchor.BN(deserializeLE(nonce).toString()),
+          randomU128(),
+          Array.from(ciphertext[0])
+        )
+        .accountsPartial({
+          controllerSigner: owner.publicKey,
+          senderBalance,
+          recipientBalance,
+          ...arciumAccounts(computationOffset, "transfer"),
+        })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(
+        provider,
+        computationOffset,
+        program.programId,
+        "confirmed"
+      );
+    }
+
+    // ==========================================
+    // STEP 1: Transfer 400
+    // ==========================================
+    const transferredPromise = awaitEvent(program, "balanceTransferred");
+    const updatesPromise = awaitEvents(program, "balanceUpdated", 2);
+    await transfer(BigInt(400));
+
+    const transferred = await transferredPromise;
+    expect(transferred.success).to.equal(true);
+    const updates = await updatesPromise;
+
+    const senderUpdate = updates.find(
+      (e) => e.balance.toBase58() === senderBalance.toBase58()
+    )!;
+    const [senderAmount, senderCommitted] = senderCipher.decrypt(
+      senderUpdate.ciphertexts,
+      Uint8Array.from(senderUpdate.nonce)
+    );
+    expect(senderAmount).to.equal(BigInt(600));
+    expect(senderCommitted).to.equal(BigInt(0));
+
+    const recipientUpdate = updates.find(
+      (e) => e.balance.toBase58() === recipientBalance.toBase58()
+    )!;
+    const [recipientAmount] = recipientCipher.decrypt(
+      recipientUpdate.ciphertexts,
+      Uint8Array.from(recipientUpdate.nonce)
+    );
+    expect(recipientAmount).to.equal(BigInt(450));
+
+    // ==========================================
+    // STEP 2: Transfer beyond the available 600 fails
+    // ==========================================
+    const senderBefore = await program.account.balanceAccount.fetch(
+      senderBalance
+    );
+    const failedPromise = awaitEvent(program, "balanceTransferred");
+    await transfer(BigInt(601));
+
+    const failed = await failedPromise;
+    expect(failed.success).to.equal(false);
+    const senderAfter = await program.account.balanceAccount.fetch(
+      senderBalance
+    );
+    expect(senderAfter.nonce).to.deep.equal(senderBefore.nonce);
+  });
+});